@@ -1,21 +1,233 @@
 //! ADB-related Tauri commands
 
 use crate::domain::errors::AppError;
-use crate::domain::models::Device;
-use crate::services::{adb_service, settings_service};
-use tauri::AppHandle;
+use crate::domain::models::{
+    AppleDevice, BatteryStatus, Device, DeviceComparison, DeviceEvent, DeviceHealth, HotspotStart,
+    RebootMode, TcpipMode, WirelessNetworkCheck, WirelessStatus,
+};
+use crate::services::adb_service::AdbCli;
+use crate::services::confirmation_service::{self, ConfirmationState};
+use crate::services::wireless_service::{self, WirelessState};
+use crate::services::{
+    adb_service, apple_device_service, battery_service, compare_service, health_service,
+    hotspot_service, settings_service, timeline_service,
+};
+use std::time::Instant;
+use tauri::{AppHandle, State};
 
 /// List all connected devices
 #[tauri::command]
 pub fn list_devices(app: AppHandle) -> Result<Vec<Device>, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound(
             "ADB not found. Install Android SDK Platform Tools or set the path in Settings."
                 .to_string(),
         )
     })?;
 
-    adb_service::list_devices(&adb_path)
+    adb_service::list_devices(&adb)
+}
+
+/// iPhones and iPads plugged in, which aren't supported, so the UI can say so
+#[tauri::command]
+pub async fn list_apple_devices() -> Vec<AppleDevice> {
+    apple_device_service::detect()
+}
+
+/// Side-by-side report of OS versions, key properties, free storage and
+/// installed package versions for two or more devices
+#[tauri::command]
+pub fn compare_devices(app: AppHandle, serials: Vec<String>) -> Result<DeviceComparison, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    compare_service::compare_devices(&adb, &serials)
+}
+
+/// Get a device's battery level and charging state
+#[tauri::command]
+pub fn get_battery_status(app: AppHandle, serial: String) -> Result<BatteryStatus, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    battery_service::get_battery(&adb, &serial)
+}
+
+/// Battery, free/total storage and memory use of a device in one call
+#[tauri::command]
+pub fn get_device_health(app: AppHandle, serial: String) -> Result<DeviceHealth, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    Ok(health_service::get_device_health(&adb, &serial))
+}
+
+/// Restart a device normally or into recovery; needs a token from
+/// `request_confirmation`
+#[tauri::command]
+pub fn reboot_device(
+    app: AppHandle,
+    confirmations: State<'_, ConfirmationState>,
+    serial: String,
+    mode: RebootMode,
+    confirmation_token: Option<String>,
+) -> Result<(), AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    confirmation_service::consume(
+        &confirmations,
+        "reboot_device",
+        &[serial.as_str(), mode.as_str()],
+        confirmation_token.as_deref(),
+        Instant::now(),
+    )?;
+
+    adb_service::reboot(&adb, &serial, mode)
+}
+
+/// What happened with a device (connects, mirror sessions, transfers, syncs),
+/// newest first
+#[tauri::command]
+pub fn get_device_timeline(app: AppHandle, serial: String) -> Result<Vec<DeviceEvent>, AppError> {
+    timeline_service::get_timeline(&app, &serial)
+}
+
+/// Wireless devices watched by the connection keeper and their state
+#[tauri::command]
+pub fn get_wireless_status(state: State<'_, WirelessState>) -> Vec<WirelessStatus> {
+    wireless_service::get_statuses(&state)
+}
+
+/// Connect to a device over TCP/IP (`ip` or `ip:port`); returns the endpoint
+#[tauri::command]
+pub fn connect_device(app: AppHandle, addr: String) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    wireless_service::connect(&adb, &addr)
+}
+
+/// Disconnect a device connected over TCP/IP, like `disconnect_wireless` but
+/// taking an `ip` or `ip:port` address
+#[tauri::command]
+pub fn disconnect_device(
+    app: AppHandle,
+    state: State<'_, WirelessState>,
+    addr: String,
+) -> Result<String, AppError> {
+    let endpoint = wireless_service::parse_endpoint(&addr)?;
+    disconnect_wireless(app, state, endpoint)
+}
+
+/// Switch a USB device into TCP mode on `port`, so it can be unplugged and
+/// connected with `connect_device`
+#[tauri::command]
+pub fn enable_tcpip(app: AppHandle, serial: String, port: u16) -> Result<TcpipMode, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    wireless_service::enable_tcpip(&adb, &serial, port)
+}
+
+/// Disconnect a wireless device and stop the keeper reconnecting it, now and
+/// on later starts
+#[tauri::command]
+pub fn disconnect_wireless(
+    app: AppHandle,
+    state: State<'_, WirelessState>,
+    endpoint: String,
+) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let output = wireless_service::disconnect(&state, &adb, &endpoint)?;
+
+    // Don't bring it back on the next start either
+    let mut settings = settings_service::load_settings(&app)?;
+    if wireless_service::forget_endpoint(&mut settings, &endpoint) {
+        settings_service::save_settings(&app, &settings)?;
+    }
+    Ok(output)
+}
+
+/// Check whether this computer and a device share a network for wireless ADB
+#[tauri::command]
+pub fn check_wireless_network(
+    app: AppHandle,
+    serial: String,
+) -> Result<WirelessNetworkCheck, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    hotspot_service::check_network(&adb, &serial)
+}
+
+/// Turn on a device's hotspot, or open its hotspot settings where the device
+/// doesn't allow that over adb
+#[tauri::command]
+pub fn start_hotspot(
+    app: AppHandle,
+    serial: String,
+    ssid: String,
+    passphrase: String,
+) -> Result<HotspotStart, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    hotspot_service::start_hotspot(&adb, &serial, &ssid, &passphrase)
+}
+
+/// Turn a device's hotspot off
+#[tauri::command]
+pub fn stop_hotspot(app: AppHandle, serial: String) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    hotspot_service::stop_hotspot(&adb, &serial)
+}
+
+/// Connect to a device through its hotspot once this computer has joined it;
+/// returns the new `ip:port` serial
+#[tauri::command]
+pub fn connect_over_hotspot(app: AppHandle, serial: String) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    hotspot_service::connect_over_hotspot(&adb, &serial)
 }
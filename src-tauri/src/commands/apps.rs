@@ -0,0 +1,174 @@
+//! App management Tauri commands: default apps, link handling, processes and
+//! network usage
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{AppLinks, DefaultApp, DefaultAppRole, DeviceProcess, NetworkUsage};
+use crate::services::adb_service::AdbCli;
+use crate::services::{
+    default_apps_service, device_process_service, network_usage_service, settings_service,
+};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Current default browser, SMS, dialer, home and assistant apps
+#[tauri::command]
+pub fn get_default_apps(app: AppHandle, serial: String) -> Result<Vec<DefaultApp>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    default_apps_service::get_default_apps(&adb, &serial)
+}
+
+/// Make a package the default app for a role
+#[tauri::command]
+pub fn set_default_app(
+    app: AppHandle,
+    serial: String,
+    role: DefaultAppRole,
+    package: String,
+) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    default_apps_service::set_default_app(&adb, &serial, role, &package)
+}
+
+/// Unset the default app for a role
+#[tauri::command]
+pub fn clear_default_app(
+    app: AppHandle,
+    serial: String,
+    role: DefaultAppRole,
+) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    default_apps_service::clear_default_app(&adb, &serial, role)
+}
+
+/// Web domains an app opens links for and their verification state
+#[tauri::command]
+pub fn get_app_links(
+    app: AppHandle,
+    serial: String,
+    package: String,
+) -> Result<AppLinks, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    default_apps_service::get_app_links(&adb, &serial, &package)
+}
+
+/// Allow or stop an app opening its links directly
+#[tauri::command]
+pub fn set_app_links_allowed(
+    app: AppHandle,
+    serial: String,
+    package: String,
+    allowed: bool,
+) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    default_apps_service::set_app_links_allowed(&adb, &serial, &package, allowed)
+}
+
+/// Reset an app's link verification and verify its domains again
+#[tauri::command]
+pub fn reset_app_links(
+    app: AppHandle,
+    serial: String,
+    package: String,
+) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    default_apps_service::reset_app_links(&adb, &serial, &package)
+}
+
+/// Processes running on a device, largest memory users first
+#[tauri::command]
+pub fn list_device_processes(
+    app: AppHandle,
+    serial: String,
+) -> Result<Vec<DeviceProcess>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    device_process_service::list_processes(&adb, &serial)
+}
+
+/// Force-stop an app, e.g. one misbehaving during a mirror session
+#[tauri::command]
+pub fn force_stop(app: AppHandle, serial: String, package: String) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    device_process_service::force_stop(&adb, &serial, &package)
+}
+
+/// Kill a process by pid; only shell-owned processes can be killed without root
+#[tauri::command]
+pub fn kill_process(app: AppHandle, serial: String, pid: u32) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    device_process_service::kill_process(&adb, &serial, pid)
+}
+
+/// Network traffic per app since the device's stats began, heaviest first
+#[tauri::command]
+pub fn get_network_usage(app: AppHandle, serial: String) -> Result<Vec<NetworkUsage>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    network_usage_service::get_network_usage(&adb, &serial)
+}
+
+/// Save a device's per-app network traffic as CSV
+#[tauri::command]
+pub fn export_network_usage(
+    app: AppHandle,
+    serial: String,
+    path: String,
+) -> Result<Vec<NetworkUsage>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let usage = network_usage_service::get_network_usage(&adb, &serial)?;
+    network_usage_service::export_csv(&usage, &PathBuf::from(path))?;
+    Ok(usage)
+}
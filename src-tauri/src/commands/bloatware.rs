@@ -0,0 +1,84 @@
+//! Bloatware manager Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{BloatwarePackage, PackageOpResult};
+use crate::services::adb_service::AdbCli;
+use crate::services::confirmation_service::{self, ConfirmationState};
+use crate::services::{bloatware_service, settings_service};
+use std::time::Instant;
+use tauri::{AppHandle, State};
+
+/// Disable-safe preinstalled packages on a device and their current state
+#[tauri::command]
+pub fn list_bloatware(app: AppHandle, serial: String) -> Result<Vec<BloatwarePackage>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    bloatware_service::list_candidates(&adb, &serial)
+}
+
+/// Disable packages from the curated list and remember them for restoring;
+/// needs a token from `request_confirmation`
+#[tauri::command]
+pub fn disable_bloatware(
+    app: AppHandle,
+    confirmations: State<'_, ConfirmationState>,
+    serial: String,
+    packages: Vec<String>,
+    confirmation_token: Option<String>,
+) -> Result<Vec<PackageOpResult>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let args: Vec<&str> = std::iter::once(serial.as_str())
+        .chain(packages.iter().map(String::as_str))
+        .collect();
+    confirmation_service::consume(
+        &confirmations,
+        "disable_bloatware",
+        &args,
+        confirmation_token.as_deref(),
+        Instant::now(),
+    )?;
+
+    let results = bloatware_service::disable_packages(&adb, &serial, &packages);
+    let mut saved = settings_service::load_settings(&app)?;
+    let record = saved.disabled_packages.entry(serial).or_default();
+    bloatware_service::update_record(record, &results, true);
+    settings_service::save_settings(&app, &saved)?;
+
+    Ok(results)
+}
+
+/// Re-enable packages; with none given, everything disabled on the device
+/// through the bloatware manager is restored
+#[tauri::command]
+pub fn restore_bloatware(
+    app: AppHandle,
+    serial: String,
+    packages: Option<Vec<String>>,
+) -> Result<Vec<PackageOpResult>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let mut saved = settings_service::load_settings(&app)?;
+    let record = saved.disabled_packages.entry(serial.clone()).or_default();
+    let packages = packages.unwrap_or_else(|| record.clone());
+    let results = bloatware_service::enable_packages(&adb, &serial, &packages);
+    bloatware_service::update_record(record, &results, false);
+    if record.is_empty() {
+        saved.disabled_packages.remove(&serial);
+    }
+    settings_service::save_settings(&app, &saved)?;
+
+    Ok(results)
+}
@@ -1,30 +1,38 @@
+//! Bluetooth hand-off commands
+//!
+//! These open the built-in Windows Bluetooth UI; other platforms have no
+//! equivalent wizard, so the commands report that instead of failing to spawn.
+//! Sends and receives are recorded in the transfer history.
+
 use crate::domain::errors::AppError;
-use std::process::Command;
+use crate::services::bluetooth_service::{self, Direction};
+use tauri::AppHandle;
 
 /// Opens the Windows Bluetooth settings panel.
 #[tauri::command]
 pub async fn open_bluetooth_settings() -> Result<(), AppError> {
-    Command::new("explorer.exe")
-        .arg("ms-settings:bluetooth")
-        .spawn()
-        .map_err(|e| AppError::IoError(format!("Failed to open Bluetooth settings: {}", e)))?;
+    bluetooth_service::spawn_handoff(
+        "explorer.exe",
+        &["ms-settings:bluetooth"],
+        "Bluetooth settings",
+    )?;
     Ok(())
 }
 
 /// Opens the Windows Bluetooth file send dialog (fsquirt).
 #[tauri::command]
-pub async fn open_bluetooth_send() -> Result<(), AppError> {
-    Command::new("fsquirt.exe")
-        .spawn()
-        .map_err(|e| AppError::IoError(format!("Failed to open Bluetooth send dialog: {}", e)))?;
-    Ok(())
+pub async fn open_bluetooth_send(
+    app: AppHandle,
+    device_name: Option<String>,
+) -> Result<(), AppError> {
+    bluetooth_service::open_wizard(&app, Direction::Send, device_name)
 }
 
 /// Opens the Windows Bluetooth file receive dialog (fsquirt -r).
 #[tauri::command]
-pub async fn open_bluetooth_receive() -> Result<(), AppError> {
-    Command::new("fsquirt.exe").arg("-r").spawn().map_err(|e| {
-        AppError::IoError(format!("Failed to open Bluetooth receive dialog: {}", e))
-    })?;
-    Ok(())
+pub async fn open_bluetooth_receive(
+    app: AppHandle,
+    device_name: Option<String>,
+) -> Result<(), AppError> {
+    bluetooth_service::open_wizard(&app, Direction::Receive, device_name)
 }
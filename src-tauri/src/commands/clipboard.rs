@@ -0,0 +1,29 @@
+//! Clipboard hand-off Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ClipboardSendMode;
+use crate::services::adb_service::AdbCli;
+use crate::services::{clipboard_service, settings_service};
+use tauri::AppHandle;
+
+/// Send the PC clipboard (or `text`, if given) to a device
+/// URLs open on the device; other text opens the share sheet
+#[tauri::command]
+pub fn send_clipboard_text(
+    app: AppHandle,
+    serial: String,
+    text: Option<String>,
+) -> Result<ClipboardSendMode, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let text = match text {
+        Some(text) => text,
+        None => clipboard_service::read_pc_clipboard()?,
+    };
+
+    clipboard_service::send_text(&adb, &serial, &text)
+}
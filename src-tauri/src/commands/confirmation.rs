@@ -0,0 +1,19 @@
+//! Confirmation token Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ConfirmationToken;
+use crate::services::confirmation_service::{self, ConfirmationState};
+use std::time::Instant;
+use tauri::State;
+
+/// Get a one-time token for a destructive command after the user confirmed
+/// it. The token is good for one call of `action` with exactly `args` within
+/// a minute.
+#[tauri::command]
+pub fn request_confirmation(
+    state: State<'_, ConfirmationState>,
+    action: String,
+    args: Vec<String>,
+) -> Result<ConfirmationToken, AppError> {
+    confirmation_service::request(&state, &action, &args, Instant::now())
+}
@@ -0,0 +1,185 @@
+//! Device group Tauri commands: manage groups and run operations on every member
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{DeviceGroup, GroupOpReport, Settings, TransferStatus};
+use crate::services::adb_service::AdbCli;
+use crate::services::confirmation_service::{self, ConfirmationState};
+use crate::services::transfer_service::{self, TransferState};
+use crate::services::{
+    group_service, install_service, power_service, progress_service, screenshot_service,
+    settings_service,
+};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+
+/// List saved device groups
+#[tauri::command]
+pub fn get_device_groups(app: AppHandle) -> Result<Vec<DeviceGroup>, AppError> {
+    Ok(settings_service::load_settings(&app)?.device_groups)
+}
+
+/// Create or update a device group
+#[tauri::command]
+pub fn save_device_group(app: AppHandle, mut group: DeviceGroup) -> Result<DeviceGroup, AppError> {
+    let mut seen = HashSet::new();
+    group
+        .serials
+        .retain(|s| !s.trim().is_empty() && seen.insert(s.clone()));
+    if group.id.is_empty() {
+        group.id = format!("group_{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    let mut settings = settings_service::load_settings(&app)?;
+    match settings.device_groups.iter_mut().find(|g| g.id == group.id) {
+        Some(existing) => *existing = group.clone(),
+        None => settings.device_groups.push(group.clone()),
+    }
+    settings_service::save_settings(&app, &settings)?;
+
+    Ok(group)
+}
+
+/// Delete a device group
+#[tauri::command]
+pub fn delete_device_group(app: AppHandle, id: String) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.device_groups.retain(|g| g.id != id);
+    settings_service::save_settings(&app, &settings)
+}
+
+/// Push files to every device in a group
+#[tauri::command]
+pub fn push_to_group(
+    app: AppHandle,
+    state: State<'_, TransferState>,
+    group_id: String,
+    paths: Vec<String>,
+    dest: Option<String>,
+) -> Result<GroupOpReport, AppError> {
+    let _awake = power_service::keep_awake();
+    let (settings, adb, group) = group_context(&app, &group_id)?;
+    let dest_dir = transfer_service::normalize_device_dir(
+        dest.as_deref().unwrap_or(&settings.default_device_dir),
+    )?;
+    let progress = progress_service::emit_progress(&app);
+
+    Ok(group_service::for_each_device(&group, |serial| {
+        let items = transfer_service::push_files(
+            &state,
+            &adb,
+            serial,
+            &paths,
+            &dest_dir,
+            false,
+            Default::default(),
+            &progress,
+        );
+        let failed: Vec<String> = items
+            .iter()
+            .filter(|i| i.status == TransferStatus::Failed)
+            .map(|i| i.error.clone().unwrap_or_else(|| i.file_name.clone()))
+            .collect();
+
+        if failed.is_empty() {
+            Ok(format!("{} file(s) pushed", items.len()))
+        } else {
+            Err(AppError::TransferError(failed.join("; ")))
+        }
+    }))
+}
+
+/// Install APKs on every device in a group
+#[tauri::command]
+pub fn install_to_group(
+    app: AppHandle,
+    group_id: String,
+    paths: Vec<String>,
+) -> Result<GroupOpReport, AppError> {
+    let (_, adb, group) = group_context(&app, &group_id)?;
+
+    Ok(group_service::for_each_device(&group, |serial| {
+        let failed: Vec<String> = paths
+            .iter()
+            .map(|path| install_service::install_apk(&adb, serial, path))
+            .filter(|r| !r.success)
+            .map(|r| format!("{}: {}", r.path, r.message))
+            .collect();
+
+        if failed.is_empty() {
+            Ok(format!("{} package(s) installed", paths.len()))
+        } else {
+            Err(AppError::AdbExecutionFailed(failed.join("; ")))
+        }
+    }))
+}
+
+/// Run a shell command on every device in a group. The command is given as
+/// separate arguments, each passed literally (no pipes or redirects). It can
+/// delete or uninstall anything, so it needs a token from `request_confirmation`.
+#[tauri::command]
+pub fn shell_on_group(
+    app: AppHandle,
+    confirmations: State<'_, ConfirmationState>,
+    group_id: String,
+    args: Vec<String>,
+    confirmation_token: Option<String>,
+) -> Result<GroupOpReport, AppError> {
+    let (_, adb, group) = group_context(&app, &group_id)?;
+
+    let confirmed: Vec<&str> = std::iter::once(group_id.as_str())
+        .chain(args.iter().map(String::as_str))
+        .collect();
+    confirmation_service::consume(
+        &confirmations,
+        "shell_on_group",
+        &confirmed,
+        confirmation_token.as_deref(),
+        Instant::now(),
+    )?;
+
+    Ok(group_service::for_each_device(&group, |serial| {
+        group_service::run_shell_args(&adb, serial, &args)
+    }))
+}
+
+/// Take a screenshot on every device in a group; each result holds the saved file path
+#[tauri::command]
+pub fn screenshot_group(
+    app: AppHandle,
+    group_id: String,
+    dest: Option<String>,
+) -> Result<GroupOpReport, AppError> {
+    let (_, adb, group) = group_context(&app, &group_id)?;
+
+    let dest_dir = match dest {
+        Some(d) => PathBuf::from(d),
+        None => dirs::download_dir()
+            .ok_or_else(|| AppError::IoError("Could not determine Downloads folder".to_string()))?,
+    };
+
+    Ok(group_service::for_each_device(&group, |serial| {
+        screenshot_service::capture(&adb, serial, &dest_dir)
+    }))
+}
+
+fn group_context(
+    app: &AppHandle,
+    group_id: &str,
+) -> Result<(Settings, AdbCli, DeviceGroup), AppError> {
+    let settings = settings_service::get_settings_with_detection(app)?;
+
+    let group = settings
+        .device_groups
+        .iter()
+        .find(|g| g.id == group_id)
+        .cloned()
+        .ok_or_else(|| AppError::SettingsError(format!("Device group not found: {}", group_id)))?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    Ok((settings, adb, group))
+}
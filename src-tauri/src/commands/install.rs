@@ -0,0 +1,146 @@
+//! Package install Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    ApkPreview, ApkUpdate, AppList, BulkInstallReport, DropRouteReport, InstallResult,
+};
+use crate::services::adb_service::AdbCli;
+use crate::services::transfer_service::TransferState;
+use crate::services::{
+    apk_service, app_icon_service, app_list_service, drop_service, install_service,
+    progress_service, settings_service,
+};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+/// Install APKs on the given devices (or the only connected one when empty)
+#[tauri::command]
+pub fn install_apks(
+    app: AppHandle,
+    serials: Vec<String>,
+    paths: Vec<String>,
+) -> Result<Vec<InstallResult>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let serials = install_service::resolve_targets(&adb, &serials)?;
+
+    Ok(serials
+        .iter()
+        .flat_map(|serial| {
+            paths
+                .iter()
+                .map(|path| install_service::install_apk(&adb, serial, path))
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+/// Handle a drag-and-drop: APKs are installed, OBBs copied to their package
+/// folder and everything else pushed, on every target device
+/// With several devices connected, `serials` must name the targets
+#[tauri::command]
+pub fn route_drop(
+    app: AppHandle,
+    state: State<'_, TransferState>,
+    serials: Vec<String>,
+    paths: Vec<String>,
+    dest: Option<String>,
+) -> Result<DropRouteReport, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let serials = install_service::resolve_targets(&adb, &serials)?;
+    let analysis = drop_service::analyze_drop(&paths);
+    let dest_dir = dest.unwrap_or(settings.default_device_dir);
+
+    Ok(install_service::route_drop(
+        &state,
+        &adb,
+        &serials,
+        &analysis,
+        &dest_dir,
+        &progress_service::emit_progress(&app),
+    ))
+}
+
+/// Save a device's third-party apps (package, version, installer) as JSON
+#[tauri::command]
+pub fn export_app_list(app: AppHandle, serial: String, path: String) -> Result<AppList, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let list = app_list_service::list_apps(&adb, &serial)?;
+    app_list_service::export_app_list(&list, &PathBuf::from(path))?;
+    Ok(list)
+}
+
+/// Install the apps of an exported list from a folder of APKs
+#[tauri::command]
+pub fn bulk_install_from_list(
+    app: AppHandle,
+    serial: String,
+    path: String,
+    apk_dir: String,
+) -> Result<BulkInstallReport, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let list = app_list_service::read_app_list(&PathBuf::from(path))?;
+    app_list_service::bulk_install(&adb, &serial, &list, &PathBuf::from(apk_dir))
+}
+
+/// Installed apps with a newer APK in the local APK repository folder
+#[tauri::command]
+pub fn check_apk_updates(app: AppHandle, serial: String) -> Result<Vec<ApkUpdate>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let repository_dir = settings.apk_repository_dir.clone().ok_or_else(|| {
+        AppError::InvalidPath("No local APK repository folder set in Settings.".to_string())
+    })?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    app_list_service::check_updates(&adb, &serial, &PathBuf::from(repository_dir))
+}
+
+/// Package, version, SDK levels and icon of a local APK, read without aapt
+#[tauri::command]
+pub fn inspect_apk(path: String) -> Result<ApkPreview, AppError> {
+    apk_service::inspect_apk(&PathBuf::from(path))
+}
+
+/// Small launcher icon of an installed app as a PNG data URL, cached with
+/// the media thumbnails
+#[tauri::command]
+pub fn get_app_icon(app: AppHandle, serial: String, package: String) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| AppError::IoError(format!("Failed to get cache dir: {}", e)))?;
+
+    let thumb_dir = cache_dir.join("thumbnails");
+    std::fs::create_dir_all(&thumb_dir)?;
+
+    app_icon_service::get_app_icon(&adb, &serial, &package, &thumb_dir)
+}
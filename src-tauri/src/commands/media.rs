@@ -1,11 +1,23 @@
 //! Media-related Tauri commands for browsing and transferring media from devices
 
 use crate::domain::errors::AppError;
-use crate::domain::models::{FolderInfo, MediaFilter, MediaItem, MediaTransferResult};
-use crate::services::{media_service, settings_service};
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
-use tauri_plugin_opener::OpenerExt;
+use crate::domain::models::{
+    CameraOffloadPlan, ConflictPolicy, DestructiveOpReport, DeviceEventKind, FolderInfo,
+    MediaFilter, MediaGroup, MediaItem, MediaLocation, MediaSelection, MediaTransferResult,
+    ProbeResult, PulledFile, Settings,
+};
+use crate::services::adb_service::{self, AdbCli};
+use crate::services::confirmation_service::{self, ConfirmationState};
+use crate::services::preview_cache_service::{self, PreviewCacheState};
+use crate::services::{
+    destination_service, exif_service, media_service, motion_photo_service, offload_service,
+    photo_group_service, power_service, privacy_service, probe_service, progress_service,
+    pull_history_service, settings_service, shell_integration_service, storage_service,
+    thumbnail_cache_service, thumbnail_warmup_service, timeline_service,
+};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
 
 /// List folders on the device at the specified path
 #[tauri::command]
@@ -13,14 +25,18 @@ pub fn list_device_folders(
     app: AppHandle,
     serial: String,
     path: Option<String>,
+    include_private: Option<bool>,
 ) -> Result<Vec<FolderInfo>, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
     })?;
 
-    media_service::list_folders(&adb_path, &serial, path.as_deref())
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+    let mut folders = media_service::list_folders(&adb, &serial, path.as_deref())?;
+    folders.retain(|f| !privacy_service::is_private(&f.path, hidden));
+    Ok(folders)
 }
 
 /// Get the best default root folder for media browsing
@@ -29,28 +45,52 @@ pub fn list_device_folders(
 pub fn get_default_media_root(app: AppHandle, serial: String) -> Result<String, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
     })?;
 
-    media_service::get_default_media_root(&adb_path, &serial)
+    media_service::get_default_media_root(&adb, &serial)
 }
 
-/// List media files in a folder on the device
+/// List media files in a folder on the device. With thumbnail warm-up on,
+/// the first items' thumbnails are then made in the background.
 #[tauri::command]
 pub fn list_device_media(
     app: AppHandle,
     serial: String,
     path: String,
     filter: Option<MediaFilter>,
+    include_private: Option<bool>,
 ) -> Result<Vec<MediaItem>, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
     })?;
 
-    media_service::list_media_files(&adb_path, &serial, &path, filter.unwrap_or_default())
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+    let mut items =
+        media_service::list_media_files(&adb, &serial, &path, filter.unwrap_or_default())?;
+    items.retain(|item| !privacy_service::is_private(&item.path, hidden));
+
+    if settings.thumbnail_warmup_count > 0 {
+        let thumb_dir = thumbnail_dir(&app)?;
+        let paths = thumbnail_warmup_service::warmup_targets(
+            &items,
+            settings.thumbnail_warmup_count,
+            &thumb_dir,
+            &serial,
+        );
+        thumbnail_warmup_service::start(
+            app.clone(),
+            adb.background(),
+            settings.ffmpeg_resolved_path.clone(),
+            serial,
+            paths,
+            thumb_dir,
+        );
+    }
+    Ok(items)
 }
 
 /// Get thumbnail for a media file
@@ -63,11 +103,66 @@ pub fn get_media_thumbnail(
 ) -> Result<String, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
-        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
-    })?;
+    // The grid asks for many thumbnails at once; they queue behind user actions
+    let adb = AdbCli::from_settings(&settings)
+        .map(AdbCli::background)
+        .ok_or_else(|| {
+            AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+        })?;
+
+    let thumb_dir = thumbnail_dir(&app)?;
 
-    // Use app cache directory for thumbnails
+    media_service::get_thumbnail(
+        &adb,
+        settings.ffmpeg_resolved_path.as_ref(),
+        &serial,
+        &path,
+        &thumb_dir,
+    )
+}
+
+/// Bursts and near-identical shots in a device folder, so only the best of
+/// each needs pulling. `find_similar` also compares thumbnails, which fetches
+/// one per photo not in a burst.
+#[tauri::command]
+pub fn group_similar_photos(
+    app: AppHandle,
+    serial: String,
+    path: String,
+    find_similar: Option<bool>,
+) -> Result<Vec<MediaGroup>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    // Thumbnail fetches queue behind user actions, like the grid's
+    let adb = AdbCli::from_settings(&settings)
+        .map(AdbCli::background)
+        .ok_or_else(|| {
+            AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+        })?;
+
+    if !find_similar.unwrap_or(false) {
+        return photo_group_service::group_photos(&adb, &serial, &path, None);
+    }
+
+    let thumb_dir = thumbnail_dir(&app)?;
+    let hash = |remote_path: &str| {
+        media_service::get_thumbnail(
+            &adb,
+            settings.ffmpeg_resolved_path.as_ref(),
+            &serial,
+            remote_path,
+            &thumb_dir,
+        )
+        .ok()?;
+        let (thumb_path, _) =
+            thumbnail_cache_service::cached_thumbnail(&thumb_dir, &serial, remote_path)?;
+        photo_group_service::image_hash(&thumb_path)
+    };
+    photo_group_service::group_photos(&adb, &serial, &path, Some(&hash))
+}
+
+/// Thumbnail cache in the app cache directory
+fn thumbnail_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
     let cache_dir = app
         .path()
         .app_cache_dir()
@@ -75,54 +170,335 @@ pub fn get_media_thumbnail(
 
     let thumb_dir = cache_dir.join("thumbnails");
     std::fs::create_dir_all(&thumb_dir)?;
+    Ok(thumb_dir)
+}
 
-    media_service::get_thumbnail(
-        &adb_path,
-        settings.ffmpeg_resolved_path.as_ref(),
+/// Pull media files from device to a local destination. Files in private
+/// folders are reported as skipped unless `include_private` is set.
+#[tauri::command]
+pub fn pull_media_files(
+    app: AppHandle,
+    serial: String,
+    paths: Vec<String>,
+    dest: Option<String>,
+    conflict: Option<ConflictPolicy>,
+    extract_motion: Option<bool>,
+    include_private: Option<bool>,
+) -> Result<Vec<MediaTransferResult>, AppError> {
+    let _awake = power_service::keep_awake();
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let dest_path = pull_destination(&settings, &serial, dest, || {
+        adb_service::get_model(&adb, &serial)
+    })?;
+
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+    let (private, paths): (Vec<String>, Vec<String>) = paths
+        .into_iter()
+        .partition(|p| privacy_service::is_private(p, hidden));
+
+    // Files already previewed are copied from the preview cache
+    let previews = app.state::<PreviewCacheState>();
+    let mut results = media_service::pull_media_files_batch(
+        &adb,
         &serial,
-        &path,
-        &thumb_dir,
-    )
+        &paths,
+        &dest_path,
+        conflict.unwrap_or_default(),
+        &|file| preview_cache_service::cached_copy(&previews, &serial, &file.path, file.size_bytes),
+        &progress_service::emit_progress(&app),
+    )?;
+    results.extend(private.iter().map(|p| privacy_service::skipped_pull(p)));
+
+    if extract_motion.unwrap_or(false) {
+        motion_photo_service::extract_pulled(&mut results);
+    }
+
+    finish_pull(&app, &serial, &results, &dest_path)?;
+    Ok(results)
 }
 
-/// Pull media files from device to a local destination
+/// Which of the given device photos are motion photos, by their contents
 #[tauri::command]
-pub fn pull_media_files(
+pub fn detect_motion_photos(
     app: AppHandle,
     serial: String,
     paths: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    motion_photo_service::detect(&adb, &serial, &paths)
+}
+
+/// Pull a whole device folder as one batch, into `dest/<folder name>` with
+/// its subfolders. `filter` limits it to media of that kind; without one
+/// every file is pulled.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn pull_folder(
+    app: AppHandle,
+    serial: String,
+    remote_dir: String,
     dest: Option<String>,
+    filter: Option<MediaFilter>,
+    recursive: Option<bool>,
+    conflict: Option<ConflictPolicy>,
+    include_private: Option<bool>,
 ) -> Result<Vec<MediaTransferResult>, AppError> {
+    let _awake = power_service::keep_awake();
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
     })?;
 
-    // Determine destination directory
-    let dest_path = match dest {
+    let dest_path = pull_destination(&settings, &serial, dest, || {
+        adb_service::get_model(&adb, &serial)
+    })?;
+
+    let results = media_service::pull_folder(
+        &adb,
+        &serial,
+        &remote_dir,
+        &dest_path,
+        recursive.unwrap_or(true),
+        filter,
+        privacy_service::hidden(&settings.private_folders, include_private),
+        conflict.unwrap_or_default(),
+        &progress_service::emit_progress(&app),
+    )?;
+
+    finish_pull(&app, &serial, &results, &dest_path)?;
+    Ok(results)
+}
+
+/// Pull part of a folder picked by rule instead of by hand: the newest N
+/// items, items since a time, or items since the last pull
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn pull_media_selection(
+    app: AppHandle,
+    serial: String,
+    path: String,
+    selection: MediaSelection,
+    filter: Option<MediaFilter>,
+    dest: Option<String>,
+    conflict: Option<ConflictPolicy>,
+    include_private: Option<bool>,
+) -> Result<Vec<MediaTransferResult>, AppError> {
+    let _awake = power_service::keep_awake();
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+    let mut items =
+        media_service::list_media_files(&adb, &serial, &path, filter.unwrap_or_default())?;
+    items.retain(|item| !privacy_service::is_private(&item.path, hidden));
+    let pulled = if selection.since_last_pull {
+        pull_history_service::device_history(&app, &serial)?
+    } else {
+        Default::default()
+    };
+    let files: Vec<_> = media_service::select_media(items, &selection, &pulled)?
+        .into_iter()
+        .map(|item| media_service::RemoteFile {
+            path: item.path,
+            size_bytes: item.size_bytes,
+        })
+        .collect();
+
+    let dest_path = pull_destination(&settings, &serial, dest, || {
+        adb_service::get_model(&adb, &serial)
+    })?;
+
+    let previews = app.state::<PreviewCacheState>();
+    let results = media_service::pull_files(
+        &adb,
+        &serial,
+        &files,
+        |_| dest_path.clone(),
+        conflict.unwrap_or_default(),
+        &|file| preview_cache_service::cached_copy(&previews, &serial, &file.path, file.size_bytes),
+        &progress_service::emit_progress(&app),
+    )?;
+
+    finish_pull(&app, &serial, &results, &dest_path)?;
+    Ok(results)
+}
+
+/// Where the JPEGs in a device folder were taken, for a map view; photos
+/// without GPS tags are left out. Videos report theirs through `probe_media_file`.
+#[tauri::command]
+pub fn get_media_locations(
+    app: AppHandle,
+    serial: String,
+    folder: String,
+) -> Result<Vec<MediaLocation>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    exif_service::get_locations(&adb, &serial, &folder)
+}
+
+/// Plan a camera offload: DCIM media on every volume by month, with what's
+/// already on this computer (pull history, or a same-size copy in the last
+/// pull folder) left out of the pending paths. Private folders are skipped
+/// unless `include_private` is set.
+#[tauri::command]
+pub fn plan_camera_offload(
+    app: AppHandle,
+    serial: String,
+    include_private: Option<bool>,
+) -> Result<CameraOffloadPlan, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let history = pull_history_service::device_history(&app, &serial)?;
+    let last_dest = settings
+        .device_registry
+        .get(&serial)
+        .and_then(|r| r.last_local_dest.as_ref())
+        .map(PathBuf::from);
+
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+    offload_service::plan_camera_offload(&adb, &serial, hidden, |file| {
+        pull_history_service::already_pulled(&history, &file.path, file.size_bytes)
+            || last_dest.as_deref().is_some_and(|dir| {
+                offload_service::copy_in_folder(dir, &file.path, file.size_bytes)
+            })
+    })
+}
+
+/// Destination directory: explicit, last used for this device, or
+/// Downloads (in a folder named after the device unless turned off).
+/// `model` is only asked when the device folder is needed.
+fn pull_destination(
+    settings: &Settings,
+    serial: &str,
+    dest: Option<String>,
+    model: impl FnOnce() -> Option<String>,
+) -> Result<PathBuf, AppError> {
+    let record = settings.device_registry.get(serial);
+    let remembered = record.and_then(|r| r.last_local_dest.clone());
+    let dest_path = match dest.or(remembered) {
         Some(d) => PathBuf::from(d),
         None => {
             // Default to user's Downloads folder
-            dirs::download_dir().ok_or_else(|| {
+            let downloads = dirs::download_dir().ok_or_else(|| {
                 AppError::IoError("Could not determine Downloads folder".to_string())
-            })?
+            })?;
+            if settings.device_pull_folders {
+                let nickname = record.and_then(|r| r.nickname.as_deref());
+                let name =
+                    destination_service::device_folder_name(nickname, model().as_deref(), serial);
+                destination_service::device_pull_dir(&downloads, &name)
+            } else {
+                downloads
+            }
         }
     };
 
     // Ensure destination exists
     std::fs::create_dir_all(&dest_path)?;
+    Ok(dest_path)
+}
 
-    let results = media_service::pull_media_files_batch(&adb_path, &serial, &paths, &dest_path);
-    Ok(results)
+/// Record a finished pull in the pull history and timeline and remember the destination
+fn finish_pull(
+    app: &AppHandle,
+    serial: &str,
+    results: &[MediaTransferResult],
+    dest_path: &Path,
+) -> Result<(), AppError> {
+    pull_history_service::record_pulls(app, serial, results);
+
+    let pulled = results.iter().filter(|r| r.success).count();
+    if pulled > 0 {
+        timeline_service::record(
+            app,
+            serial,
+            DeviceEventKind::FilesPulled,
+            Some(timeline_service::files_detail(
+                pulled,
+                results.len(),
+                &dest_path.to_string_lossy(),
+            )),
+        );
+    }
+
+    settings_service::update_device_record(app, serial, |record| {
+        record.last_local_dest = Some(dest_path.to_string_lossy().to_string())
+    })?;
+    destination_service::record_used(app, dest_path)
 }
 
-/// Preview a media file by pulling it to temp and returning the local path
+/// Delete media files from the device
+/// With `dry_run`, returns exactly what would be deleted (files, byte total) without deleting;
+/// otherwise a token from `request_confirmation` is needed
+#[tauri::command]
+pub fn delete_media_files(
+    app: AppHandle,
+    confirmations: State<'_, ConfirmationState>,
+    serial: String,
+    paths: Vec<String>,
+    dry_run: bool,
+    confirmation_token: Option<String>,
+) -> Result<DestructiveOpReport, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    if !dry_run {
+        let args: Vec<&str> = std::iter::once(serial.as_str())
+            .chain(paths.iter().map(String::as_str))
+            .collect();
+        confirmation_service::consume(
+            &confirmations,
+            "delete_media_files",
+            &args,
+            confirmation_token.as_deref(),
+            Instant::now(),
+        )?;
+    }
+
+    Ok(media_service::delete_media_files(
+        &adb, &serial, &paths, dry_run,
+    ))
+}
+
+/// Files pulled from a device before, most recent first. Pulling an entry's
+/// `remote_path` into the folder of its `local_path` downloads it again.
+#[tauri::command]
+pub fn get_pull_history(app: AppHandle, serial: String) -> Result<Vec<PulledFile>, AppError> {
+    pull_history_service::get_history(&app, &serial)
+}
+
+/// Preview a media file by pulling it to temp and returning the local path.
+/// The copy is kept so pulling the file later doesn't download it again.
 #[tauri::command]
 pub fn preview_media(app: AppHandle, serial: String, path: String) -> Result<String, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
     })?;
 
@@ -135,7 +511,17 @@ pub fn preview_media(app: AppHandle, serial: String, path: String) -> Result<Str
     let preview_dir = cache_dir.join("previews");
     std::fs::create_dir_all(&preview_dir)?;
 
-    let local_path = media_service::pull_media_file(&adb_path, &serial, &path, &preview_dir)?;
+    let cache_path = preview_cache_service::cache_path(&preview_dir, &serial, &path)?;
+    if let Some(parent) = cache_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    storage_service::ensure_space(
+        &preview_dir,
+        media_service::get_file_size(&adb, &serial, &path).unwrap_or(0),
+    )?;
+    let local_path = media_service::pull_to(&adb, &serial, &path, &cache_path)?;
+    let previews = app.state::<PreviewCacheState>();
+    preview_cache_service::record(&previews, &serial, &path, &cache_path);
 
     // Check if it's an image
     let path_buf = std::path::PathBuf::from(&local_path);
@@ -156,12 +542,67 @@ pub fn preview_media(app: AppHandle, serial: String, path: String) -> Result<Str
     }
 }
 
+/// Keep a previewed file: move it from the preview cache into `dest` (or the
+/// last folder used for this device) under its device name, without pulling
+/// it again. Fails if the file hasn't been previewed.
+#[tauri::command]
+pub fn save_preview_as(
+    app: AppHandle,
+    serial: String,
+    path: String,
+    dest: Option<String>,
+    conflict: Option<ConflictPolicy>,
+) -> Result<MediaTransferResult, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    let dest_path = pull_destination(&settings, &serial, dest, || {
+        let adb = AdbCli::from_settings(&settings)?;
+        adb_service::get_model(&adb, &serial)
+    })?;
+
+    let previews = app.state::<PreviewCacheState>();
+    let result = preview_cache_service::save_as(
+        &previews,
+        &serial,
+        &path,
+        &dest_path,
+        conflict.unwrap_or_default(),
+    )?;
+
+    finish_pull(&app, &serial, std::slice::from_ref(&result), &dest_path)?;
+    Ok(result)
+}
+
 /// Open the media file in the system default application (External Player)
 #[tauri::command]
 pub fn open_media_folder(app: AppHandle, path: String) -> Result<(), AppError> {
-    app.opener()
-        .open_path(&path, None::<String>)
-        .map_err(|e| AppError::IoError(format!("Failed to open file: {}", e)))?;
+    shell_integration_service::open(&app, &path, None)
+}
+
+/// Open a pulled file with a chosen app (an app name or program path), or
+/// the default one when none is given
+#[tauri::command]
+pub fn open_media_file_with(
+    app: AppHandle,
+    path: String,
+    with_app: Option<String>,
+) -> Result<(), AppError> {
+    shell_integration_service::open(&app, &path, with_app.as_deref())
+}
+
+/// Show a pulled file selected in its folder in the system file manager
+#[tauri::command]
+pub fn reveal_media_file(app: AppHandle, path: String) -> Result<(), AppError> {
+    shell_integration_service::reveal(&app, &path)
+}
+
+/// Read media metadata (duration, resolution, codecs) of a local file with ffprobe
+#[tauri::command]
+pub fn probe_media_file(app: AppHandle, path: String) -> Result<ProbeResult, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let ffprobe_path = settings.ffprobe_resolved_path.ok_or_else(|| {
+        AppError::FfprobeNotFound("ffprobe not found. Configure it in Settings.".to_string())
+    })?;
 
-    Ok(())
+    probe_service::probe_file(&ffprobe_path, &PathBuf::from(path))
 }
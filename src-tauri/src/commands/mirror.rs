@@ -1,38 +1,222 @@
 //! Mirror-related Tauri commands
 
 use crate::domain::errors::AppError;
-use crate::domain::models::MirrorSession;
-use crate::services::{scrcpy_service, settings_service};
-use tauri::AppHandle;
+use crate::domain::models::{
+    CameraOptions, DeviceEventKind, DisplaySize, LinkQuality, MirrorOptions, MirrorSession,
+    RecordingKind, Settings,
+};
+use crate::services::adb_service::{AdbCli, AdbRunner};
+use crate::services::scrcpy_service::MirrorState;
+use crate::services::{
+    kiosk_service, link_service, notification_service, recording_service, scrcpy_service,
+    screenshot_service, settings_service, storage_service, timeline_service,
+};
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
 
 /// Start a screen mirror session for a device
+/// Uses the mirror options from Settings when none are given
 #[tauri::command]
 pub fn start_mirror(
     app: AppHandle,
+    state: State<'_, MirrorState>,
     serial: String,
     screen_off: bool,
+    options: Option<MirrorOptions>,
 ) -> Result<MirrorSession, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
+    let options = options.unwrap_or_else(|| settings.mirror_options.clone());
 
-    let scrcpy_path = settings.scrcpy_resolved_path.ok_or_else(|| {
+    launch_mirror(&app, &settings, &state, &serial, screen_off, &options)
+}
+
+/// Start a "headless phone" mirror: the device screen stays off and awake
+/// while it is operated from the PC, and powers off when the window is closed
+#[tauri::command]
+pub fn start_headless_mirror(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+) -> Result<MirrorSession, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    let options = scrcpy_service::headless_options(&settings.mirror_options, &serial);
+
+    launch_mirror(&app, &settings, &state, &serial, true, &options)
+}
+
+/// Kiosk/demo mode: launch an app, pin it with screen pinning and mirror
+/// the device fullscreen
+#[tauri::command]
+pub fn start_kiosk(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+    package: String,
+) -> Result<MirrorSession, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    kiosk_service::pin_app(&adb, &serial, &package)?;
+
+    let options = kiosk_service::kiosk_options(&settings.mirror_options);
+    launch_mirror(&app, &settings, &state, &serial, false, &options).inspect_err(|_| {
+        let _ = kiosk_service::unpin(&adb, &serial);
+    })
+}
+
+/// Leave kiosk mode: stop the mirror and unpin the app
+#[tauri::command]
+pub fn stop_kiosk(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+) -> Result<(), AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    // The mirror may already have been closed from its window
+    if let Ok(session) = scrcpy_service::stop_mirror(&state, Some(&adb), &serial) {
+        finish_recording(Some(&settings), &session);
+        timeline_service::record(&app, &serial, DeviceEventKind::MirrorStopped, None);
+    }
+    kiosk_service::unpin(&adb, &serial)?;
+    Ok(())
+}
+
+fn launch_mirror(
+    app: &AppHandle,
+    settings: &Settings,
+    state: &MirrorState,
+    serial: &str,
+    screen_off: bool,
+    options: &MirrorOptions,
+) -> Result<MirrorSession, AppError> {
+    let scrcpy_path = settings.scrcpy_resolved_path.clone().ok_or_else(|| {
         AppError::ScrcpyNotFound(
             "scrcpy not found. Install scrcpy or set the path in Settings.".to_string(),
         )
     })?;
 
-    scrcpy_service::start_mirror(&scrcpy_path, &serial, screen_off)
+    let adb = AdbCli::from_settings(settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let read_only;
+    let options = if settings.read_only_mode {
+        read_only = scrcpy_service::read_only_options(options);
+        &read_only
+    } else {
+        options
+    };
+    let record_to = recording_path(options.record, RecordingKind::Screen, serial)?;
+
+    let session = scrcpy_service::start_mirror(
+        state,
+        &scrcpy_path,
+        &adb,
+        serial,
+        screen_off,
+        options,
+        record_to.as_deref(),
+    )?;
+
+    // Best effort: older devices have no `cmd notification`
+    if settings.notify_on_mirror {
+        let _ = notification_service::post_notification(
+            &adb,
+            serial,
+            notification_service::MIRROR_STARTED_TEXT,
+        );
+    }
+
+    timeline_service::record(app, serial, DeviceEventKind::MirrorStarted, None);
+    Ok(session)
 }
 
 /// Stop a screen mirror session for a device
 #[tauri::command]
-pub fn stop_mirror(serial: String) -> Result<(), AppError> {
-    scrcpy_service::stop_mirror(&serial)
+pub fn stop_mirror(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+) -> Result<(), AppError> {
+    // Restoring device settings is best effort; stopping must work regardless
+    let settings = settings_service::get_settings_with_detection(&app).ok();
+    let adb = settings.as_ref().and_then(AdbCli::from_settings);
+
+    let session =
+        scrcpy_service::stop_mirror(&state, adb.as_ref().map(|a| a as &dyn AdbRunner), &serial)?;
+    finish_recording(settings.as_ref(), &session);
+    timeline_service::record(&app, &serial, DeviceEventKind::MirrorStopped, None);
+    Ok(())
 }
 
 /// Get all active screen mirror sessions
 #[tauri::command]
-pub fn get_mirror_sessions() -> Vec<MirrorSession> {
-    scrcpy_service::get_active_sessions()
+pub fn get_mirror_sessions(state: State<'_, MirrorState>) -> Vec<MirrorSession> {
+    scrcpy_service::get_active_sessions(&state)
+}
+
+/// Save a frame of an active mirror session as a PNG and return its path.
+/// Saves to Downloads unless `dest` is given.
+#[tauri::command]
+pub fn capture_mirror_frame(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+    dest: Option<String>,
+) -> Result<String, AppError> {
+    if !scrcpy_service::is_mirroring(&state, &serial) {
+        return Err(AppError::MirrorError(format!(
+            "No active mirror session for device {}",
+            serial
+        )));
+    }
+
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let dest_dir = match dest {
+        Some(d) => PathBuf::from(d),
+        None => dirs::download_dir()
+            .ok_or_else(|| AppError::IoError("Could not determine Downloads folder".to_string()))?,
+    };
+
+    // screencap reads the device framebuffer, which is what scrcpy is showing
+    screenshot_service::capture(&adb, &serial, &dest_dir)
+}
+
+/// Get a device's display resolution, for choosing a crop region
+#[tauri::command]
+pub fn get_display_size(app: AppHandle, serial: String) -> Result<DisplaySize, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    scrcpy_service::get_display_size(&adb, &serial)
+}
+
+/// Measure a wireless device's link to see how well it will mirror
+#[tauri::command]
+pub fn measure_wifi_link(app: AppHandle, serial: String) -> Result<LinkQuality, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    link_service::measure_link(&adb, &serial)
 }
 
 // ============================================
@@ -40,41 +224,155 @@ pub fn get_mirror_sessions() -> Vec<MirrorSession> {
 // ============================================
 
 /// Start a camera mirror session for a device
+/// Falls back to the options last used for this device when none are given
 #[tauri::command]
 pub fn start_camera(
     app: AppHandle,
+    state: State<'_, MirrorState>,
     serial: String,
-    facing: String,      // "front" or "back"
-    resolution: String,  // e.g., "1920x1080"
-    no_audio: bool,      // disable audio forwarding
-    orientation: String, // "portrait" or "landscape"
+    options: Option<CameraOptions>,
 ) -> Result<MirrorSession, AppError> {
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let scrcpy_path = settings.scrcpy_resolved_path.ok_or_else(|| {
+    let scrcpy_path = settings.scrcpy_resolved_path.clone().ok_or_else(|| {
         AppError::ScrcpyNotFound(
             "scrcpy not found. Install scrcpy or set the path in Settings.".to_string(),
         )
     })?;
 
-    scrcpy_service::start_camera_mirror(
+    let options = options.unwrap_or_else(|| {
+        settings
+            .camera_defaults
+            .get(&serial)
+            .cloned()
+            .unwrap_or_default()
+    });
+
+    let record_to = recording_path(options.record, RecordingKind::Camera, &serial)?;
+    let session = scrcpy_service::start_camera_mirror(
+        &state,
         &scrcpy_path,
         &serial,
-        &facing,
-        &resolution,
-        no_audio,
-        &orientation,
-    )
+        &options,
+        record_to.as_deref(),
+    )?;
+
+    // Remember these options as the device's defaults for next time
+    if settings.camera_defaults.get(&serial) != Some(&options) {
+        let mut saved = settings_service::load_settings(&app)?;
+        saved.camera_defaults.insert(serial, options);
+        settings_service::save_settings(&app, &saved)?;
+    }
+
+    Ok(session)
+}
+
+/// Get the persisted camera options for a device (or defaults)
+#[tauri::command]
+pub fn get_camera_options(app: AppHandle, serial: String) -> Result<CameraOptions, AppError> {
+    let settings = settings_service::load_settings(&app)?;
+    Ok(settings
+        .camera_defaults
+        .get(&serial)
+        .cloned()
+        .unwrap_or_default())
 }
 
 /// Stop a camera mirror session for a device
 #[tauri::command]
-pub fn stop_camera(serial: String) -> Result<(), AppError> {
-    scrcpy_service::stop_camera_mirror(&serial)
+pub fn stop_camera(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+) -> Result<(), AppError> {
+    let session = scrcpy_service::stop_camera_mirror(&state, &serial)?;
+    let settings = settings_service::get_settings_with_detection(&app).ok();
+    finish_recording(settings.as_ref(), &session);
+    Ok(())
 }
 
 /// Get all active camera sessions
 #[tauri::command]
-pub fn get_camera_sessions() -> Vec<MirrorSession> {
-    scrcpy_service::get_camera_sessions()
+pub fn get_camera_sessions(state: State<'_, MirrorState>) -> Vec<MirrorSession> {
+    scrcpy_service::get_camera_sessions(&state)
+}
+
+// ============================================
+// Audio Capture Commands
+// ============================================
+
+/// Start recording a device's microphone to the PC.
+/// Saves to the recordings folder unless a `dest` folder is given.
+#[tauri::command]
+pub fn start_audio_capture(
+    app: AppHandle,
+    state: State<'_, MirrorState>,
+    serial: String,
+    dest: Option<String>,
+) -> Result<MirrorSession, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let scrcpy_path = settings.scrcpy_resolved_path.ok_or_else(|| {
+        AppError::ScrcpyNotFound(
+            "scrcpy not found. Install scrcpy or set the path in Settings.".to_string(),
+        )
+    })?;
+
+    let dest_dir = match dest {
+        Some(d) => PathBuf::from(d),
+        None => recording_service::recordings_dir()?,
+    };
+    storage_service::ensure_space(&dest_dir, storage_service::RECORDING_RESERVE)?;
+    let record_to = recording_service::new_recording_path(&dest_dir, RecordingKind::Audio, &serial);
+
+    scrcpy_service::start_audio_capture(&state, &scrcpy_path, &serial, &record_to)
+}
+
+/// Stop recording a device's microphone
+#[tauri::command]
+pub fn stop_audio_capture(state: State<'_, MirrorState>, serial: String) -> Result<(), AppError> {
+    scrcpy_service::stop_audio_capture(&state, &serial)?;
+    Ok(())
+}
+
+/// Get all active audio captures
+#[tauri::command]
+pub fn get_audio_sessions(state: State<'_, MirrorState>) -> Vec<MirrorSession> {
+    scrcpy_service::get_audio_sessions(&state)
+}
+
+/// New file in the recordings folder when recording was requested
+fn recording_path(
+    record: bool,
+    kind: RecordingKind,
+    serial: &str,
+) -> Result<Option<PathBuf>, AppError> {
+    if !record {
+        return Ok(None);
+    }
+    let dir = recording_service::recordings_dir()?;
+    storage_service::ensure_space(&dir, storage_service::RECORDING_RESERVE)?;
+    Ok(Some(recording_service::new_recording_path(
+        &dir, kind, serial,
+    )))
+}
+
+/// Remux a stopped session's recording to MP4 in the background when enabled
+fn finish_recording(settings: Option<&Settings>, session: &MirrorSession) {
+    let (Some(settings), Some(path)) = (settings, session.recording_path.clone()) else {
+        return;
+    };
+    let Some(ffmpeg_path) = settings.ffmpeg_resolved_path.clone() else {
+        return;
+    };
+    if !settings.remux_recordings {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        // Without a remux the recording stays playable as .mkv
+        let _ = recording_service::recordings_dir().and_then(|dir| {
+            recording_service::remux_to_mp4(&ffmpeg_path, &dir, &PathBuf::from(&path))
+        });
+    });
 }
@@ -1,6 +1,22 @@
 pub mod adb;
+pub mod apps;
+pub mod bloatware;
 pub mod bluetooth;
+pub mod clipboard;
+pub mod confirmation;
+pub mod group;
+pub mod install;
 pub mod media;
 pub mod mirror;
+pub mod mtp;
+pub mod onboarding;
+pub mod perf;
+pub mod provisioning;
+pub mod quick_share;
+pub mod recording;
 pub mod settings;
+pub mod sync;
 pub mod transfer;
+pub mod troubleshoot;
+pub mod update;
+pub mod watch;
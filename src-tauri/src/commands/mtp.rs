@@ -0,0 +1,84 @@
+//! Read-only browsing of devices without USB debugging, over MTP
+//!
+//! Windows only; the same folder and media models as the adb browser are
+//! used, with paths as Explorer shows them. Private folders are hidden the
+//! same way.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{FolderInfo, MediaFilter, MediaItem, MediaTransferResult};
+use crate::services::{mtp_service, power_service, privacy_service, settings_service};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Phones Windows sees as portable devices (file transfer mode)
+#[tauri::command]
+pub async fn list_mtp_devices() -> Result<Vec<String>, AppError> {
+    mtp_service::list_devices()
+}
+
+/// Folders in a device folder; no path lists the device's storages
+#[tauri::command]
+pub async fn list_mtp_folders(
+    app: AppHandle,
+    device: String,
+    path: Option<String>,
+    include_private: Option<bool>,
+) -> Result<Vec<FolderInfo>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+
+    let mut folders = mtp_service::list_folders(&device, path.as_deref())?;
+    folders
+        .retain(|f| !privacy_service::is_private(mtp_service::storage_relative(&f.path), hidden));
+    Ok(folders)
+}
+
+/// Images and videos in a device folder, newest first
+#[tauri::command]
+pub async fn list_mtp_media(
+    app: AppHandle,
+    device: String,
+    path: String,
+    filter: Option<MediaFilter>,
+    include_private: Option<bool>,
+) -> Result<Vec<MediaItem>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+
+    let mut items = mtp_service::list_media(&device, &path, filter.unwrap_or_default())?;
+    items.retain(|item| {
+        !privacy_service::is_private(mtp_service::storage_relative(&item.path), hidden)
+    });
+    Ok(items)
+}
+
+/// Copy device files to a local folder (Downloads by default). Files that
+/// already exist there are skipped, as are private ones unless
+/// `include_private` is set.
+#[tauri::command]
+pub async fn pull_mtp_files(
+    app: AppHandle,
+    device: String,
+    paths: Vec<String>,
+    dest: Option<String>,
+    include_private: Option<bool>,
+) -> Result<Vec<MediaTransferResult>, AppError> {
+    let _awake = power_service::keep_awake();
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let dest_path = match dest {
+        Some(d) => PathBuf::from(d),
+        None => dirs::download_dir()
+            .ok_or_else(|| AppError::IoError("Could not determine Downloads folder".to_string()))?,
+    };
+    std::fs::create_dir_all(&dest_path)?;
+
+    let hidden = privacy_service::hidden(&settings.private_folders, include_private);
+    let (private, paths): (Vec<String>, Vec<String>) = paths
+        .into_iter()
+        .partition(|p| privacy_service::is_private(mtp_service::storage_relative(p), hidden));
+
+    let mut results = mtp_service::pull_files(&device, &paths, &dest_path);
+    results.extend(private.iter().map(|p| privacy_service::skipped_pull(p)));
+    Ok(results)
+}
@@ -0,0 +1,21 @@
+//! First-run setup Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{OnboardingState, OnboardingStep};
+use crate::services::onboarding_service;
+use tauri::AppHandle;
+
+/// Setup progress: which steps are completed and which pass their check now
+#[tauri::command]
+pub fn get_onboarding_state(app: AppHandle) -> Result<OnboardingState, AppError> {
+    onboarding_service::get_state(&app)
+}
+
+/// Complete a setup step; fails with the reason when its check doesn't pass
+#[tauri::command]
+pub fn complete_onboarding_step(
+    app: AppHandle,
+    step: OnboardingStep,
+) -> Result<OnboardingState, AppError> {
+    onboarding_service::complete_step(&app, step)
+}
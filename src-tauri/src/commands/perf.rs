@@ -0,0 +1,42 @@
+//! Live performance stream and tool metrics Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ToolMetrics;
+use crate::services::adb_service::AdbCli;
+use crate::services::perf_service::{self, PerfState};
+use crate::services::{process_service, settings_service};
+use tauri::{AppHandle, State};
+
+/// Sample CPU, memory, battery current and FPS every second, of one app when
+/// `package` is given or of the whole device otherwise
+/// Samples are emitted as `perf-sample` events until `stop_perf_stream`
+#[tauri::command]
+pub fn start_perf_stream(
+    app: AppHandle,
+    state: State<'_, PerfState>,
+    serial: String,
+    package: Option<String>,
+) -> Result<(), AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings)
+        .map(AdbCli::background)
+        .ok_or_else(|| {
+            AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+        })?;
+
+    perf_service::start_stream(app.clone(), &state, adb, serial, package)
+}
+
+/// Stop a device's performance stream; returns whether one was running
+#[tauri::command]
+pub fn stop_perf_stream(state: State<'_, PerfState>, serial: String) -> bool {
+    perf_service::stop_stream(&state, &serial)
+}
+
+/// Call counts, durations and failure rates of adb, ffmpeg and the other
+/// tools since the app started
+#[tauri::command]
+pub fn get_perf_metrics() -> Vec<ToolMetrics> {
+    process_service::tool_metrics()
+}
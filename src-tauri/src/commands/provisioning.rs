@@ -0,0 +1,91 @@
+//! Provisioning profile Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{ProvisioningProfile, ProvisioningReport};
+use crate::services::adb_service::AdbCli;
+use crate::services::transfer_service::TransferState;
+use crate::services::{
+    device_settings_service, progress_service, provisioning_service, settings_service,
+};
+use tauri::{AppHandle, State};
+
+/// List saved provisioning profiles
+#[tauri::command]
+pub fn get_provisioning_profiles(app: AppHandle) -> Result<Vec<ProvisioningProfile>, AppError> {
+    Ok(settings_service::load_settings(&app)?.provisioning_profiles)
+}
+
+/// Create or update a provisioning profile
+#[tauri::command]
+pub fn save_provisioning_profile(
+    app: AppHandle,
+    mut profile: ProvisioningProfile,
+) -> Result<ProvisioningProfile, AppError> {
+    if let Some(bad) = profile
+        .settings
+        .iter()
+        .find(|s| !device_settings_service::is_valid_setting_key(&s.key))
+    {
+        return Err(AppError::SettingsError(format!(
+            "Invalid settings key: {}",
+            bad.key
+        )));
+    }
+    if profile.id.is_empty() {
+        profile.id = format!("profile_{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    let mut settings = settings_service::load_settings(&app)?;
+    match settings
+        .provisioning_profiles
+        .iter_mut()
+        .find(|p| p.id == profile.id)
+    {
+        Some(existing) => *existing = profile.clone(),
+        None => settings.provisioning_profiles.push(profile.clone()),
+    }
+    settings_service::save_settings(&app, &settings)?;
+
+    Ok(profile)
+}
+
+/// Delete a provisioning profile
+#[tauri::command]
+pub fn delete_provisioning_profile(app: AppHandle, id: String) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.provisioning_profiles.retain(|p| p.id != id);
+    settings_service::save_settings(&app, &settings)
+}
+
+/// Apply a profile to a device. Progress is emitted as batch-progress events;
+/// the report lists the outcome of every step.
+#[tauri::command]
+pub fn apply_provisioning(
+    app: AppHandle,
+    state: State<'_, TransferState>,
+    serial: String,
+    profile_id: String,
+) -> Result<ProvisioningReport, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let profile = settings
+        .provisioning_profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .cloned()
+        .ok_or_else(|| {
+            AppError::SettingsError(format!("Provisioning profile not found: {}", profile_id))
+        })?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    Ok(provisioning_service::apply_profile(
+        &state,
+        &adb,
+        &serial,
+        &profile,
+        &progress_service::emit_progress(&app),
+    ))
+}
@@ -0,0 +1,25 @@
+//! Quick Share hand-off commands
+//!
+//! Quick Share for Windows only exists on Windows; elsewhere it's reported
+//! as not installed. Sends are recorded in the transfer history.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::QuickShareStatus;
+use crate::services::quick_share_service;
+use tauri::AppHandle;
+
+/// Whether Google's Quick Share for Windows is installed
+#[tauri::command]
+pub fn get_quick_share_status() -> QuickShareStatus {
+    quick_share_service::get_status()
+}
+
+/// Open Quick Share with local files preselected
+#[tauri::command]
+pub async fn send_with_quick_share(
+    app: AppHandle,
+    paths: Vec<String>,
+    device_name: Option<String>,
+) -> Result<(), AppError> {
+    quick_share_service::send(&app, &paths, device_name)
+}
@@ -0,0 +1,66 @@
+//! Recording library Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::Recording;
+use crate::services::confirmation_service::{self, ConfirmationState};
+use crate::services::{recording_service, settings_service};
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+/// List screen and camera recordings, newest first
+#[tauri::command]
+pub fn list_recordings(app: AppHandle) -> Result<Vec<Recording>, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    let dir = recording_service::recordings_dir()?;
+
+    recording_service::list_recordings(&dir, settings.ffprobe_resolved_path.as_deref())
+}
+
+/// Delete a recording from the recordings folder; needs a token from
+/// `request_confirmation`
+#[tauri::command]
+pub fn delete_recording(
+    confirmations: State<'_, ConfirmationState>,
+    path: String,
+    confirmation_token: Option<String>,
+) -> Result<(), AppError> {
+    let dir = recording_service::recordings_dir()?;
+
+    confirmation_service::consume(
+        &confirmations,
+        "delete_recording",
+        &[path.as_str()],
+        confirmation_token.as_deref(),
+        Instant::now(),
+    )?;
+    recording_service::delete_recording(&dir, &PathBuf::from(path))
+}
+
+/// Open the recordings folder in the system file manager
+#[tauri::command]
+pub fn open_recordings_folder(app: AppHandle) -> Result<(), AppError> {
+    let dir = recording_service::recordings_dir()?;
+
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<String>)
+        .map_err(|e| AppError::IoError(format!("Failed to open folder: {}", e)))?;
+
+    Ok(())
+}
+
+/// Convert an MKV recording to MP4 (stream copy) and return the new path
+#[tauri::command]
+pub fn remux_recording(app: AppHandle, path: String) -> Result<String, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let ffmpeg_path = settings.ffmpeg_resolved_path.ok_or_else(|| {
+        AppError::FfmpegNotFound("ffmpeg not found. Configure it in Settings.".to_string())
+    })?;
+
+    let dir = recording_service::recordings_dir()?;
+    let output = recording_service::remux_to_mp4(&ffmpeg_path, &dir, &PathBuf::from(path))?;
+
+    Ok(output.to_string_lossy().to_string())
+}
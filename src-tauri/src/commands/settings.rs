@@ -1,8 +1,12 @@
 //! Settings-related Tauri commands
 
 use crate::domain::errors::AppError;
-use crate::domain::models::Settings;
-use crate::services::settings_service;
+use crate::domain::i18n;
+use crate::domain::models::{BatteryPolicy, LocalDestination, MirrorOptions, Settings};
+use crate::services::{
+    destination_service, privacy_service, settings_service, thumbnail_warmup_service,
+};
+use std::collections::HashMap;
 use tauri::AppHandle;
 
 /// Get current settings with ADB detection
@@ -61,3 +65,220 @@ pub fn set_ffmpeg_path(app: AppHandle, path: Option<String>) -> Result<Settings,
 pub fn detect_ffmpeg(app: AppHandle) -> Option<String> {
     settings_service::detect_ffmpeg_path(&app)
 }
+
+/// Set a custom ffprobe path
+#[tauri::command]
+pub fn set_ffprobe_path(app: AppHandle, path: Option<String>) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.ffprobe_path = path;
+    settings_service::save_settings(&app, &settings)?;
+
+    // Return updated settings with detection
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Trigger auto-detection of ffprobe path (looks next to FFmpeg first)
+#[tauri::command]
+pub fn detect_ffprobe(app: AppHandle) -> Option<String> {
+    let ffmpeg_path = settings_service::get_settings_with_detection(&app)
+        .ok()
+        .and_then(|s| s.ffmpeg_resolved_path);
+    settings_service::detect_ffprobe_path(&app, ffmpeg_path.as_deref())
+}
+
+/// Set the language used for backend-generated messages
+#[tauri::command]
+pub fn set_language(app: AppHandle, language: String) -> Result<Settings, AppError> {
+    if !i18n::is_supported(&language) {
+        return Err(AppError::SettingsError(format!(
+            "Unsupported language: {}",
+            language
+        )));
+    }
+
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.language = language;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Get the message catalog (key -> text) for a language, defaulting to the configured one
+#[tauri::command]
+pub fn get_message_catalog(
+    app: AppHandle,
+    language: Option<String>,
+) -> Result<HashMap<String, String>, AppError> {
+    let language = match language {
+        Some(l) => l,
+        None => settings_service::load_settings(&app)?.language,
+    };
+
+    Ok(i18n::catalog_map(&language))
+}
+
+/// Set timeouts for adb commands and transfers (seconds, minimum 1)
+#[tauri::command]
+pub fn set_timeouts(
+    app: AppHandle,
+    command_timeout_secs: u64,
+    transfer_timeout_secs: u64,
+) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.command_timeout_secs = command_timeout_secs.max(1);
+    settings.transfer_timeout_secs = transfer_timeout_secs.max(1);
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Set the default screen mirror options (e.g. the shortcut modifier keys)
+#[tauri::command]
+pub fn set_mirror_options(app: AppHandle, options: MirrorOptions) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.mirror_options = options;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Set what happens to sessions when a device runs low on battery
+#[tauri::command]
+pub fn set_battery_policy(app: AppHandle, policy: BatteryPolicy) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.battery_policy = policy;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Turn the on-device notification shown when mirroring starts on or off
+#[tauri::command]
+pub fn set_notify_on_mirror(app: AppHandle, enabled: bool) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.notify_on_mirror = enabled;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Set the local APK repository folder checked for app updates
+#[tauri::command]
+pub fn set_apk_repository_dir(app: AppHandle, path: Option<String>) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.apk_repository_dir = path;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Set how many thumbnails are made in the background when a folder is
+/// listed (0 turns warm-up off)
+#[tauri::command]
+pub fn set_thumbnail_warmup(app: AppHandle, count: usize) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.thumbnail_warmup_count = count.min(thumbnail_warmup_service::MAX_WARMUP_COUNT);
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Turn automatic MKV -> MP4 conversion of finished recordings on or off
+#[tauri::command]
+pub fn set_remux_recordings(app: AppHandle, enabled: bool) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.remux_recordings = enabled;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Set the retry policy for transient adb failures
+#[tauri::command]
+pub fn set_retry_policy(
+    app: AppHandle,
+    attempts: u32,
+    initial_delay_ms: u64,
+) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.retry_attempts = attempts.max(1);
+    settings.retry_initial_delay_ms = initial_delay_ms;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Turn read-only (guest) mode on or off; while on, pushes, deletes, installs
+/// and input fail with a ReadOnlyMode error and mirrors are view only
+#[tauri::command]
+pub fn set_read_only_mode(app: AppHandle, enabled: bool) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.read_only_mode = enabled;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Replace the private folder list; entries may be full device paths or
+/// paths below the storage volume
+#[tauri::command]
+pub fn set_private_folders(app: AppHandle, folders: Vec<String>) -> Result<Settings, AppError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for folder in &folders {
+        let folder = privacy_service::normalize_folder(folder)?;
+        if !normalized.iter().any(|f| f.eq_ignore_ascii_case(&folder)) {
+            normalized.push(folder);
+        }
+    }
+
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.private_folders = normalized;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Quick destination choices for pull dialogs: pinned folders, then the
+/// most recently pulled into
+#[tauri::command]
+pub fn get_recent_destinations(app: AppHandle) -> Result<Vec<LocalDestination>, AppError> {
+    destination_service::get_destinations(&app)
+}
+
+/// Pin a local folder as a pull destination
+#[tauri::command]
+pub fn pin_destination(app: AppHandle, path: String) -> Result<Vec<LocalDestination>, AppError> {
+    destination_service::set_pinned(&app, &path, true)
+}
+
+/// Unpin a local folder
+#[tauri::command]
+pub fn unpin_destination(app: AppHandle, path: String) -> Result<Vec<LocalDestination>, AppError> {
+    destination_service::set_pinned(&app, &path, false)
+}
+
+/// Name a device; pulls without a destination go to a Downloads folder of
+/// that name. `None` or an empty name goes back to the model name.
+#[tauri::command]
+pub fn set_device_nickname(
+    app: AppHandle,
+    serial: String,
+    nickname: Option<String>,
+) -> Result<Settings, AppError> {
+    let nickname = nickname
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    settings_service::update_device_record(&app, &serial, |record| record.nickname = nickname)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Turn the per-device Downloads subfolder for pulls on or off
+#[tauri::command]
+pub fn set_device_pull_folders(app: AppHandle, enabled: bool) -> Result<Settings, AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.device_pull_folders = enabled;
+    settings_service::save_settings(&app, &settings)?;
+
+    settings_service::get_settings_with_detection(&app)
+}
@@ -0,0 +1,156 @@
+//! Folder sync Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    ConflictPolicy, DeviceEventKind, SyncDirection, SyncJob, SyncJobRun, SyncPlan, SyncReport,
+};
+use crate::services::adb_service::AdbCli;
+use crate::services::scheduler_service::SchedulerState;
+use crate::services::{
+    power_service, scheduler_service, settings_service, sync_service, timeline_service,
+};
+use tauri::{AppHandle, State};
+
+/// Preview the files a sync would copy, without transferring anything
+#[tauri::command]
+pub fn preview_sync(
+    app: AppHandle,
+    serial: String,
+    local_root: String,
+    remote_root: String,
+    direction: SyncDirection,
+    compare_hash: Option<bool>,
+    conflict: Option<ConflictPolicy>,
+) -> Result<SyncPlan, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    sync_service::plan_sync(
+        &adb,
+        &serial,
+        &local_root,
+        &remote_root,
+        direction,
+        compare_hash.unwrap_or(false),
+        conflict.unwrap_or_default(),
+    )
+}
+
+/// Sync a local folder with a device folder, copying only differences
+#[tauri::command]
+pub fn run_sync(
+    app: AppHandle,
+    serial: String,
+    local_root: String,
+    remote_root: String,
+    direction: SyncDirection,
+    compare_hash: Option<bool>,
+    conflict: Option<ConflictPolicy>,
+) -> Result<SyncReport, AppError> {
+    let _awake = power_service::keep_awake();
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let report = sync_service::run_sync(
+        &adb,
+        &serial,
+        &local_root,
+        &remote_root,
+        direction,
+        compare_hash.unwrap_or(false),
+        conflict.unwrap_or_default(),
+    )?;
+
+    timeline_service::record(
+        &app,
+        &serial,
+        DeviceEventKind::Synced,
+        Some(timeline_service::files_detail(
+            report.transferred,
+            report.transferred + report.failures.len(),
+            &remote_root,
+        )),
+    );
+    Ok(report)
+}
+
+/// List configured scheduled sync jobs
+#[tauri::command]
+pub fn get_sync_jobs(app: AppHandle) -> Result<Vec<SyncJob>, AppError> {
+    Ok(settings_service::load_settings(&app)?.sync_jobs)
+}
+
+/// Create or update a scheduled sync job
+#[tauri::command]
+pub fn save_sync_job(app: AppHandle, mut job: SyncJob) -> Result<SyncJob, AppError> {
+    if !job.remote_root.starts_with('/') {
+        return Err(AppError::InvalidPath(format!(
+            "Device folder must be an absolute path: {}",
+            job.remote_root
+        )));
+    }
+
+    let mut settings = settings_service::load_settings(&app)?;
+
+    if job.id.is_empty() {
+        job.id = format!("job_{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    match settings.sync_jobs.iter_mut().find(|j| j.id == job.id) {
+        Some(existing) => {
+            // Editing a job doesn't reset its schedule
+            job.last_run_at = existing.last_run_at.clone();
+            *existing = job.clone();
+        }
+        None => settings.sync_jobs.push(job.clone()),
+    }
+
+    settings_service::save_settings(&app, &settings)?;
+    Ok(job)
+}
+
+/// Delete a scheduled sync job and its history
+#[tauri::command]
+pub fn delete_sync_job(
+    app: AppHandle,
+    state: State<'_, SchedulerState>,
+    id: String,
+) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.sync_jobs.retain(|j| j.id != id);
+    settings_service::save_settings(&app, &settings)?;
+
+    scheduler_service::clear_history(&state, &id);
+    Ok(())
+}
+
+/// Past runs of a scheduled job, newest first
+#[tauri::command]
+pub fn get_sync_job_history(state: State<'_, SchedulerState>, id: String) -> Vec<SyncJobRun> {
+    scheduler_service::get_history(&state, &id)
+}
+
+/// Run a scheduled job immediately, regardless of its interval
+#[tauri::command]
+pub fn run_sync_job_now(app: AppHandle, id: String) -> Result<SyncJobRun, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let job = settings
+        .sync_jobs
+        .iter()
+        .find(|j| j.id == id)
+        .cloned()
+        .ok_or_else(|| AppError::SettingsError(format!("Sync job not found: {}", id)))?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    Ok(scheduler_service::run_and_record(&app, &adb, &job))
+}
@@ -1,65 +1,211 @@
 //! Transfer-related Tauri commands
 
 use crate::domain::errors::AppError;
-use crate::domain::models::TransferItem;
-use crate::services::{settings_service, transfer_service};
-use tauri::AppHandle;
+use crate::domain::models::{
+    ConflictPolicy, DeviceEventKind, DeviceRecord, DropAnalysis, TransferItem, TransferPreset,
+    TransferStatus,
+};
+use crate::services::adb_service::AdbCli;
+use crate::services::transfer_service::TransferState;
+use crate::services::{
+    drop_service, power_service, progress_service, settings_service, timeline_service,
+    transfer_service,
+};
+use tauri::{AppHandle, State};
 
-/// Push files to a device
+/// Push files to a device, optionally as one tar archive extracted on-device
+/// Progress for the whole batch is emitted as `batch-progress` events
+/// Without `dest`, the folder last used for this device is reused
 #[tauri::command]
 pub fn push_files(
     app: AppHandle,
+    state: State<'_, TransferState>,
     serial: String,
     paths: Vec<String>,
     dest: Option<String>,
+    archive: Option<bool>,
+    conflict: Option<ConflictPolicy>,
 ) -> Result<Vec<TransferItem>, AppError> {
+    let _awake = power_service::keep_awake();
     let settings = settings_service::get_settings_with_detection(&app)?;
 
-    let adb_path = settings.adb_resolved_path.ok_or_else(|| {
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
         AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
     })?;
 
-    let dest_dir = dest.unwrap_or(settings.default_device_dir);
-
-    let mut results = Vec::new();
-    for path in paths {
-        match transfer_service::push_file(&adb_path, &serial, &path, &dest_dir) {
-            Ok(item) => results.push(item),
-            Err(e) => {
-                // Continue with other files but report this error
-                results.push(TransferItem {
-                    id: format!("error_{}", chrono::Utc::now().timestamp_millis()),
-                    file_name: std::path::Path::new(&path)
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown")
-                        .to_string(),
-                    source_path: path.clone(),
-                    dest_path: format!("/sdcard/{}/", dest_dir),
-                    size_bytes: 0,
-                    transferred_bytes: 0,
-                    status: crate::domain::models::TransferStatus::Failed,
-                    error: Some(e.to_string()),
-                    started_at: chrono::Utc::now().to_rfc3339(),
-                });
-            }
-        }
-    }
+    // Fall back to the folder last used for this device, then the global default
+    let dest_dir = dest
+        .or_else(|| {
+            settings
+                .device_registry
+                .get(&serial)
+                .and_then(|r| r.last_device_dir.clone())
+        })
+        .unwrap_or_else(|| settings.default_device_dir.clone());
+
+    let results = transfer_service::push_files(
+        &state,
+        &adb,
+        &serial,
+        &paths,
+        &dest_dir,
+        archive.unwrap_or(false),
+        conflict.unwrap_or_default(),
+        &progress_service::emit_progress(&app),
+    );
+    record_push(&app, &serial, &results, &dest_dir);
+
+    // Remember the folder as this device's default for next time
+    settings_service::update_device_record(&app, &serial, |record| {
+        record.last_device_dir = Some(dest_dir)
+    })?;
+
+    Ok(results)
+}
+
+/// Get what is remembered about a device (last push folder, last pull folder)
+#[tauri::command]
+pub fn get_device_record(app: AppHandle, serial: String) -> Result<DeviceRecord, AppError> {
+    let settings = settings_service::load_settings(&app)?;
+    Ok(settings
+        .device_registry
+        .get(&serial)
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Push files using a saved preset's destination and options
+#[tauri::command]
+pub fn push_with_preset(
+    app: AppHandle,
+    state: State<'_, TransferState>,
+    serial: String,
+    preset_id: String,
+    paths: Vec<String>,
+) -> Result<Vec<TransferItem>, AppError> {
+    let _awake = power_service::keep_awake();
+    let settings = settings_service::get_settings_with_detection(&app)?;
 
+    let preset = settings
+        .transfer_presets
+        .iter()
+        .find(|p| p.id == preset_id)
+        .cloned()
+        .ok_or_else(|| {
+            AppError::SettingsError(format!("Transfer preset not found: {}", preset_id))
+        })?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    let results = transfer_service::push_files(
+        &state,
+        &adb,
+        &serial,
+        &paths,
+        &preset.dest_dir,
+        preset.archive,
+        preset.conflict,
+        &progress_service::emit_progress(&app),
+    );
+    record_push(&app, &serial, &results, &preset.dest_dir);
     Ok(results)
 }
 
+/// Add a push to the device's timeline if anything was copied
+fn record_push(app: &AppHandle, serial: &str, items: &[TransferItem], dest_dir: &str) {
+    let pushed = items
+        .iter()
+        .filter(|i| i.status == TransferStatus::Complete)
+        .count();
+    if pushed > 0 {
+        timeline_service::record(
+            app,
+            serial,
+            DeviceEventKind::FilesPushed,
+            Some(timeline_service::files_detail(
+                pushed,
+                items.len(),
+                dest_dir,
+            )),
+        );
+    }
+}
+
+/// List saved transfer presets
+#[tauri::command]
+pub fn get_transfer_presets(app: AppHandle) -> Result<Vec<TransferPreset>, AppError> {
+    Ok(settings_service::load_settings(&app)?.transfer_presets)
+}
+
+/// Create or update a transfer preset
+#[tauri::command]
+pub fn save_transfer_preset(
+    app: AppHandle,
+    mut preset: TransferPreset,
+) -> Result<TransferPreset, AppError> {
+    preset.dest_dir = transfer_service::normalize_device_dir(&preset.dest_dir)?;
+    if preset.id.is_empty() {
+        preset.id = format!("preset_{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    let mut settings = settings_service::load_settings(&app)?;
+    match settings
+        .transfer_presets
+        .iter_mut()
+        .find(|p| p.id == preset.id)
+    {
+        Some(existing) => *existing = preset.clone(),
+        None => settings.transfer_presets.push(preset.clone()),
+    }
+    settings_service::save_settings(&app, &settings)?;
+
+    Ok(preset)
+}
+
+/// Delete a transfer preset
+#[tauri::command]
+pub fn delete_transfer_preset(app: AppHandle, id: String) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(&app)?;
+    settings.transfer_presets.retain(|p| p.id != id);
+    settings_service::save_settings(&app, &settings)
+}
+
+/// Expand and classify dropped files/folders and suggest push, install or OBB copy
+#[tauri::command]
+pub fn analyze_drop(paths: Vec<String>) -> DropAnalysis {
+    drop_service::analyze_drop(&paths)
+}
+
 /// Get active transfers and history
 #[tauri::command]
-pub fn get_transfers() -> (Vec<TransferItem>, Vec<TransferItem>) {
+pub fn get_transfers(state: State<'_, TransferState>) -> (Vec<TransferItem>, Vec<TransferItem>) {
     (
-        transfer_service::get_active_transfers(),
-        transfer_service::get_transfer_history(),
+        transfer_service::get_active_transfers(&state),
+        transfer_service::get_transfer_history(&state),
     )
 }
 
+/// Resume interrupted transfers for a device (also triggered automatically on reconnect)
+#[tauri::command]
+pub fn resume_transfers(
+    app: AppHandle,
+    state: State<'_, TransferState>,
+    serial: String,
+) -> Result<Vec<TransferItem>, AppError> {
+    let _awake = power_service::keep_awake();
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+        AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+    })?;
+
+    Ok(transfer_service::resume_interrupted(&state, &adb, &serial))
+}
+
 /// Cancel a transfer
 #[tauri::command]
-pub fn cancel_transfer(id: String) -> Result<(), AppError> {
-    transfer_service::cancel_transfer(&id)
+pub fn cancel_transfer(state: State<'_, TransferState>, id: String) -> Result<(), AppError> {
+    transfer_service::cancel_transfer(&state, &id)
 }
@@ -0,0 +1,59 @@
+//! Troubleshooting Tauri commands
+//!
+//! Every command returns a [`TroubleshootResult`], also when the fix didn't
+//! work, so the UI can offer its suggested next action.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{TroubleshootAction, TroubleshootResult};
+use crate::services::adb_service::AdbCli;
+use crate::services::{settings_service, troubleshoot_service};
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// Stop the adb server and start a fresh one
+#[tauri::command]
+pub fn restart_adb_server(app: AppHandle) -> Result<TroubleshootResult, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    Ok(match AdbCli::from_settings(&settings) {
+        Some(adb) => troubleshoot_service::restart_adb_server(&adb),
+        None => adb_missing(TroubleshootAction::RestartAdbServer),
+    })
+}
+
+/// Look for the external tools again and report what was found
+#[tauri::command]
+pub fn redetect_tools(app: AppHandle) -> Result<TroubleshootResult, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    Ok(troubleshoot_service::tool_report(&settings))
+}
+
+/// Check a device answers a shell echo round trip
+#[tauri::command]
+pub fn test_device_connection(
+    app: AppHandle,
+    serial: String,
+) -> Result<TroubleshootResult, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+    Ok(match AdbCli::from_settings(&settings) {
+        Some(adb) => troubleshoot_service::test_device_connection(&adb, &serial),
+        None => adb_missing(TroubleshootAction::TestDeviceConnection),
+    })
+}
+
+/// Open the Android SDK Platform Tools download page
+#[tauri::command]
+pub fn open_platform_tools_download(app: AppHandle) -> TroubleshootResult {
+    troubleshoot_service::open_platform_tools_download(&app)
+}
+
+fn adb_missing(action: TroubleshootAction) -> TroubleshootResult {
+    troubleshoot_service::result_with_details(
+        action,
+        Instant::now(),
+        Err((
+            AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string()),
+            Vec::new(),
+        )),
+        Some(TroubleshootAction::OpenPlatformToolsDownload),
+    )
+}
@@ -0,0 +1,54 @@
+//! App update Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{PendingUpdate, Settings, UpdateChannel, UpdatePlanKind};
+use crate::services::{settings_service, update_service};
+use tauri::AppHandle;
+
+/// The update available on the chosen channel with its release notes, or
+/// None when this is the latest version
+#[tauri::command]
+pub async fn get_pending_update(app: AppHandle) -> Result<Option<PendingUpdate>, AppError> {
+    update_service::pending_update(&app).await
+}
+
+/// Download and install the pending update now, then restart the app
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), AppError> {
+    update_service::install(&app).await
+}
+
+/// Follow the stable or beta release channel
+#[tauri::command]
+pub fn set_update_channel(app: AppHandle, channel: UpdateChannel) -> Result<Settings, AppError> {
+    update_service::set_channel(&app, channel)?;
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Don't offer this update version again before `until` (RFC 3339)
+#[tauri::command]
+pub fn defer_update(app: AppHandle, version: String, until: String) -> Result<Settings, AppError> {
+    let plan = update_service::make_plan(&version, UpdatePlanKind::Deferred, &until)?;
+    update_service::set_plan(&app, Some(plan))?;
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Install this update version at `at` (RFC 3339), if the app is open then
+/// and once no transfer is running
+#[tauri::command]
+pub fn schedule_update_install(
+    app: AppHandle,
+    version: String,
+    at: String,
+) -> Result<Settings, AppError> {
+    let plan = update_service::make_plan(&version, UpdatePlanKind::Scheduled, &at)?;
+    update_service::set_plan(&app, Some(plan))?;
+    settings_service::get_settings_with_detection(&app)
+}
+
+/// Forget a deferred or scheduled install
+#[tauri::command]
+pub fn clear_update_plan(app: AppHandle) -> Result<Settings, AppError> {
+    update_service::set_plan(&app, None)?;
+    settings_service::get_settings_with_detection(&app)
+}
@@ -0,0 +1,41 @@
+//! Setting and property watch Tauri commands
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{SettingWatch, WatchNamespace};
+use crate::services::adb_service::AdbCli;
+use crate::services::settings_service;
+use crate::services::watch_service::{self, WatchState};
+use tauri::{AppHandle, State};
+
+/// Watch a setting (`system`, `secure`, `global`) or system property (`prop`)
+/// Changes are emitted as `setting-changed` events until `unwatch_setting`
+#[tauri::command]
+pub fn watch_setting(
+    app: AppHandle,
+    state: State<'_, WatchState>,
+    serial: String,
+    namespace: WatchNamespace,
+    key: String,
+) -> Result<SettingWatch, AppError> {
+    let settings = settings_service::get_settings_with_detection(&app)?;
+
+    let adb = AdbCli::from_settings(&settings)
+        .map(AdbCli::background)
+        .ok_or_else(|| {
+            AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+        })?;
+
+    watch_service::watch_setting(app.clone(), &state, adb, serial, namespace, key)
+}
+
+/// Stop a watch; returns whether it was running
+#[tauri::command]
+pub fn unwatch_setting(state: State<'_, WatchState>, watch_id: String) -> bool {
+    watch_service::unwatch_setting(&state, &watch_id)
+}
+
+/// Ids of the running watches
+#[tauri::command]
+pub fn list_setting_watches(state: State<'_, WatchState>) -> Vec<String> {
+    watch_service::list_watches(&state)
+}
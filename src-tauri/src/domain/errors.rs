@@ -1,10 +1,12 @@
 //! Application error types
 
-use serde::{Deserialize, Serialize};
+use super::i18n;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
 use std::fmt;
 
 /// Application-level errors with user-friendly messages
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", content = "message")]
 pub enum AppError {
     /// ADB executable not found
@@ -29,23 +31,50 @@ pub enum AppError {
     ThumbnailNotAvailable(String),
     /// FFmpeg executable not found
     FfmpegNotFound(String),
+    /// ffprobe executable not found
+    FfprobeNotFound(String),
+    /// ffprobe failed or returned unreadable output
+    ProbeError(String),
+    /// An external process did not finish in time and was killed
+    Timeout(String),
+    /// A destructive command ran without a valid confirmation token
+    ConfirmationRequired(String),
+    /// A command would change the device while read-only mode is on
+    ReadOnlyMode(String),
+    /// Not enough free space on the computer; the message has the shortfall
+    InsufficientLocalStorage(String),
+    /// Checking for, downloading or installing an app update failed
+    UpdateError(String),
 }
 
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            AppError::AdbNotFound(msg) => write!(f, "ADB not found: {}", msg),
-            AppError::AdbExecutionFailed(msg) => write!(f, "ADB execution failed: {}", msg),
-            AppError::InvalidPath(msg) => write!(f, "Invalid path: {}", msg),
-            AppError::IoError(msg) => write!(f, "IO error: {}", msg),
-            AppError::SettingsError(msg) => write!(f, "Settings error: {}", msg),
-            AppError::DeviceNotFound(msg) => write!(f, "Device not found: {}", msg),
-            AppError::ScrcpyNotFound(msg) => write!(f, "scrcpy not found: {}", msg),
-            AppError::MirrorError(msg) => write!(f, "Mirror error: {}", msg),
-            AppError::TransferError(msg) => write!(f, "Transfer error: {}", msg),
-            AppError::ThumbnailNotAvailable(msg) => write!(f, "Thumbnail not available: {}", msg),
-            AppError::FfmpegNotFound(msg) => write!(f, "FFmpeg not found: {}", msg),
+        f.write_str(&self.message_in(i18n::DEFAULT_LANGUAGE))
+    }
+}
+
+/// What the frontend receives: the English `message` for display as is, plus
+/// the catalog `key` and `params` to render it in the user's language
+#[derive(Serialize)]
+struct ErrorPayload<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    message: &'a str,
+    key: String,
+    params: HashMap<String, String>,
+    guidance_key: String,
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorPayload {
+            kind: self.kind(),
+            message: self.detail(),
+            key: self.message_key(),
+            params: self.params(),
+            guidance_key: self.guidance_key(),
         }
+        .serialize(serializer)
     }
 }
 
@@ -57,38 +86,140 @@ impl From<std::io::Error> for AppError {
     }
 }
 
-/// Helper to convert AppError to a serializable result for Tauri
 impl AppError {
-    #[allow(dead_code)]
-    pub fn user_guidance(&self) -> &'static str {
+    /// Variant name, identical to the serialized `type` field
+    pub fn kind(&self) -> &'static str {
         match self {
-            AppError::AdbNotFound(_) => {
-                "Install Android SDK Platform Tools or set the ADB path manually in Settings."
-            }
-            AppError::AdbExecutionFailed(_) => {
-                "Check if ADB is configured correctly and the device is connected."
-            }
-            AppError::InvalidPath(_) => "The specified path does not exist or is not accessible.",
-            AppError::IoError(_) => "A file system operation failed. Check permissions.",
-            AppError::SettingsError(_) => "Failed to save or load settings. Try restarting the app.",
-            AppError::DeviceNotFound(_) => {
-                "No device found with this serial. Ensure cable is connected and USB debugging is enabled."
-            }
-            AppError::ScrcpyNotFound(_) => {
-                "Install scrcpy or set the scrcpy path manually in Settings."
-            }
-            AppError::MirrorError(_) => {
-                "Mirror operation failed. Ensure device is connected and authorized."
-            }
-            AppError::TransferError(_) => {
-                "File transfer failed. Check device connection and storage permissions."
-            }
-            AppError::ThumbnailNotAvailable(_) => {
-                "Thumbnail preview not available for this media file."
-            }
-            AppError::FfmpegNotFound(_) => {
-                "Install FFmpeg or set the FFmpeg path manually in Settings to enable transcoding."
-            }
+            AppError::AdbNotFound(_) => "AdbNotFound",
+            AppError::AdbExecutionFailed(_) => "AdbExecutionFailed",
+            AppError::InvalidPath(_) => "InvalidPath",
+            AppError::IoError(_) => "IoError",
+            AppError::SettingsError(_) => "SettingsError",
+            AppError::DeviceNotFound(_) => "DeviceNotFound",
+            AppError::ScrcpyNotFound(_) => "ScrcpyNotFound",
+            AppError::MirrorError(_) => "MirrorError",
+            AppError::TransferError(_) => "TransferError",
+            AppError::ThumbnailNotAvailable(_) => "ThumbnailNotAvailable",
+            AppError::FfmpegNotFound(_) => "FfmpegNotFound",
+            AppError::FfprobeNotFound(_) => "FfprobeNotFound",
+            AppError::ProbeError(_) => "ProbeError",
+            AppError::Timeout(_) => "Timeout",
+            AppError::ConfirmationRequired(_) => "ConfirmationRequired",
+            AppError::ReadOnlyMode(_) => "ReadOnlyMode",
+            AppError::InsufficientLocalStorage(_) => "InsufficientLocalStorage",
+            AppError::UpdateError(_) => "UpdateError",
         }
     }
+
+    /// The detail text the error was created with
+    pub fn detail(&self) -> &str {
+        match self {
+            AppError::AdbNotFound(msg)
+            | AppError::AdbExecutionFailed(msg)
+            | AppError::InvalidPath(msg)
+            | AppError::IoError(msg)
+            | AppError::SettingsError(msg)
+            | AppError::DeviceNotFound(msg)
+            | AppError::ScrcpyNotFound(msg)
+            | AppError::MirrorError(msg)
+            | AppError::TransferError(msg)
+            | AppError::ThumbnailNotAvailable(msg)
+            | AppError::FfmpegNotFound(msg)
+            | AppError::FfprobeNotFound(msg)
+            | AppError::ProbeError(msg)
+            | AppError::Timeout(msg)
+            | AppError::ConfirmationRequired(msg)
+            | AppError::ReadOnlyMode(msg)
+            | AppError::InsufficientLocalStorage(msg)
+            | AppError::UpdateError(msg) => msg,
+        }
+    }
+
+    /// Catalog key for the message of this error
+    pub fn message_key(&self) -> String {
+        format!("error.{}", self.kind())
+    }
+
+    /// Values for the placeholders of the message
+    pub fn params(&self) -> HashMap<String, String> {
+        HashMap::from([("detail".to_string(), self.detail().to_string())])
+    }
+
+    /// Message in the given language (falls back to English)
+    pub fn message_in(&self, language: &str) -> String {
+        i18n::format(language, &self.message_key(), &self.params())
+    }
+
+    /// Catalog key for the guidance text of this error
+    pub fn guidance_key(&self) -> String {
+        format!("guidance.{}", self.kind())
+    }
+
+    /// Guidance text in the given language (falls back to English)
+    pub fn user_guidance_in(&self, language: &str) -> &'static str {
+        i18n::translate(language, &self.guidance_key()).unwrap_or("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_error_has_english_catalog_entries() {
+        let errors = [
+            AppError::AdbNotFound(String::new()),
+            AppError::AdbExecutionFailed(String::new()),
+            AppError::InvalidPath(String::new()),
+            AppError::IoError(String::new()),
+            AppError::SettingsError(String::new()),
+            AppError::DeviceNotFound(String::new()),
+            AppError::ScrcpyNotFound(String::new()),
+            AppError::MirrorError(String::new()),
+            AppError::TransferError(String::new()),
+            AppError::ThumbnailNotAvailable(String::new()),
+            AppError::FfmpegNotFound(String::new()),
+            AppError::FfprobeNotFound(String::new()),
+            AppError::ProbeError(String::new()),
+            AppError::Timeout(String::new()),
+            AppError::ConfirmationRequired(String::new()),
+            AppError::ReadOnlyMode(String::new()),
+            AppError::InsufficientLocalStorage(String::new()),
+            AppError::UpdateError(String::new()),
+        ];
+
+        for err in errors {
+            assert!(!err.user_guidance_in("en").is_empty(), "{}", err.kind());
+            assert!(
+                i18n::translate("en", &err.message_key()).is_some(),
+                "{}",
+                err.kind()
+            );
+            // kind() must stay in sync with the serde tag
+            let json = serde_json::to_value(&err).unwrap();
+            assert_eq!(json["type"], err.kind());
+        }
+    }
+
+    #[test]
+    fn test_errors_carry_their_catalog_key_and_params() {
+        let err = AppError::Timeout("adb shell ls did not finish within 5s".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Timed out: adb shell ls did not finish within 5s"
+        );
+        assert_eq!(
+            err.message_in("id"),
+            "Waktu habis: adb shell ls did not finish within 5s"
+        );
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["message"], "adb shell ls did not finish within 5s");
+        assert_eq!(json["key"], "error.Timeout");
+        assert_eq!(
+            json["params"]["detail"],
+            "adb shell ls did not finish within 5s"
+        );
+        assert_eq!(json["guidance_key"], "guidance.Timeout");
+    }
 }
@@ -0,0 +1,283 @@
+//! Message catalogs for backend-generated text
+//!
+//! Keys are `error.<Kind>` / `guidance.<Kind>` (Kind = serialized AppError `type`)
+//! plus `message.<name>` keys for notices the backend hands the UI by key.
+//! Values may contain `{param}` placeholders, filled in by `format`.
+
+use std::collections::HashMap;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Languages with a catalog, as (code, native name)
+pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[("en", "English"), ("id", "Bahasa Indonesia")];
+
+const EN: &[(&str, &str)] = &[
+    ("error.AdbNotFound", "ADB not found: {detail}"),
+    ("error.AdbExecutionFailed", "ADB execution failed: {detail}"),
+    ("error.InvalidPath", "Invalid path: {detail}"),
+    ("error.IoError", "IO error: {detail}"),
+    ("error.SettingsError", "Settings error: {detail}"),
+    ("error.DeviceNotFound", "Device not found: {detail}"),
+    ("error.ScrcpyNotFound", "scrcpy not found: {detail}"),
+    ("error.MirrorError", "Mirror error: {detail}"),
+    ("error.TransferError", "Transfer error: {detail}"),
+    ("error.ThumbnailNotAvailable", "Thumbnail not available: {detail}"),
+    ("error.FfmpegNotFound", "FFmpeg not found: {detail}"),
+    ("error.FfprobeNotFound", "ffprobe not found: {detail}"),
+    ("error.ProbeError", "Probe error: {detail}"),
+    ("error.Timeout", "Timed out: {detail}"),
+    ("error.ConfirmationRequired", "Confirmation required: {detail}"),
+    ("error.ReadOnlyMode", "Read-only mode: {detail}"),
+    ("error.InsufficientLocalStorage", "Not enough space on this computer: {detail}"),
+    ("error.UpdateError", "Update failed: {detail}"),
+    (
+        "guidance.AdbNotFound",
+        "Install Android SDK Platform Tools or set the ADB path manually in Settings.",
+    ),
+    (
+        "guidance.AdbExecutionFailed",
+        "Check if ADB is configured correctly and the device is connected.",
+    ),
+    (
+        "guidance.InvalidPath",
+        "The specified path does not exist or is not accessible.",
+    ),
+    (
+        "guidance.IoError",
+        "A file system operation failed. Check permissions.",
+    ),
+    (
+        "guidance.SettingsError",
+        "Failed to save or load settings. Try restarting the app.",
+    ),
+    (
+        "guidance.DeviceNotFound",
+        "No device found with this serial. Ensure cable is connected and USB debugging is enabled.",
+    ),
+    (
+        "guidance.ScrcpyNotFound",
+        "Install scrcpy or set the scrcpy path manually in Settings.",
+    ),
+    (
+        "guidance.MirrorError",
+        "Mirror operation failed. Ensure device is connected and authorized.",
+    ),
+    (
+        "guidance.TransferError",
+        "File transfer failed. Check device connection and storage permissions.",
+    ),
+    (
+        "guidance.ThumbnailNotAvailable",
+        "Thumbnail preview not available for this media file.",
+    ),
+    (
+        "guidance.FfmpegNotFound",
+        "Install FFmpeg or set the FFmpeg path manually in Settings to enable transcoding.",
+    ),
+    (
+        "guidance.FfprobeNotFound",
+        "ffprobe ships with FFmpeg. Install FFmpeg or set the ffprobe path in Settings.",
+    ),
+    (
+        "guidance.ProbeError",
+        "Could not read media metadata. The file may be corrupt.",
+    ),
+    (
+        "guidance.Timeout",
+        "The device stopped responding. Reconnect the cable or raise the timeout in Settings.",
+    ),
+    (
+        "guidance.ConfirmationRequired",
+        "This action deletes or disables data. Confirm it again to continue.",
+    ),
+    (
+        "guidance.ReadOnlyMode",
+        "Read-only mode is on, so nothing on the device can be changed. Turn it off in Settings.",
+    ),
+    (
+        "guidance.InsufficientLocalStorage",
+        "Free up the amount shown on this computer, or choose a folder on another drive.",
+    ),
+    (
+        "guidance.UpdateError",
+        "Check your internet connection and try again, or download the latest release from GitHub.",
+    ),
+    (
+        "message.ios_not_supported",
+        "iPhones and iPads can't be mirrored or managed here. To import photos, unlock the device, tap Trust and copy them from its DCIM folder as a portable device.",
+    ),
+];
+
+const ID: &[(&str, &str)] = &[
+    ("error.AdbNotFound", "ADB tidak ditemukan: {detail}"),
+    ("error.AdbExecutionFailed", "Eksekusi ADB gagal: {detail}"),
+    ("error.InvalidPath", "Path tidak valid: {detail}"),
+    ("error.IoError", "Kesalahan IO: {detail}"),
+    ("error.SettingsError", "Kesalahan pengaturan: {detail}"),
+    ("error.DeviceNotFound", "Perangkat tidak ditemukan: {detail}"),
+    ("error.ScrcpyNotFound", "scrcpy tidak ditemukan: {detail}"),
+    ("error.MirrorError", "Kesalahan mirror: {detail}"),
+    ("error.TransferError", "Kesalahan transfer: {detail}"),
+    ("error.ThumbnailNotAvailable", "Thumbnail tidak tersedia: {detail}"),
+    ("error.FfmpegNotFound", "FFmpeg tidak ditemukan: {detail}"),
+    ("error.FfprobeNotFound", "ffprobe tidak ditemukan: {detail}"),
+    ("error.ProbeError", "Gagal membaca metadata: {detail}"),
+    ("error.Timeout", "Waktu habis: {detail}"),
+    ("error.ConfirmationRequired", "Perlu konfirmasi: {detail}"),
+    ("error.ReadOnlyMode", "Mode baca saja: {detail}"),
+    ("error.InsufficientLocalStorage", "Ruang disk tidak cukup: {detail}"),
+    ("error.UpdateError", "Pembaruan gagal: {detail}"),
+    (
+        "guidance.AdbNotFound",
+        "Pasang Android SDK Platform Tools atau atur path ADB secara manual di Pengaturan.",
+    ),
+    (
+        "guidance.AdbExecutionFailed",
+        "Periksa apakah ADB sudah dikonfigurasi dengan benar dan perangkat terhubung.",
+    ),
+    (
+        "guidance.InvalidPath",
+        "Path yang ditentukan tidak ada atau tidak dapat diakses.",
+    ),
+    (
+        "guidance.IoError",
+        "Operasi sistem berkas gagal. Periksa izin akses.",
+    ),
+    (
+        "guidance.SettingsError",
+        "Gagal menyimpan atau memuat pengaturan. Coba mulai ulang aplikasi.",
+    ),
+    (
+        "guidance.DeviceNotFound",
+        "Tidak ada perangkat dengan serial ini. Pastikan kabel terhubung dan USB debugging aktif.",
+    ),
+    (
+        "guidance.ScrcpyNotFound",
+        "Pasang scrcpy atau atur path scrcpy secara manual di Pengaturan.",
+    ),
+    (
+        "guidance.MirrorError",
+        "Mirror gagal. Pastikan perangkat terhubung dan sudah diotorisasi.",
+    ),
+    (
+        "guidance.TransferError",
+        "Transfer berkas gagal. Periksa koneksi perangkat dan izin penyimpanan.",
+    ),
+    (
+        "guidance.ThumbnailNotAvailable",
+        "Pratinjau thumbnail tidak tersedia untuk berkas media ini.",
+    ),
+    (
+        "guidance.FfmpegNotFound",
+        "Pasang FFmpeg atau atur path FFmpeg secara manual di Pengaturan untuk transcoding.",
+    ),
+    (
+        "guidance.FfprobeNotFound",
+        "ffprobe disertakan bersama FFmpeg. Pasang FFmpeg atau atur path ffprobe di Pengaturan.",
+    ),
+    (
+        "guidance.ProbeError",
+        "Metadata media tidak dapat dibaca. Berkas mungkin rusak.",
+    ),
+    (
+        "guidance.Timeout",
+        "Perangkat berhenti merespons. Sambungkan ulang kabel atau naikkan batas waktu di Pengaturan.",
+    ),
+    (
+        "guidance.ConfirmationRequired",
+        "Tindakan ini menghapus atau menonaktifkan data. Konfirmasi lagi untuk melanjutkan.",
+    ),
+    (
+        "guidance.ReadOnlyMode",
+        "Mode baca saja aktif, jadi tidak ada yang bisa diubah di perangkat. Matikan di Pengaturan.",
+    ),
+    (
+        "guidance.InsufficientLocalStorage",
+        "Kosongkan ruang sebesar yang ditampilkan di komputer ini, atau pilih folder di drive lain.",
+    ),
+    (
+        "guidance.UpdateError",
+        "Periksa koneksi internet lalu coba lagi, atau unduh rilis terbaru dari GitHub.",
+    ),
+    (
+        "message.ios_not_supported",
+        "iPhone dan iPad tidak dapat di-mirror atau dikelola di sini. Untuk mengimpor foto, buka kunci perangkat, ketuk Percayai dan salin dari folder DCIM-nya sebagai perangkat portabel.",
+    ),
+];
+
+fn catalog(language: &str) -> &'static [(&'static str, &'static str)] {
+    match language {
+        "id" => ID,
+        _ => EN,
+    }
+}
+
+/// Whether a catalog exists for the language code
+pub fn is_supported(language: &str) -> bool {
+    SUPPORTED_LANGUAGES
+        .iter()
+        .any(|(code, _)| *code == language)
+}
+
+/// Look up a key, falling back to English when the language lacks it
+pub fn translate(language: &str, key: &str) -> Option<&'static str> {
+    let lookup = |entries: &'static [(&'static str, &'static str)]| {
+        entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    };
+
+    lookup(catalog(language)).or_else(|| lookup(EN))
+}
+
+/// Translate a key and substitute `{name}` placeholders (returns the key if unknown)
+pub fn format(language: &str, key: &str, params: &HashMap<String, String>) -> String {
+    let mut text = translate(language, key).unwrap_or(key).to_string();
+    for (name, value) in params {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// Full catalog for a language with English fallbacks filled in
+pub fn catalog_map(language: &str) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = EN
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    for (k, v) in catalog(language) {
+        map.insert(k.to_string(), v.to_string());
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_have_same_keys() {
+        for (key, _) in EN {
+            assert!(
+                ID.iter().any(|(k, _)| k == key),
+                "missing Indonesian translation for {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_substitutes_params() {
+        let params = HashMap::from([("detail".to_string(), "adb shell ls".to_string())]);
+        assert_eq!(
+            format("id", "error.Timeout", &params),
+            "Waktu habis: adb shell ls"
+        );
+        assert_eq!(
+            format("fr", "error.IoError", &params),
+            "IO error: adb shell ls",
+            "unknown languages fall back to English"
+        );
+        assert_eq!(format("fr", "message.unknown", &params), "message.unknown");
+    }
+}
@@ -1,6 +1,7 @@
 //! Domain models for GesuBridge
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Device connection state
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -45,12 +46,300 @@ impl Device {
     }
 }
 
+/// Which device camera to mirror
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CameraFacing {
+    Front,
+    #[default]
+    Back,
+}
+
+impl CameraFacing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CameraFacing::Front => "front",
+            CameraFacing::Back => "back",
+        }
+    }
+}
+
+/// Orientation of the camera mirror window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CameraOrientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+/// Options for a camera mirror session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CameraOptions {
+    pub facing: CameraFacing,
+    /// Capture size, e.g. "1920x1080" (empty = scrcpy default)
+    pub resolution: String,
+    /// Disable audio forwarding
+    pub no_audio: bool,
+    pub orientation: CameraOrientation,
+    /// Record the session to the recordings folder
+    pub record: bool,
+    /// Small always-on-top window in the top-left screen corner
+    pub pip: bool,
+    /// Relaunch the mirror when scrcpy exits, e.g. after the camera app took the camera
+    pub auto_restart: bool,
+}
+
+impl Default for CameraOptions {
+    fn default() -> Self {
+        Self {
+            facing: CameraFacing::Back,
+            resolution: "1280x720".to_string(),
+            no_audio: true,
+            orientation: CameraOrientation::Portrait,
+            record: false,
+            pip: false,
+            auto_restart: false,
+        }
+    }
+}
+
+/// Modifier key for scrcpy shortcuts (`--shortcut-mod`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ShortcutMod {
+    Lctrl,
+    Rctrl,
+    Lalt,
+    Ralt,
+    Lsuper,
+    Rsuper,
+}
+
+impl ShortcutMod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShortcutMod::Lctrl => "lctrl",
+            ShortcutMod::Rctrl => "rctrl",
+            ShortcutMod::Lalt => "lalt",
+            ShortcutMod::Ralt => "ralt",
+            ShortcutMod::Lsuper => "lsuper",
+            ShortcutMod::Rsuper => "rsuper",
+        }
+    }
+}
+
+/// Options for a screen mirror session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct MirrorOptions {
+    /// Keys that trigger scrcpy shortcuts; any of them works
+    /// (empty = scrcpy default, left Alt or left Super)
+    pub shortcut_mod: Vec<ShortcutMod>,
+    /// Show touches on the device (the previous setting is restored on stop)
+    pub show_touches: bool,
+    /// Keep the device awake while plugged in during the session
+    pub stay_awake: bool,
+    /// Screen timeout to use during the session, restored on stop
+    pub screen_timeout_secs: Option<u32>,
+    /// Mirror only this region of the screen
+    pub crop: Option<CropRegion>,
+    /// Record the session to the recordings folder
+    pub record: bool,
+    /// Video bitrate (empty = scrcpy default, 8 Mbit/s)
+    pub bit_rate_mbps: Option<u32>,
+    /// Lower the bitrate when the Wi-Fi preflight finds a weak link
+    pub adapt_to_link: bool,
+    /// Limit the longer side of the video to this many pixels (empty = full size)
+    pub max_size: Option<u32>,
+    /// Restart at a lower bitrate or resolution when frames are being skipped
+    pub auto_tune: bool,
+    /// Turn the device off when the mirror window is closed
+    pub power_off_on_close: bool,
+    /// Mirror window title (empty = device model)
+    pub window_title: Option<String>,
+    /// Start the mirror window fullscreen
+    pub fullscreen: bool,
+    /// View only: no keyboard, mouse or clipboard input reaches the device
+    pub no_control: bool,
+}
+
+/// Result of the Wi-Fi preflight run before mirroring a wireless device
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LinkQuality {
+    /// Fastest adb round trip
+    pub latency_ms: u64,
+    /// Measured device-to-PC throughput in kilobits per second
+    pub throughput_kbps: u64,
+    /// Too slow or laggy for mirroring at the default bitrate
+    pub weak: bool,
+    /// Video bitrate the link can sustain
+    pub recommended_bit_rate_mbps: u32,
+}
+
+/// Battery state read from `dumpsys battery`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BatteryStatus {
+    /// Charge in percent
+    pub level: u8,
+    /// Plugged in, or charging/full according to the device
+    pub charging: bool,
+}
+
+/// At-a-glance state of a device: battery, shared storage and memory.
+/// A part that couldn't be read is None, with the reason in `errors`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceHealth {
+    pub device_serial: String,
+    pub battery: Option<BatteryStatus>,
+    /// Size of the shared storage (`/sdcard`)
+    pub storage_total_bytes: Option<u64>,
+    pub storage_free_bytes: Option<u64>,
+    pub ram_total_bytes: Option<u64>,
+    /// Memory in use, i.e. not available to new apps
+    pub ram_used_bytes: Option<u64>,
+    pub errors: Vec<String>,
+}
+
+/// What to do when a device with active sessions runs low on battery
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LowBatteryAction {
+    #[default]
+    Warn,
+    /// Stop the device's mirror, camera and audio sessions
+    Stop,
+}
+
+/// Guard for unattended sessions against draining the device battery
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BatteryPolicy {
+    pub enabled: bool,
+    /// Act when the level drops below this percentage while not charging
+    pub min_level: u8,
+    pub action: LowBatteryAction,
+}
+
+impl Default for BatteryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_level: 15,
+            action: LowBatteryAction::Warn,
+        }
+    }
+}
+
+/// Payload of the low battery event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LowBatteryAlert {
+    pub device_serial: String,
+    pub level: u8,
+    /// Whether the device's sessions were stopped
+    pub stopped: bool,
+}
+
+/// Payload of the device hot-plug events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceChange {
+    pub serial: String,
+    /// None once disconnected
+    pub state: Option<DeviceState>,
+    /// None when just connected
+    pub previous_state: Option<DeviceState>,
+}
+
+/// An iPhone or iPad plugged in over USB. iOS isn't supported; this lets
+/// the UI say so instead of showing nothing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppleDevice {
+    /// Name the system gives the device, e.g. "Apple iPhone"
+    pub name: String,
+    /// USB `vendor:product` ID in hex
+    pub usb_id: String,
+    /// Message catalog key of the explanation to show
+    pub notice_key: String,
+}
+
+/// What a device restarts into with `reboot_device`. Bootloader and
+/// fastboot are left out: flashing is out of scope for the app.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RebootMode {
+    /// A normal restart
+    System,
+    /// The recovery menu (factory reset, applying an OTA package)
+    Recovery,
+}
+
+impl RebootMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebootMode::System => "system",
+            RebootMode::Recovery => "recovery",
+        }
+    }
+}
+
+/// Payload of the clock drift event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockDrift {
+    pub device_serial: String,
+    /// Seconds the device's clock is ahead of this computer's; negative when behind
+    pub drift_secs: i64,
+}
+
+/// Payload of the settings error event: a state file didn't parse
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StateFileRecovery {
+    pub file: String,
+    /// Why it didn't parse
+    pub error: String,
+    /// The last good version was put back; otherwise defaults are used
+    pub restored_from_backup: bool,
+    /// Where the unreadable file was moved, for fixing by hand
+    pub corrupt_copy: String,
+}
+
+/// Screen region in device pixels, relative to the natural (portrait) orientation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CropRegion {
+    pub width: u32,
+    pub height: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Device display resolution in pixels
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DisplaySize {
+    pub width: u32,
+    pub height: u32,
+}
+
 /// Active mirror session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MirrorSession {
     pub device_serial: String,
     pub process_id: u32,
     pub started_at: String,
+    /// Whether the device screen was turned off (screen mirror only)
+    #[serde(default)]
+    pub screen_off: bool,
+    /// Options used to start the session (camera mirror only)
+    #[serde(default)]
+    pub camera_options: Option<CameraOptions>,
+    /// Options used to start the session (screen mirror only)
+    #[serde(default)]
+    pub mirror_options: Option<MirrorOptions>,
+    /// Local file scrcpy is recording to, if any
+    #[serde(default)]
+    pub recording_path: Option<String>,
+    /// Wi-Fi preflight result (screen mirror of a wireless device only)
+    #[serde(default)]
+    pub link_quality: Option<LinkQuality>,
 }
 
 /// Application settings
@@ -82,6 +371,251 @@ pub struct Settings {
     /// Whether FFmpeg was successfully detected/validated
     #[serde(default)]
     pub ffmpeg_available: bool,
+    /// FFmpeg version reported by `ffmpeg -version`
+    #[serde(default)]
+    pub ffmpeg_version: Option<String>,
+    /// User-configured ffprobe path
+    #[serde(default)]
+    pub ffprobe_path: Option<String>,
+    /// Resolved ffprobe path (after auto-detection)
+    #[serde(default)]
+    pub ffprobe_resolved_path: Option<String>,
+    /// Whether ffprobe was successfully detected/validated
+    #[serde(default)]
+    pub ffprobe_available: bool,
+    /// Last used camera options per device serial
+    #[serde(default)]
+    pub camera_defaults: HashMap<String, CameraOptions>,
+    /// Screen mirror options used when a session is started without any
+    #[serde(default)]
+    pub mirror_options: MirrorOptions,
+    /// Convert MKV recordings to MP4 with ffmpeg when a session stops
+    #[serde(default)]
+    pub remux_recordings: bool,
+    /// Warn about or stop sessions on devices running low on battery
+    #[serde(default)]
+    pub battery_policy: BatteryPolicy,
+    /// Post a notification on the device when a mirror session starts controlling it
+    #[serde(default)]
+    pub notify_on_mirror: bool,
+    /// Local folder of APKs checked for newer versions of installed apps
+    #[serde(default)]
+    pub apk_repository_dir: Option<String>,
+    /// Packages disabled through the bloatware manager, per device serial
+    #[serde(default)]
+    pub disabled_packages: HashMap<String, Vec<String>>,
+    /// UI language code for backend-generated messages
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Timeout for adb shell/query commands, in seconds
+    #[serde(default = "default_command_timeout_secs")]
+    pub command_timeout_secs: u64,
+    /// Timeout for a single adb push/pull, in seconds
+    #[serde(default = "default_transfer_timeout_secs")]
+    pub transfer_timeout_secs: u64,
+    /// Attempts for idempotent adb operations that fail transiently (1 = no retry)
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: u32,
+    /// Delay before the first retry, doubled on each further retry (ms)
+    #[serde(default = "default_retry_initial_delay_ms")]
+    pub retry_initial_delay_ms: u64,
+    /// Configured scheduled sync/backup jobs
+    #[serde(default)]
+    pub sync_jobs: Vec<SyncJob>,
+    /// Named quick-send destinations
+    #[serde(default)]
+    pub transfer_presets: Vec<TransferPreset>,
+    /// Remembered per-device state, keyed by serial
+    #[serde(default)]
+    pub device_registry: HashMap<String, DeviceRecord>,
+    /// Named sets of devices for group operations
+    #[serde(default)]
+    pub device_groups: Vec<DeviceGroup>,
+    /// Setup profiles for new devices
+    #[serde(default)]
+    pub provisioning_profiles: Vec<ProvisioningProfile>,
+    /// Guest mode: refuse everything that changes a device (push, delete,
+    /// install, input), e.g. on someone else's phone or in demos
+    #[serde(default)]
+    pub read_only_mode: bool,
+    /// Folders below a storage volume (e.g. `Pictures/Banking`) left out of
+    /// listings and pulls unless a request includes private folders
+    #[serde(default)]
+    pub private_folders: Vec<String>,
+    /// Thumbnails made in the background for the first items of a folder
+    /// when it's listed (0 = off)
+    #[serde(default)]
+    pub thumbnail_warmup_count: usize,
+    /// Local folders pulled into lately, most recent first
+    #[serde(default)]
+    pub recent_destinations: Vec<String>,
+    /// Local folders the user pinned as pull destinations
+    #[serde(default)]
+    pub pinned_destinations: Vec<String>,
+    /// Pulls without a destination go to `Downloads/GesuBridge/<device>`
+    /// instead of Downloads itself
+    #[serde(default = "default_true")]
+    pub device_pull_folders: bool,
+    /// First-run setup steps whose check passed
+    #[serde(default)]
+    pub onboarding_completed: Vec<OnboardingStep>,
+    /// Release channel updates are checked on
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// A pending update put off or set to install at a later time
+    #[serde(default)]
+    pub update_plan: Option<UpdatePlan>,
+}
+
+/// Release channel the updater follows
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Prereleases too
+    Beta,
+}
+
+/// What to do with one pending update version
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePlanKind {
+    /// Don't offer it again before `at`
+    Deferred,
+    /// Install it at `at`, once no transfer is running
+    Scheduled,
+}
+
+/// A choice made about one pending update
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdatePlan {
+    pub version: String,
+    pub kind: UpdatePlanKind,
+    /// RFC 3339
+    pub at: String,
+}
+
+/// An update available on the chosen channel
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingUpdate {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Release notes, as written in the release (usually Markdown)
+    pub notes: Option<String>,
+    /// The plan for this version, if one was made
+    pub plan: Option<UpdatePlan>,
+    /// Put off until the plan's time, so not worth a notice yet
+    pub deferred: bool,
+}
+
+/// A step of first-run setup
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// adb is found and runs
+    ToolsConfigured,
+    /// A device accepted this computer and is listed as ready
+    DeviceAuthorized,
+    /// A push, pull or sync copied files
+    FirstTransfer,
+}
+
+/// Where one setup step stands
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OnboardingStepStatus {
+    pub step: OnboardingStep,
+    /// Marked done through `complete_onboarding_step`
+    pub completed: bool,
+    /// Done, or its check passes now so it can be completed
+    pub ready: bool,
+}
+
+/// First-run setup progress
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OnboardingState {
+    /// Every step, in wizard order
+    pub steps: Vec<OnboardingStepStatus>,
+    /// First step not completed yet
+    pub next_step: Option<OnboardingStep>,
+    pub finished: bool,
+}
+
+/// A fix-it action on the troubleshooting screen
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TroubleshootAction {
+    /// Stop the adb server and start a fresh one
+    RestartAdbServer,
+    /// Look for adb, scrcpy, ffmpeg and ffprobe again
+    RedetectTools,
+    /// Echo a token through `adb shell` and check it comes back
+    TestDeviceConnection,
+    /// Open the Platform Tools download page in the browser
+    OpenPlatformToolsDownload,
+}
+
+/// What a troubleshooting action found
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TroubleshootResult {
+    pub action: TroubleshootAction,
+    pub success: bool,
+    /// One-line outcome, or the error message when it failed
+    pub message: String,
+    /// Extra lines, e.g. each tool's detected path
+    pub details: Vec<String>,
+    /// `AppError` kind when it failed, for the localized guidance
+    pub error_kind: Option<String>,
+    pub duration_ms: u64,
+    /// Action worth trying next when this one failed
+    pub suggested_next: Option<TroubleshootAction>,
+}
+
+/// What the app remembers about a device between sessions
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DeviceRecord {
+    /// Last device folder (below /sdcard) pushed to
+    #[serde(default)]
+    pub last_device_dir: Option<String>,
+    /// Last local folder pulled into
+    #[serde(default)]
+    pub last_local_dest: Option<String>,
+    /// `ip:port` the device was last connected through, reconnected on startup
+    #[serde(default)]
+    pub wireless_endpoint: Option<String>,
+    /// Name the user gave the device, used for its Downloads subfolder
+    #[serde(default)]
+    pub nickname: Option<String>,
+}
+
+/// A quick choice of local folder for pull dialogs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocalDestination {
+    pub path: String,
+    pub pinned: bool,
+    /// False when the folder was removed since it was used
+    pub exists: bool,
+}
+
+fn default_command_timeout_secs() -> u64 {
+    30
+}
+
+fn default_transfer_timeout_secs() -> u64 {
+    3600
+}
+
+fn default_retry_attempts() -> u32 {
+    3
+}
+
+fn default_retry_initial_delay_ms() -> u64 {
+    300
+}
+
+fn default_language() -> String {
+    crate::domain::i18n::DEFAULT_LANGUAGE.to_string()
 }
 
 impl Settings {
@@ -97,10 +631,55 @@ impl Settings {
             ffmpeg_path: None,
             ffmpeg_resolved_path: None,
             ffmpeg_available: false,
+            ffmpeg_version: None,
+            ffprobe_path: None,
+            ffprobe_resolved_path: None,
+            ffprobe_available: false,
+            camera_defaults: HashMap::new(),
+            mirror_options: MirrorOptions::default(),
+            remux_recordings: false,
+            battery_policy: BatteryPolicy::default(),
+            notify_on_mirror: false,
+            apk_repository_dir: None,
+            disabled_packages: HashMap::new(),
+            language: default_language(),
+            command_timeout_secs: default_command_timeout_secs(),
+            transfer_timeout_secs: default_transfer_timeout_secs(),
+            retry_attempts: default_retry_attempts(),
+            retry_initial_delay_ms: default_retry_initial_delay_ms(),
+            sync_jobs: Vec::new(),
+            transfer_presets: Vec::new(),
+            device_registry: HashMap::new(),
+            device_groups: Vec::new(),
+            provisioning_profiles: Vec::new(),
+            read_only_mode: false,
+            private_folders: Vec::new(),
+            thumbnail_warmup_count: 0,
+            recent_destinations: Vec::new(),
+            pinned_destinations: Vec::new(),
+            device_pull_folders: true,
+            onboarding_completed: Vec::new(),
+            update_channel: UpdateChannel::Stable,
+            update_plan: None,
         }
     }
 }
 
+/// What to do when a transfer destination already exists
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Replace the existing file
+    #[default]
+    Overwrite,
+    /// Leave the existing file and skip this one
+    Skip,
+    /// Keep both, writing the new file as `name (1).ext`
+    Rename,
+    /// Replace only if the source is newer than the existing file
+    KeepNewer,
+}
+
 /// Transfer status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -110,6 +689,30 @@ pub enum TransferStatus {
     Complete,
     Failed,
     Cancelled,
+    /// Device disconnected mid-transfer; resumed when it reappears
+    Interrupted,
+    /// Not transferred because of the conflict policy
+    Skipped,
+    /// Passed to another program (Bluetooth wizard, Quick Share) that doesn't
+    /// report the outcome
+    #[serde(rename = "handed_off")]
+    HandedOff,
+}
+
+/// A saved destination and options for recurring pushes, e.g. "Music -> /sdcard/Music"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferPreset {
+    /// Empty when creating a preset; assigned on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Folder below /sdcard, same form as `default_device_dir`
+    pub dest_dir: String,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+    /// Send as one tar archive (see `push_files`)
+    #[serde(default)]
+    pub archive: bool,
 }
 
 /// Represents a file transfer operation
@@ -124,6 +727,16 @@ pub struct TransferItem {
     pub status: TransferStatus,
     pub error: Option<String>,
     pub started_at: String,
+    #[serde(default)]
+    pub device_serial: String,
+}
+
+/// Whether Google's Quick Share for Windows is installed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuickShareStatus {
+    pub installed: bool,
+    /// Path of the Quick Share executable
+    pub path: Option<String>,
 }
 
 // ============================================
@@ -157,8 +770,17 @@ pub struct MediaItem {
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub duration_ms: Option<u64>,
+    /// When it was taken, as ISO-8601 UTC (`2024-02-01T08:59:00Z`)
     pub date_taken: Option<String>,
+    /// The device's UTC offset when listed (`+07:00`), for showing times
+    /// in the device's own time
+    #[serde(default)]
+    pub timezone: Option<String>,
     pub thumbnail_url: Option<String>,
+    /// A still with an embedded video (motion photo). Guessed from the name
+    /// when listing; `detect_motion_photos` checks the file contents.
+    #[serde(default)]
+    pub motion_photo: bool,
 }
 
 /// Media filter for listing
@@ -171,6 +793,116 @@ pub enum MediaFilter {
     Videos,
 }
 
+impl MediaFilter {
+    /// Whether media of this type passes the filter
+    pub fn accepts(&self, media_type: &MediaType) -> bool {
+        match self {
+            MediaFilter::All => true,
+            MediaFilter::Images => *media_type == MediaType::Image,
+            MediaFilter::Videos => *media_type == MediaType::Video,
+        }
+    }
+}
+
+/// Which items of a folder to pull without picking them by hand. Options
+/// combine: `newest` applies last, to whatever the others left.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediaSelection {
+    /// Only the newest N items
+    #[serde(default)]
+    pub newest: Option<usize>,
+    /// Only items modified at or after this time (`2024-01-30`,
+    /// `2024-01-30 10:30` or RFC 3339)
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Only items newer than the newest one already pulled from the folder
+    #[serde(default)]
+    pub since_last_pull: bool,
+}
+
+/// Why photos were grouped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaGroupKind {
+    /// Burst: a burst file name, or shot within seconds of each other
+    Burst,
+    /// Near-duplicates by perceptual hash of their thumbnails
+    Similar,
+}
+
+/// Photos that are (nearly) the same shot, so only one needs pulling
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediaGroup {
+    pub kind: MediaGroupKind,
+    /// Members, oldest first
+    pub paths: Vec<String>,
+    /// Suggested shot to keep: the burst cover if marked, else the largest file
+    pub best_path: String,
+    pub total_bytes: u64,
+}
+
+/// A storage volume's camera folder in an offload plan
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OffloadVolume {
+    /// Volume root, e.g. `/storage/emulated/0` or `/storage/1234-ABCD`
+    pub root: String,
+    pub dcim_path: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+}
+
+/// One month of camera media in an offload plan
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OffloadMonth {
+    /// `YYYY-MM`, by the device's clock
+    pub month: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Files already on this computer
+    pub backed_up_count: usize,
+    pub backed_up_bytes: u64,
+    /// Device paths still to pull, newest first
+    pub pending_paths: Vec<String>,
+}
+
+/// Camera media on a device and what an offload would still have to pull
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CameraOffloadPlan {
+    pub device_serial: String,
+    pub volumes: Vec<OffloadVolume>,
+    /// Newest month first
+    pub months: Vec<OffloadMonth>,
+    pub total_count: usize,
+    pub total_bytes: u64,
+    pub pending_count: usize,
+    pub pending_bytes: u64,
+}
+
+/// Media metadata parsed from ffprobe JSON output
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProbeResult {
+    pub format_name: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub bit_rate: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub rotation: Option<i32>,
+    pub creation_time: Option<String>,
+    /// Where the video was recorded, from the container's location tag
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Where a photo was taken, from its EXIF GPS tags
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediaLocation {
+    pub path: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
 /// Transfer result for media operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaTransferResult {
@@ -179,4 +911,797 @@ pub struct MediaTransferResult {
     pub success: bool,
     pub error: Option<String>,
     pub size_bytes: u64,
+    /// Not pulled because of the conflict policy (reason in `error`)
+    #[serde(default)]
+    pub skipped: bool,
+    /// Video extracted from a pulled motion photo, when asked for
+    #[serde(default)]
+    pub motion_video_path: Option<String>,
+    /// The device file name, when it wasn't valid here and was saved under
+    /// the name in `dest_path` instead
+    #[serde(default)]
+    pub renamed_from: Option<String>,
+}
+
+// ============================================
+// Destructive Operation Models
+// ============================================
+
+/// An item removed (or, in a dry run, that would be removed) by a destructive operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffectedItem {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Why the item was skipped or failed; None when it is (or would be) affected
+    pub error: Option<String>,
+}
+
+/// One-time permission to run a destructive command, from `request_confirmation`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfirmationToken {
+    pub token: String,
+    /// Command the token is good for
+    pub action: String,
+    pub expires_in_secs: u64,
+}
+
+/// Report for delete/uninstall/sync style operations, identical in shape for dry runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DestructiveOpReport {
+    pub dry_run: bool,
+    pub items: Vec<AffectedItem>,
+    /// Sum of sizes of items without an error
+    pub total_bytes: u64,
+}
+
+impl DestructiveOpReport {
+    pub fn new(dry_run: bool, items: Vec<AffectedItem>) -> Self {
+        let total_bytes = items
+            .iter()
+            .filter(|i| i.error.is_none())
+            .map(|i| i.size_bytes)
+            .sum();
+
+        Self {
+            dry_run,
+            items,
+            total_bytes,
+        }
+    }
+}
+
+// ============================================
+// Sync Models
+// ============================================
+
+/// Which side of a folder pair is treated as the source
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncDirection {
+    /// Local folder is the source; changed files are pushed
+    ToDevice,
+    /// Device folder is the source; changed files are pulled
+    FromDevice,
+    /// Missing files are copied both ways; on conflict the newer copy wins
+    TwoWay,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncActionKind {
+    Push,
+    Pull,
+}
+
+/// One planned file copy in a sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncAction {
+    /// Path relative to both roots, using `/` separators
+    pub relative_path: String,
+    pub kind: SyncActionKind,
+    /// Short explanation, e.g. "missing on device" or "size differs"
+    pub reason: String,
+    pub size_bytes: u64,
+    /// Relative path written instead of `relative_path` (rename conflict policy)
+    #[serde(default)]
+    pub target_path: Option<String>,
+}
+
+/// What a sync would do, computed without transferring anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPlan {
+    pub local_root: String,
+    pub remote_root: String,
+    pub direction: SyncDirection,
+    pub actions: Vec<SyncAction>,
+    /// Files present on both sides that are already identical
+    pub unchanged: usize,
+    /// Differing files left alone because of the conflict policy
+    #[serde(default)]
+    pub skipped_conflicts: usize,
+    pub total_bytes: u64,
+}
+
+/// Outcome of executing a sync plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub plan: SyncPlan,
+    pub transferred: usize,
+    /// Actions that failed, with the error for each
+    pub failures: Vec<AffectedItem>,
+}
+
+/// A sync profile the scheduler runs whenever its device is connected and the job is due
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncJob {
+    /// Empty when creating a job; assigned on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub device_serial: String,
+    pub local_root: String,
+    pub remote_root: String,
+    pub direction: SyncDirection,
+    #[serde(default)]
+    pub compare_hash: bool,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+    /// Minimum time between runs
+    pub interval_minutes: u32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// RFC 3339 time of the last run, successful or not
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One execution of a scheduled sync job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncJobRun {
+    pub job_id: String,
+    pub job_name: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    pub transferred: usize,
+    pub failed: usize,
+    /// Why the run failed before any file was copied (e.g. folder missing)
+    pub error: Option<String>,
+}
+
+// ============================================
+// Batch Progress Models
+// ============================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchKind {
+    Push,
+    Pull,
+    /// Provisioning steps; byte counts stay at zero
+    Provision,
+}
+
+/// Combined progress of a multi-file push or pull, emitted after every file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    pub kind: BatchKind,
+    pub total_files: usize,
+    pub completed_files: usize,
+    pub total_bytes: u64,
+    /// Bytes of files finished so far, including failed and skipped ones
+    pub completed_bytes: u64,
+    /// File being transferred now; None once the batch is finished
+    pub current_file: Option<String>,
+    pub failures: Vec<AffectedItem>,
+    pub finished: bool,
+}
+
+/// How clipboard text was delivered to the device
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardSendMode {
+    /// A link opened in the device's default app
+    OpenedUrl,
+    /// Text handed to the share sheet
+    SharedText,
+}
+
+// ============================================
+// Drop Analysis Models
+// ============================================
+
+/// Type of a dropped file, by extension
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DropFileKind {
+    Image,
+    Video,
+    Audio,
+    Apk,
+    /// Split APK bundle (.apks, .xapk, .apkm)
+    SplitApk,
+    Obb,
+    Other,
+}
+
+/// What a drop should most likely do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DropAction {
+    Push,
+    Install,
+    /// Copy expansion files to Android/obb/<package>
+    CopyObb,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropFile {
+    pub path: String,
+    pub size_bytes: u64,
+    pub kind: DropFileKind,
+}
+
+/// Summary of dropped files and folders, with a suggested action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropAnalysis {
+    /// Every file found, with folders expanded
+    pub files: Vec<DropFile>,
+    pub total_files: usize,
+    pub total_bytes: u64,
+    pub media_files: usize,
+    pub apk_files: usize,
+    pub obb_files: usize,
+    pub other_files: usize,
+    pub suggested_action: DropAction,
+    /// Device folder (below /sdcard) that fits the content, if there is an obvious one
+    pub suggested_dest: Option<String>,
+    /// Dropped paths that no longer exist
+    pub missing: Vec<String>,
+    /// True if expansion stopped at the file limit
+    pub truncated: bool,
+}
+
+// ============================================
+// Install Models
+// ============================================
+
+/// Outcome of installing one package on one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallResult {
+    pub serial: String,
+    pub path: String,
+    pub success: bool,
+    /// "Success", or the failure reason (e.g. INSTALL_FAILED_VERSION_DOWNGRADE)
+    pub message: String,
+}
+
+/// An installed third-party app in an exported app list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppListEntry {
+    pub package: String,
+    #[serde(default)]
+    pub version_code: Option<String>,
+    /// Package that installed the app (e.g. `com.android.vending`), if known
+    #[serde(default)]
+    pub installer: Option<String>,
+}
+
+/// Installed apps of a device, exported for reinstalling elsewhere
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppList {
+    pub device_serial: String,
+    pub exported_at: String,
+    pub apps: Vec<AppListEntry>,
+}
+
+/// Result of reinstalling an app list from a folder of APKs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkInstallReport {
+    pub installed: Vec<InstallResult>,
+    /// Packages with no matching APK in the folder
+    pub missing: Vec<String>,
+}
+
+/// Package, version and SDK levels read from a local APK's manifest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApkInfo {
+    pub package: String,
+    pub version_code: u64,
+    pub version_name: Option<String>,
+    #[serde(default)]
+    pub min_sdk: Option<u32>,
+    #[serde(default)]
+    pub target_sdk: Option<u32>,
+}
+
+/// What the install UI shows about a local APK before installing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApkPreview {
+    pub path: String,
+    pub info: ApkInfo,
+    /// Launcher icon as a data URL, when the APK has a bitmap one
+    pub icon: Option<String>,
+}
+
+/// An installed app with a newer APK in the local APK repository
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApkUpdate {
+    pub package: String,
+    pub installed_version_code: u64,
+    pub available_version_code: u64,
+    pub available_version_name: Option<String>,
+    pub apk_path: String,
+}
+
+/// A disable-safe preinstalled package found on a device
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BloatwarePackage {
+    pub package: String,
+    pub label: String,
+    pub disabled: bool,
+}
+
+/// Outcome of enabling or disabling one package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageOpResult {
+    pub package: String,
+    pub success: bool,
+    /// Output of `pm`, or the failure reason
+    pub message: String,
+}
+
+/// Combined report for a routed drop: installs and pushes across all target devices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropRouteReport {
+    pub serials: Vec<String>,
+    pub installs: Vec<InstallResult>,
+    pub transfers: Vec<TransferItem>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+// ============================================
+// Device Group Models
+// ============================================
+
+/// A named set of devices that commands can target together (labs, classrooms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGroup {
+    /// Empty when creating a group; assigned on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub serials: Vec<String>,
+}
+
+/// Result of a group operation on one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceOpResult {
+    pub serial: String,
+    pub success: bool,
+    /// Command output or a short summary
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Consolidated per-device results of a group operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupOpReport {
+    pub group_id: String,
+    pub results: Vec<DeviceOpResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+// ============================================
+// Provisioning Models
+// ============================================
+
+/// Android `settings` namespace
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsNamespace {
+    System,
+    Secure,
+    Global,
+}
+
+impl SettingsNamespace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingsNamespace::System => "system",
+            SettingsNamespace::Secure => "secure",
+            SettingsNamespace::Global => "global",
+        }
+    }
+}
+
+/// A value in the Android `settings` store
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceSetting {
+    pub namespace: SettingsNamespace,
+    pub key: String,
+    pub value: String,
+}
+
+/// A process running on a device
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceProcess {
+    pub pid: u32,
+    /// Owning user, e.g. `root`, `shell` or `u0_a163` for an app
+    pub user: String,
+    /// Resident memory in KiB
+    pub rss_kb: u64,
+    pub name: String,
+}
+
+/// Connection state of a wireless (`ip:port`) device
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WirelessLinkState {
+    Connected,
+    /// Dropped off; `adb connect` is being retried with backoff
+    Reconnecting,
+    /// Retries ran out; the device has to be connected again by hand
+    Lost,
+}
+
+/// Status of a wireless device watched by the connection keeper
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WirelessStatus {
+    /// `ip:port` the device is connected through, which is also its serial
+    pub endpoint: String,
+    pub state: WirelessLinkState,
+    /// Reconnect attempts since the device dropped off
+    pub attempts: u32,
+    /// Seconds until the next reconnect attempt, while reconnecting
+    pub next_retry_secs: Option<u64>,
+}
+
+/// Something that happened with a device, for its timeline
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceEventKind {
+    Connected,
+    Disconnected,
+    MirrorStarted,
+    MirrorStopped,
+    FilesPushed,
+    FilesPulled,
+    Synced,
+}
+
+/// An entry of a device's timeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceEvent {
+    pub kind: DeviceEventKind,
+    /// RFC 3339 time the event happened
+    pub at: String,
+    /// Short summary, e.g. how many files were copied where
+    pub detail: Option<String>,
+}
+
+/// A device file copied to this computer, from the pull history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PulledFile {
+    pub remote_path: String,
+    pub size_bytes: u64,
+    /// SHA-256 of the local copy right after the pull
+    pub sha256: Option<String>,
+    pub local_path: String,
+    /// RFC 3339 time of the latest pull
+    pub pulled_at: String,
+    /// The local copy is still there; checked whenever the history is read
+    #[serde(default)]
+    pub local_exists: bool,
+}
+
+/// How a device's network relates to this computer's, for wireless ADB
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkMatch {
+    /// The computer reaches the device's Wi-Fi subnet directly
+    SameNetwork,
+    /// Both are online, but on different networks
+    DifferentNetwork,
+    /// The device isn't connected to a Wi-Fi network
+    DeviceOffWifi,
+}
+
+/// Result of checking whether wireless ADB can work between this computer and a device
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WirelessNetworkCheck {
+    pub device_serial: String,
+    /// Device address on the Wi-Fi network it joined, with prefix (`192.168.1.23/24`)
+    pub device_address: Option<String>,
+    /// Device address on its own hotspot while the hotspot is on
+    pub hotspot_address: Option<String>,
+    /// Address this computer would reach the device from
+    pub host_address: Option<String>,
+    pub network: NetworkMatch,
+    /// The computer has joined the device's hotspot, so it can connect over it
+    pub host_on_hotspot: bool,
+}
+
+/// A device switched to TCP mode with `enable_tcpip`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TcpipMode {
+    pub device_serial: String,
+    pub port: u16,
+    /// `ip:port` to connect to, when the device was on Wi-Fi
+    pub endpoint: Option<String>,
+}
+
+/// Outcome of turning a device's hotspot on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HotspotStart {
+    pub started: bool,
+    /// The device refused, so its hotspot settings were opened for the user instead
+    pub settings_opened: bool,
+    pub message: String,
+}
+
+/// Network traffic of one app (Linux uid) since the device's stats began
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetworkUsage {
+    pub uid: i32,
+    /// Packages sharing the uid; empty for system uids and removed apps
+    pub packages: Vec<String>,
+    /// Package name(s), or a name for system uids like `system` or `Removed apps`
+    pub label: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// Part of the traffic that went over mobile data
+    pub mobile_rx_bytes: u64,
+    pub mobile_tx_bytes: u64,
+}
+
+/// One reading of the live performance stream
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PerfSample {
+    pub device_serial: String,
+    /// App being profiled; `None` samples the whole device
+    pub package: Option<String>,
+    /// Unix time in milliseconds
+    pub timestamp_ms: i64,
+    /// CPU use as a share of all cores, of the app's process or the whole device
+    pub cpu_percent: Option<f32>,
+    /// Resident memory of the app, or memory in use on the device, in KiB
+    pub mem_kb: Option<u64>,
+    /// Battery current in mA; the sign for charging vs discharging varies by vendor
+    pub battery_current_ma: Option<i32>,
+    /// Frames composited per second by SurfaceFlinger
+    pub fps: Option<f32>,
+}
+
+/// Invocations of one external tool (adb, ffmpeg, ...) since the app started
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolMetrics {
+    pub tool: String,
+    pub calls: u64,
+    /// Calls that couldn't start, exited with an error or timed out
+    pub failures: u64,
+    pub timeouts: u64,
+    /// Share of calls that failed, 0.0 to 1.0
+    pub failure_rate: f64,
+    pub total_ms: u64,
+    pub average_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Where a watched value is read from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchNamespace {
+    System,
+    Secure,
+    Global,
+    /// A system property, read with `getprop`
+    Prop,
+}
+
+impl WatchNamespace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchNamespace::System => "system",
+            WatchNamespace::Secure => "secure",
+            WatchNamespace::Global => "global",
+            WatchNamespace::Prop => "prop",
+        }
+    }
+}
+
+/// A watch started by `watch_setting`, with the value it started from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettingWatch {
+    pub watch_id: String,
+    /// `None` when unset
+    pub value: Option<String>,
+}
+
+/// A watched setting or property that changed value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettingChange {
+    /// Id returned by `watch_setting`
+    pub watch_id: String,
+    pub device_serial: String,
+    pub namespace: WatchNamespace,
+    pub key: String,
+    /// `None` when the value was unset
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// Unix time in milliseconds
+    pub timestamp_ms: i64,
+}
+
+/// A default-app role a user picks a handler for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultAppRole {
+    Browser,
+    Sms,
+    Dialer,
+    Home,
+    Assistant,
+}
+
+impl DefaultAppRole {
+    pub const ALL: [DefaultAppRole; 5] = [
+        DefaultAppRole::Browser,
+        DefaultAppRole::Sms,
+        DefaultAppRole::Dialer,
+        DefaultAppRole::Home,
+        DefaultAppRole::Assistant,
+    ];
+
+    /// Name of the role in `RoleManager`
+    pub fn role_name(&self) -> &'static str {
+        match self {
+            DefaultAppRole::Browser => "android.app.role.BROWSER",
+            DefaultAppRole::Sms => "android.app.role.SMS",
+            DefaultAppRole::Dialer => "android.app.role.DIALER",
+            DefaultAppRole::Home => "android.app.role.HOME",
+            DefaultAppRole::Assistant => "android.app.role.ASSISTANT",
+        }
+    }
+}
+
+/// The app currently holding a default-app role
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DefaultApp {
+    pub role: DefaultAppRole,
+    /// `None` when no app is set and the device asks each time
+    pub holder: Option<String>,
+}
+
+/// Verification state of one web domain an app declares links for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppLinkDomain {
+    pub domain: String,
+    /// `verified`, `none`, `approved`, ... or a numeric error code
+    pub state: String,
+}
+
+/// How an app opens web links (Android 12+ domain verification)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppLinks {
+    pub package: String,
+    pub domains: Vec<AppLinkDomain>,
+}
+
+/// A local file pushed during provisioning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningFile {
+    pub local_path: String,
+    /// Device folder, e.g. `/sdcard/Documents`
+    pub device_dir: String,
+}
+
+/// Steps for setting up a new device: installs, then pushes, then settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningProfile {
+    /// Empty when creating a profile; assigned on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub apks: Vec<String>,
+    #[serde(default)]
+    pub files: Vec<ProvisioningFile>,
+    #[serde(default)]
+    pub settings: Vec<DeviceSetting>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProvisioningStepKind {
+    Install,
+    Push,
+    Setting,
+}
+
+/// Outcome of one provisioning step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningStepResult {
+    pub kind: ProvisioningStepKind,
+    /// APK or file path, or `namespace/key` for settings
+    pub target: String,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Final report of applying a profile to a device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningReport {
+    pub serial: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    pub steps: Vec<ProvisioningStepResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+// ============================================
+// Device Comparison Models
+// ============================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComparisonCategory {
+    Property,
+    Storage,
+    Package,
+}
+
+/// One compared value across devices, in the same order as the serials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonRow {
+    pub category: ComparisonCategory,
+    /// Property name, storage figure, or package name
+    pub key: String,
+    /// `None` when missing on that device (e.g. package not installed)
+    pub values: Vec<Option<String>>,
+    pub differs: bool,
+}
+
+/// Side-by-side report of several devices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceComparison {
+    pub serials: Vec<String>,
+    /// Per-device error when some information couldn't be read
+    pub errors: Vec<Option<String>>,
+    pub rows: Vec<ComparisonRow>,
+}
+
+// ============================================
+// Recording Models
+// ============================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingKind {
+    Screen,
+    Camera,
+    /// Device microphone only
+    Audio,
+}
+
+/// A recording in the recordings folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub path: String,
+    pub file_name: String,
+    pub kind: RecordingKind,
+    /// Device serial as written in the file name (':' and '.' become '_')
+    pub device_serial: String,
+    pub size_bytes: u64,
+    /// Read with ffprobe; `None` when ffprobe isn't available
+    pub duration_ms: Option<u64>,
+    pub created_at: Option<String>,
 }
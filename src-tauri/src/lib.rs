@@ -1,23 +1,96 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
 mod commands;
-mod domain;
-mod services;
+pub mod domain;
+pub mod services;
+mod state;
 
-use commands::adb::list_devices;
+use commands::adb::{
+    check_wireless_network, compare_devices, connect_device, connect_over_hotspot,
+    disconnect_device, disconnect_wireless, enable_tcpip, get_battery_status, get_device_health,
+    get_device_timeline, get_wireless_status, list_apple_devices, list_devices, reboot_device,
+    start_hotspot, stop_hotspot,
+};
+use commands::apps::{
+    clear_default_app, export_network_usage, force_stop, get_app_links, get_default_apps,
+    get_network_usage, kill_process, list_device_processes, reset_app_links, set_app_links_allowed,
+    set_default_app,
+};
+use commands::bloatware::{disable_bloatware, list_bloatware, restore_bloatware};
 use commands::bluetooth::{open_bluetooth_receive, open_bluetooth_send, open_bluetooth_settings};
+use commands::clipboard::send_clipboard_text;
+use commands::confirmation::request_confirmation;
+use commands::group::{
+    delete_device_group, get_device_groups, install_to_group, push_to_group, save_device_group,
+    screenshot_group, shell_on_group,
+};
+use commands::install::{
+    bulk_install_from_list, check_apk_updates, export_app_list, get_app_icon, inspect_apk,
+    install_apks, route_drop,
+};
 use commands::media::{
-    get_default_media_root, get_media_thumbnail, list_device_folders, list_device_media,
-    open_media_folder, preview_media, pull_media_files,
+    delete_media_files, detect_motion_photos, get_default_media_root, get_media_locations,
+    get_media_thumbnail, get_pull_history, group_similar_photos, list_device_folders,
+    list_device_media, open_media_file_with, open_media_folder, plan_camera_offload, preview_media,
+    probe_media_file, pull_folder, pull_media_files, pull_media_selection, reveal_media_file,
+    save_preview_as,
 };
 use commands::mirror::{
-    get_camera_sessions, get_mirror_sessions, start_camera, start_mirror, stop_camera, stop_mirror,
+    capture_mirror_frame, get_audio_sessions, get_camera_options, get_camera_sessions,
+    get_display_size, get_mirror_sessions, measure_wifi_link, start_audio_capture, start_camera,
+    start_headless_mirror, start_kiosk, start_mirror, stop_audio_capture, stop_camera, stop_kiosk,
+    stop_mirror,
+};
+use commands::mtp::{list_mtp_devices, list_mtp_folders, list_mtp_media, pull_mtp_files};
+use commands::onboarding::{complete_onboarding_step, get_onboarding_state};
+use commands::perf::{get_perf_metrics, start_perf_stream, stop_perf_stream};
+use commands::provisioning::{
+    apply_provisioning, delete_provisioning_profile, get_provisioning_profiles,
+    save_provisioning_profile,
+};
+use commands::quick_share::{get_quick_share_status, send_with_quick_share};
+use commands::recording::{
+    delete_recording, list_recordings, open_recordings_folder, remux_recording,
 };
 use commands::settings::{
-    detect_adb, detect_ffmpeg, detect_scrcpy, get_settings, set_adb_path, set_ffmpeg_path,
-    set_scrcpy_path,
+    detect_adb, detect_ffmpeg, detect_ffprobe, detect_scrcpy, get_message_catalog,
+    get_recent_destinations, get_settings, pin_destination, set_adb_path, set_apk_repository_dir,
+    set_battery_policy, set_device_nickname, set_device_pull_folders, set_ffmpeg_path,
+    set_ffprobe_path, set_language, set_mirror_options, set_notify_on_mirror, set_private_folders,
+    set_read_only_mode, set_remux_recordings, set_retry_policy, set_scrcpy_path,
+    set_thumbnail_warmup, set_timeouts, unpin_destination,
+};
+use commands::sync::{
+    delete_sync_job, get_sync_job_history, get_sync_jobs, preview_sync, run_sync, run_sync_job_now,
+    save_sync_job,
+};
+use commands::transfer::{
+    analyze_drop, cancel_transfer, delete_transfer_preset, get_device_record, get_transfer_presets,
+    get_transfers, push_files, push_with_preset, resume_transfers, save_transfer_preset,
+};
+use commands::troubleshoot::{
+    open_platform_tools_download, redetect_tools, restart_adb_server, test_device_connection,
+};
+use commands::update::{
+    clear_update_plan, defer_update, get_pending_update, install_update, schedule_update_install,
+    set_update_channel,
 };
-use commands::transfer::{cancel_transfer, get_transfers, push_files};
+use commands::watch::{list_setting_watches, unwatch_setting, watch_setting};
+use services::battery_service;
+use services::confirmation_service::ConfirmationState;
+use services::device_watch_service;
+use services::perf_service::PerfState;
+use services::platform_integration_service::TaskbarState;
+use services::preview_cache_service::PreviewCacheState;
+use services::pull_history_service::PullHistoryState;
+use services::scheduler_service::{self, SchedulerState};
+use services::scrcpy_service::{self, MirrorState};
+use services::settings_watch_service;
+use services::timeline_service::TimelineState;
+use services::transfer_service::TransferState;
+use services::update_service;
+use services::watch_service::WatchState;
+use services::wireless_service::{self, WirelessState};
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -38,6 +111,27 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(MirrorState::default())
+        .manage(TransferState::default())
+        .manage(SchedulerState::default())
+        .manage(PerfState::default())
+        .manage(ConfirmationState::default())
+        .manage(WatchState::default())
+        .manage(WirelessState::default())
+        .manage(TimelineState::default())
+        .manage(PullHistoryState::default())
+        .manage(PreviewCacheState::default())
+        .manage(TaskbarState::default())
+        .setup(|app| {
+            scheduler_service::start(app.handle().clone());
+            battery_service::start(app.handle().clone());
+            scrcpy_service::start_watchdog(app.handle().clone());
+            wireless_service::start(app.handle().clone());
+            device_watch_service::start(app.handle().clone());
+            update_service::start(app.handle().clone());
+            settings_watch_service::start(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             ping,
@@ -48,27 +142,160 @@ pub fn run() {
             detect_scrcpy,
             set_ffmpeg_path,
             detect_ffmpeg,
+            set_ffprobe_path,
+            detect_ffprobe,
+            set_language,
+            get_message_catalog,
+            set_timeouts,
+            set_mirror_options,
+            set_remux_recordings,
+            set_thumbnail_warmup,
+            set_battery_policy,
+            set_notify_on_mirror,
+            set_read_only_mode,
+            set_private_folders,
+            get_recent_destinations,
+            pin_destination,
+            unpin_destination,
+            set_device_nickname,
+            set_device_pull_folders,
+            set_apk_repository_dir,
+            set_retry_policy,
             list_devices,
+            list_apple_devices,
+            get_battery_status,
+            get_device_health,
+            reboot_device,
+            compare_devices,
+            get_device_timeline,
+            get_wireless_status,
+            disconnect_wireless,
+            connect_device,
+            disconnect_device,
+            enable_tcpip,
+            check_wireless_network,
+            start_hotspot,
+            stop_hotspot,
+            connect_over_hotspot,
             start_mirror,
+            start_headless_mirror,
+            start_kiosk,
+            stop_kiosk,
             stop_mirror,
             get_mirror_sessions,
+            get_display_size,
+            measure_wifi_link,
+            capture_mirror_frame,
+            list_recordings,
+            delete_recording,
+            open_recordings_folder,
+            remux_recording,
             start_camera,
             stop_camera,
             get_camera_sessions,
+            get_camera_options,
+            start_audio_capture,
+            stop_audio_capture,
+            get_audio_sessions,
             push_files,
             get_transfers,
             cancel_transfer,
+            resume_transfers,
+            push_with_preset,
+            analyze_drop,
+            get_device_record,
+            install_apks,
+            inspect_apk,
+            get_app_icon,
+            list_bloatware,
+            disable_bloatware,
+            restore_bloatware,
+            get_default_apps,
+            set_default_app,
+            clear_default_app,
+            get_app_links,
+            set_app_links_allowed,
+            reset_app_links,
+            list_device_processes,
+            force_stop,
+            kill_process,
+            get_network_usage,
+            export_network_usage,
+            start_perf_stream,
+            stop_perf_stream,
+            get_perf_metrics,
+            get_onboarding_state,
+            complete_onboarding_step,
+            restart_adb_server,
+            redetect_tools,
+            test_device_connection,
+            open_platform_tools_download,
+            get_pending_update,
+            install_update,
+            set_update_channel,
+            defer_update,
+            schedule_update_install,
+            clear_update_plan,
+            request_confirmation,
+            watch_setting,
+            unwatch_setting,
+            list_setting_watches,
+            export_app_list,
+            bulk_install_from_list,
+            check_apk_updates,
+            route_drop,
+            get_device_groups,
+            save_device_group,
+            delete_device_group,
+            push_to_group,
+            install_to_group,
+            shell_on_group,
+            screenshot_group,
+            get_provisioning_profiles,
+            save_provisioning_profile,
+            delete_provisioning_profile,
+            apply_provisioning,
+            get_transfer_presets,
+            save_transfer_preset,
+            delete_transfer_preset,
+            preview_sync,
+            run_sync,
+            get_sync_jobs,
+            save_sync_job,
+            delete_sync_job,
+            get_sync_job_history,
+            run_sync_job_now,
             open_bluetooth_settings,
             open_bluetooth_send,
             open_bluetooth_receive,
+            get_quick_share_status,
+            send_with_quick_share,
+            send_clipboard_text,
             // Media Previewer commands
             get_default_media_root,
             list_device_folders,
             list_device_media,
             get_media_thumbnail,
+            group_similar_photos,
             pull_media_files,
+            get_pull_history,
+            pull_folder,
+            pull_media_selection,
+            plan_camera_offload,
+            get_media_locations,
+            detect_motion_photos,
+            delete_media_files,
             preview_media,
-            open_media_folder
+            save_preview_as,
+            open_media_folder,
+            open_media_file_with,
+            reveal_media_file,
+            probe_media_file,
+            // MTP fallback (no USB debugging)
+            list_mtp_devices,
+            list_mtp_folders,
+            list_mtp_media,
+            pull_mtp_files
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
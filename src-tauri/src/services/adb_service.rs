@@ -1,14 +1,13 @@
 //! ADB service for device discovery and command execution
 
 use crate::domain::errors::AppError;
-use crate::domain::models::{Device, DeviceState};
+use crate::domain::models::{Device, DeviceChange, DeviceState, RebootMode, Settings};
+use crate::services::device_queue_service::{self, Priority};
+use crate::services::retry_service::{self, RetryPolicy};
+use crate::services::{process_service, read_only_service};
+use std::collections::HashMap;
 use std::process::Command;
-
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+use std::time::Duration;
 
 /// Parse the output of `adb devices -l`
 ///
@@ -57,87 +56,475 @@ pub fn parse_devices_output(output: &str) -> Vec<Device> {
     devices
 }
 
+/// Default timeout for adb shell/query commands
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default timeout for a single adb push/pull
+pub const DEFAULT_TRANSFER_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// Abstraction over invoking adb so services can be tested against canned output
+pub trait AdbRunner {
+    /// Run adb with the given arguments and return stdout
+    fn run(&self, args: &[&str]) -> Result<String, AppError>;
+
+    /// Run a long adb push/pull (uses the transfer timeout instead of the command timeout)
+    fn run_transfer(&self, args: &[&str]) -> Result<String, AppError> {
+        self.run(args)
+    }
+
+    /// Retry policy for idempotent operations
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Run a read-only or idempotent command, retrying transient failures
+    fn run_idempotent(&self, args: &[&str]) -> Result<String, AppError> {
+        retry_service::retry(&self.retry_policy(), || self.run(args))
+    }
+}
+
+/// Runs the real adb executable at a resolved path
+pub struct AdbCli {
+    path: String,
+    command_timeout: Duration,
+    transfer_timeout: Duration,
+    retry_policy: RetryPolicy,
+    /// Refuse commands that change the device (read-only mode)
+    read_only: bool,
+    /// Place in the per-device queue
+    priority: Priority,
+}
+
+impl AdbCli {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            transfer_timeout: DEFAULT_TRANSFER_TIMEOUT,
+            retry_policy: RetryPolicy::default(),
+            read_only: false,
+            priority: Priority::Interactive,
+        }
+    }
+
+    /// Build from resolved settings (None if ADB is not available)
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        let path = settings.adb_resolved_path.clone()?;
+        Some(Self {
+            path,
+            command_timeout: Duration::from_secs(settings.command_timeout_secs.max(1)),
+            transfer_timeout: Duration::from_secs(settings.transfer_timeout_secs.max(1)),
+            retry_policy: RetryPolicy {
+                max_attempts: settings.retry_attempts.max(1),
+                initial_delay: Duration::from_millis(settings.retry_initial_delay_ms),
+                ..RetryPolicy::default()
+            },
+            read_only: settings.read_only_mode,
+            priority: Priority::Interactive,
+        })
+    }
+
+    /// Queue behind interactive requests; for thumbnails, polling and watchers
+    pub fn background(mut self) -> Self {
+        self.priority = Priority::Background;
+        self
+    }
+
+    fn execute(&self, args: &[&str], timeout: Duration) -> Result<String, AppError> {
+        if self.read_only {
+            read_only_service::check(args)?;
+        }
+        let _permit = device_queue_service::target_serial(args)
+            .map(|serial| {
+                device_queue_service::global().acquire(serial, self.priority, self.command_timeout)
+            })
+            .transpose()?;
+        run_adb_command_with_timeout(&self.path, args, timeout)
+    }
+}
+
+impl AdbRunner for AdbCli {
+    fn run(&self, args: &[&str]) -> Result<String, AppError> {
+        self.execute(args, self.command_timeout)
+    }
+
+    fn run_transfer(&self, args: &[&str]) -> Result<String, AppError> {
+        self.execute(args, self.transfer_timeout)
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+}
+
 /// Run ADB command and return output
 pub fn run_adb_command(adb_path: &str, args: &[&str]) -> Result<String, AppError> {
+    run_adb_command_with_timeout(adb_path, args, DEFAULT_COMMAND_TIMEOUT)
+}
+
+/// Run ADB command and return output, killing adb if it exceeds `timeout`
+pub fn run_adb_command_with_timeout(
+    adb_path: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String, AppError> {
     let mut cmd = Command::new(adb_path);
     cmd.args(args);
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
-
-    let output = cmd
-        .output()
-        .map_err(|e| AppError::AdbExecutionFailed(format!("Failed to execute adb: {}", e)))?;
+    let output = process_service::output_hidden(&mut cmd, timeout)
+        .map_err(|e| AppError::AdbExecutionFailed(format!("Failed to execute adb: {}", e)))?
+        .ok_or_else(|| {
+            AppError::Timeout(format!(
+                "adb {} did not finish within {}s",
+                args.join(" "),
+                timeout.as_secs()
+            ))
+        })?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = normalize_output(&output.stderr);
         return Err(AppError::AdbExecutionFailed(format!(
             "ADB command failed: {}",
             stderr
         )));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(stdout)
+    Ok(normalize_output(&output.stdout))
+}
+
+/// adb output as text with `\n` line endings, so parsers never see a `\r`.
+/// `adb shell` on older devices, and adb on Windows, end lines with `\r\n`
+/// (`\r\r\n` when both apply), and some builds lead with a UTF-8 byte-order
+/// mark. Invalid UTF-8 becomes U+FFFD. A `\r` inside a line, as in progress
+/// output, is kept.
+pub fn normalize_output(bytes: &[u8]) -> String {
+    const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+    let text = String::from_utf8_lossy(bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes));
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut carriage_returns = 0;
+    for c in text.chars() {
+        match c {
+            '\r' => carriage_returns += 1,
+            '\n' => {
+                carriage_returns = 0;
+                normalized.push('\n');
+            }
+            c => {
+                normalized.extend(std::iter::repeat_n('\r', carriage_returns));
+                carriage_returns = 0;
+                normalized.push(c);
+            }
+        }
+    }
+    normalized
+}
+
+/// Serials of devices in the Ready state, without the extra per-device queries
+pub fn ready_serials(adb: &dyn AdbRunner) -> Result<Vec<String>, AppError> {
+    Ok(parse_devices_output(&adb.run_idempotent(&["devices"])?)
+        .into_iter()
+        .filter(|d| d.state == DeviceState::Ready)
+        .map(|d| d.serial)
+        .collect())
 }
 
 /// List connected devices
-pub fn list_devices(adb_path: &str) -> Result<Vec<Device>, AppError> {
-    let output = run_adb_command(adb_path, &["devices", "-l"])?;
+pub fn list_devices(adb: &dyn AdbRunner) -> Result<Vec<Device>, AppError> {
+    let output = adb.run_idempotent(&["devices", "-l"])?;
     let mut devices = parse_devices_output(&output);
 
     // Try to get additional device info for ready devices
     for device in devices.iter_mut() {
         if device.state == DeviceState::Ready {
-            // Get Android version
-            if let Ok(version) = run_adb_command(
-                adb_path,
-                &[
-                    "-s",
-                    &device.serial,
-                    "shell",
-                    "getprop",
-                    "ro.build.version.release",
-                ],
-            ) {
-                device.android_version = Some(version.trim().to_string());
-            }
+            enrich_device(adb, device);
+        }
+    }
 
-            // Get manufacturer if not already set
-            if device.manufacturer.is_none() {
-                if let Ok(mfr) = run_adb_command(
-                    adb_path,
-                    &[
-                        "-s",
-                        &device.serial,
-                        "shell",
-                        "getprop",
-                        "ro.product.manufacturer",
-                    ],
-                ) {
-                    device.manufacturer = Some(mfr.trim().to_string());
-                }
-            }
+    Ok(devices)
+}
 
-            // Get model if not already set
-            if device.model.is_none() {
-                if let Ok(model) = run_adb_command(
-                    adb_path,
-                    &["-s", &device.serial, "shell", "getprop", "ro.product.model"],
-                ) {
-                    device.model = Some(model.trim().to_string());
-                }
-            }
+/// Fill in Android version, and manufacturer and model where `adb devices -l`
+/// didn't list them. Only works once the device is ready.
+pub fn enrich_device(adb: &dyn AdbRunner, device: &mut Device) {
+    // Get Android version
+    if let Ok(version) = adb.run_idempotent(&[
+        "-s",
+        &device.serial,
+        "shell",
+        "getprop",
+        "ro.build.version.release",
+    ]) {
+        device.android_version = Some(version.trim().to_string());
+    }
+
+    // Get manufacturer if not already set
+    if device.manufacturer.is_none() {
+        if let Ok(mfr) = adb.run_idempotent(&[
+            "-s",
+            &device.serial,
+            "shell",
+            "getprop",
+            "ro.product.manufacturer",
+        ]) {
+            device.manufacturer = Some(mfr.trim().to_string());
         }
     }
 
-    Ok(devices)
+    // Get model if not already set
+    if device.model.is_none() {
+        device.model = get_model(adb, &device.serial);
+    }
+}
+
+/// The device's model name (`ro.product.model`)
+pub fn get_model(adb: &dyn AdbRunner, serial: &str) -> Option<String> {
+    adb.run_idempotent(&["-s", serial, "shell", "getprop", "ro.product.model"])
+        .ok()
+        .map(|model| model.trim().to_string())
+}
+
+/// Restart a device, normally or into recovery. Its mirror sessions and
+/// transfers are cut off and it drops off the device list until it's back.
+pub fn reboot(adb: &dyn AdbRunner, serial: &str, mode: RebootMode) -> Result<(), AppError> {
+    match mode {
+        RebootMode::System => adb.run(&["-s", serial, "reboot"]),
+        RebootMode::Recovery => adb.run(&["-s", serial, "reboot", "recovery"]),
+    }
+    .map(|_| ())
+}
+
+/// Event emitted with a [`DeviceChange`] payload when a device shows up
+pub const DEVICE_CONNECTED_EVENT: &str = "device-connected";
+
+/// Event emitted with a [`DeviceChange`] payload when a device goes away
+pub const DEVICE_DISCONNECTED_EVENT: &str = "device-disconnected";
+
+/// Event emitted with a [`DeviceChange`] payload when a listed device changes
+/// state, e.g. from unauthorized to ready
+pub const DEVICE_STATE_CHANGED_EVENT: &str = "device-state-changed";
+
+/// Split the complete messages off the front of `adb track-devices` output.
+/// Each is four hex digits giving its length, then the device list.
+pub fn take_track_messages(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut messages = Vec::new();
+    while let Some(len) = buffer
+        .get(..4)
+        .and_then(|hex| std::str::from_utf8(hex).ok())
+        .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+    {
+        if buffer.len() < 4 + len {
+            break;
+        }
+        messages.push(normalize_output(&buffer[4..4 + len]));
+        buffer.drain(..4 + len);
+    }
+    messages
+}
+
+/// Device states in a `track-devices` message (`serial<TAB>state` lines)
+pub fn parse_track_message(message: &str) -> HashMap<String, DeviceState> {
+    message
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), DeviceState::from(parts.next()?)))
+        })
+        .collect()
+}
+
+/// Events for the differences between two device lists, by serial
+pub fn device_changes(
+    previous: &HashMap<String, DeviceState>,
+    current: &HashMap<String, DeviceState>,
+) -> Vec<(&'static str, DeviceChange)> {
+    let mut serials: Vec<&String> = previous.keys().chain(current.keys()).collect();
+    serials.sort();
+    serials.dedup();
+    serials
+        .into_iter()
+        .filter_map(|serial| {
+            let before = previous.get(serial);
+            let now = current.get(serial);
+            let event = match (before, now) {
+                (None, Some(_)) => DEVICE_CONNECTED_EVENT,
+                (Some(_), None) => DEVICE_DISCONNECTED_EVENT,
+                (Some(before), Some(now)) if before != now => DEVICE_STATE_CHANGED_EVENT,
+                _ => return None,
+            };
+            Some((
+                event,
+                DeviceChange {
+                    serial: serial.clone(),
+                    state: now.cloned(),
+                    previous_state: before.cloned(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Canned-output adb runner for unit tests
+#[cfg(test)]
+pub mod mock {
+    use super::AdbRunner;
+    use crate::domain::errors::AppError;
+    use crate::services::retry_service::RetryPolicy;
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    /// Replies to adb invocations whose joined arguments contain a registered pattern
+    #[derive(Default)]
+    pub struct MockAdb {
+        responses: Vec<(String, Result<String, AppError>)>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl MockAdb {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Reply with `output` when the joined args contain `pattern`
+        pub fn on(mut self, pattern: &str, output: &str) -> Self {
+            self.responses
+                .push((pattern.to_string(), Ok(output.to_string())));
+            self
+        }
+
+        /// Fail with `error` when the joined args contain `pattern`
+        pub fn fail(mut self, pattern: &str, error: AppError) -> Self {
+            self.responses.push((pattern.to_string(), Err(error)));
+            self
+        }
+
+        /// All invocations so far, each as space-joined args
+        pub fn calls(&self) -> Vec<String> {
+            self.calls.borrow().clone()
+        }
+    }
+
+    impl AdbRunner for MockAdb {
+        fn retry_policy(&self) -> RetryPolicy {
+            RetryPolicy {
+                initial_delay: Duration::ZERO,
+                ..RetryPolicy::default()
+            }
+        }
+
+        fn run(&self, args: &[&str]) -> Result<String, AppError> {
+            let joined = args.join(" ");
+            self.calls.borrow_mut().push(joined.clone());
+
+            self.responses
+                .iter()
+                .find(|(pattern, _)| joined.contains(pattern.as_str()))
+                .map(|(_, response)| response.clone())
+                .unwrap_or_else(|| {
+                    Err(AppError::AdbExecutionFailed(format!(
+                        "No mock response for: {}",
+                        joined
+                    )))
+                })
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::mock::MockAdb;
     use super::*;
 
+    #[test]
+    fn test_take_track_messages() {
+        let mut buffer =
+            b"0015R58M123\tunauthorized\n00000024R58M123\tdevice\nemulator-5554\t".to_vec();
+        let messages = take_track_messages(&mut buffer);
+        assert_eq!(messages, vec!["R58M123\tunauthorized\n", ""]);
+        // The last message is incomplete until the rest arrives
+        assert_eq!(buffer, b"0024R58M123\tdevice\nemulator-5554\t");
+
+        buffer.extend_from_slice(b"device\n");
+        let messages = take_track_messages(&mut buffer);
+        assert!(buffer.is_empty());
+        let devices = parse_track_message(&messages[0]);
+        assert_eq!(devices.get("R58M123"), Some(&DeviceState::Ready));
+        assert_eq!(devices.get("emulator-5554"), Some(&DeviceState::Ready));
+        assert!(parse_track_message("").is_empty());
+    }
+
+    #[test]
+    fn test_device_changes() {
+        let previous =
+            parse_track_message("R58M123\tunauthorized\nHT7A1B\tdevice\nemulator-5554\tdevice\n");
+        let current = parse_track_message(
+            "R58M123\tdevice\nemulator-5554\tdevice\n192.168.1.20:5555\tdevice\n",
+        );
+        let changes = device_changes(&previous, &current);
+        let events: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|(event, change)| (*event, change.serial.as_str()))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (DEVICE_CONNECTED_EVENT, "192.168.1.20:5555"),
+                (DEVICE_DISCONNECTED_EVENT, "HT7A1B"),
+                (DEVICE_STATE_CHANGED_EVENT, "R58M123"),
+            ]
+        );
+        assert_eq!(changes[1].1.state, None);
+        assert_eq!(changes[2].1.previous_state, Some(DeviceState::Unauthorized));
+        assert!(device_changes(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_reboot() {
+        let adb = MockAdb::new().on("reboot", "");
+        reboot(&adb, "SER1", RebootMode::System).unwrap();
+        reboot(&adb, "SER1", RebootMode::Recovery).unwrap();
+        assert_eq!(
+            adb.calls(),
+            vec!["-s SER1 reboot", "-s SER1 reboot recovery"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_output() {
+        assert_eq!(normalize_output(b"14\r\n"), "14\n");
+        assert_eq!(normalize_output(b"a\r\r\nb\r"), "a\nb");
+        assert_eq!(normalize_output(b"\xEF\xBB\xBFGoogle\n"), "Google\n");
+        assert_eq!(normalize_output(b"10%\r20%\n"), "10%\r20%\n");
+        assert_eq!(normalize_output(b"caf\xC3\xA9 \xFF"), "caf\u{e9} \u{fffd}");
+        assert_eq!(
+            normalize_output("🎉 誕生日.jpg\r\n".as_bytes()),
+            "🎉 誕生日.jpg\n"
+        );
+    }
+
+    #[test]
+    fn test_parsers_read_normalized_crlf_output() {
+        let devices = parse_devices_output(&normalize_output(
+            b"List of devices attached\r\r\nSER1\tdevice\r\r\n\r\r\n",
+        ));
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].serial, "SER1");
+        assert_eq!(devices[0].state, DeviceState::Ready);
+
+        let props = crate::services::compare_service::parse_getprop(&normalize_output(
+            b"[ro.build.version.sdk]: [34]\r\n[ro.product.model]: [Pixel 8]\r\n",
+        ));
+        assert_eq!(
+            props.get("ro.product.model").map(String::as_str),
+            Some("Pixel 8")
+        );
+
+        let taken = crate::services::media_time_service::parse_date_taken(&normalize_output(
+            b"Row: 0 _data=/storage/emulated/0/DCIM/a.jpg, datetaken=1706777940000\r\n",
+        ));
+        assert_eq!(taken.get("DCIM/a.jpg"), Some(&1_706_777_940));
+    }
+
     #[test]
     fn test_parse_devices_output() {
         let output = r#"List of devices attached
@@ -159,4 +546,25 @@ RFCT80XXXXX            unauthorized
         assert_eq!(devices[2].serial, "192.168.1.100:5555");
         assert_eq!(devices[2].state, DeviceState::Offline);
     }
+
+    #[test]
+    fn test_list_devices_queries_props_for_ready_devices() {
+        let adb = MockAdb::new()
+            .on(
+                "devices -l",
+                "List of devices attached\nABC123 device product:x model:Pixel_7\nDEF456 unauthorized\n",
+            )
+            .on("ro.build.version.release", "14\n")
+            .on("ro.product.manufacturer", "Google\n");
+
+        let devices = list_devices(&adb).unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].model, Some("Pixel 7".to_string()));
+        assert_eq!(devices[0].android_version, Some("14".to_string()));
+        assert_eq!(devices[0].manufacturer, Some("Google".to_string()));
+        assert_eq!(devices[1].android_version, None);
+
+        // Unauthorized devices must not be queried
+        assert!(adb.calls().iter().all(|c| !c.contains("DEF456")));
+    }
 }
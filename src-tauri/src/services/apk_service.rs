@@ -0,0 +1,815 @@
+//! APK metadata without aapt: reads the binary AndroidManifest.xml (AXML)
+//! and resources.arsc straight out of the archive
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{ApkInfo, ApkPreview};
+use crate::services::media_service;
+use crate::services::zip_service;
+use std::path::Path;
+
+const MANIFEST_ENTRY: &str = "AndroidManifest.xml";
+const RESOURCES_ENTRY: &str = "resources.arsc";
+
+const CHUNK_STRING_POOL: u16 = 0x0001;
+const CHUNK_TABLE: u16 = 0x0002;
+const CHUNK_XML: u16 = 0x0003;
+const CHUNK_START_ELEMENT: u16 = 0x0102;
+const CHUNK_RESOURCE_MAP: u16 = 0x0180;
+const CHUNK_TABLE_PACKAGE: u16 = 0x0200;
+const CHUNK_TABLE_TYPE: u16 = 0x0201;
+
+/// String pool flag: strings are UTF-8 rather than UTF-16
+const UTF8_FLAG: u32 = 0x100;
+
+/// Typed value kinds (`Res_value::dataType`)
+const TYPE_REFERENCE: u8 = 0x01;
+const TYPE_STRING: u8 = 0x03;
+const TYPE_INT_DEC: u8 = 0x10;
+const TYPE_INT_HEX: u8 = 0x11;
+
+/// `android:` attribute resource ids, used when attribute names are stripped
+const ATTR_ICON: u32 = 0x0101_0002;
+const ATTR_MIN_SDK: u32 = 0x0101_020c;
+const ATTR_VERSION_CODE: u32 = 0x0101_021b;
+const ATTR_VERSION_NAME: u32 = 0x0101_021c;
+const ATTR_TARGET_SDK: u32 = 0x0101_0270;
+
+/// `ResTable_type` flags: entries are `(index, offset)` pairs / offsets are 16-bit
+const TYPE_FLAG_SPARSE: u8 = 0x01;
+const TYPE_FLAG_OFFSET16: u8 = 0x02;
+
+/// `ResTable_entry` flags: a bag of values / value packed into the entry
+const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+const ENTRY_FLAG_COMPACT: u16 = 0x0008;
+
+/// Densities from this one up mean "any" or "none" rather than a dpi value
+const DENSITY_ANY: u16 = 0xfffe;
+
+/// How many resource references are followed before giving up on a loop
+const MAX_REFERENCE_DEPTH: usize = 4;
+
+/// Icon formats a webview can show; adaptive icons are XML and are skipped
+const ICON_EXTENSIONS: &[&str] = &["png", "webp", "jpg"];
+
+/// Package name and version of a local APK
+pub fn read_apk_info(path: &Path) -> Result<ApkInfo, AppError> {
+    let manifest = read_manifest_entry(path)?;
+    parse_manifest(&manifest)
+        .ok_or_else(|| AppError::InvalidPath(format!("Unreadable manifest in {}", path.display())))
+}
+
+/// Everything the install UI shows about an APK before installing it. The
+/// icon is best effort: APKs with only adaptive (XML) icons have none.
+pub fn inspect_apk(path: &Path) -> Result<ApkPreview, AppError> {
+    let manifest = read_manifest_entry(path)?;
+    let (info, icon_id) = read_manifest(&manifest).ok_or_else(|| {
+        AppError::InvalidPath(format!("Unreadable manifest in {}", path.display()))
+    })?;
+
+    Ok(ApkPreview {
+        path: path.to_string_lossy().into_owned(),
+        info,
+        icon: icon_id.and_then(|id| read_icon(path, id)),
+    })
+}
+
+/// Read the `<manifest>` and `<uses-sdk>` elements of a binary manifest
+pub fn parse_manifest(data: &[u8]) -> Option<ApkInfo> {
+    read_manifest(data).map(|(info, _)| info)
+}
+
+fn read_manifest_entry(path: &Path) -> Result<Vec<u8>, AppError> {
+    zip_service::read_entry(path, MANIFEST_ENTRY)
+        .map_err(|e| AppError::InvalidPath(format!("{}: {}", path.display(), e)))?
+        .ok_or_else(|| {
+            AppError::InvalidPath(format!("{} has no {}", path.display(), MANIFEST_ENTRY))
+        })
+}
+
+/// Manifest info plus the resource id of the application icon
+fn read_manifest(data: &[u8]) -> Option<(ApkInfo, Option<u32>)> {
+    let document = Document::parse(data)?;
+    let elements: Vec<Element> = document.elements().collect();
+    let find = |name: &str| elements.iter().find(|e| e.name == name);
+
+    let manifest = find("manifest")?;
+    let uses_sdk = find("uses-sdk");
+    let icon = find("application").and_then(|e| e.reference("icon", ATTR_ICON));
+
+    let info = ApkInfo {
+        package: manifest.string("package", None)?,
+        version_code: manifest.int("versionCode", Some(ATTR_VERSION_CODE))? as u64,
+        version_name: manifest.string("versionName", Some(ATTR_VERSION_NAME)),
+        min_sdk: uses_sdk.and_then(|e| e.int("minSdkVersion", Some(ATTR_MIN_SDK))),
+        target_sdk: uses_sdk.and_then(|e| e.int("targetSdkVersion", Some(ATTR_TARGET_SDK))),
+    };
+    Some((info, icon))
+}
+
+/// Icon image of an APK as a data URL
+fn read_icon(path: &Path, icon_id: u32) -> Option<String> {
+    let (icon_path, image) = read_icon_entry(path, icon_id)?;
+    let extension = Path::new(&icon_path).extension()?.to_str()?;
+    Some(media_service::image_data_url(&image, extension))
+}
+
+/// Launcher icon of an APK as `(entry name, image bytes)`, if it has a bitmap one
+pub fn extract_icon(path: &Path) -> Option<(String, Vec<u8>)> {
+    let manifest = read_manifest_entry(path).ok()?;
+    let (_, icon_id) = read_manifest(&manifest)?;
+    read_icon_entry(path, icon_id?)
+}
+
+fn read_icon_entry(path: &Path, icon_id: u32) -> Option<(String, Vec<u8>)> {
+    let table = zip_service::read_entry(path, RESOURCES_ENTRY).ok()??;
+    let icon_path = ResourceTable::parse(&table)?.icon_path(icon_id)?;
+    let image = zip_service::read_entry(path, &icon_path).ok()??;
+    Some((icon_path, image))
+}
+
+/// A parsed AXML document: its string pool, resource map and raw chunks
+struct Document<'a> {
+    data: &'a [u8],
+    strings: Vec<String>,
+    resource_ids: Vec<u32>,
+    /// Offset of the first chunk after the XML header
+    body: usize,
+}
+
+struct Attribute {
+    name: String,
+    resource_id: Option<u32>,
+    raw_value: Option<String>,
+    data_type: u8,
+    data: u32,
+}
+
+struct Element {
+    name: String,
+    attributes: Vec<Attribute>,
+}
+
+impl Element {
+    fn attribute(&self, name: &str, resource_id: Option<u32>) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|a| a.name == name || (resource_id.is_some() && a.resource_id == resource_id))
+    }
+
+    fn string(&self, name: &str, resource_id: Option<u32>) -> Option<String> {
+        let attribute = self.attribute(name, resource_id)?;
+        attribute.raw_value.clone().or_else(|| {
+            matches!(attribute.data_type, TYPE_INT_DEC | TYPE_INT_HEX)
+                .then(|| attribute.data.to_string())
+        })
+    }
+
+    fn reference(&self, name: &str, resource_id: u32) -> Option<u32> {
+        let attribute = self.attribute(name, Some(resource_id))?;
+        (attribute.data_type == TYPE_REFERENCE).then_some(attribute.data)
+    }
+
+    fn int(&self, name: &str, resource_id: Option<u32>) -> Option<u32> {
+        let attribute = self.attribute(name, resource_id)?;
+        match attribute.data_type {
+            TYPE_INT_DEC | TYPE_INT_HEX => Some(attribute.data),
+            TYPE_STRING => attribute.raw_value.as_deref()?.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Document<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if u16_at(data, 0)? != CHUNK_XML {
+            return None;
+        }
+        let body = u16_at(data, 2)? as usize;
+
+        let mut document = Document {
+            data,
+            strings: Vec::new(),
+            resource_ids: Vec::new(),
+            body,
+        };
+        for (kind, chunk) in document.chunks() {
+            match kind {
+                CHUNK_STRING_POOL => document.strings = parse_string_pool(chunk)?,
+                CHUNK_RESOURCE_MAP => {
+                    let header = u16_at(chunk, 2)? as usize;
+                    document.resource_ids = chunk
+                        .get(header..)?
+                        .chunks_exact(4)
+                        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+        Some(document)
+    }
+
+    fn chunks(&self) -> impl Iterator<Item = (u16, &'a [u8])> {
+        chunks(self.data, self.body)
+    }
+
+    fn elements(&self) -> impl Iterator<Item = Element> + '_ {
+        self.chunks()
+            .filter(|(kind, _)| *kind == CHUNK_START_ELEMENT)
+            .filter_map(|(_, chunk)| self.parse_element(chunk))
+    }
+
+    fn parse_element(&self, chunk: &[u8]) -> Option<Element> {
+        let header = u16_at(chunk, 2)? as usize;
+        let name = self.string(u32_at(chunk, header + 4)?)?;
+        let attribute_start = u16_at(chunk, header + 8)? as usize;
+        let attribute_size = u16_at(chunk, header + 10)? as usize;
+        let attribute_count = u16_at(chunk, header + 12)? as usize;
+        if attribute_size < 20 {
+            return None;
+        }
+
+        let attributes = (0..attribute_count)
+            .map(|i| {
+                let at = header + attribute_start + i * attribute_size;
+                let name_index = u32_at(chunk, at + 4)?;
+                Some(Attribute {
+                    name: self.string(name_index).unwrap_or_default(),
+                    resource_id: self.resource_ids.get(name_index as usize).copied(),
+                    raw_value: self.string(u32_at(chunk, at + 8)?),
+                    data_type: *chunk.get(at + 15)?,
+                    data: u32_at(chunk, at + 16)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Element { name, attributes })
+    }
+
+    /// String pool entry; `0xffffffff` means no string
+    fn string(&self, index: u32) -> Option<String> {
+        self.strings.get(index as usize).cloned()
+    }
+}
+
+/// A parsed resources.arsc: the global value strings and each package chunk
+struct ResourceTable<'a> {
+    strings: Vec<String>,
+    packages: Vec<&'a [u8]>,
+}
+
+/// One configuration's value of a resource
+struct ResourceValue {
+    density: u16,
+    data_type: u8,
+    data: u32,
+}
+
+impl<'a> ResourceTable<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if u16_at(data, 0)? != CHUNK_TABLE {
+            return None;
+        }
+
+        let mut table = ResourceTable {
+            strings: Vec::new(),
+            packages: Vec::new(),
+        };
+        for (kind, chunk) in chunks(data, u16_at(data, 2)? as usize) {
+            match kind {
+                CHUNK_STRING_POOL => table.strings = parse_string_pool(chunk)?,
+                CHUNK_TABLE_PACKAGE => table.packages.push(chunk),
+                _ => {}
+            }
+        }
+        Some(table)
+    }
+
+    /// Image file of a drawable resource at the highest density, following
+    /// references to other resources
+    fn icon_path(&self, id: u32) -> Option<String> {
+        let mut files = Vec::new();
+        self.collect_files(id, 0, &mut files);
+
+        files
+            .into_iter()
+            .filter(|(_, path)| {
+                Path::new(path)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| ICON_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            })
+            .max_by_key(|(density, _)| *density)
+            .map(|(_, path)| path)
+    }
+
+    fn collect_files(&self, id: u32, depth: usize, files: &mut Vec<(u16, String)>) {
+        if depth > MAX_REFERENCE_DEPTH {
+            return;
+        }
+        for value in self.values(id) {
+            match value.data_type {
+                TYPE_STRING => {
+                    if let Some(path) = self.strings.get(value.data as usize) {
+                        files.push((density_rank(value.density), path.clone()));
+                    }
+                }
+                TYPE_REFERENCE => self.collect_files(value.data, depth + 1, files),
+                _ => {}
+            }
+        }
+    }
+
+    /// Values of a resource id (`0xPPTTEEEE`) in every configuration
+    fn values(&self, id: u32) -> Vec<ResourceValue> {
+        let package_id = id >> 24;
+        let type_id = ((id >> 16) & 0xff) as u8;
+        let entry = id & 0xffff;
+
+        self.packages
+            .iter()
+            .filter(|package| u32_at(package, 8) == Some(package_id))
+            .flat_map(|package| chunks(package, u16_at(package, 2).unwrap_or(0) as usize))
+            .filter(|(kind, chunk)| *kind == CHUNK_TABLE_TYPE && chunk.get(8) == Some(&type_id))
+            .filter_map(|(_, chunk)| type_entry(chunk, entry))
+            .collect()
+    }
+}
+
+/// An entry's simple value in one `ResTable_type` chunk
+fn type_entry(chunk: &[u8], entry: u32) -> Option<ResourceValue> {
+    let header = u16_at(chunk, 2)? as usize;
+    let flags = *chunk.get(9)?;
+    let count = u32_at(chunk, 12)? as usize;
+    let entries_start = u32_at(chunk, 16)? as usize;
+    // ResTable_config starts at byte 20; density is 14 bytes into it
+    let density = u16_at(chunk, 20 + 14)?;
+    let entry = entry as usize;
+
+    let offset = if flags & TYPE_FLAG_SPARSE != 0 {
+        (0..count).find_map(|i| {
+            let at = header + i * 4;
+            if u16_at(chunk, at)? as usize == entry {
+                u16_at(chunk, at + 2).map(|offset| offset as usize * 4)
+            } else {
+                None
+            }
+        })?
+    } else if entry >= count {
+        return None;
+    } else if flags & TYPE_FLAG_OFFSET16 != 0 {
+        let offset = u16_at(chunk, header + entry * 2)?;
+        if offset == 0xffff {
+            return None;
+        }
+        offset as usize * 4
+    } else {
+        let offset = u32_at(chunk, header + entry * 4)?;
+        if offset == 0xffff_ffff {
+            return None;
+        }
+        offset as usize
+    };
+
+    let at = entries_start.checked_add(offset)?;
+    let size = u16_at(chunk, at)? as usize;
+    let entry_flags = u16_at(chunk, at + 2)?;
+    let (data_type, data) = if entry_flags & ENTRY_FLAG_COMPACT != 0 {
+        ((entry_flags >> 8) as u8, u32_at(chunk, at + 4)?)
+    } else if entry_flags & ENTRY_FLAG_COMPLEX != 0 {
+        return None;
+    } else {
+        // Res_value: size (2), res0 (1), dataType (1), data (4)
+        (*chunk.get(at + size + 3)?, u32_at(chunk, at + size + 4)?)
+    };
+
+    Some(ResourceValue {
+        density,
+        data_type,
+        data,
+    })
+}
+
+/// Order densities so real dpi values beat "any" and "none"
+fn density_rank(density: u16) -> u16 {
+    if density >= DENSITY_ANY {
+        0
+    } else {
+        density
+    }
+}
+
+/// `(type, bytes)` of each chunk from `start`, stopping at malformed ones
+fn chunks(data: &[u8], start: usize) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut pos = start;
+    std::iter::from_fn(move || {
+        let kind = u16_at(data, pos)?;
+        let size = u32_at(data, pos + 4)? as usize;
+        if size < 8 {
+            return None;
+        }
+        let chunk = data.get(pos..pos.checked_add(size)?)?;
+        pos += size;
+        Some((kind, chunk))
+    })
+}
+
+fn parse_string_pool(chunk: &[u8]) -> Option<Vec<String>> {
+    let header = u16_at(chunk, 2)? as usize;
+    let count = u32_at(chunk, 8)? as usize;
+    let flags = u32_at(chunk, 16)?;
+    let strings_start = u32_at(chunk, 20)? as usize;
+    let utf8 = flags & UTF8_FLAG != 0;
+
+    (0..count)
+        .map(|i| {
+            let offset = u32_at(chunk, header + i * 4)? as usize;
+            let at = strings_start.checked_add(offset)?;
+            if utf8 {
+                read_utf8(chunk, at)
+            } else {
+                read_utf16(chunk, at)
+            }
+        })
+        .collect()
+}
+
+/// UTF-8 pool string: character count, byte count, bytes. Counts over 0x7f
+/// take two bytes.
+fn read_utf8(data: &[u8], at: usize) -> Option<String> {
+    let (_, at) = read_utf8_len(data, at)?;
+    let (len, at) = read_utf8_len(data, at)?;
+    let bytes = data.get(at..at + len)?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn read_utf8_len(data: &[u8], at: usize) -> Option<(usize, usize)> {
+    let first = *data.get(at)? as usize;
+    if first & 0x80 == 0 {
+        return Some((first, at + 1));
+    }
+    let second = *data.get(at + 1)? as usize;
+    Some((((first & 0x7f) << 8) | second, at + 2))
+}
+
+/// UTF-16 pool string: unit count (two units when over 0x7fff), then units
+fn read_utf16(data: &[u8], at: usize) -> Option<String> {
+    let first = u16_at(data, at)? as usize;
+    let (len, at) = if first & 0x8000 == 0 {
+        (first, at + 2)
+    } else {
+        let second = u16_at(data, at + 2)? as usize;
+        (((first & 0x7fff) << 16) | second, at + 4)
+    };
+
+    let units = (0..len)
+        .map(|i| u16_at(data, at + i * 2))
+        .collect::<Option<Vec<u16>>>()?;
+    Some(String::from_utf16_lossy(&units))
+}
+
+/// Little-endian `u16` at `at`, `None` past the end of `data`
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    let bytes = data.get(at..at.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Little-endian `u32` at `at`, `None` past the end of `data`
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    let bytes = data.get(at..at.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+    use crate::services::zip_service::fixtures::build_zip;
+
+    const NO_STRING: u32 = 0xffff_ffff;
+
+    fn chunk(kind: u16, header_size: u16, header_rest: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&kind.to_le_bytes());
+        out.extend_from_slice(&header_size.to_le_bytes());
+        out.extend_from_slice(&((8 + header_rest.len() + body.len()) as u32).to_le_bytes());
+        out.extend_from_slice(header_rest);
+        out.extend_from_slice(body);
+        out
+    }
+
+    /// UTF-16 string pool chunk
+    fn string_pool(strings: &[&str]) -> Vec<u8> {
+        let mut offsets = Vec::new();
+        let mut pool = Vec::new();
+        for s in strings {
+            offsets.extend_from_slice(&(pool.len() as u32).to_le_bytes());
+            let units: Vec<u16> = s.encode_utf16().collect();
+            pool.extend_from_slice(&(units.len() as u16).to_le_bytes());
+            for unit in units {
+                pool.extend_from_slice(&unit.to_le_bytes());
+            }
+            pool.extend_from_slice(&[0, 0]);
+        }
+        while pool.len() % 4 != 0 {
+            pool.push(0);
+        }
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&((28 + offsets.len()) as u32).to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        chunk(CHUNK_STRING_POOL, 28, &header, &[offsets, pool].concat())
+    }
+
+    /// Attribute as `(name index, string value index, type, data)`
+    pub type TestAttribute = (u32, u32, u8, u32);
+
+    /// Build a binary manifest from a UTF-16 string pool, resource ids for
+    /// the first strings and `(name index, attributes)` start elements
+    pub fn build_axml(
+        strings: &[&str],
+        resource_ids: &[u32],
+        elements: &[(u32, Vec<TestAttribute>)],
+    ) -> Vec<u8> {
+        let mut body = string_pool(strings);
+
+        let ids: Vec<u8> = resource_ids
+            .iter()
+            .flat_map(|id| id.to_le_bytes())
+            .collect();
+        body.extend(chunk(CHUNK_RESOURCE_MAP, 8, &[], &ids));
+
+        for (name, attributes) in elements {
+            let mut header = Vec::new();
+            header.extend_from_slice(&1u32.to_le_bytes());
+            header.extend_from_slice(&NO_STRING.to_le_bytes());
+            let mut ext = Vec::new();
+            ext.extend_from_slice(&NO_STRING.to_le_bytes());
+            ext.extend_from_slice(&name.to_le_bytes());
+            ext.extend_from_slice(&20u16.to_le_bytes());
+            ext.extend_from_slice(&20u16.to_le_bytes());
+            ext.extend_from_slice(&(attributes.len() as u16).to_le_bytes());
+            ext.extend_from_slice(&[0; 6]);
+            for (attr_name, raw, data_type, data) in attributes {
+                ext.extend_from_slice(&NO_STRING.to_le_bytes());
+                ext.extend_from_slice(&attr_name.to_le_bytes());
+                ext.extend_from_slice(&raw.to_le_bytes());
+                ext.extend_from_slice(&8u16.to_le_bytes());
+                ext.push(0);
+                ext.push(*data_type);
+                ext.extend_from_slice(&data.to_le_bytes());
+            }
+            body.extend(chunk(CHUNK_START_ELEMENT, 16, &header, &ext));
+        }
+
+        chunk(CHUNK_XML, 8, &[], &body)
+    }
+
+    /// Resource id of the launcher icon in the sample APKs
+    pub const ICON_ID: u32 = 0x7f0d_0000;
+
+    pub fn sample_manifest(package: &str, version_code: u32, version_name: &str) -> Vec<u8> {
+        // Names 0-4 are stripped down to their resource ids, as R8 and
+        // aapt2 --no-xml-namespaces leave them
+        build_axml(
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "package",
+                "manifest",
+                "uses-sdk",
+                "application",
+                package,
+                version_name,
+            ],
+            &[
+                ATTR_VERSION_CODE,
+                ATTR_VERSION_NAME,
+                ATTR_MIN_SDK,
+                ATTR_TARGET_SDK,
+                ATTR_ICON,
+            ],
+            &[
+                (
+                    6,
+                    vec![
+                        (0, NO_STRING, TYPE_INT_DEC, version_code),
+                        (1, 10, TYPE_STRING, 10),
+                        (5, 9, TYPE_STRING, 9),
+                    ],
+                ),
+                (
+                    7,
+                    vec![
+                        (2, NO_STRING, TYPE_INT_DEC, 24),
+                        (3, NO_STRING, TYPE_INT_DEC, 34),
+                    ],
+                ),
+                (8, vec![(4, NO_STRING, TYPE_REFERENCE, ICON_ID)]),
+            ],
+        )
+    }
+
+    /// Entry of a type chunk: a plain `(data type, data)` value
+    pub type TestEntry = Option<(u8, u32)>;
+
+    /// Build a resources.arsc for package 0x7f from value strings and
+    /// `(type id, density, entries, sparse)` type chunks
+    pub fn build_arsc(strings: &[&str], types: &[(u8, u16, Vec<TestEntry>, bool)]) -> Vec<u8> {
+        let mut package_header = Vec::new();
+        package_header.extend_from_slice(&0x7fu32.to_le_bytes());
+        package_header.extend_from_slice(&[0; 256]);
+        package_header.extend_from_slice(&[0; 20]);
+
+        let mut package_body = Vec::new();
+        for (type_id, density, entries, sparse) in types {
+            let mut config = vec![0u8; 64];
+            config[..4].copy_from_slice(&64u32.to_le_bytes());
+            config[14..16].copy_from_slice(&density.to_le_bytes());
+
+            let mut offsets = Vec::new();
+            let mut values = Vec::new();
+            let mut present = 0u32;
+            for (index, entry) in entries.iter().enumerate() {
+                let Some((data_type, data)) = entry else {
+                    if !sparse {
+                        offsets.extend_from_slice(&u32::MAX.to_le_bytes());
+                    }
+                    continue;
+                };
+                if *sparse {
+                    offsets.extend_from_slice(&(index as u16).to_le_bytes());
+                    offsets.extend_from_slice(&((values.len() / 4) as u16).to_le_bytes());
+                } else {
+                    offsets.extend_from_slice(&(values.len() as u32).to_le_bytes());
+                }
+                present += 1;
+                // ResTable_entry (size 8, flags 0, key 0) then Res_value
+                values.extend_from_slice(&8u16.to_le_bytes());
+                values.extend_from_slice(&0u16.to_le_bytes());
+                values.extend_from_slice(&0u32.to_le_bytes());
+                values.extend_from_slice(&8u16.to_le_bytes());
+                values.push(0);
+                values.push(*data_type);
+                values.extend_from_slice(&data.to_le_bytes());
+            }
+
+            let count = if *sparse {
+                present
+            } else {
+                entries.len() as u32
+            };
+            let header_size = 8 + 12 + config.len();
+            let mut header = vec![*type_id, if *sparse { TYPE_FLAG_SPARSE } else { 0 }, 0, 0];
+            header.extend_from_slice(&count.to_le_bytes());
+            header.extend_from_slice(&((header_size + offsets.len()) as u32).to_le_bytes());
+            header.extend_from_slice(&config);
+            package_body.extend(chunk(
+                CHUNK_TABLE_TYPE,
+                header_size as u16,
+                &header,
+                &[offsets, values].concat(),
+            ));
+        }
+
+        let mut body = string_pool(strings);
+        body.extend(chunk(
+            CHUNK_TABLE_PACKAGE,
+            288,
+            &package_header,
+            &package_body,
+        ));
+        chunk(CHUNK_TABLE, 12, &1u32.to_le_bytes(), &body)
+    }
+
+    /// Write an APK holding only a manifest
+    pub fn write_sample_apk(path: &Path, package: &str, version_code: u32, version_name: &str) {
+        let manifest = sample_manifest(package, version_code, version_name);
+        let zip = build_zip(&[(MANIFEST_ENTRY, &manifest)]);
+        std::fs::write(path, zip).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::*;
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let info = parse_manifest(&sample_manifest("org.example.notes", 42, "1.4.2")).unwrap();
+        assert_eq!(
+            info,
+            ApkInfo {
+                package: "org.example.notes".to_string(),
+                version_code: 42,
+                version_name: Some("1.4.2".to_string()),
+                min_sdk: Some(24),
+                target_sdk: Some(34),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_garbage() {
+        assert!(parse_manifest(b"<manifest package=\"x\"/>").is_none());
+        assert!(parse_manifest(&[]).is_none());
+
+        let truncated = sample_manifest("org.example.notes", 42, "1.4.2");
+        assert!(parse_manifest(&truncated[..40]).is_none());
+    }
+
+    #[test]
+    fn test_read_apk_info() {
+        let path = std::env::temp_dir().join(format!("gesu_apk_test_{}.apk", std::process::id()));
+        write_sample_apk(&path, "org.example.notes", 7, "0.7");
+        assert_eq!(read_apk_info(&path).unwrap().version_code, 7);
+
+        std::fs::write(&path, b"not an apk").unwrap();
+        assert!(read_apk_info(&path).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+
+    const ICON_STRINGS: &[&str] = &[
+        "res/mipmap-mdpi-v4/ic_launcher.png",
+        "res/mipmap-xxxhdpi-v4/ic_launcher.webp",
+        "res/mipmap-anydpi-v26/ic_launcher.xml",
+    ];
+
+    /// Icon in three densities; entry 1 is an alias referencing it
+    fn icon_table() -> Vec<u8> {
+        build_arsc(
+            ICON_STRINGS,
+            &[
+                (
+                    0x0d,
+                    160,
+                    vec![Some((TYPE_STRING, 0)), Some((TYPE_REFERENCE, ICON_ID))],
+                    false,
+                ),
+                (0x0d, 640, vec![Some((TYPE_STRING, 1)), None], true),
+                (0x0d, DENSITY_ANY, vec![Some((TYPE_STRING, 2)), None], false),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_icon_path() {
+        let data = icon_table();
+        let table = ResourceTable::parse(&data).unwrap();
+
+        assert_eq!(table.values(ICON_ID).len(), 3);
+        assert_eq!(table.icon_path(ICON_ID).as_deref(), Some(ICON_STRINGS[1]));
+        assert_eq!(
+            table.icon_path(ICON_ID + 1).as_deref(),
+            Some(ICON_STRINGS[1])
+        );
+        assert_eq!(table.icon_path(ICON_ID + 2), None);
+        assert_eq!(table.icon_path(0x7f0e_0000), None);
+    }
+
+    #[test]
+    fn test_inspect_apk() {
+        let manifest = sample_manifest("org.example.notes", 42, "1.4.2");
+        let table = icon_table();
+        let zip = crate::services::zip_service::fixtures::build_zip(&[
+            (MANIFEST_ENTRY, &manifest),
+            (RESOURCES_ENTRY, &table),
+            (ICON_STRINGS[1], b"RIFF"),
+        ]);
+        let path = std::env::temp_dir().join(format!("gesu_apk_icon_{}.apk", std::process::id()));
+        std::fs::write(&path, zip).unwrap();
+
+        let preview = inspect_apk(&path).unwrap();
+        assert_eq!(preview.info.package, "org.example.notes");
+        assert_eq!(preview.info.min_sdk, Some(24));
+        assert_eq!(
+            preview.icon.as_deref(),
+            Some("data:image/webp;base64,UklGRg==")
+        );
+
+        // No resources.arsc: still previewable, just without an icon
+        write_sample_apk(&path, "org.example.notes", 42, "1.4.2");
+        assert_eq!(inspect_apk(&path).unwrap().icon, None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_read_utf8() {
+        assert_eq!(
+            read_utf8(&[3, 3, b'a', b'b', b'c', 0], 0).as_deref(),
+            Some("abc")
+        );
+
+        let long = "x".repeat(200);
+        let mut data = vec![0x80, 200, 0x80, 200];
+        data.extend_from_slice(long.as_bytes());
+        assert_eq!(read_utf8(&data, 0), Some(long));
+    }
+}
@@ -0,0 +1,134 @@
+//! Launcher icons of installed apps for the apps panel, cached alongside
+//! media thumbnails
+
+use crate::domain::errors::AppError;
+use crate::services::adb_service::AdbRunner;
+use crate::services::{apk_service, kiosk_service, media_service};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+/// Icons are scaled down to fit this many pixels per side
+const ICON_SIZE: u32 = 96;
+
+/// Path of an app's base APK on the device
+pub fn base_apk_path(adb: &dyn AdbRunner, serial: &str, package: &str) -> Result<String, AppError> {
+    let output = adb.run_idempotent(&["-s", serial, "shell", "pm", "path", package])?;
+    parse_pm_path(&output).ok_or_else(|| {
+        AppError::ThumbnailNotAvailable(format!("{} is not installed on {}", package, serial))
+    })
+}
+
+/// Parse `pm path` output; split apps list several APKs, the base one holds the icon
+///
+/// Example output:
+/// ```text
+/// package:/data/app/~~Xq1w==/com.example.app-9f3A==/base.apk
+/// package:/data/app/~~Xq1w==/com.example.app-9f3A==/split_config.arm64_v8a.apk
+/// ```
+pub fn parse_pm_path(output: &str) -> Option<String> {
+    let paths: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .collect();
+
+    paths
+        .iter()
+        .find(|path| path.ends_with("/base.apk"))
+        .or_else(|| paths.first())
+        .map(|path| path.to_string())
+}
+
+/// Small PNG of an installed app's launcher icon as a data URL. The APK is
+/// pulled once per installed version; later calls are served from `cache_dir`.
+pub fn get_app_icon(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    package: &str,
+    cache_dir: &Path,
+) -> Result<String, AppError> {
+    if !kiosk_service::is_valid_package_name(package) {
+        return Err(AppError::ThumbnailNotAvailable(format!(
+            "Invalid package name: {}",
+            package
+        )));
+    }
+
+    let remote_apk = base_apk_path(adb, serial, package)?;
+    let icon_path = cache_dir.join(icon_file_name(package, &remote_apk));
+    if std::fs::metadata(&icon_path).is_ok_and(|m| m.len() > 0) {
+        return media_service::read_file_as_base64(&icon_path);
+    }
+
+    let local_apk = icon_path.with_extension("apk");
+    media_service::pull_to(adb, serial, &remote_apk, &local_apk)?;
+    let icon = apk_service::extract_icon(&local_apk);
+    let _ = std::fs::remove_file(&local_apk);
+
+    let (_, image_data) = icon.ok_or_else(|| {
+        AppError::ThumbnailNotAvailable(format!("{} has no bitmap launcher icon", package))
+    })?;
+    image::load_from_memory(&image_data)
+        .map_err(|e| AppError::ThumbnailNotAvailable(format!("Unreadable icon: {}", e)))?
+        .thumbnail(ICON_SIZE, ICON_SIZE)
+        .save(&icon_path)
+        .map_err(|e| AppError::IoError(format!("Failed to save icon: {}", e)))?;
+
+    media_service::read_file_as_base64(&icon_path)
+}
+
+/// Cache file name for an app's icon. The APK path changes with every update,
+/// so it's part of the key and an updated app gets its new icon.
+fn icon_file_name(package: &str, remote_apk: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    remote_apk.hash(&mut hasher);
+    format!("icon_{}_{:016x}.png", package, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const PM_PATH: &str = "package:/data/app/~~Xq1w==/com.example.app-9f3A==/split_config.arm64_v8a.apk\npackage:/data/app/~~Xq1w==/com.example.app-9f3A==/base.apk\n";
+
+    #[test]
+    fn test_parse_pm_path() {
+        assert_eq!(
+            parse_pm_path(PM_PATH).as_deref(),
+            Some("/data/app/~~Xq1w==/com.example.app-9f3A==/base.apk")
+        );
+        assert_eq!(
+            parse_pm_path("package:/system/app/Calc/Calc.apk\n").as_deref(),
+            Some("/system/app/Calc/Calc.apk")
+        );
+        assert_eq!(parse_pm_path(""), None);
+    }
+
+    #[test]
+    fn test_icon_file_name_follows_updates() {
+        let before = icon_file_name("com.example.app", "/data/app/~~a==/base.apk");
+        let after = icon_file_name("com.example.app", "/data/app/~~b==/base.apk");
+        assert!(before.starts_with("icon_com.example.app_"));
+        assert!(before.ends_with(".png"));
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn test_get_app_icon_uses_cache() {
+        let dir = std::env::temp_dir().join(format!("gesu_icon_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cached = dir.join(icon_file_name(
+            "com.example.app",
+            "/data/app/~~Xq1w==/com.example.app-9f3A==/base.apk",
+        ));
+        std::fs::write(&cached, b"png").unwrap();
+
+        let adb = MockAdb::new().on("pm path", PM_PATH);
+        assert!(get_app_icon(&adb, "SER1", "com.example.app", &dir).is_ok());
+        assert_eq!(adb.calls(), vec!["-s SER1 shell pm path com.example.app"]);
+
+        assert!(get_app_icon(&adb, "SER1", "com.example.app;reboot", &dir).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
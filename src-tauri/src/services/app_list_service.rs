@@ -0,0 +1,266 @@
+//! Export the list of installed apps, reinstall it on another device and
+//! find updates in a local APK repository
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{ApkInfo, ApkUpdate, AppList, AppListEntry, BulkInstallReport};
+use crate::services::adb_service::AdbRunner;
+use crate::services::{apk_service, install_service};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Third-party apps on a device with their version and installer
+pub fn list_apps(adb: &dyn AdbRunner, serial: &str) -> Result<AppList, AppError> {
+    let output = adb.run_idempotent(&[
+        "-s",
+        serial,
+        "shell",
+        "pm",
+        "list",
+        "packages",
+        "-3",
+        "--show-versioncode",
+        "-i",
+    ])?;
+
+    Ok(AppList {
+        device_serial: serial.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        apps: parse_app_list(&output),
+    })
+}
+
+/// Parse `package:com.app versionCode:42 installer=com.android.vending` lines,
+/// sorted by package name
+pub fn parse_app_list(output: &str) -> Vec<AppListEntry> {
+    let mut apps: Vec<AppListEntry> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().strip_prefix("package:")?.split_whitespace();
+            let package = parts.next()?.to_string();
+            let mut entry = AppListEntry {
+                package,
+                version_code: None,
+                installer: None,
+            };
+            for part in parts {
+                if let Some(version) = part.strip_prefix("versionCode:") {
+                    entry.version_code = Some(version.to_string());
+                } else if let Some(installer) = part.strip_prefix("installer=") {
+                    entry.installer = (installer != "null").then(|| installer.to_string());
+                }
+            }
+            Some(entry)
+        })
+        .collect();
+
+    apps.sort_by(|a, b| a.package.cmp(&b.package));
+    apps
+}
+
+/// Write an app list as JSON
+pub fn export_app_list(list: &AppList, path: &Path) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(list)
+        .map_err(|e| AppError::IoError(format!("Failed to serialize app list: {}", e)))?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Read an app list written by [`export_app_list`]
+pub fn read_app_list(path: &Path) -> Result<AppList, AppError> {
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::InvalidPath(format!("Not an app list: {} ({})", path.display(), e)))
+}
+
+/// Install every app of the list whose APK is in `apk_dir`. APKs are matched
+/// by file name: `<package>.apk`, or `<package>-…`/`<package>_…` for names
+/// carrying a version.
+pub fn bulk_install(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    list: &AppList,
+    apk_dir: &Path,
+) -> Result<BulkInstallReport, AppError> {
+    let apks = apk_files(apk_dir)?;
+    let mut report = BulkInstallReport {
+        installed: Vec::new(),
+        missing: Vec::new(),
+    };
+
+    for app in &list.apps {
+        match find_apk(&apks, &app.package) {
+            Some(apk) => report.installed.push(install_service::install_apk(
+                adb,
+                serial,
+                &apk.to_string_lossy(),
+            )),
+            None => report.missing.push(app.package.clone()),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Installed third-party apps with a higher versionCode among the APKs in
+/// `repository_dir`. Files that aren't readable APKs are skipped.
+pub fn check_updates(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    repository_dir: &Path,
+) -> Result<Vec<ApkUpdate>, AppError> {
+    let available = newest_apks(repository_dir)?;
+    let installed = list_apps(adb, serial)?;
+    Ok(find_updates(&installed.apps, &available))
+}
+
+/// Newest APK per package in a folder
+fn newest_apks(dir: &Path) -> Result<BTreeMap<String, (ApkInfo, PathBuf)>, AppError> {
+    let mut newest: BTreeMap<String, (ApkInfo, PathBuf)> = BTreeMap::new();
+    for path in apk_files(dir)? {
+        let Ok(info) = apk_service::read_apk_info(&path) else {
+            continue;
+        };
+        let is_newer = newest
+            .get(&info.package)
+            .is_none_or(|(current, _)| info.version_code > current.version_code);
+        if is_newer {
+            newest.insert(info.package.clone(), (info, path));
+        }
+    }
+    Ok(newest)
+}
+
+fn find_updates(
+    installed: &[AppListEntry],
+    available: &BTreeMap<String, (ApkInfo, PathBuf)>,
+) -> Vec<ApkUpdate> {
+    installed
+        .iter()
+        .filter_map(|app| {
+            let installed_version_code = app.version_code.as_deref()?.parse::<u64>().ok()?;
+            let (info, path) = available.get(&app.package)?;
+            (info.version_code > installed_version_code).then(|| ApkUpdate {
+                package: app.package.clone(),
+                installed_version_code,
+                available_version_code: info.version_code,
+                available_version_name: info.version_name.clone(),
+                apk_path: path.to_string_lossy().into_owned(),
+            })
+        })
+        .collect()
+}
+
+fn apk_files(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut apks: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("apk"))
+        })
+        .collect();
+    apks.sort();
+    Ok(apks)
+}
+
+/// APK for a package by file name; an exact `<package>.apk` wins
+fn find_apk<'a>(apks: &'a [PathBuf], package: &str) -> Option<&'a PathBuf> {
+    let stem_of = |path: &Path| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+    };
+
+    apks.iter()
+        .find(|path| stem_of(path).as_deref() == Some(package))
+        .or_else(|| {
+            apks.iter().find(|path| {
+                stem_of(path).is_some_and(|stem| {
+                    stem.strip_prefix(package)
+                        .is_some_and(|rest| rest.starts_with(['-', '_']))
+                })
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+    use crate::services::apk_service::fixtures::write_sample_apk;
+
+    #[test]
+    fn test_parse_app_list() {
+        let output = "package:org.videolan.vlc versionCode:13050407 installer=com.android.vending\npackage:com.example.tool versionCode:3 installer=null\npackage:com.old\n";
+        let apps = parse_app_list(output);
+
+        assert_eq!(apps.len(), 3);
+        assert_eq!(apps[0].package, "com.example.tool");
+        assert_eq!(apps[0].installer, None);
+        assert_eq!(apps[1].package, "com.old");
+        assert_eq!(apps[1].version_code, None);
+        assert_eq!(apps[2].version_code.as_deref(), Some("13050407"));
+        assert_eq!(apps[2].installer.as_deref(), Some("com.android.vending"));
+    }
+
+    #[test]
+    fn test_find_apk() {
+        let apks = vec![
+            PathBuf::from("/apks/com.example.tool_1.2.apk"),
+            PathBuf::from("/apks/com.example.apk"),
+            PathBuf::from("/apks/com.example.toolbox.apk"),
+        ];
+
+        assert_eq!(find_apk(&apks, "com.example"), Some(&apks[1]));
+        assert_eq!(find_apk(&apks, "com.example.tool"), Some(&apks[0]));
+        assert_eq!(find_apk(&apks, "com.example.toolbox"), Some(&apks[2]));
+        assert_eq!(find_apk(&apks, "com.missing"), None);
+    }
+
+    #[test]
+    fn test_bulk_install() {
+        let dir = std::env::temp_dir().join(format!("gesu_apps_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("com.example.tool-3.apk"), b"apk").unwrap();
+
+        let list = AppList {
+            device_serial: "OLD".to_string(),
+            exported_at: String::new(),
+            apps: parse_app_list("package:com.example.tool versionCode:3\npackage:com.gone\n"),
+        };
+        let adb = MockAdb::new().on("install -r", "Success\n");
+
+        let report = bulk_install(&adb, "NEW", &list, &dir).unwrap();
+        assert_eq!(report.installed.len(), 1);
+        assert!(report.installed[0].success);
+        assert_eq!(report.missing, vec!["com.gone"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_check_updates() {
+        let dir = std::env::temp_dir().join(format!("gesu_repo_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_sample_apk(&dir.join("notes-41.apk"), "org.example.notes", 41, "4.1");
+        write_sample_apk(&dir.join("notes-43.apk"), "org.example.notes", 43, "4.3");
+        write_sample_apk(&dir.join("maps.apk"), "org.example.maps", 10, "1.0");
+        write_sample_apk(&dir.join("other.apk"), "org.example.other", 5, "0.5");
+        std::fs::write(dir.join("broken.apk"), b"not a zip").unwrap();
+
+        let adb = MockAdb::new().on(
+            "pm list packages",
+            "package:org.example.notes versionCode:42 installer=null\npackage:org.example.maps versionCode:10 installer=null\n",
+        );
+
+        let updates = check_updates(&adb, "SER1", &dir).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].package, "org.example.notes");
+        assert_eq!(updates[0].installed_version_code, 42);
+        assert_eq!(updates[0].available_version_code, 43);
+        assert_eq!(updates[0].available_version_name.as_deref(), Some("4.3"));
+        assert!(updates[0].apk_path.ends_with("notes-43.apk"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,149 @@
+//! Recognizing iPhones and iPads plugged in over USB
+//!
+//! The app doesn't support iOS, but a phone that silently doesn't show up
+//! looks like a broken cable. Apple devices are spotted by their USB vendor
+//! and product IDs only (no Apple tools or libraries), so the UI can say
+//! they aren't supported and point to photo import: an unlocked, trusted
+//! iPhone shows up as a portable device with a DCIM folder, which on
+//! Windows the MTP browser can read.
+
+use crate::domain::models::AppleDevice;
+use crate::services::process_service::{self, TOOL_CHECK_TIMEOUT};
+use std::process::Command;
+
+/// Apple's USB vendor ID
+const APPLE_VENDOR_ID: u16 = 0x05ac;
+
+/// Catalog message explaining what works with an Apple device
+pub const NOTICE_KEY: &str = "message.ios_not_supported";
+
+/// iPhone, iPad and iPod touch product IDs are 0x12xx; other Apple USB
+/// devices (keyboards, adapters, Macs in target mode) are not
+pub fn is_ios_device(vendor_id: u16, product_id: u16) -> bool {
+    vendor_id == APPLE_VENDOR_ID && product_id & 0xff00 == 0x1200
+}
+
+fn device(name: &str, vendor_id: u16, product_id: u16) -> AppleDevice {
+    AppleDevice {
+        name: name.to_string(),
+        usb_id: format!("{:04x}:{:04x}", vendor_id, product_id),
+        notice_key: NOTICE_KEY.to_string(),
+    }
+}
+
+/// Parse `lsusb`: `Bus 001 Device 007: ID 05ac:12a8 Apple, Inc. iPhone 5/5C/5S/6/SE/7/8/X/XR`
+pub fn parse_lsusb(output: &str) -> Vec<AppleDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" ID ")?;
+            let (id, name) = rest.split_once(' ').unwrap_or((rest, ""));
+            let (vendor, product) = id.split_once(':')?;
+            let vendor_id = u16::from_str_radix(vendor, 16).ok()?;
+            let product_id = u16::from_str_radix(product, 16).ok()?;
+            is_ios_device(vendor_id, product_id).then(|| {
+                device(
+                    name.trim_start_matches("Apple, Inc.").trim(),
+                    vendor_id,
+                    product_id,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parse `instance id<TAB>friendly name` lines of present Windows USB
+/// devices, e.g. `USB\VID_05AC&PID_12A8\00008030001A2B3C<TAB>Apple iPhone`.
+/// Interfaces of a composite device (`&MI_xx`) are the same phone again.
+pub fn parse_pnp_devices(output: &str) -> Vec<AppleDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (instance_id, name) = line.split_once('\t').unwrap_or((line, ""));
+            let hardware = instance_id.trim().to_uppercase();
+            let hardware = hardware.strip_prefix("USB\\")?.split('\\').next()?;
+            if hardware.contains("&MI_") {
+                return None;
+            }
+            let (vendor, product) = hardware.split_once('&')?;
+            let vendor_id = u16::from_str_radix(vendor.strip_prefix("VID_")?, 16).ok()?;
+            let product_id = u16::from_str_radix(product.strip_prefix("PID_")?, 16).ok()?;
+            is_ios_device(vendor_id, product_id).then(|| device(name.trim(), vendor_id, product_id))
+        })
+        .collect()
+}
+
+/// Apple phones and tablets plugged in right now. Best effort: an empty list
+/// when the system can't be asked.
+pub fn detect() -> Vec<AppleDevice> {
+    let (program, args, parse) = list_command();
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    match process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT) {
+        Ok(Some(output)) if output.status.success() => {
+            parse(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+type Parser = fn(&str) -> Vec<AppleDevice>;
+
+#[cfg(target_os = "windows")]
+fn list_command() -> (&'static str, &'static [&'static str], Parser) {
+    (
+        "powershell",
+        &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "[Console]::OutputEncoding=[Text.Encoding]::UTF8; \
+             Get-PnpDevice -PresentOnly | Where-Object { $_.InstanceId -like 'USB\\VID_05AC&*' } | \
+             ForEach-Object { \"$($_.InstanceId)`t$($_.FriendlyName)\" }",
+        ],
+        parse_pnp_devices,
+    )
+}
+
+#[cfg(not(target_os = "windows"))]
+fn list_command() -> (&'static str, &'static [&'static str], Parser) {
+    ("lsusb", &[], parse_lsusb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ios_device() {
+        assert!(is_ios_device(0x05ac, 0x12a8));
+        assert!(is_ios_device(0x05ac, 0x12ab));
+        // Magic Keyboard
+        assert!(!is_ios_device(0x05ac, 0x0267));
+        assert!(!is_ios_device(0x18d1, 0x12a8));
+    }
+
+    #[test]
+    fn test_parse_lsusb() {
+        let output =
+            "Bus 001 Device 002: ID 18d1:4ee7 Google Inc. Nexus/Pixel Device (charging + debug)\n\
+                      Bus 001 Device 007: ID 05ac:12a8 Apple, Inc. iPhone 5/5C/5S/6/SE/7/8/X/XR\n\
+                      Bus 002 Device 003: ID 05ac:024f Apple, Inc. Aluminium Keyboard (ANSI)\n";
+        let devices = parse_lsusb(output);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].name, "iPhone 5/5C/5S/6/SE/7/8/X/XR");
+        assert_eq!(devices[0].usb_id, "05ac:12a8");
+        assert_eq!(devices[0].notice_key, NOTICE_KEY);
+    }
+
+    #[test]
+    fn test_parse_pnp_devices() {
+        let output = "USB\\VID_05AC&PID_12A8\\00008030001A2B3C\tApple iPhone\r\n\
+                      USB\\VID_05AC&PID_12A8&MI_00\\7&2C3D&0&0000\tApple Mobile Device USB Composite Device\r\n\
+                      USB\\VID_05AC&PID_0267\\5&1A2B\tUSB Input Device\r\n";
+        let devices = parse_pnp_devices(output);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].name, "Apple iPhone");
+        assert_eq!(devices[0].usb_id, "05ac:12a8");
+    }
+}
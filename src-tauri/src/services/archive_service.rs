@@ -0,0 +1,93 @@
+//! Tar archives for batching many small files into one push
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use tar::Builder;
+
+/// Longest entry name the plain ustar `name` field can hold
+pub const MAX_NAME_LEN: usize = 100;
+
+/// Largest file a ustar entry can describe (11 octal digits)
+pub const MAX_ENTRY_SIZE: u64 = 0o77777777777;
+
+/// Whether a file can be stored as a flat entry with this name
+pub fn fits_in_archive(name: &str, size: u64) -> bool {
+    !name.is_empty() && name.len() <= MAX_NAME_LEN && size <= MAX_ENTRY_SIZE
+}
+
+/// Write `(local path, entry name)` pairs to an uncompressed tar archive.
+/// Returns the archive size in bytes.
+pub fn write_tar(entries: &[(&Path, &str)], out: &Path) -> io::Result<u64> {
+    let mut builder = Builder::new(BufWriter::new(File::create(out)?));
+
+    for (path, name) in entries {
+        let mut file = File::open(path)?;
+        if !fits_in_archive(name, file.metadata()?.len()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Cannot store {} in a tar archive", name),
+            ));
+        }
+        builder.append_file(name, &mut file)?;
+    }
+
+    let file = builder
+        .into_inner()?
+        .into_inner()
+        .map_err(|e| e.into_error())?;
+    Ok(file.metadata()?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_write_tar_round_trips() {
+        let dir = std::env::temp_dir().join(format!("gesu_tar_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("a.txt");
+        let second = dir.join("b.jpg");
+        std::fs::write(&first, b"hello").unwrap();
+        std::fs::write(&second, vec![7u8; 1000]).unwrap();
+        let out = dir.join("out.tar");
+
+        let size = write_tar(
+            &[(first.as_path(), "a.txt"), (second.as_path(), "b.jpg")],
+            &out,
+        )
+        .unwrap();
+        assert_eq!(size, std::fs::metadata(&out).unwrap().len());
+
+        let mut archive = tar::Archive::new(File::open(&out).unwrap());
+        let entries: Vec<(String, Vec<u8>)> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let name = entry.path().unwrap().to_string_lossy().to_string();
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data).unwrap();
+                (name, data)
+            })
+            .collect();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("a.txt".to_string(), b"hello".to_vec()),
+                ("b.jpg".to_string(), vec![7u8; 1000])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fits_in_archive() {
+        assert!(fits_in_archive("photo.jpg", 1024));
+        assert!(!fits_in_archive("", 1));
+        assert!(!fits_in_archive(&"x".repeat(101), 1));
+    }
+}
@@ -0,0 +1,166 @@
+//! Mirror bitrate auto-tuning: reads scrcpy's log output to spot a struggling
+//! stream and picks lower quality settings to restart it with
+
+use crate::domain::models::MirrorOptions;
+use crate::services::link_service::DEFAULT_BIT_RATE_MBPS;
+use crate::state::write_lock;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::{Arc, RwLock};
+
+/// Lowest bitrate auto-tuning steps down to, in Mbit/s
+const MIN_BIT_RATE_MBPS: u32 = 2;
+
+/// `--max-size` values tried once the bitrate is at its minimum, largest first
+const MAX_SIZE_STEPS: &[u32] = &[1280, 1024, 720];
+
+/// Share of frames skipped (in percent) that counts as struggling
+const MAX_SKIPPED_PERCENT: u64 = 20;
+
+/// Frames needed in a window before the skipped share is judged
+const MIN_FRAMES_TO_JUDGE: u64 = 30;
+
+/// Warnings or errors in a window that count as struggling
+const MAX_ERRORS: u64 = 3;
+
+/// Counters collected from scrcpy's output since the last check
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StreamHealth {
+    pub frames: u64,
+    pub skipped: u64,
+    pub errors: u64,
+}
+
+/// Shared between the output readers and the watchdog
+pub type SharedHealth = Arc<RwLock<StreamHealth>>;
+
+/// Parse a `--print-fps` line such as `INFO: 58 fps (+3 frames skipped)`
+pub fn parse_fps_line(line: &str) -> Option<(u64, u64)> {
+    let rest = line.trim().strip_prefix("INFO:")?.trim();
+    let (fps, rest) = rest.split_once(" fps")?;
+    let fps = fps.trim().parse().ok()?;
+
+    let skipped = rest
+        .trim()
+        .strip_prefix("(+")
+        .and_then(|r| r.split_whitespace().next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0);
+
+    Some((fps, skipped))
+}
+
+/// Fold one line of scrcpy output into the counters
+pub fn record_line(health: &mut StreamHealth, line: &str) {
+    if let Some((fps, skipped)) = parse_fps_line(line) {
+        health.frames += fps;
+        health.skipped += skipped;
+    } else {
+        let line = line.trim_start();
+        if line.starts_with("ERROR:") || line.starts_with("WARN:") {
+            health.errors += 1;
+        }
+    }
+}
+
+/// Read scrcpy output on a background thread until the process closes it
+pub fn watch_output<R: Read + Send + 'static>(reader: R, health: SharedHealth) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            record_line(&mut write_lock(&health), &line);
+        }
+    });
+}
+
+/// Whether a window of counters shows the stream can't keep up
+pub fn is_struggling(health: &StreamHealth) -> bool {
+    let total = health.frames + health.skipped;
+    let skipping =
+        total >= MIN_FRAMES_TO_JUDGE && health.skipped * 100 >= total * MAX_SKIPPED_PERCENT;
+    skipping || health.errors >= MAX_ERRORS
+}
+
+/// Next lower quality step: halve the bitrate, then shrink the resolution.
+/// `None` once there is nothing left to lower.
+pub fn lower_quality(options: &MirrorOptions) -> Option<MirrorOptions> {
+    let mut lowered = options.clone();
+
+    let bit_rate = options.bit_rate_mbps.unwrap_or(DEFAULT_BIT_RATE_MBPS);
+    if bit_rate > MIN_BIT_RATE_MBPS {
+        lowered.bit_rate_mbps = Some((bit_rate / 2).max(MIN_BIT_RATE_MBPS));
+        return Some(lowered);
+    }
+
+    let max_size = MAX_SIZE_STEPS
+        .iter()
+        .copied()
+        .find(|&size| options.max_size.is_none_or(|current| size < current))?;
+    lowered.max_size = Some(max_size);
+    Some(lowered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fps_line() {
+        assert_eq!(parse_fps_line("INFO: 60 fps"), Some((60, 0)));
+        assert_eq!(
+            parse_fps_line("INFO: 41 fps (+17 frames skipped)"),
+            Some((41, 17))
+        );
+        assert_eq!(parse_fps_line("INFO: Renderer: opengl"), None);
+        assert_eq!(parse_fps_line("WARN: Demuxer error"), None);
+    }
+
+    #[test]
+    fn test_is_struggling() {
+        let mut health = StreamHealth::default();
+        for line in [
+            "INFO: 60 fps",
+            "INFO: 45 fps (+15 frames skipped)",
+            "INFO: Device: Google Pixel 7",
+        ] {
+            record_line(&mut health, line);
+        }
+        assert_eq!(
+            health,
+            StreamHealth {
+                frames: 105,
+                skipped: 15,
+                errors: 0
+            }
+        );
+        assert!(!is_struggling(&health));
+
+        record_line(&mut health, "INFO: 30 fps (+30 frames skipped)");
+        assert!(is_struggling(&health));
+
+        let mut errors = StreamHealth::default();
+        for _ in 0..MAX_ERRORS {
+            record_line(&mut errors, "WARN: Could not decode packet");
+        }
+        assert!(is_struggling(&errors));
+    }
+
+    #[test]
+    fn test_lower_quality_steps() {
+        let mut options = MirrorOptions::default();
+        let mut steps = Vec::new();
+        while let Some(lowered) = lower_quality(&options) {
+            steps.push((lowered.bit_rate_mbps, lowered.max_size));
+            options = lowered;
+        }
+
+        assert_eq!(
+            steps,
+            vec![
+                (Some(4), None),
+                (Some(2), None),
+                (Some(2), Some(1280)),
+                (Some(2), Some(1024)),
+                (Some(2), Some(720)),
+            ]
+        );
+    }
+}
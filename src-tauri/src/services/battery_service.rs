@@ -0,0 +1,196 @@
+//! Battery watcher: applies the low battery policy to devices with active sessions
+//!
+//! Runs only while the app is open and only queries devices that are being
+//! mirrored, recorded or captured.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{BatteryPolicy, BatteryStatus, LowBatteryAction, LowBatteryAlert};
+use crate::services::adb_service::{AdbCli, AdbRunner};
+use crate::services::scrcpy_service::{self, MirrorState};
+use crate::services::settings_service;
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often battery levels are checked
+const TICK: Duration = Duration::from_secs(30);
+
+/// Event emitted with a [`LowBatteryAlert`] payload when a device runs low
+pub const LOW_BATTERY_EVENT: &str = "low-battery";
+
+/// Parse `dumpsys battery`
+///
+/// Example output:
+/// ```text
+/// Current Battery Service state:
+///   AC powered: false
+///   USB powered: true
+///   Wireless powered: false
+///   status: 2
+///   level: 85
+///   scale: 100
+/// ```
+pub fn parse_dumpsys_battery(output: &str) -> Option<BatteryStatus> {
+    let mut level = None;
+    let mut scale = 100u32;
+    let mut powered = false;
+    let mut status = 0u32;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "level" => level = value.parse::<u32>().ok(),
+            "scale" => scale = value.parse().unwrap_or(100).max(1),
+            "status" => status = value.parse().unwrap_or(0),
+            k if k.ends_with(" powered") => powered |= value == "true",
+            _ => {}
+        }
+    }
+
+    // BatteryManager: 2 = charging, 5 = full
+    Some(BatteryStatus {
+        level: (level? * 100 / scale).min(100) as u8,
+        charging: powered || status == 2 || status == 5,
+    })
+}
+
+/// Read a device's battery level and charging state
+pub fn get_battery(adb: &dyn AdbRunner, device_serial: &str) -> Result<BatteryStatus, AppError> {
+    let output = adb.run_idempotent(&["-s", device_serial, "shell", "dumpsys", "battery"])?;
+    parse_dumpsys_battery(&output).ok_or_else(|| {
+        AppError::AdbExecutionFailed(format!(
+            "Unexpected `dumpsys battery` output from {}",
+            device_serial
+        ))
+    })
+}
+
+/// Whether the policy should act on this battery state
+pub fn is_low(policy: &BatteryPolicy, status: &BatteryStatus) -> bool {
+    policy.enabled && !status.charging && status.level < policy.min_level
+}
+
+/// Start the battery watcher thread; it lives as long as the app
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        // Devices already warned about, so each drop below the threshold alerts once
+        let mut alerted = HashSet::new();
+        loop {
+            std::thread::sleep(TICK);
+            let _ = tick(&app, &mut alerted);
+        }
+    });
+}
+
+fn tick(app: &AppHandle, alerted: &mut HashSet<String>) -> Result<(), AppError> {
+    let settings = settings_service::load_settings(app)?;
+    let policy = settings.battery_policy;
+    if !policy.enabled {
+        alerted.clear();
+        return Ok(());
+    }
+
+    let state = app.state::<MirrorState>();
+    let serials = scrcpy_service::active_serials(&state);
+    alerted.retain(|serial| serials.contains(serial));
+    if serials.is_empty() {
+        return Ok(());
+    }
+
+    let settings = settings_service::get_settings_with_detection(app)?;
+    let Some(adb) = AdbCli::from_settings(&settings).map(AdbCli::background) else {
+        return Ok(());
+    };
+
+    for serial in serials {
+        let Ok(status) = get_battery(&adb, &serial) else {
+            continue;
+        };
+        if !is_low(&policy, &status) {
+            alerted.remove(&serial);
+            continue;
+        }
+        if alerted.contains(&serial) {
+            continue;
+        }
+
+        let stopped = policy.action == LowBatteryAction::Stop
+            && scrcpy_service::stop_device_sessions(&state, &adb, &serial) > 0;
+        let _ = app.emit(
+            LOW_BATTERY_EVENT,
+            &LowBatteryAlert {
+                device_serial: serial.clone(),
+                level: status.level,
+                stopped,
+            },
+        );
+        alerted.insert(serial);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parse_dumpsys_battery() {
+        let output = "Current Battery Service state:\n  AC powered: false\n  USB powered: true\n  Wireless powered: false\n  status: 2\n  health: 2\n  level: 85\n  scale: 100\n";
+        assert_eq!(
+            parse_dumpsys_battery(output),
+            Some(BatteryStatus {
+                level: 85,
+                charging: true
+            })
+        );
+
+        let unplugged =
+            "  AC powered: false\n  USB powered: false\n  status: 3\n  level: 40\n  scale: 50\n";
+        assert_eq!(
+            parse_dumpsys_battery(unplugged),
+            Some(BatteryStatus {
+                level: 80,
+                charging: false
+            })
+        );
+
+        assert_eq!(parse_dumpsys_battery("Can't find service: battery"), None);
+    }
+
+    #[test]
+    fn test_is_low() {
+        let policy = BatteryPolicy {
+            enabled: true,
+            min_level: 20,
+            action: LowBatteryAction::Stop,
+        };
+        let low = BatteryStatus {
+            level: 10,
+            charging: false,
+        };
+        assert!(is_low(&policy, &low));
+        assert!(!is_low(
+            &policy,
+            &BatteryStatus {
+                charging: true,
+                ..low
+            }
+        ));
+        assert!(!is_low(&policy, &BatteryStatus { level: 20, ..low }));
+        assert!(!is_low(&BatteryPolicy::default(), &low));
+    }
+
+    #[test]
+    fn test_get_battery() {
+        let adb = MockAdb::new().on("dumpsys battery", "  USB powered: false\n  level: 7\n");
+        let status = get_battery(&adb, "SER1").unwrap();
+        assert_eq!(status.level, 7);
+        assert!(!status.charging);
+        assert_eq!(adb.calls(), vec!["-s SER1 shell dumpsys battery"]);
+    }
+}
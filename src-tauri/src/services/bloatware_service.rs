@@ -0,0 +1,242 @@
+//! Freeze and unfreeze preinstalled bloatware with `pm disable-user`
+//!
+//! Only packages on a curated list can be disabled, so a misclick can't take
+//! down the launcher, the dialer or system UI. Disabling is per user and
+//! fully reversible; nothing is uninstalled.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{BloatwarePackage, PackageOpResult};
+use crate::services::adb_service::AdbRunner;
+use crate::services::kiosk_service;
+use std::collections::BTreeSet;
+
+/// Preinstalled packages that are safe to disable: `(package, label)`.
+/// Each is a standalone app or vendor add-on the system doesn't depend on.
+pub const DISABLE_SAFE: &[(&str, &str)] = &[
+    ("com.facebook.appmanager", "Facebook App Manager"),
+    ("com.facebook.services", "Facebook Services"),
+    ("com.facebook.system", "Facebook App Installer"),
+    ("com.facebook.katana", "Facebook"),
+    (
+        "com.netflix.partner.activation",
+        "Netflix Partner Activation",
+    ),
+    ("com.netflix.mediaclient", "Netflix"),
+    ("com.linkedin.android", "LinkedIn"),
+    ("com.microsoft.skydrive", "OneDrive"),
+    ("com.microsoft.office.officehubrow", "Microsoft Office hub"),
+    ("com.spotify.music", "Spotify"),
+    ("com.amazon.mShop.android.shopping", "Amazon Shopping"),
+    ("com.booking", "Booking.com"),
+    ("com.samsung.android.bixby.agent", "Bixby Voice"),
+    ("com.samsung.android.app.spage", "Samsung Free"),
+    ("com.samsung.android.game.gamehome", "Samsung Game Launcher"),
+    ("com.samsung.android.arzone", "Samsung AR Zone"),
+    ("com.miui.analytics", "MIUI Analytics"),
+    ("com.miui.msa.global", "MIUI System Ads"),
+    ("com.xiaomi.mipicks", "Xiaomi GetApps"),
+    ("com.google.android.apps.tachyon", "Google Meet (Duo)"),
+    ("com.google.android.videos", "Google TV"),
+    ("com.google.android.apps.youtube.music", "YouTube Music"),
+];
+
+/// Whether a package is on the curated disable-safe list
+pub fn is_disable_safe(package: &str) -> bool {
+    DISABLE_SAFE.iter().any(|(p, _)| *p == package)
+}
+
+/// Disable-safe packages installed on a device and whether each is disabled
+pub fn list_candidates(
+    adb: &dyn AdbRunner,
+    serial: &str,
+) -> Result<Vec<BloatwarePackage>, AppError> {
+    let installed =
+        package_set(&adb.run_idempotent(&["-s", serial, "shell", "pm", "list", "packages"])?);
+    let disabled =
+        package_set(&adb.run_idempotent(&["-s", serial, "shell", "pm", "list", "packages", "-d"])?);
+
+    Ok(DISABLE_SAFE
+        .iter()
+        .filter(|(package, _)| installed.contains(*package))
+        .map(|(package, label)| BloatwarePackage {
+            package: package.to_string(),
+            label: label.to_string(),
+            disabled: disabled.contains(*package),
+        })
+        .collect())
+}
+
+/// Disable packages for the device's main user. Packages off the curated
+/// list are refused.
+pub fn disable_packages(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    packages: &[String],
+) -> Vec<PackageOpResult> {
+    packages
+        .iter()
+        .map(|package| {
+            if !is_disable_safe(package) {
+                return PackageOpResult {
+                    package: package.clone(),
+                    success: false,
+                    message: "Not on the disable-safe list".to_string(),
+                };
+            }
+            set_state(adb, serial, package, "disable-user", "disabled-user")
+        })
+        .collect()
+}
+
+/// Re-enable packages, e.g. everything recorded as disabled on a device
+pub fn enable_packages(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    packages: &[String],
+) -> Vec<PackageOpResult> {
+    packages
+        .iter()
+        .map(|package| {
+            if !kiosk_service::is_valid_package_name(package) {
+                return PackageOpResult {
+                    package: package.clone(),
+                    success: false,
+                    message: format!("Invalid package name: {}", package),
+                };
+            }
+            set_state(adb, serial, package, "enable", "enabled")
+        })
+        .collect()
+}
+
+/// Fold operation results into a device's record of disabled packages
+pub fn update_record(record: &mut Vec<String>, results: &[PackageOpResult], disabled: bool) {
+    for result in results.iter().filter(|r| r.success) {
+        record.retain(|p| *p != result.package);
+        if disabled {
+            record.push(result.package.clone());
+        }
+    }
+    record.sort();
+}
+
+/// Run `pm <command> --user 0 <package>`, which prints
+/// `Package <package> new state: <state>` on success
+fn set_state(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    package: &str,
+    command: &str,
+    expected_state: &str,
+) -> PackageOpResult {
+    let result = adb.run(&["-s", serial, "shell", "pm", command, "--user", "0", package]);
+    let (success, message) = match result {
+        Ok(output) => {
+            let output = output.trim().to_string();
+            let success = output
+                .split_once("new state:")
+                .is_some_and(|(_, state)| state.trim() == expected_state);
+            (success, output)
+        }
+        Err(e) => (false, e.to_string()),
+    };
+
+    PackageOpResult {
+        package: package.to_string(),
+        success,
+        message,
+    }
+}
+
+fn package_set(output: &str) -> BTreeSet<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_list_candidates() {
+        let adb = MockAdb::new()
+            .on(
+                "list packages -d",
+                "package:com.facebook.appmanager\npackage:com.android.chrome\n",
+            )
+            .on(
+                "list packages",
+                "package:com.facebook.appmanager\npackage:com.miui.analytics\npackage:com.android.systemui\n",
+            );
+
+        let candidates = list_candidates(&adb, "SER1").unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].package, "com.facebook.appmanager");
+        assert!(candidates[0].disabled);
+        assert_eq!(candidates[1].package, "com.miui.analytics");
+        assert!(!candidates[1].disabled);
+    }
+
+    #[test]
+    fn test_disable_only_curated_packages() {
+        let adb = MockAdb::new().on(
+            "disable-user",
+            "Package com.miui.analytics new state: disabled-user\n",
+        );
+
+        let results = disable_packages(
+            &adb,
+            "SER1",
+            &[
+                "com.miui.analytics".to_string(),
+                "com.android.systemui".to_string(),
+            ],
+        );
+        assert!(results[0].success);
+        assert!(!results[1].success);
+        assert_eq!(
+            adb.calls(),
+            vec!["-s SER1 shell pm disable-user --user 0 com.miui.analytics"]
+        );
+    }
+
+    #[test]
+    fn test_enable_packages() {
+        let adb = MockAdb::new().on(
+            "pm enable",
+            "Package com.miui.analytics new state: enabled\n",
+        );
+        let results = enable_packages(
+            &adb,
+            "SER1",
+            &["com.miui.analytics".to_string(), "x; reboot".to_string()],
+        );
+        assert!(results[0].success);
+        assert!(!results[1].success);
+        assert_eq!(adb.calls().len(), 1);
+    }
+
+    #[test]
+    fn test_update_record() {
+        let ok = |package: &str| PackageOpResult {
+            package: package.to_string(),
+            success: true,
+            message: String::new(),
+        };
+        let mut record = vec!["com.miui.analytics".to_string()];
+
+        update_record(
+            &mut record,
+            &[ok("com.facebook.services"), ok("com.miui.analytics")],
+            true,
+        );
+        assert_eq!(record, vec!["com.facebook.services", "com.miui.analytics"]);
+
+        update_record(&mut record, &[ok("com.miui.analytics")], false);
+        assert_eq!(record, vec!["com.facebook.services"]);
+    }
+}
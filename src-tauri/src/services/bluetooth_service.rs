@@ -0,0 +1,217 @@
+//! Bluetooth hand-offs and their place in the transfer history
+//!
+//! Sending and receiving go through the built-in Windows Bluetooth wizard
+//! (`fsquirt`). It shows as an active transfer while open and is moved to
+//! the history when it closes. The wizard doesn't report what it sent or
+//! whether it worked, so that entry is marked as handed off; files that
+//! arrived in its default folder (Documents) while it was open are recorded
+//! as received.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{TransferItem, TransferStatus};
+use crate::services::transfer_service::{self, TransferState};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+
+/// Which way files go through the wizard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Send,
+    Receive,
+}
+
+/// Spawn a Windows system hand-off executable
+#[cfg(target_os = "windows")]
+pub fn spawn_handoff(exe: &str, args: &[&str], what: &str) -> Result<Child, AppError> {
+    std::process::Command::new(exe)
+        .args(args)
+        .spawn()
+        .map_err(|e| AppError::IoError(format!("Failed to open {}: {}", what, e)))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn spawn_handoff(_exe: &str, _args: &[&str], what: &str) -> Result<Child, AppError> {
+    Err(AppError::IoError(format!(
+        "Failed to open {}: only available on Windows",
+        what
+    )))
+}
+
+/// Open the Bluetooth send or receive wizard and track it in the transfer
+/// history. `device_name` is the phone the user means to use, if known.
+pub fn open_wizard(
+    app: &AppHandle,
+    direction: Direction,
+    device_name: Option<String>,
+) -> Result<(), AppError> {
+    let started = SystemTime::now();
+    let mut child = match direction {
+        Direction::Send => spawn_handoff("fsquirt.exe", &[], "Bluetooth send dialog")?,
+        Direction::Receive => spawn_handoff("fsquirt.exe", &["-r"], "Bluetooth receive dialog")?,
+    };
+
+    let item = handoff_item(
+        transfer_service::generate_id(),
+        direction,
+        device_name.as_deref(),
+        chrono::Utc::now().to_rfc3339(),
+    );
+    transfer_service::start_external(&app.state::<TransferState>(), item.clone());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let closed = child.wait();
+        let received = match direction {
+            Direction::Receive => dirs::document_dir()
+                .map(|dir| received_files(&dir, started))
+                .unwrap_or_default(),
+            Direction::Send => Vec::new(),
+        };
+        let status = if closed.is_ok() {
+            TransferStatus::HandedOff
+        } else {
+            TransferStatus::Failed
+        };
+        transfer_service::finish_external(
+            &app.state::<TransferState>(),
+            &item.id,
+            finished_items(&item, status, &received),
+        );
+    });
+    Ok(())
+}
+
+/// The active transfer shown while the wizard is open
+pub fn handoff_item(
+    id: String,
+    direction: Direction,
+    device_name: Option<&str>,
+    started_at: String,
+) -> TransferItem {
+    let device = device_name.unwrap_or("Bluetooth device");
+    let (file_name, source_path, dest_path) = match direction {
+        Direction::Send => ("Bluetooth send", "This computer", device),
+        Direction::Receive => ("Bluetooth receive", device, "This computer"),
+    };
+    TransferItem {
+        id,
+        file_name: file_name.to_string(),
+        source_path: source_path.to_string(),
+        dest_path: dest_path.to_string(),
+        size_bytes: 0,
+        transferred_bytes: 0,
+        status: TransferStatus::Transferring,
+        error: None,
+        started_at,
+        device_serial: device.to_string(),
+    }
+}
+
+/// Files directly in `dir` modified since `since`, with their sizes
+pub fn received_files(dir: &Path, since: SystemTime) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            (metadata.is_file() && metadata.modified().ok()? >= since)
+                .then(|| (entry.path(), metadata.len()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// History entries for a closed wizard: one per received file, or the
+/// hand-off itself when no files are known
+pub fn finished_items(
+    item: &TransferItem,
+    status: TransferStatus,
+    received: &[(PathBuf, u64)],
+) -> Vec<TransferItem> {
+    if received.is_empty() {
+        return vec![TransferItem {
+            status,
+            ..item.clone()
+        }];
+    }
+    received
+        .iter()
+        .enumerate()
+        .map(|(i, (path, size))| TransferItem {
+            id: format!("{}_{}", item.id, i),
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            dest_path: path.to_string_lossy().to_string(),
+            size_bytes: *size,
+            transferred_bytes: *size,
+            status: TransferStatus::Complete,
+            ..item.clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_finished_items() {
+        let item = handoff_item(
+            "transfer_1".to_string(),
+            Direction::Receive,
+            Some("Pixel 7"),
+            "2026-10-16T10:00:00+00:00".to_string(),
+        );
+        assert_eq!(item.source_path, "Pixel 7");
+        assert_eq!(item.status, TransferStatus::Transferring);
+
+        let handed_off = finished_items(&item, TransferStatus::HandedOff, &[]);
+        assert_eq!(handed_off.len(), 1);
+        assert_eq!(handed_off[0].status, TransferStatus::HandedOff);
+        assert_eq!(handed_off[0].file_name, "Bluetooth receive");
+
+        let received = finished_items(
+            &item,
+            TransferStatus::HandedOff,
+            &[
+                (PathBuf::from("/home/me/Documents/IMG_0001.jpg"), 2048),
+                (PathBuf::from("/home/me/Documents/notes.txt"), 12),
+            ],
+        );
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].file_name, "IMG_0001.jpg");
+        assert_eq!(received[0].size_bytes, 2048);
+        assert_eq!(received[0].status, TransferStatus::Complete);
+        assert_eq!(received[1].id, "transfer_1_1");
+        assert_eq!(received[1].device_serial, "Pixel 7");
+    }
+
+    #[test]
+    fn test_received_files() {
+        let dir = std::env::temp_dir().join(format!("gesu_bt_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("old.txt"), b"old").unwrap();
+        let since = SystemTime::now() + Duration::from_secs(1);
+        let later = since + Duration::from_secs(1);
+
+        std::fs::write(dir.join("new.jpg"), b"jpeg").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join("new.jpg"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        let files = received_files(&dir, since);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(files, vec![(dir.join("new.jpg"), 4)]);
+    }
+}
@@ -0,0 +1,145 @@
+//! Send PC clipboard text to a device
+//!
+//! Plain adb can't write the Android clipboard (scrcpy does it through its own
+//! server while mirroring), so URLs are opened with a VIEW intent and other
+//! text goes to the share sheet, where the user can copy it or pick an app.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ClipboardSendMode;
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+use crate::services::process_service::{self, TOOL_CHECK_TIMEOUT};
+use std::process::Command;
+
+/// Longest text sent in one shell command
+pub const MAX_TEXT_BYTES: usize = 64 * 1024;
+
+/// Read text from the PC clipboard using the platform's own tools
+pub fn read_pc_clipboard() -> Result<String, AppError> {
+    for (program, args) in clipboard_commands() {
+        let mut cmd = Command::new(program);
+        cmd.args(*args);
+
+        if let Ok(Some(output)) = process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT) {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+            }
+        }
+    }
+
+    Err(AppError::IoError(
+        "Could not read the clipboard".to_string(),
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn clipboard_commands() -> &'static [(&'static str, &'static [&'static str])] {
+    &[(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "[Console]::OutputEncoding=[Text.Encoding]::UTF8; Get-Clipboard -Raw",
+        ],
+    )]
+}
+
+#[cfg(target_os = "macos")]
+fn clipboard_commands() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("pbpaste", &[])]
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn clipboard_commands() -> &'static [(&'static str, &'static [&'static str])] {
+    &[
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-o"]),
+        ("xsel", &["--clipboard", "--output"]),
+    ]
+}
+
+/// A single http(s) link with nothing else around it
+pub fn is_url(text: &str) -> bool {
+    let text = text.trim();
+    (text.starts_with("https://") || text.starts_with("http://"))
+        && !text.contains(char::is_whitespace)
+}
+
+/// Deliver text to the device: URLs open in the default app, anything else
+/// opens the share sheet
+pub fn send_text(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    text: &str,
+) -> Result<ClipboardSendMode, AppError> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::IoError("Clipboard is empty".to_string()));
+    }
+    if trimmed.len() > MAX_TEXT_BYTES {
+        return Err(AppError::IoError(format!(
+            "Clipboard text is too long to send ({} KB max)",
+            MAX_TEXT_BYTES / 1024
+        )));
+    }
+
+    let quoted = quote_remote_path(trimmed);
+
+    if is_url(trimmed) {
+        adb.run(&[
+            "-s",
+            serial,
+            "shell",
+            "am",
+            "start",
+            "-a",
+            "android.intent.action.VIEW",
+            "-d",
+            &quoted,
+        ])?;
+        Ok(ClipboardSendMode::OpenedUrl)
+    } else {
+        adb.run(&[
+            "-s",
+            serial,
+            "shell",
+            "am",
+            "start",
+            "-a",
+            "android.intent.action.SEND",
+            "-t",
+            "text/plain",
+            "--es",
+            "android.intent.extra.TEXT",
+            &quoted,
+        ])?;
+        Ok(ClipboardSendMode::SharedText)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com/a?b=c"));
+        assert!(is_url("  http://example.com\n"));
+        assert!(!is_url("see https://example.com"));
+        assert!(!is_url("ftp://example.com"));
+    }
+
+    #[test]
+    fn test_send_text_quotes_content() {
+        let adb = MockAdb::new().on("am start", "Starting: Intent");
+
+        let mode = send_text(&adb, "SER1", "it's a note; rm -rf /").unwrap();
+        assert_eq!(mode, ClipboardSendMode::SharedText);
+        assert!(adb.calls()[0].contains("'it'\\''s a note; rm -rf /'"));
+
+        let mode = send_text(&adb, "SER1", "https://example.com").unwrap();
+        assert_eq!(mode, ClipboardSendMode::OpenedUrl);
+        assert!(send_text(&adb, "SER1", "   ").is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Device clock drift: how far a device's clock is from this computer's
+//!
+//! A device whose clock is off makes "keep newer" and sync comparisons pick
+//! the wrong side and puts photos in the wrong place on a timeline. The
+//! device watcher checks each device as it becomes ready and warns when the
+//! clocks are further apart than [`DRIFT_THRESHOLD_SECS`].
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ClockDrift;
+use crate::services::adb_service::AdbRunner;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Drift worth a warning. A few seconds is normal without network time.
+pub const DRIFT_THRESHOLD_SECS: i64 = 120;
+
+/// Event emitted with a [`ClockDrift`] payload when a device's clock is off
+pub const CLOCK_DRIFT_EVENT: &str = "clock-drift";
+
+/// Parse `date +%s` output
+pub fn parse_epoch(output: &str) -> Option<i64> {
+    output.trim().parse().ok()
+}
+
+/// Seconds the device's clock is ahead of this computer's (negative when
+/// behind). The computer's time is taken halfway through the adb round trip.
+pub fn device_clock_drift(adb: &dyn AdbRunner, serial: &str) -> Result<i64, AppError> {
+    let before = now_secs();
+    let output = adb.run_idempotent(&["-s", serial, "shell", "date", "+%s"])?;
+    let after = now_secs();
+    let device = parse_epoch(&output).ok_or_else(|| {
+        AppError::AdbExecutionFailed(format!(
+            "Unexpected `date +%s` output from {}: {}",
+            serial,
+            output.trim()
+        ))
+    })?;
+    Ok(device - (before + after) / 2)
+}
+
+/// The drift to warn about, if any
+pub fn excessive_drift(serial: &str, drift_secs: i64) -> Option<ClockDrift> {
+    (drift_secs.abs() > DRIFT_THRESHOLD_SECS).then(|| ClockDrift {
+        device_serial: serial.to_string(),
+        drift_secs,
+    })
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_device_clock_drift() {
+        let ahead = (now_secs() + 3600).to_string();
+        let adb = MockAdb::new().on("date +%s", &ahead);
+        let drift = device_clock_drift(&adb, "SER1").unwrap();
+        assert!((3599..=3601).contains(&drift));
+        assert_eq!(adb.calls(), vec!["-s SER1 shell date +%s"]);
+
+        let adb = MockAdb::new().on("date +%s", "date: bad format\n");
+        assert!(device_clock_drift(&adb, "SER1").is_err());
+    }
+
+    #[test]
+    fn test_excessive_drift() {
+        assert!(excessive_drift("SER1", 5).is_none());
+        assert!(excessive_drift("SER1", -DRIFT_THRESHOLD_SECS).is_none());
+        let drift = excessive_drift("SER1", -3600).unwrap();
+        assert_eq!(drift.device_serial, "SER1");
+        assert_eq!(drift.drift_secs, -3600);
+    }
+}
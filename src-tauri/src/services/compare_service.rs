@@ -0,0 +1,225 @@
+//! Side-by-side comparison of connected devices
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{ComparisonCategory, ComparisonRow, DeviceComparison};
+use crate::services::adb_service::AdbRunner;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Properties most likely to explain "works on one phone, not the other"
+const KEY_PROPS: &[&str] = &[
+    "ro.product.manufacturer",
+    "ro.product.model",
+    "ro.build.version.release",
+    "ro.build.version.sdk",
+    "ro.build.version.security_patch",
+    "ro.build.id",
+    "ro.build.type",
+    "ro.build.fingerprint",
+    "ro.product.cpu.abi",
+    "ro.sf.lcd_density",
+    "persist.sys.locale",
+    "persist.sys.timezone",
+];
+
+/// Storage row key: free bytes on the data partition
+pub const FREE_STORAGE_KEY: &str = "free_data_bytes";
+
+/// What was read from one device
+#[derive(Debug, Default)]
+struct Snapshot {
+    props: BTreeMap<String, String>,
+    /// Third-party package name -> version code
+    packages: BTreeMap<String, String>,
+    free_bytes: Option<u64>,
+    errors: Vec<String>,
+}
+
+/// Compare OS versions, key properties, free storage and installed
+/// third-party package versions across devices
+pub fn compare_devices(
+    adb: &dyn AdbRunner,
+    serials: &[String],
+) -> Result<DeviceComparison, AppError> {
+    if serials.len() < 2 {
+        return Err(AppError::DeviceNotFound(
+            "Choose at least two devices to compare".to_string(),
+        ));
+    }
+
+    let snapshots: Vec<Snapshot> = serials.iter().map(|s| snapshot(adb, s)).collect();
+    Ok(build_comparison(serials, &snapshots))
+}
+
+fn snapshot(adb: &dyn AdbRunner, serial: &str) -> Snapshot {
+    let mut snap = Snapshot::default();
+
+    match adb.run_idempotent(&["-s", serial, "shell", "getprop"]) {
+        Ok(out) => snap.props = parse_getprop(&out),
+        Err(e) => snap.errors.push(format!("Properties: {}", e)),
+    }
+
+    match adb.run_idempotent(&[
+        "-s",
+        serial,
+        "shell",
+        "pm",
+        "list",
+        "packages",
+        "-3",
+        "--show-versioncode",
+    ]) {
+        Ok(out) => snap.packages = parse_package_list(&out),
+        Err(e) => snap.errors.push(format!("Packages: {}", e)),
+    }
+
+    match adb.run_idempotent(&["-s", serial, "shell", "df", "-k", "/data"]) {
+        Ok(out) => snap.free_bytes = parse_df_available(&out),
+        Err(e) => snap.errors.push(format!("Storage: {}", e)),
+    }
+
+    snap
+}
+
+fn build_comparison(serials: &[String], snapshots: &[Snapshot]) -> DeviceComparison {
+    let mut rows = Vec::new();
+
+    for prop in KEY_PROPS {
+        rows.push(row(
+            ComparisonCategory::Property,
+            prop,
+            snapshots
+                .iter()
+                .map(|s| s.props.get(*prop).cloned())
+                .collect(),
+        ));
+    }
+
+    rows.push(row(
+        ComparisonCategory::Storage,
+        FREE_STORAGE_KEY,
+        snapshots
+            .iter()
+            .map(|s| s.free_bytes.map(|b| b.to_string()))
+            .collect(),
+    ));
+
+    let packages: BTreeSet<&String> = snapshots.iter().flat_map(|s| s.packages.keys()).collect();
+    for package in packages {
+        rows.push(row(
+            ComparisonCategory::Package,
+            package,
+            snapshots
+                .iter()
+                .map(|s| s.packages.get(package).cloned())
+                .collect(),
+        ));
+    }
+
+    DeviceComparison {
+        serials: serials.to_vec(),
+        errors: snapshots
+            .iter()
+            .map(|s| (!s.errors.is_empty()).then(|| s.errors.join("; ")))
+            .collect(),
+        rows,
+    }
+}
+
+fn row(category: ComparisonCategory, key: &str, values: Vec<Option<String>>) -> ComparisonRow {
+    let differs = values.windows(2).any(|w| w[0] != w[1]);
+    ComparisonRow {
+        category,
+        key: key.to_string(),
+        values,
+        differs,
+    }
+}
+
+/// Parse `[key]: [value]` lines from `getprop`
+pub fn parse_getprop(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once("]: [")?;
+            let key = key.strip_prefix('[')?;
+            let value = value.strip_suffix(']')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Parse `package:com.app versionCode:42` lines from `pm list packages`.
+/// Older Android ignores `--show-versioncode`; those packages map to "installed".
+pub fn parse_package_list(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("package:")?;
+            let mut parts = rest.split_whitespace();
+            let name = parts.next()?.to_string();
+            let version = parts
+                .find_map(|p| p.strip_prefix("versionCode:"))
+                .unwrap_or("installed")
+                .to_string();
+            Some((name, version))
+        })
+        .collect()
+}
+
+/// Available bytes from `df -k` output (last line, fourth column)
+pub fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().rev().find(|l| !l.trim().is_empty())?;
+    let kb: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parsers() {
+        let props = parse_getprop("[ro.build.version.sdk]: [34]\n[ro.product.model]: [Pixel 8]\n");
+        assert_eq!(props.get("ro.product.model").unwrap(), "Pixel 8");
+
+        let packages = parse_package_list("package:com.a versionCode:42\npackage:com.b\nnoise\n");
+        assert_eq!(packages.get("com.a").unwrap(), "42");
+        assert_eq!(packages.get("com.b").unwrap(), "installed");
+
+        let df = "Filesystem 1K-blocks Used Available Use% Mounted on\n\
+                  /dev/block/dm-5 100 60 40 60% /data\n";
+        assert_eq!(parse_df_available(df), Some(40 * 1024));
+        assert_eq!(parse_df_available("/data: not found"), None);
+    }
+
+    #[test]
+    fn test_compare_devices_marks_differences() {
+        let adb = MockAdb::new()
+            .on("-s A shell getprop", "[ro.build.version.sdk]: [34]")
+            .on("-s B shell getprop", "[ro.build.version.sdk]: [33]")
+            .on("-s A shell pm", "package:com.app versionCode:5")
+            .on(
+                "-s B shell pm",
+                "package:com.app versionCode:5\npackage:com.x versionCode:1",
+            )
+            .on(
+                "df",
+                "Filesystem 1K-blocks Used Available Use% Mounted on\nfs 10 5 5 50% /data",
+            );
+
+        let report = compare_devices(&adb, &["A".to_string(), "B".to_string()]).unwrap();
+        let find = |key: &str| report.rows.iter().find(|r| r.key == key).unwrap();
+
+        assert!(find("ro.build.version.sdk").differs);
+        assert!(!find("com.app").differs);
+        assert_eq!(find("com.x").values, vec![None, Some("1".to_string())]);
+        assert!(!find(FREE_STORAGE_KEY).differs);
+        assert_eq!(report.errors, vec![None, None]);
+    }
+
+    #[test]
+    fn test_compare_requires_two_devices() {
+        assert!(compare_devices(&MockAdb::new(), &["A".to_string()]).is_err());
+    }
+}
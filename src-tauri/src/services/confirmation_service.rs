@@ -0,0 +1,241 @@
+//! Two-step confirmation for destructive commands
+//!
+//! A destructive command only runs with a token from `request_confirmation`
+//! for that same action and the same arguments (device or group, paths,
+//! packages, mode, command). Tokens expire quickly and work once, so a stray
+//! or repeated frontend call can't delete data without the user confirming.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ConfirmationToken;
+use crate::state::write_lock;
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// How long a token stays valid
+pub const TOKEN_TTL: Duration = Duration::from_secs(60);
+
+/// Commands that need a confirmation token
+pub const DESTRUCTIVE_ACTIONS: &[&str] = &[
+    "delete_media_files",
+    "disable_bloatware",
+    "delete_recording",
+    "reboot_device",
+    "shell_on_group",
+];
+
+struct PendingConfirmation {
+    action: String,
+    /// [`digest`] of the arguments the user confirmed
+    args: u64,
+    expires: Instant,
+}
+
+/// Tokens handed out and not yet used
+#[derive(Default)]
+pub struct ConfirmationState {
+    pending: RwLock<HashMap<String, PendingConfirmation>>,
+}
+
+/// Fingerprint of a command's arguments, in the order the command lists them
+pub fn digest<S: AsRef<str>>(args: &[S]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for arg in args {
+        arg.as_ref().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Issue a token for one run of `action` with `args`, after the user
+/// confirmed what it will do
+pub fn request<S: AsRef<str>>(
+    state: &ConfirmationState,
+    action: &str,
+    args: &[S],
+    now: Instant,
+) -> Result<ConfirmationToken, AppError> {
+    if !DESTRUCTIVE_ACTIONS.contains(&action) {
+        return Err(AppError::ConfirmationRequired(format!(
+            "{} doesn't take a confirmation",
+            action
+        )));
+    }
+
+    let mut pending = write_lock(&state.pending);
+    pending.retain(|_, p| p.expires > now);
+
+    let token = new_token();
+    pending.insert(
+        token.clone(),
+        PendingConfirmation {
+            action: action.to_string(),
+            args: digest(args),
+            expires: now + TOKEN_TTL,
+        },
+    );
+
+    Ok(ConfirmationToken {
+        token,
+        action: action.to_string(),
+        expires_in_secs: TOKEN_TTL.as_secs(),
+    })
+}
+
+/// Use up a token for `action` with `args`. Fails if it's missing, expired,
+/// already used or was issued for another action or other arguments.
+pub fn consume<S: AsRef<str>>(
+    state: &ConfirmationState,
+    action: &str,
+    args: &[S],
+    token: Option<&str>,
+    now: Instant,
+) -> Result<(), AppError> {
+    let Some(token) = token else {
+        return Err(AppError::ConfirmationRequired(format!(
+            "{} needs a confirmation token",
+            action
+        )));
+    };
+
+    // Removed whatever the outcome, so a token is never tried twice
+    let pending = write_lock(&state.pending).remove(token);
+    match pending {
+        Some(p) if p.action != action => Err(AppError::ConfirmationRequired(format!(
+            "Token was issued for {}, not {}",
+            p.action, action
+        ))),
+        Some(p) if p.args != digest(args) => Err(AppError::ConfirmationRequired(
+            "Token was issued for other arguments; confirm again".to_string(),
+        )),
+        Some(p) if p.expires > now => Ok(()),
+        _ => Err(AppError::ConfirmationRequired(
+            "Confirmation expired or was already used; confirm again".to_string(),
+        )),
+    }
+}
+
+/// 128 unpredictable bits from the std hasher's random keys, no extra crates
+fn new_token() -> String {
+    let half = || {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default(),
+        );
+        hasher.finish()
+    };
+    format!("{:016x}{:016x}", half(), half())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_works_once_for_its_action() {
+        let state = ConfirmationState::default();
+        let now = Instant::now();
+        let args = ["SER1", "/sdcard/DCIM/a.jpg"];
+        let issued = request(&state, "delete_media_files", &args, now).unwrap();
+        assert_eq!(issued.token.len(), 32);
+
+        assert!(consume(
+            &state,
+            "delete_media_files",
+            &args,
+            Some(&issued.token),
+            now
+        )
+        .is_ok());
+        assert!(consume(
+            &state,
+            "delete_media_files",
+            &args,
+            Some(&issued.token),
+            now
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_token_rejected_for_other_action_or_when_expired() {
+        let state = ConfirmationState::default();
+        let now = Instant::now();
+
+        let args = ["SER1", "com.example.game"];
+        let issued = request(&state, "disable_bloatware", &args, now).unwrap();
+        let err = consume(
+            &state,
+            "delete_media_files",
+            &args,
+            Some(&issued.token),
+            now,
+        )
+        .unwrap_err();
+        assert!(matches!(err, AppError::ConfirmationRequired(_)));
+
+        let issued = request(&state, "disable_bloatware", &args, now).unwrap();
+        let later = now + TOKEN_TTL + Duration::from_secs(1);
+        assert!(consume(
+            &state,
+            "disable_bloatware",
+            &args,
+            Some(&issued.token),
+            later
+        )
+        .is_err());
+
+        assert!(consume(&state, "disable_bloatware", &args, None, now).is_err());
+        assert!(consume(&state, "disable_bloatware", &args, Some("made-up"), now).is_err());
+    }
+
+    #[test]
+    fn test_token_rejected_for_other_arguments() {
+        let state = ConfirmationState::default();
+        let now = Instant::now();
+
+        let issued = request(&state, "reboot_device", &["SER1", "system"], now).unwrap();
+        assert!(consume(
+            &state,
+            "reboot_device",
+            &["SER1", "recovery"],
+            Some(&issued.token),
+            now
+        )
+        .is_err());
+
+        let issued = request(&state, "reboot_device", &["SER1", "system"], now).unwrap();
+        assert!(consume(
+            &state,
+            "reboot_device",
+            &["SER2", "system"],
+            Some(&issued.token),
+            now
+        )
+        .is_err());
+
+        // A group command confirmed for a listing can't run a delete
+        let issued = request(&state, "shell_on_group", &["lab", "ls", "/sdcard"], now).unwrap();
+        assert!(consume(
+            &state,
+            "shell_on_group",
+            &["lab", "rm", "-rf", "/sdcard"],
+            Some(&issued.token),
+            now
+        )
+        .is_err());
+
+        // Argument boundaries count: ["a", "bc"] isn't ["ab", "c"]
+        assert_ne!(digest(&["a", "bc"]), digest(&["ab", "c"]));
+    }
+
+    #[test]
+    fn test_request_only_for_destructive_actions() {
+        let state = ConfirmationState::default();
+        assert!(request(&state, "list_devices", &["SER1"], Instant::now()).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Decide what to do when a transfer destination already exists
+
+use crate::domain::models::ConflictPolicy;
+
+/// Highest ` (n)` suffix tried before giving up on renaming
+const MAX_RENAME_ATTEMPTS: u32 = 999;
+
+/// Outcome of checking a destination against a conflict policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// Write to this path (the original target or a renamed one)
+    Write(String),
+    /// Leave the destination alone, for the given reason
+    Skip(String),
+}
+
+/// Resolve a destination path.
+///
+/// `existing_mtime` is `None` when nothing exists at `target`, otherwise
+/// `Some` with the existing file's mtime if known. `exists` is used to probe
+/// candidate names for [`ConflictPolicy::Rename`].
+pub fn resolve(
+    policy: ConflictPolicy,
+    target: &str,
+    existing_mtime: Option<Option<i64>>,
+    source_mtime: Option<i64>,
+    exists: impl Fn(&str) -> bool,
+) -> Resolution {
+    let Some(existing_mtime) = existing_mtime else {
+        return Resolution::Write(target.to_string());
+    };
+
+    match policy {
+        ConflictPolicy::Overwrite => Resolution::Write(target.to_string()),
+        ConflictPolicy::Skip => Resolution::Skip("Destination already exists".to_string()),
+        ConflictPolicy::KeepNewer => match (source_mtime, existing_mtime) {
+            (Some(source), Some(existing)) if source > existing => {
+                Resolution::Write(target.to_string())
+            }
+            // Unknown times are treated as "not newer" so nothing is lost
+            _ => Resolution::Skip("Destination is the same age or newer".to_string()),
+        },
+        ConflictPolicy::Rename => (1..=MAX_RENAME_ATTEMPTS)
+            .map(|n| suffixed_name(target, n))
+            .find(|candidate| !exists(candidate))
+            .map(Resolution::Write)
+            .unwrap_or_else(|| Resolution::Skip("No free file name".to_string())),
+    }
+}
+
+/// `dir/photo.jpg` -> `dir/photo (n).jpg`; works with `/` and `\` separators
+pub fn suffixed_name(path: &str, n: u32) -> String {
+    let name_start = path.rfind(['/', '\\']).map(|i| i + 1).unwrap_or(0);
+    let (dir, name) = path.split_at(name_start);
+
+    // A leading dot is a hidden file, not an extension
+    match name.rfind('.').filter(|&i| i > 0) {
+        Some(dot) => format!("{}{} ({}){}", dir, &name[..dot], n, &name[dot..]),
+        None => format!("{}{} ({})", dir, name, n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffixed_name() {
+        assert_eq!(
+            suffixed_name("/sdcard/DCIM/a.jpg", 1),
+            "/sdcard/DCIM/a (1).jpg"
+        );
+        assert_eq!(
+            suffixed_name("C:\\Pics\\b.tar.gz", 2),
+            "C:\\Pics\\b.tar (2).gz"
+        );
+        assert_eq!(suffixed_name("/sdcard/.nomedia", 1), "/sdcard/.nomedia (1)");
+        assert_eq!(suffixed_name("README", 3), "README (3)");
+    }
+
+    #[test]
+    fn test_resolve_policies() {
+        let none = |_: &str| false;
+        let target = "/sdcard/a.jpg";
+
+        // Nothing at the destination: always write
+        assert_eq!(
+            resolve(ConflictPolicy::Skip, target, None, None, none),
+            Resolution::Write(target.to_string())
+        );
+        assert!(matches!(
+            resolve(ConflictPolicy::Skip, target, Some(Some(1)), Some(2), none),
+            Resolution::Skip(_)
+        ));
+        assert_eq!(
+            resolve(
+                ConflictPolicy::KeepNewer,
+                target,
+                Some(Some(1)),
+                Some(2),
+                none
+            ),
+            Resolution::Write(target.to_string())
+        );
+        assert!(matches!(
+            resolve(
+                ConflictPolicy::KeepNewer,
+                target,
+                Some(Some(5)),
+                Some(2),
+                none
+            ),
+            Resolution::Skip(_)
+        ));
+
+        let taken = |p: &str| p == "/sdcard/a (1).jpg";
+        assert_eq!(
+            resolve(ConflictPolicy::Rename, target, Some(None), None, taken),
+            Resolution::Write("/sdcard/a (2).jpg".to_string())
+        );
+    }
+}
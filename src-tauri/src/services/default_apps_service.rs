@@ -0,0 +1,250 @@
+//! Default apps (browser, SMS, ...) and web link handling
+//!
+//! Roles use `cmd role` (Android 10+), link handling uses the domain
+//! verification commands of `pm` (Android 12+).
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{AppLinkDomain, AppLinks, DefaultApp, DefaultAppRole};
+use crate::services::adb_service::AdbRunner;
+use crate::services::kiosk_service;
+
+/// Current holder of every default-app role
+pub fn get_default_apps(adb: &dyn AdbRunner, serial: &str) -> Result<Vec<DefaultApp>, AppError> {
+    DefaultAppRole::ALL
+        .iter()
+        .map(|role| {
+            let output = adb.run_idempotent(&[
+                "-s",
+                serial,
+                "shell",
+                "cmd",
+                "role",
+                "get-role-holders",
+                "--user",
+                "0",
+                role.role_name(),
+            ])?;
+            Ok(DefaultApp {
+                role: *role,
+                holder: parse_role_holder(&output),
+            })
+        })
+        .collect()
+}
+
+/// First package in `cmd role get-role-holders` output; empty means unset
+pub fn parse_role_holder(output: &str) -> Option<String> {
+    output
+        .lines()
+        .flat_map(|line| line.split(';'))
+        .map(str::trim)
+        .find(|p| kiosk_service::is_valid_package_name(p))
+        .map(str::to_string)
+}
+
+/// Make a package the default app for a role
+pub fn set_default_app(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    role: DefaultAppRole,
+    package: &str,
+) -> Result<String, AppError> {
+    check_package(package)?;
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "cmd",
+        "role",
+        "add-role-holder",
+        "--user",
+        "0",
+        role.role_name(),
+        package,
+    ])
+}
+
+/// Unset the default app for a role, so the device asks again next time
+pub fn clear_default_app(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    role: DefaultAppRole,
+) -> Result<String, AppError> {
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "cmd",
+        "role",
+        "clear-role-holders",
+        "--user",
+        "0",
+        role.role_name(),
+    ])
+}
+
+/// Domains an app handles links for and their verification state
+pub fn get_app_links(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    package: &str,
+) -> Result<AppLinks, AppError> {
+    check_package(package)?;
+    let output = adb.run_idempotent(&["-s", serial, "shell", "pm", "get-app-links", package])?;
+    Ok(AppLinks {
+        package: package.to_string(),
+        domains: parse_app_links(&output),
+    })
+}
+
+/// Parse the `Domain verification state:` section of `pm get-app-links`
+///
+/// Example output:
+/// ```text
+///   com.example.app:
+///     ID: 3a1c9f0e-5b2d-4c1a-9e7f-0d6b8a2c4e11
+///     Signatures: [AB:CD:...]
+///     Domain verification state:
+///       example.com: verified
+///       www.example.com: 1024
+/// ```
+pub fn parse_app_links(output: &str) -> Vec<AppLinkDomain> {
+    output
+        .lines()
+        .skip_while(|line| line.trim() != "Domain verification state:")
+        .skip(1)
+        .map_while(|line| {
+            let (domain, state) = line.trim().split_once(": ")?;
+            (!domain.is_empty() && !domain.contains(' ')).then(|| AppLinkDomain {
+                domain: domain.to_string(),
+                state: state.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Allow or stop an app opening its verified links directly
+pub fn set_app_links_allowed(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    package: &str,
+    allowed: bool,
+) -> Result<String, AppError> {
+    check_package(package)?;
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "pm",
+        "set-app-links-allowed",
+        "--user",
+        "0",
+        "--package",
+        package,
+        if allowed { "true" } else { "false" },
+    ])
+}
+
+/// Forget an app's verification results and re-verify its domains
+pub fn reset_app_links(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    package: &str,
+) -> Result<String, AppError> {
+    check_package(package)?;
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "pm",
+        "reset-app-links",
+        "--user",
+        "0",
+        package,
+    ])?;
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "pm",
+        "verify-app-links",
+        "--re-verify",
+        package,
+    ])
+}
+
+fn check_package(package: &str) -> Result<(), AppError> {
+    if kiosk_service::is_valid_package_name(package) {
+        Ok(())
+    } else {
+        Err(AppError::AdbExecutionFailed(format!(
+            "Invalid package name: {}",
+            package
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parse_role_holder() {
+        assert_eq!(
+            parse_role_holder("com.android.chrome\n").as_deref(),
+            Some("com.android.chrome")
+        );
+        assert_eq!(parse_role_holder("\n"), None);
+    }
+
+    #[test]
+    fn test_get_default_apps() {
+        let adb = MockAdb::new()
+            .on("android.app.role.BROWSER", "org.mozilla.firefox\n")
+            .on(
+                "android.app.role.SMS",
+                "com.google.android.apps.messaging\n",
+            )
+            .on("get-role-holders", "");
+
+        let apps = get_default_apps(&adb, "SER1").unwrap();
+        assert_eq!(apps.len(), DefaultAppRole::ALL.len());
+        assert_eq!(apps[0].holder.as_deref(), Some("org.mozilla.firefox"));
+        assert_eq!(apps[2].role, DefaultAppRole::Dialer);
+        assert_eq!(apps[2].holder, None);
+    }
+
+    #[test]
+    fn test_parse_app_links() {
+        let output = "  com.example.app:\n    ID: 3a1c9f0e-5b2d-4c1a-9e7f-0d6b8a2c4e11\n    Signatures: [AB:CD]\n    Domain verification state:\n      example.com: verified\n      www.example.com: 1024\n";
+        assert_eq!(
+            parse_app_links(output),
+            vec![
+                AppLinkDomain {
+                    domain: "example.com".to_string(),
+                    state: "verified".to_string(),
+                },
+                AppLinkDomain {
+                    domain: "www.example.com".to_string(),
+                    state: "1024".to_string(),
+                },
+            ]
+        );
+        assert!(parse_app_links("Unknown command: get-app-links").is_empty());
+    }
+
+    #[test]
+    fn test_package_names_are_checked() {
+        let adb = MockAdb::new().on("", "");
+        assert!(set_default_app(&adb, "SER1", DefaultAppRole::Browser, "x;reboot").is_err());
+        assert!(reset_app_links(&adb, "SER1", "com.example.app").is_ok());
+        assert_eq!(
+            adb.calls(),
+            vec![
+                "-s SER1 shell pm reset-app-links --user 0 com.example.app",
+                "-s SER1 shell pm verify-app-links --re-verify com.example.app",
+            ]
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Local pull destinations: recent and pinned folders offered as quick
+//! choices, and the per-device Downloads subfolder used when none is given
+
+use crate::domain::errors::AppError;
+use crate::domain::models::LocalDestination;
+use crate::services::settings_service;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Recent destinations kept
+pub const MAX_RECENT_DESTINATIONS: usize = 8;
+
+/// Folder in Downloads holding the per-device folders
+pub const APP_DOWNLOADS_FOLDER: &str = "GesuBridge";
+
+/// Name of a device's Downloads subfolder: its nickname, else its model,
+/// else its serial, made safe for Windows, macOS and Linux file names
+pub fn device_folder_name(nickname: Option<&str>, model: Option<&str>, serial: &str) -> String {
+    let label = [nickname, model]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|label| !label.is_empty())
+        .unwrap_or(serial);
+    let name: String = label
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows drops trailing dots and spaces
+    let name = name.trim_end_matches(['.', ' ']);
+    if name.is_empty() {
+        "device".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `downloads/GesuBridge/<device folder name>`
+pub fn device_pull_dir(downloads: &Path, folder_name: &str) -> PathBuf {
+    downloads.join(APP_DOWNLOADS_FOLDER).join(folder_name)
+}
+
+/// Move `path` to the front of the recent list
+pub fn remember(recent: &mut Vec<String>, path: &str) {
+    recent.retain(|p| !same_folder(p, path));
+    recent.insert(0, path.to_string());
+    recent.truncate(MAX_RECENT_DESTINATIONS);
+}
+
+/// Pinned folders in the user's order, then recent ones not pinned
+pub fn quick_choices(
+    pinned: &[String],
+    recent: &[String],
+    exists: impl Fn(&str) -> bool,
+) -> Vec<LocalDestination> {
+    let pinned_choices = pinned.iter().map(|path| (path, true));
+    let recent_choices = recent
+        .iter()
+        .filter(|path| !pinned.iter().any(|p| same_folder(p, path)))
+        .map(|path| (path, false));
+    pinned_choices
+        .chain(recent_choices)
+        .map(|(path, pinned)| LocalDestination {
+            path: path.clone(),
+            pinned,
+            exists: exists(path),
+        })
+        .collect()
+}
+
+/// Record a folder pulled into; only written when the list changes
+pub fn record_used(app: &AppHandle, dest: &Path) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(app)?;
+    let before = settings.recent_destinations.clone();
+    remember(&mut settings.recent_destinations, &dest.to_string_lossy());
+    if settings.recent_destinations != before {
+        settings_service::save_settings(app, &settings)?;
+    }
+    Ok(())
+}
+
+/// Quick choices from the saved settings
+pub fn get_destinations(app: &AppHandle) -> Result<Vec<LocalDestination>, AppError> {
+    let settings = settings_service::load_settings(app)?;
+    Ok(quick_choices(
+        &settings.pinned_destinations,
+        &settings.recent_destinations,
+        |path| Path::new(path).is_dir(),
+    ))
+}
+
+/// Pin or unpin a folder; pinning needs an existing folder
+pub fn set_pinned(
+    app: &AppHandle,
+    path: &str,
+    pinned: bool,
+) -> Result<Vec<LocalDestination>, AppError> {
+    let path = path.trim();
+    let mut settings = settings_service::load_settings(app)?;
+    settings
+        .pinned_destinations
+        .retain(|p| !same_folder(p, path));
+    if pinned {
+        if !Path::new(path).is_absolute() || !Path::new(path).is_dir() {
+            return Err(AppError::InvalidPath(format!("Not a folder: {}", path)));
+        }
+        settings.pinned_destinations.push(path.to_string());
+    }
+    settings_service::save_settings(app, &settings)?;
+    get_destinations(app)
+}
+
+/// Same folder, ignoring a trailing separator (and case on Windows)
+fn same_folder(a: &str, b: &str) -> bool {
+    let trim = |p: &str| p.trim_end_matches(['/', '\\']).to_string();
+    if cfg!(windows) {
+        trim(a).eq_ignore_ascii_case(&trim(b))
+    } else {
+        trim(a) == trim(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_folder_name() {
+        assert_eq!(
+            device_folder_name(Some("Mom's phone"), Some("Pixel 7"), "SER1"),
+            "Mom's phone"
+        );
+        assert_eq!(
+            device_folder_name(Some("  "), Some("SM-G991B"), "SER1"),
+            "SM-G991B"
+        );
+        assert_eq!(
+            device_folder_name(None, None, "192.168.1.5:5555"),
+            "192.168.1.5_5555"
+        );
+        assert_eq!(device_folder_name(Some("a/b?."), None, "SER1"), "a_b_");
+        assert_eq!(device_folder_name(Some("..."), None, "SER1"), "device");
+        assert_eq!(
+            device_pull_dir(Path::new("/home/u/Downloads"), "Pixel 7"),
+            Path::new("/home/u/Downloads/GesuBridge/Pixel 7")
+        );
+    }
+
+    #[test]
+    fn test_remember_moves_to_front_and_caps() {
+        let mut recent = vec!["/a".to_string(), "/b".to_string()];
+        remember(&mut recent, "/b/");
+        assert_eq!(recent, vec!["/b/", "/a"]);
+
+        for i in 0..10 {
+            remember(&mut recent, &format!("/d{}", i));
+        }
+        assert_eq!(recent.len(), MAX_RECENT_DESTINATIONS);
+        assert_eq!(recent[0], "/d9");
+    }
+
+    #[test]
+    fn test_quick_choices_pinned_first() {
+        let pinned = vec!["/photos".to_string()];
+        let recent = vec![
+            "/tmp/x".to_string(),
+            "/photos/".to_string(),
+            "/gone".to_string(),
+        ];
+        let choices = quick_choices(&pinned, &recent, |p| p != "/gone");
+        let summary: Vec<_> = choices
+            .iter()
+            .map(|c| (c.path.as_str(), c.pinned, c.exists))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/photos", true, true),
+                ("/tmp/x", false, true),
+                ("/gone", false, false)
+            ]
+        );
+    }
+}
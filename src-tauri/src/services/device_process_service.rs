@@ -0,0 +1,118 @@
+//! Processes running on a device: list, force-stop apps and kill processes
+//!
+//! Without root, `kill` only works on processes owned by the adb shell user;
+//! app processes are stopped with `am force-stop` instead.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::DeviceProcess;
+use crate::services::adb_service::AdbRunner;
+use crate::services::kiosk_service;
+
+/// Processes on the device, largest memory users first
+pub fn list_processes(adb: &dyn AdbRunner, serial: &str) -> Result<Vec<DeviceProcess>, AppError> {
+    let output =
+        adb.run_idempotent(&["-s", serial, "shell", "ps", "-A", "-o", "PID,USER,RSS,NAME"])?;
+    Ok(parse_ps(&output))
+}
+
+/// Parse `ps -A -o PID,USER,RSS,NAME`
+///
+/// Example output:
+/// ```text
+///   PID USER            RSS NAME
+///     1 root          10580 init
+///  4211 u0_a163      184220 com.example.app
+/// ```
+pub fn parse_ps(output: &str) -> Vec<DeviceProcess> {
+    let mut processes: Vec<DeviceProcess> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let user = fields.next()?.to_string();
+            let rss_kb = fields.next()?.parse().ok()?;
+            let name = fields.collect::<Vec<_>>().join(" ");
+            (!name.is_empty()).then_some(DeviceProcess {
+                pid,
+                user,
+                rss_kb,
+                name,
+            })
+        })
+        .collect();
+
+    processes.sort_by_key(|p| std::cmp::Reverse(p.rss_kb));
+    processes
+}
+
+/// Stop every process of an app, like "Force stop" in the app's settings
+pub fn force_stop(adb: &dyn AdbRunner, serial: &str, package: &str) -> Result<String, AppError> {
+    if !kiosk_service::is_valid_package_name(package) {
+        return Err(AppError::AdbExecutionFailed(format!(
+            "Invalid package name: {}",
+            package
+        )));
+    }
+    adb.run(&["-s", serial, "shell", "am", "force-stop", package])
+}
+
+/// Send SIGTERM to a process
+pub fn kill_process(adb: &dyn AdbRunner, serial: &str, pid: u32) -> Result<String, AppError> {
+    let output = adb.run(&["-s", serial, "shell", "kill", &pid.to_string()])?;
+    if output.contains("Operation not permitted") {
+        return Err(AppError::AdbExecutionFailed(format!(
+            "Process {} belongs to another user. Use Force stop for apps.",
+            pid
+        )));
+    }
+    if output.contains("No such process") {
+        return Err(AppError::AdbExecutionFailed(format!(
+            "Process {} is not running",
+            pid
+        )));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parse_ps() {
+        let output = "  PID USER            RSS NAME\n    1 root          10580 init\n 4211 u0_a163      184220 com.example.app\n 4300 u0_a163       52000 com.example.app:sync\n";
+        let processes = parse_ps(output);
+
+        assert_eq!(processes.len(), 3);
+        assert_eq!(processes[0].pid, 4211);
+        assert_eq!(processes[0].user, "u0_a163");
+        assert_eq!(processes[0].name, "com.example.app");
+        assert_eq!(processes[2].name, "init");
+    }
+
+    #[test]
+    fn test_force_stop() {
+        let adb = MockAdb::new().on("force-stop", "");
+        assert!(force_stop(&adb, "SER1", "com.example.app").is_ok());
+        assert!(force_stop(&adb, "SER1", "com.example.app; reboot").is_err());
+        assert_eq!(
+            adb.calls(),
+            vec!["-s SER1 shell am force-stop com.example.app"]
+        );
+    }
+
+    #[test]
+    fn test_kill_process() {
+        let adb = MockAdb::new()
+            .on(
+                "kill 4211",
+                "/system/bin/sh: kill: 4211: Operation not permitted\n",
+            )
+            .on("kill 99", "");
+
+        assert!(kill_process(&adb, "SER1", 99).is_ok());
+        let err = kill_process(&adb, "SER1", 4211).unwrap_err();
+        assert!(err.to_string().contains("Force stop"));
+    }
+}
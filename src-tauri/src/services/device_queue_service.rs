@@ -0,0 +1,245 @@
+//! Per-device adb concurrency limit with priority for interactive requests
+//!
+//! Thumbnails, listings and background polls can otherwise start dozens of
+//! adb processes against one phone at once, which some devices don't survive.
+//! Every adb call that targets a device (`-s <serial>`) takes a slot here
+//! first. Background work (thumbnails, pollers, watchers) never takes the
+//! last slots and yields to interactive requests that are waiting.
+
+use crate::domain::errors::AppError;
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+/// adb processes allowed at once per device
+pub const DEVICE_CONCURRENCY: usize = 4;
+
+/// Slots background work may use at once per device
+pub const BACKGROUND_CONCURRENCY: usize = 2;
+
+/// Who is waiting for a device
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Priority {
+    /// A user action waiting for its result
+    #[default]
+    Interactive,
+    /// Thumbnails, polling and watchers
+    Background,
+}
+
+/// Slot usage of one device
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DeviceSlots {
+    pub running: usize,
+    pub background_running: usize,
+    pub interactive_waiting: usize,
+}
+
+impl DeviceSlots {
+    /// Whether a request of this priority may start now
+    pub fn can_start(&self, priority: Priority) -> bool {
+        match priority {
+            Priority::Interactive => self.running < DEVICE_CONCURRENCY,
+            Priority::Background => {
+                self.interactive_waiting == 0
+                    && self.running < DEVICE_CONCURRENCY
+                    && self.background_running < BACKGROUND_CONCURRENCY
+            }
+        }
+    }
+}
+
+/// Slots of all devices
+#[derive(Default)]
+pub struct DeviceQueue {
+    devices: Mutex<HashMap<String, DeviceSlots>>,
+    freed: Condvar,
+}
+
+/// A held slot, given back when dropped
+pub struct Permit<'a> {
+    queue: &'a DeviceQueue,
+    serial: String,
+    priority: Priority,
+}
+
+impl DeviceQueue {
+    /// Wait up to `timeout` for a slot on the device
+    pub fn acquire(
+        &self,
+        serial: &str,
+        priority: Priority,
+        timeout: Duration,
+    ) -> Result<Permit<'_>, AppError> {
+        let deadline = Instant::now() + timeout;
+        let mut devices = self.devices.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut waiting = false;
+        loop {
+            let slots = devices.entry(serial.to_string()).or_default();
+            if slots.can_start(priority) {
+                if waiting {
+                    slots.interactive_waiting -= 1;
+                }
+                slots.running += 1;
+                if priority == Priority::Background {
+                    slots.background_running += 1;
+                }
+                break;
+            }
+            if priority == Priority::Interactive && !waiting {
+                slots.interactive_waiting += 1;
+                waiting = true;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                if waiting {
+                    slots.interactive_waiting -= 1;
+                }
+                if *slots == DeviceSlots::default() {
+                    devices.remove(serial);
+                }
+                drop(devices);
+                // Background work held back for this request may go now
+                self.freed.notify_all();
+                return Err(AppError::Timeout(format!(
+                    "{} was busy for {}s",
+                    serial,
+                    timeout.as_secs()
+                )));
+            }
+            devices = self
+                .freed
+                .wait_timeout(devices, left)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        Ok(Permit {
+            queue: self,
+            serial: serial.to_string(),
+            priority,
+        })
+    }
+
+    /// Current slot usage of a device
+    pub fn slots(&self, serial: &str) -> DeviceSlots {
+        self.devices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(serial)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let mut devices = self
+            .queue
+            .devices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(slots) = devices.get_mut(&self.serial) {
+            slots.running -= 1;
+            if self.priority == Priority::Background {
+                slots.background_running -= 1;
+            }
+            if *slots == DeviceSlots::default() {
+                devices.remove(&self.serial);
+            }
+        }
+        drop(devices);
+        self.queue.freed.notify_all();
+    }
+}
+
+/// The queue shared by every adb call in the app. adb is reached from many
+/// places that have no app handle, so it lives for the whole process.
+pub fn global() -> &'static DeviceQueue {
+    static QUEUE: OnceLock<DeviceQueue> = OnceLock::new();
+    QUEUE.get_or_init(DeviceQueue::default)
+}
+
+/// The device an adb invocation targets, if it names one
+pub fn target_serial<'a>(args: &[&'a str]) -> Option<&'a str> {
+    match args {
+        ["-s", serial, ..] => Some(serial),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const WAIT: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_background_leaves_room_and_yields() {
+        let mut slots = DeviceSlots {
+            running: 1,
+            background_running: 1,
+            interactive_waiting: 0,
+        };
+        assert!(slots.can_start(Priority::Background));
+
+        slots.background_running = BACKGROUND_CONCURRENCY;
+        slots.running = BACKGROUND_CONCURRENCY;
+        assert!(!slots.can_start(Priority::Background));
+        assert!(slots.can_start(Priority::Interactive));
+
+        slots.background_running = 0;
+        slots.interactive_waiting = 1;
+        assert!(!slots.can_start(Priority::Background));
+
+        slots.running = DEVICE_CONCURRENCY;
+        assert!(!slots.can_start(Priority::Interactive));
+    }
+
+    #[test]
+    fn test_acquire_blocks_until_a_slot_frees() {
+        let queue = Arc::new(DeviceQueue::default());
+        let permits: Vec<_> = (0..DEVICE_CONCURRENCY)
+            .map(|_| queue.acquire("SER1", Priority::Interactive, WAIT).unwrap())
+            .collect();
+        // Other devices aren't affected
+        drop(queue.acquire("SER2", Priority::Interactive, WAIT).unwrap());
+
+        let waiter = {
+            let queue = queue.clone();
+            std::thread::spawn(move || {
+                drop(queue.acquire("SER1", Priority::Interactive, WAIT).unwrap())
+            })
+        };
+        while queue.slots("SER1").interactive_waiting == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        drop(permits);
+        waiter.join().unwrap();
+        assert_eq!(queue.slots("SER1"), DeviceSlots::default());
+    }
+
+    #[test]
+    fn test_acquire_gives_up_after_the_timeout() {
+        let queue = DeviceQueue::default();
+        let permits: Vec<_> = (0..DEVICE_CONCURRENCY)
+            .map(|_| queue.acquire("SER1", Priority::Interactive, WAIT).unwrap())
+            .collect();
+
+        let waited = queue.acquire("SER1", Priority::Interactive, Duration::from_millis(20));
+        assert!(matches!(waited, Err(AppError::Timeout(_))));
+        // The abandoned wait no longer holds background work back
+        assert_eq!(queue.slots("SER1").interactive_waiting, 0);
+
+        drop(permits);
+        assert_eq!(queue.slots("SER1"), DeviceSlots::default());
+    }
+
+    #[test]
+    fn test_target_serial() {
+        assert_eq!(target_serial(&["-s", "SER1", "shell", "ls"]), Some("SER1"));
+        assert_eq!(target_serial(&["devices", "-l"]), None);
+    }
+}
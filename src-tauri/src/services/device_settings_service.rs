@@ -0,0 +1,184 @@
+//! Read and write the Android `settings` store
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{DeviceSetting, SettingsNamespace};
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+
+/// Read a setting; `None` when it has never been set
+pub fn get_setting(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    namespace: SettingsNamespace,
+    key: &str,
+) -> Result<Option<String>, AppError> {
+    check_key(key)?;
+
+    let output = adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "settings",
+        "get",
+        namespace.as_str(),
+        key,
+    ])?;
+    let value = output.trim();
+
+    // `settings get` prints the literal "null" for unset keys
+    Ok((value != "null").then(|| value.to_string()))
+}
+
+/// Run `settings put`; keys are validated and values quoted so neither can
+/// inject extra shell commands
+pub fn put_setting(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    setting: &DeviceSetting,
+) -> Result<String, AppError> {
+    check_key(&setting.key)?;
+
+    let value = quote_remote_path(&setting.value);
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "settings",
+        "put",
+        setting.namespace.as_str(),
+        &setting.key,
+        &value,
+    ])
+}
+
+/// Remove a setting so the system default applies again
+pub fn delete_setting(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    namespace: SettingsNamespace,
+    key: &str,
+) -> Result<String, AppError> {
+    check_key(key)?;
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "settings",
+        "delete",
+        namespace.as_str(),
+        key,
+    ])
+}
+
+/// A setting's value from before the app changed it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSetting {
+    pub namespace: SettingsNamespace,
+    pub key: String,
+    pub previous: Option<String>,
+}
+
+/// Remember a setting's current value so it can be restored later
+pub fn save_setting(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    namespace: SettingsNamespace,
+    key: &str,
+) -> Result<SavedSetting, AppError> {
+    Ok(SavedSetting {
+        namespace,
+        key: key.to_string(),
+        previous: get_setting(adb, serial, namespace, key)?,
+    })
+}
+
+/// Put back a saved value, deleting the key if it wasn't set before
+pub fn restore_setting(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    saved: &SavedSetting,
+) -> Result<String, AppError> {
+    match &saved.previous {
+        Some(value) => put_setting(
+            adb,
+            serial,
+            &DeviceSetting {
+                namespace: saved.namespace,
+                key: saved.key.clone(),
+                value: value.clone(),
+            },
+        ),
+        None => delete_setting(adb, serial, saved.namespace, &saved.key),
+    }
+}
+
+/// Android settings keys are plain identifiers like `screen_off_timeout`
+pub fn is_valid_setting_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+fn check_key(key: &str) -> Result<(), AppError> {
+    if is_valid_setting_key(key) {
+        Ok(())
+    } else {
+        Err(AppError::SettingsError(format!(
+            "Invalid settings key: {}",
+            key
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_is_valid_setting_key() {
+        assert!(is_valid_setting_key("screen_off_timeout"));
+        assert!(is_valid_setting_key("wifi.sleep-policy"));
+        assert!(!is_valid_setting_key(""));
+        assert!(!is_valid_setting_key("a b"));
+        assert!(!is_valid_setting_key("x$(id)"));
+    }
+
+    #[test]
+    fn test_get_setting_maps_null_to_none() {
+        let adb = MockAdb::new()
+            .on("get system show_touches", "1\n")
+            .on("get system font_scale", "null\n");
+
+        assert_eq!(
+            get_setting(&adb, "A", SettingsNamespace::System, "show_touches").unwrap(),
+            Some("1".to_string())
+        );
+        assert_eq!(
+            get_setting(&adb, "A", SettingsNamespace::System, "font_scale").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_restore_setting() {
+        let adb = MockAdb::new().on("settings", "");
+        let saved = |previous: Option<&str>| SavedSetting {
+            namespace: SettingsNamespace::System,
+            key: "show_touches".to_string(),
+            previous: previous.map(|v| v.to_string()),
+        };
+
+        restore_setting(&adb, "A", &saved(Some("0"))).unwrap();
+        restore_setting(&adb, "A", &saved(None)).unwrap();
+
+        assert_eq!(
+            adb.calls(),
+            vec![
+                "-s A shell settings put system show_touches '0'",
+                "-s A shell settings delete system show_touches",
+            ]
+        );
+    }
+}
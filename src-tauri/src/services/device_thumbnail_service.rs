@@ -0,0 +1,168 @@
+//! Video thumbnails made on the device, so a grid image costs a small JPEG
+//! over adb instead of pulling the whole video
+//!
+//! Tried in order: MediaStore's thumbnail through `content read`, an ffmpeg
+//! binary on the device, then asking MediaStore to index the file and reading
+//! the thumbnail it makes. Android has no shell command for
+//! MediaMetadataRetriever itself; MediaStore's thumbnailer runs the same
+//! one-frame extraction on the device.
+
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::{self, quote_remote_path};
+use std::path::Path;
+use std::time::Duration;
+
+/// Where thumbnails are written on the device before they're pulled
+const DEVICE_TEMP_DIR: &str = "/data/local/tmp";
+
+/// Checks for the file's MediaStore entry after asking for a scan
+const SCAN_ATTEMPTS: usize = 3;
+
+/// Wait between those checks
+const SCAN_WAIT: Duration = Duration::from_millis(500);
+
+/// MediaStore thumbnail URI of a video
+pub fn video_thumbnail_uri(media_id: &str) -> String {
+    format!(
+        "content://media/external/video/media/{}/thumbnail",
+        media_id
+    )
+}
+
+/// Shell command for a device-side ffmpeg to write one 320px-wide frame
+pub fn ffmpeg_frame_command(remote_path: &str, device_out: &str) -> String {
+    format!(
+        "ffmpeg -nostdin -loglevel error -ss 1 -i {} -frames:v 1 -vf scale=320:-1 -y {}",
+        quote_remote_path(remote_path),
+        quote_remote_path(device_out)
+    )
+}
+
+/// Make the thumbnail of a device video at `thumb_path` without pulling the
+/// video. `media_id` is the file's MediaStore id if it has one;
+/// `lookup_id` finds it again after a scan is requested. Returns whether a
+/// thumbnail was written.
+pub fn generate_video_thumbnail(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    remote_path: &str,
+    thumb_path: &Path,
+    media_id: Option<&str>,
+    lookup_id: impl Fn() -> Option<String>,
+) -> bool {
+    let Some(out_name) = thumb_path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let device_out = format!("{}/gesu_{}", DEVICE_TEMP_DIR, out_name);
+
+    if let Some(id) = media_id {
+        if content_read(adb, serial, id, &device_out, thumb_path) {
+            return true;
+        }
+    }
+
+    if has_device_ffmpeg(adb, serial) {
+        let command = ffmpeg_frame_command(remote_path, &device_out);
+        if adb.run(&["-s", serial, "shell", &command]).is_ok()
+            && pull_and_clean(adb, serial, &device_out, thumb_path)
+        {
+            return true;
+        }
+    }
+
+    if media_id.is_none() {
+        request_scan(adb, serial, remote_path);
+        for attempt in 0..SCAN_ATTEMPTS {
+            if attempt > 0 {
+                std::thread::sleep(SCAN_WAIT);
+            }
+            if let Some(id) = lookup_id() {
+                return content_read(adb, serial, &id, &device_out, thumb_path);
+            }
+        }
+    }
+    false
+}
+
+/// Whether the device has an ffmpeg binary on its PATH
+pub fn has_device_ffmpeg(adb: &dyn AdbRunner, serial: &str) -> bool {
+    adb.run_idempotent(&["-s", serial, "shell", "command -v ffmpeg"])
+        .is_ok_and(|output| !output.trim().is_empty())
+}
+
+/// Ask MediaStore to index a file it hasn't seen yet
+fn request_scan(adb: &dyn AdbRunner, serial: &str, remote_path: &str) {
+    let command = format!(
+        "am broadcast -a android.intent.action.MEDIA_SCANNER_SCAN_FILE -d {}",
+        quote_remote_path(&format!("file://{}", remote_path))
+    );
+    let _ = adb.run(&["-s", serial, "shell", &command]);
+}
+
+/// Write MediaStore's thumbnail to the device temp file and pull it
+fn content_read(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    media_id: &str,
+    device_out: &str,
+    thumb_path: &Path,
+) -> bool {
+    let command = format!(
+        "content read --uri {} > {}",
+        video_thumbnail_uri(media_id),
+        quote_remote_path(device_out)
+    );
+    adb.run(&["-s", serial, "shell", &command]).is_ok()
+        && pull_and_clean(adb, serial, device_out, thumb_path)
+}
+
+/// Pull the device temp file, remove it, and check something arrived
+fn pull_and_clean(adb: &dyn AdbRunner, serial: &str, device_out: &str, thumb_path: &Path) -> bool {
+    let pulled = media_service::pull_to(adb, serial, device_out, thumb_path).is_ok();
+    let _ = adb.run(&["-s", serial, "shell", "rm", "-f", device_out]);
+    pulled && std::fs::metadata(thumb_path).is_ok_and(|m| m.len() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_ffmpeg_frame_command() {
+        assert_eq!(
+            ffmpeg_frame_command(
+                "/sdcard/It's.mp4",
+                "/data/local/tmp/gesu_thumb_It_s.mp4.jpg"
+            ),
+            "ffmpeg -nostdin -loglevel error -ss 1 -i '/sdcard/It'\\''s.mp4' -frames:v 1 \
+             -vf scale=320:-1 -y '/data/local/tmp/gesu_thumb_It_s.mp4.jpg'"
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_device_ffmpeg_then_scan() {
+        // Nothing is pulled by the mock, so every strategy comes up empty
+        let thumb = std::env::temp_dir().join("gesu_device_thumb_missing.jpg");
+        let adb = MockAdb::new().on("command -v ffmpeg", "/system/bin/ffmpeg\n");
+        let lookups = Cell::new(0);
+
+        let made =
+            generate_video_thumbnail(&adb, "SER1", "/sdcard/DCIM/a.mp4", &thumb, None, || {
+                lookups.set(lookups.get() + 1);
+                (lookups.get() == 2).then(|| "42".to_string())
+            });
+        assert!(!made);
+
+        let calls = adb.calls().join("\n");
+        let ffmpeg = calls.find("ffmpeg -nostdin").unwrap();
+        let scan = calls
+            .find("MEDIA_SCANNER_SCAN_FILE -d 'file:///sdcard/DCIM/a.mp4'")
+            .unwrap();
+        let read = calls.find("video/media/42/thumbnail").unwrap();
+        assert!(ffmpeg < scan && scan < read);
+        assert!(!calls.contains("pull /sdcard/DCIM/a.mp4"));
+        assert_eq!(lookups.get(), 2);
+    }
+}
@@ -0,0 +1,236 @@
+//! Device watcher: follows `adb track-devices` and reports devices as they
+//! come, go and become ready
+//!
+//! Connection and state changes are emitted as they're reported, so the UI
+//! doesn't have to poll `list_devices`. A device that was just authorized or
+//! came back online is listed before its properties were read; the watcher
+//! reads them and emits the full device so the UI doesn't show a ready device
+//! without model or Android version. Its clock is checked against this
+//! computer's at the same time, and transfers its disconnect interrupted are
+//! resumed.
+//!
+//! `track-devices` is started again when it ends, e.g. when the adb server
+//! restarts; the first list of the new run is compared with the last one
+//! seen. While adb isn't configured or won't start, it's tried less and less
+//! often. Runs only while the app is open (the adb client exits on its next
+//! write once the app is gone).
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{Device, DeviceEventKind, DeviceState};
+use crate::services::adb_service::{self, AdbCli, AdbRunner};
+use crate::services::transfer_service::{self, TransferState};
+use crate::services::{clock_service, process_service, settings_service, timeline_service};
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Wait before running `adb track-devices` again after it ended
+const RESTART_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest wait between attempts while adb is missing or failing
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// Event emitted with a [`Device`] payload when a device becomes ready
+pub const DEVICE_READY_EVENT: &str = "device-ready";
+
+/// Devices that are ready now but weren't in the previous listing (absent,
+/// unauthorized or offline), with their properties filled in
+pub fn newly_ready(
+    adb: &dyn AdbRunner,
+    previous: &HashMap<String, DeviceState>,
+    current: &HashMap<String, DeviceState>,
+) -> Vec<Device> {
+    let mut serials: Vec<&String> = current
+        .iter()
+        .filter(|(serial, state)| {
+            **state == DeviceState::Ready && previous.get(*serial) != Some(&DeviceState::Ready)
+        })
+        .map(|(serial, _)| serial)
+        .collect();
+    serials.sort();
+    serials
+        .into_iter()
+        .map(|serial| {
+            let mut device = Device::new(serial.clone(), DeviceState::Ready);
+            adb_service::enrich_device(adb, &mut device);
+            device
+        })
+        .collect()
+}
+
+/// Devices that were ready in the previous listing but no longer are
+pub fn no_longer_ready(
+    previous: &HashMap<String, DeviceState>,
+    current: &HashMap<String, DeviceState>,
+) -> Vec<String> {
+    let mut serials: Vec<String> = previous
+        .iter()
+        .filter(|(serial, state)| {
+            **state == DeviceState::Ready && current.get(*serial) != Some(&DeviceState::Ready)
+        })
+        .map(|(serial, _)| serial.clone())
+        .collect();
+    serials.sort();
+    serials
+}
+
+/// The wait after a failed attempt, doubling up to [`MAX_RESTART_DELAY`]
+pub fn backoff(delay: Duration) -> Duration {
+    (delay * 2).min(MAX_RESTART_DELAY)
+}
+
+/// Start the watcher thread; it lives as long as the app
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut known = HashMap::new();
+        let mut delay = RESTART_DELAY;
+        loop {
+            delay = match watch(&app, &mut known) {
+                Ok(true) => RESTART_DELAY,
+                Ok(false) | Err(_) => backoff(delay),
+            };
+            std::thread::sleep(delay);
+        }
+    });
+}
+
+/// Follow `adb track-devices` until it ends. `Ok(false)` when adb isn't
+/// configured.
+fn watch(app: &AppHandle, known: &mut HashMap<String, DeviceState>) -> Result<bool, AppError> {
+    let settings = settings_service::get_settings_with_detection(app)?;
+    let (Some(path), Some(adb)) = (
+        settings.adb_resolved_path.clone(),
+        AdbCli::from_settings(&settings).map(AdbCli::background),
+    ) else {
+        return Ok(false);
+    };
+    let mut child = process_service::spawn_hidden(
+        Command::new(&path)
+            .arg("track-devices")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null()),
+    )?;
+    let Some(mut stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        return Ok(true);
+    };
+
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let result = loop {
+        let read = match stdout.read(&mut chunk) {
+            Ok(0) => break Ok(true),
+            Ok(read) => read,
+            Err(e) => break Err(e.into()),
+        };
+        buffer.extend_from_slice(&chunk[..read]);
+        for message in adb_service::take_track_messages(&mut buffer) {
+            let current = adb_service::parse_track_message(&message);
+            report(app, &adb, known, &current);
+            *known = current;
+        }
+    };
+    let _ = child.kill();
+    let _ = child.wait();
+    result
+}
+
+/// Emit the changes between the last device list and `current`
+fn report(
+    app: &AppHandle,
+    adb: &AdbCli,
+    previous: &HashMap<String, DeviceState>,
+    current: &HashMap<String, DeviceState>,
+) {
+    for (event, change) in adb_service::device_changes(previous, current) {
+        let _ = app.emit(event, &change);
+    }
+    for device in newly_ready(adb, previous, current) {
+        timeline_service::record(app, &device.serial, DeviceEventKind::Connected, None);
+        let _ = app.emit(DEVICE_READY_EVENT, &device);
+        if let Some(drift) = clock_service::device_clock_drift(adb, &device.serial)
+            .ok()
+            .and_then(|secs| clock_service::excessive_drift(&device.serial, secs))
+        {
+            let _ = app.emit(clock_service::CLOCK_DRIFT_EVENT, &drift);
+        }
+        resume_transfers(app, &device.serial);
+    }
+    for serial in no_longer_ready(previous, current) {
+        timeline_service::record(app, &serial, DeviceEventKind::Disconnected, None);
+    }
+}
+
+/// Resume the transfers a device's disconnect interrupted, off the watcher
+/// thread so device events keep coming meanwhile
+fn resume_transfers(app: &AppHandle, serial: &str) {
+    if !transfer_service::has_interrupted(&app.state::<TransferState>(), serial) {
+        return;
+    }
+    let Some(adb) = settings_service::get_settings_with_detection(app)
+        .ok()
+        .and_then(|settings| AdbCli::from_settings(&settings))
+    else {
+        return;
+    };
+    let app = app.clone();
+    let serial = serial.to_string();
+    std::thread::spawn(move || {
+        transfer_service::resume_interrupted(&app.state::<TransferState>(), &adb, &serial);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_newly_ready_devices_are_enriched() {
+        let adb = MockAdb::new()
+            .on("ro.build.version.release", "14\n")
+            .on("ro.product.manufacturer", "Google\n")
+            .on("ro.product.model", "Pixel 5\n");
+        let devices = adb_service::parse_track_message(
+            "RFCT80XXXXX\tdevice\nemulator-5554\tdevice\nHT7A1B\tunauthorized\n",
+        );
+        let previous = HashMap::from([
+            ("RFCT80XXXXX".to_string(), DeviceState::Unauthorized),
+            ("emulator-5554".to_string(), DeviceState::Ready),
+        ]);
+
+        let ready = newly_ready(&adb, &previous, &devices);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].serial, "RFCT80XXXXX");
+        assert_eq!(ready[0].model.as_deref(), Some("Pixel 5"));
+        assert_eq!(ready[0].manufacturer.as_deref(), Some("Google"));
+        assert_eq!(ready[0].android_version.as_deref(), Some("14"));
+        assert!(adb.calls().iter().all(|c| c.starts_with("-s RFCT80XXXXX")));
+    }
+
+    #[test]
+    fn test_no_longer_ready() {
+        let devices =
+            adb_service::parse_track_message("RFCT80XXXXX\toffline\nemulator-5554\tdevice\n");
+        let previous = HashMap::from([
+            ("RFCT80XXXXX".to_string(), DeviceState::Ready),
+            ("emulator-5554".to_string(), DeviceState::Ready),
+            ("HT7A1B".to_string(), DeviceState::Ready),
+            ("R58M123".to_string(), DeviceState::Unauthorized),
+        ]);
+
+        assert_eq!(
+            no_longer_ready(&previous, &devices),
+            vec!["HT7A1B", "RFCT80XXXXX"]
+        );
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(RESTART_DELAY), Duration::from_secs(4));
+        assert_eq!(backoff(Duration::from_secs(40)), MAX_RESTART_DELAY);
+        assert_eq!(backoff(MAX_RESTART_DELAY), MAX_RESTART_DELAY);
+    }
+}
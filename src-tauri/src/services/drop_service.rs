@@ -0,0 +1,202 @@
+//! Classify drag-and-drop payloads so one drop can be routed to the right action
+
+use crate::domain::models::{DropAction, DropAnalysis, DropFile, DropFileKind};
+use crate::services::media_service::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS};
+use std::path::Path;
+
+/// Stop expanding folders after this many files
+const MAX_FILES: usize = 50_000;
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "ogg", "opus", "m4a", "aac", "wav", "wma"];
+
+/// Classify a file by its extension
+pub fn classify(path: &Path) -> DropFileKind {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "apk" => DropFileKind::Apk,
+        "apks" | "xapk" | "apkm" => DropFileKind::SplitApk,
+        "obb" => DropFileKind::Obb,
+        e if IMAGE_EXTENSIONS.contains(&e) => DropFileKind::Image,
+        e if VIDEO_EXTENSIONS.contains(&e) => DropFileKind::Video,
+        e if AUDIO_EXTENSIONS.contains(&e) => DropFileKind::Audio,
+        _ => DropFileKind::Other,
+    }
+}
+
+/// Package name from an OBB file name: `main.<version>.<package>.obb`
+pub fn obb_package(file_name: &str) -> Option<String> {
+    let stem = file_name.strip_suffix(".obb")?;
+    let mut parts = stem.splitn(3, '.');
+    let kind = parts.next()?;
+    let version = parts.next()?;
+    let package = parts.next()?;
+
+    let valid = matches!(kind, "main" | "patch")
+        && version.chars().all(|c| c.is_ascii_digit())
+        && package.contains('.');
+    valid.then(|| package.to_string())
+}
+
+/// Expand dropped paths and summarize them
+pub fn analyze_drop(paths: &[String]) -> DropAnalysis {
+    let mut files = Vec::new();
+    let mut missing = Vec::new();
+    let mut truncated = false;
+
+    for path in paths {
+        let path = Path::new(path);
+        if !path.exists() {
+            missing.push(path.to_string_lossy().to_string());
+            continue;
+        }
+        if !collect_files(path, &mut files) {
+            truncated = true;
+            break;
+        }
+    }
+
+    let count = |pred: &dyn Fn(DropFileKind) -> bool| files.iter().filter(|f| pred(f.kind)).count();
+    let media_files = count(&|k| {
+        matches!(
+            k,
+            DropFileKind::Image | DropFileKind::Video | DropFileKind::Audio
+        )
+    });
+    let apk_files = count(&|k| matches!(k, DropFileKind::Apk | DropFileKind::SplitApk));
+    let obb_files = count(&|k| k == DropFileKind::Obb);
+    let other_files = count(&|k| k == DropFileKind::Other);
+
+    let (suggested_action, suggested_dest) = suggest(&files, apk_files, obb_files);
+
+    DropAnalysis {
+        total_files: files.len(),
+        total_bytes: files.iter().map(|f| f.size_bytes).sum(),
+        files,
+        media_files,
+        apk_files,
+        obb_files,
+        other_files,
+        suggested_action,
+        suggested_dest,
+        missing,
+        truncated,
+    }
+}
+
+/// Add files under `path` (or `path` itself); false once the limit is hit
+fn collect_files(path: &Path, files: &mut Vec<DropFile>) -> bool {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return true;
+    };
+
+    if metadata.is_file() {
+        if files.len() >= MAX_FILES {
+            return false;
+        }
+        files.push(DropFile {
+            path: path.to_string_lossy().to_string(),
+            size_bytes: metadata.len(),
+            kind: classify(path),
+        });
+    } else if metadata.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return true;
+        };
+        for entry in entries.flatten() {
+            if !collect_files(&entry.path(), files) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn suggest(files: &[DropFile], apk_files: usize, obb_files: usize) -> (DropAction, Option<String>) {
+    // Any APK means the user most likely wants an install
+    if apk_files > 0 {
+        return (DropAction::Install, None);
+    }
+
+    if obb_files > 0 && obb_files == files.len() {
+        let packages: Vec<String> = files
+            .iter()
+            .filter_map(|f| {
+                Path::new(&f.path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(obb_package)
+            })
+            .collect();
+        let dest = match packages.first() {
+            Some(first) if packages.len() == files.len() && packages.iter().all(|p| p == first) => {
+                Some(format!("Android/obb/{}", first))
+            }
+            _ => None,
+        };
+        return (DropAction::CopyObb, dest);
+    }
+
+    let all = |kind: DropFileKind| !files.is_empty() && files.iter().all(|f| f.kind == kind);
+    let dest = if all(DropFileKind::Image) {
+        Some("Pictures")
+    } else if all(DropFileKind::Video) {
+        Some("Movies")
+    } else if all(DropFileKind::Audio) {
+        Some("Music")
+    } else {
+        None
+    };
+
+    (DropAction::Push, dest.map(|d| d.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(Path::new("a.APK")), DropFileKind::Apk);
+        assert_eq!(classify(Path::new("a.xapk")), DropFileKind::SplitApk);
+        assert_eq!(classify(Path::new("a.jpg")), DropFileKind::Image);
+        assert_eq!(classify(Path::new("a.flac")), DropFileKind::Audio);
+        assert_eq!(classify(Path::new("notes")), DropFileKind::Other);
+    }
+
+    #[test]
+    fn test_obb_package() {
+        assert_eq!(
+            obb_package("main.1234.com.example.game.obb"),
+            Some("com.example.game".to_string())
+        );
+        assert_eq!(obb_package("random.obb"), None);
+        assert_eq!(obb_package("main.v1.com.example.obb"), None);
+    }
+
+    #[test]
+    fn test_analyze_drop_expands_folders() {
+        let dir = std::env::temp_dir().join(format!("gesu_drop_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.mp3"), b"abc").unwrap();
+        std::fs::write(dir.join("sub").join("b.mp3"), b"defg").unwrap();
+
+        let analysis = analyze_drop(&[
+            dir.to_string_lossy().to_string(),
+            "/definitely/missing.txt".to_string(),
+        ]);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(analysis.total_files, 2);
+        assert_eq!(analysis.total_bytes, 7);
+        assert_eq!(analysis.media_files, 2);
+        assert_eq!(analysis.suggested_action, DropAction::Push);
+        assert_eq!(analysis.suggested_dest.as_deref(), Some("Music"));
+        assert_eq!(analysis.missing.len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! GPS coordinates from photo EXIF, for a map of where photos were taken
+//!
+//! EXIF sits at the start of a JPEG, so only the first [`HEADER_BYTES`] of
+//! each photo are read on the device and sent over adb as base64. The GPS IFD
+//! is parsed here; no image library is needed for it.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{MediaFilter, MediaLocation};
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::{self, quote_remote_path};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+/// Bytes read from the start of each photo; EXIF is limited to one 64 KiB segment
+const HEADER_BYTES: usize = 65536;
+
+/// Photos read per adb call
+const LOCATION_CHUNK: usize = 20;
+
+/// Line that starts each file in the header dump
+const DUMP_MARKER: &str = "==> ";
+
+const GPS_IFD_POINTER: u16 = 0x8825;
+const GPS_LATITUDE_REF: u16 = 1;
+const GPS_LATITUDE: u16 = 2;
+const GPS_LONGITUDE_REF: u16 = 3;
+const GPS_LONGITUDE: u16 = 4;
+
+/// Locations of the JPEGs in a device folder that have GPS tags
+pub fn get_locations(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    folder: &str,
+) -> Result<Vec<MediaLocation>, AppError> {
+    let paths: Vec<String> =
+        media_service::list_media_files(adb, serial, folder, MediaFilter::Images)?
+            .into_iter()
+            .map(|item| item.path)
+            .filter(|path| {
+                let lower = path.to_lowercase();
+                lower.ends_with(".jpg") || lower.ends_with(".jpeg")
+            })
+            .collect();
+
+    let mut locations = Vec::new();
+    for chunk in paths.chunks(LOCATION_CHUNK) {
+        let quoted: Vec<String> = chunk.iter().map(|p| quote_remote_path(p)).collect();
+        let command = format!(
+            "for f in {}; do echo \"{}$f\"; head -c {} \"$f\" | base64; done",
+            quoted.join(" "),
+            DUMP_MARKER,
+            HEADER_BYTES
+        );
+        let output = adb.run_idempotent(&["-s", serial, "shell", &command])?;
+
+        for (path, encoded) in parse_header_dump(&output) {
+            let Ok(header) = STANDARD.decode(encoded) else {
+                continue;
+            };
+            if let Some((latitude, longitude)) = gps_from_jpeg(&header) {
+                locations.push(MediaLocation {
+                    path,
+                    latitude,
+                    longitude,
+                });
+            }
+        }
+    }
+    Ok(locations)
+}
+
+/// Split the dump into `(path, base64 header)` pairs
+pub fn parse_header_dump(output: &str) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(path) = line.strip_prefix(DUMP_MARKER) {
+            files.push((path.to_string(), String::new()));
+        } else if let Some((_, encoded)) = files.last_mut() {
+            encoded.push_str(line.trim());
+        }
+    }
+    files
+}
+
+/// Latitude and longitude from the EXIF GPS tags of a JPEG (or its start)
+pub fn gps_from_jpeg(data: &[u8]) -> Option<(f64, f64)> {
+    let tiff = Tiff::new(exif_segment(data)?)?;
+    let ifd0 = tiff.u32(4)? as usize;
+    let gps = tiff.u32(tiff.entry(ifd0, GPS_IFD_POINTER)? + 8)? as usize;
+
+    let latitude = tiff.coordinate(gps, GPS_LATITUDE, GPS_LATITUDE_REF, b'S')?;
+    let longitude = tiff.coordinate(gps, GPS_LONGITUDE, GPS_LONGITUDE_REF, b'W')?;
+    ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude))
+        .then_some((latitude, longitude))
+}
+
+/// The TIFF data of the JPEG's APP1 Exif segment
+fn exif_segment(data: &[u8]) -> Option<&[u8]> {
+    if data.get(..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+    let mut at = 2;
+    loop {
+        let (marker, len) = match data.get(at..at + 4)? {
+            [0xFF, marker, high, low] => (*marker, u16::from_be_bytes([*high, *low]) as usize),
+            _ => return None,
+        };
+        // Image data starts; no EXIF before it
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+        let segment = data.get(at + 4..at + 2 + len)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return Some(&segment[6..]);
+        }
+        at += 2 + len;
+    }
+}
+
+/// Reader for the TIFF structure inside EXIF
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        Some(Self {
+            data,
+            little_endian,
+        })
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(at..at + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(at..at + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// Offset of the IFD entry with this tag
+    fn entry(&self, ifd: usize, tag: u16) -> Option<usize> {
+        let count = self.u16(ifd)? as usize;
+        (0..count)
+            .map(|i| ifd + 2 + i * 12)
+            .find(|&entry| self.u16(entry) == Some(tag))
+    }
+
+    /// Degrees from a degrees/minutes/seconds rational triple, negative
+    /// when the reference tag holds `negative_ref`
+    fn coordinate(&self, ifd: usize, tag: u16, ref_tag: u16, negative_ref: u8) -> Option<f64> {
+        let values = self.u32(self.entry(ifd, tag)? + 8)? as usize;
+        let rational = |i: usize| -> Option<f64> {
+            let numerator = self.u32(values + i * 8)?;
+            let denominator = self.u32(values + i * 8 + 4)?;
+            (denominator != 0).then(|| f64::from(numerator) / f64::from(denominator))
+        };
+        let degrees = rational(0)? + rational(1)? / 60.0 + rational(2)? / 3600.0;
+
+        let reference = self.entry(ifd, ref_tag).and_then(|e| self.data.get(e + 8));
+        Some(if reference == Some(&negative_ref) {
+            -degrees
+        } else {
+            degrees
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A little-endian EXIF block with only GPS tags, wrapped in a JPEG
+    fn jpeg_with_gps(lat_ref: u8, lat: [u32; 6], lon_ref: u8, lon: [u32; 6]) -> Vec<u8> {
+        let mut tiff: Vec<u8> = b"II".to_vec();
+        tiff.extend(42u16.to_le_bytes());
+        tiff.extend(8u32.to_le_bytes());
+        // IFD0: the GPS pointer
+        let entry = |tag: u16, kind: u16, count: u32, value: [u8; 4]| {
+            let mut e = tag.to_le_bytes().to_vec();
+            e.extend(kind.to_le_bytes());
+            e.extend(count.to_le_bytes());
+            e.extend(value);
+            e
+        };
+        tiff.extend(1u16.to_le_bytes());
+        tiff.extend(entry(GPS_IFD_POINTER, 4, 1, 26u32.to_le_bytes()));
+        tiff.extend(0u32.to_le_bytes());
+        // GPS IFD at 26, values after its 4 entries at 80 and 104
+        tiff.extend(4u16.to_le_bytes());
+        tiff.extend(entry(GPS_LATITUDE_REF, 2, 2, [lat_ref, 0, 0, 0]));
+        tiff.extend(entry(GPS_LATITUDE, 5, 3, 80u32.to_le_bytes()));
+        tiff.extend(entry(GPS_LONGITUDE_REF, 2, 2, [lon_ref, 0, 0, 0]));
+        tiff.extend(entry(GPS_LONGITUDE, 5, 3, 104u32.to_le_bytes()));
+        tiff.extend(0u32.to_le_bytes());
+        for value in lat.into_iter().chain(lon) {
+            tiff.extend(value.to_le_bytes());
+        }
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend(((2 + 6 + tiff.len()) as u16).to_be_bytes());
+        jpeg.extend(b"Exif\0\0");
+        jpeg.extend(tiff);
+        jpeg.extend([0xFF, 0xDA, 0x00, 0x02]);
+        jpeg
+    }
+
+    #[test]
+    fn test_gps_from_jpeg() {
+        let jpeg = jpeg_with_gps(
+            b'N',
+            [37, 1, 25, 1, 1908, 100],
+            b'W',
+            [122, 1, 5, 1, 240, 100],
+        );
+        let (latitude, longitude) = gps_from_jpeg(&jpeg).unwrap();
+        assert!((latitude - 37.42197).abs() < 1e-4, "{}", latitude);
+        assert!((longitude + 122.084).abs() < 1e-4, "{}", longitude);
+
+        let south = jpeg_with_gps(b'S', [33, 1, 52, 1, 0, 1], b'E', [151, 1, 12, 1, 0, 1]);
+        let (latitude, longitude) = gps_from_jpeg(&south).unwrap();
+        assert!((latitude + 33.8667).abs() < 1e-3);
+        assert!((longitude - 151.2).abs() < 1e-3);
+
+        // No GPS fix written: zero denominators
+        let unset = jpeg_with_gps(b'N', [0, 0, 0, 0, 0, 0], b'E', [0, 0, 0, 0, 0, 0]);
+        assert_eq!(gps_from_jpeg(&unset), None);
+        assert_eq!(gps_from_jpeg(&[0xFF, 0xD8, 0xFF, 0xDA, 0, 2]), None);
+        assert_eq!(gps_from_jpeg(b"not a jpeg"), None);
+    }
+
+    #[test]
+    fn test_parse_header_dump() {
+        let output = "==> /sdcard/DCIM/Camera/a b.jpg\r\n/9j/4QAY\r\nRXhpZgAA\n==> /sdcard/DCIM/Camera/c.jpg\n";
+        assert_eq!(
+            parse_header_dump(output),
+            vec![
+                (
+                    "/sdcard/DCIM/Camera/a b.jpg".to_string(),
+                    "/9j/4QAYRXhpZgAA".to_string()
+                ),
+                ("/sdcard/DCIM/Camera/c.jpg".to_string(), String::new()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Run an operation across every device in a group
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{DeviceGroup, DeviceOpResult, GroupOpReport};
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+
+/// Run `op` for each device in the group, collecting a result per device.
+/// One device failing doesn't stop the others.
+pub fn for_each_device(
+    group: &DeviceGroup,
+    mut op: impl FnMut(&str) -> Result<String, AppError>,
+) -> GroupOpReport {
+    let results: Vec<DeviceOpResult> = group
+        .serials
+        .iter()
+        .map(|serial| match op(serial) {
+            Ok(output) => DeviceOpResult {
+                serial: serial.clone(),
+                success: true,
+                output: Some(output),
+                error: None,
+            },
+            Err(e) => DeviceOpResult {
+                serial: serial.clone(),
+                success: false,
+                output: None,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect();
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    GroupOpReport {
+        group_id: group.id.clone(),
+        failed: results.len() - succeeded,
+        succeeded,
+        results,
+    }
+}
+
+/// Run a shell command given as separate arguments; each one is quoted, so
+/// pipes, redirects and `;` are passed literally instead of interpreted
+pub fn run_shell_args(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    args: &[String],
+) -> Result<String, AppError> {
+    if args.is_empty() {
+        return Err(AppError::AdbExecutionFailed("No command given".to_string()));
+    }
+
+    let quoted: Vec<String> = args.iter().map(|a| quote_remote_path(a)).collect();
+    let mut full = vec!["-s", serial, "shell"];
+    full.extend(quoted.iter().map(|a| a.as_str()));
+
+    adb.run(&full)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+    use crate::services::read_only_service;
+
+    #[test]
+    fn test_for_each_device_reports_per_device() {
+        let group = DeviceGroup {
+            id: "lab".to_string(),
+            name: "Lab".to_string(),
+            serials: vec!["A".to_string(), "B".to_string()],
+        };
+
+        let report = for_each_device(&group, |serial| {
+            if serial == "A" {
+                Ok("ok".to_string())
+            } else {
+                Err(AppError::DeviceNotFound(serial.to_string()))
+            }
+        });
+
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.results[1].serial, "B");
+    }
+
+    #[test]
+    fn test_run_shell_args_quotes_each_arg() {
+        let adb = MockAdb::new().on("shell", "done");
+        let args = vec!["echo".to_string(), "a; reboot".to_string()];
+
+        run_shell_args(&adb, "A", &args).unwrap();
+        assert_eq!(adb.calls()[0], "-s A shell 'echo' 'a; reboot'");
+    }
+
+    #[test]
+    fn test_read_only_mode_sees_through_the_quoting() {
+        let adb = MockAdb::new().on("shell", "");
+        for args in [
+            vec!["rm", "-rf", "/sdcard/DCIM"],
+            vec!["pm", "uninstall", "com.example.app"],
+            vec!["echo", "a; reboot"],
+            vec!["ls", "-la", "/sdcard"],
+        ] {
+            let args: Vec<String> = args.into_iter().map(String::from).collect();
+            run_shell_args(&adb, "A", &args).unwrap();
+        }
+
+        // The mock records the arguments joined by spaces, which is how the
+        // read-only check joins a shell command anyway
+        let verdicts: Vec<bool> = adb
+            .calls()
+            .iter()
+            .map(|call| {
+                let args: Vec<&str> = call.split(' ').collect();
+                read_only_service::check(&args).is_err()
+            })
+            .collect();
+        assert_eq!(verdicts, vec![true, true, false, false]);
+    }
+}
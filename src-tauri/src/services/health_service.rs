@@ -0,0 +1,148 @@
+//! Device health panel: battery, shared storage and memory in one read
+
+use crate::domain::models::DeviceHealth;
+use crate::services::adb_service::AdbRunner;
+use crate::services::battery_service;
+
+/// Total and available bytes from `df -k` output (last line, second and
+/// fourth columns)
+pub fn parse_df_sizes(output: &str) -> Option<(u64, u64)> {
+    let line = output.lines().rev().find(|l| !l.trim().is_empty())?;
+    let mut columns = line.split_whitespace();
+    let total: u64 = columns.nth(1)?.parse().ok()?;
+    let available: u64 = columns.nth(1)?.parse().ok()?;
+    Some((total * 1024, available * 1024))
+}
+
+/// Total and available memory from `/proc/meminfo`, in bytes
+pub fn parse_meminfo(output: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.split_whitespace().next()?.parse::<u64>().ok())
+    };
+    Some((field("MemTotal:")? * 1024, field("MemAvailable:")? * 1024))
+}
+
+/// Read battery, storage and memory. Parts that fail are reported in
+/// `errors` rather than failing the whole panel.
+pub fn get_device_health(adb: &dyn AdbRunner, serial: &str) -> DeviceHealth {
+    let mut health = DeviceHealth {
+        device_serial: serial.to_string(),
+        battery: None,
+        storage_total_bytes: None,
+        storage_free_bytes: None,
+        ram_total_bytes: None,
+        ram_used_bytes: None,
+        errors: Vec::new(),
+    };
+
+    match battery_service::get_battery(adb, serial) {
+        Ok(battery) => health.battery = Some(battery),
+        Err(e) => health.errors.push(format!("Battery: {}", e)),
+    }
+
+    match adb
+        .run_idempotent(&["-s", serial, "shell", "df", "-k", "/sdcard"])
+        .map(|out| parse_df_sizes(&out))
+    {
+        Ok(Some((total, free))) => {
+            health.storage_total_bytes = Some(total);
+            health.storage_free_bytes = Some(free);
+        }
+        Ok(None) => health
+            .errors
+            .push("Storage: unexpected `df` output".to_string()),
+        Err(e) => health.errors.push(format!("Storage: {}", e)),
+    }
+
+    match adb
+        .run_idempotent(&["-s", serial, "shell", "cat", "/proc/meminfo"])
+        .map(|out| parse_meminfo(&out))
+    {
+        Ok(Some((total, available))) => {
+            health.ram_total_bytes = Some(total);
+            health.ram_used_bytes = Some(total.saturating_sub(available));
+        }
+        Ok(None) => health
+            .errors
+            .push("Memory: unexpected `/proc/meminfo` output".to_string()),
+        Err(e) => health.errors.push(format!("Memory: {}", e)),
+    }
+
+    health
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::errors::AppError;
+    use crate::domain::models::BatteryStatus;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parsers() {
+        let df = "Filesystem 1K-blocks Used Available Use% Mounted on\n\
+                  /dev/fuse 1000 600 400 60% /storage/emulated\n";
+        assert_eq!(parse_df_sizes(df), Some((1000 * 1024, 400 * 1024)));
+        assert_eq!(parse_df_sizes("df: /sdcard: No such file"), None);
+
+        let meminfo = "MemTotal:        7812344 kB\nMemFree:          301212 kB\n\
+                       MemAvailable:    3212344 kB\n";
+        assert_eq!(
+            parse_meminfo(meminfo),
+            Some((7812344 * 1024, 3212344 * 1024))
+        );
+        assert_eq!(parse_meminfo("MemTotal: 100 kB\n"), None);
+    }
+
+    #[test]
+    fn test_get_device_health() {
+        let adb = MockAdb::new()
+            .on(
+                "dumpsys battery",
+                "  AC powered: true\n  level: 64\n  scale: 100\n",
+            )
+            .on(
+                "df -k /sdcard",
+                "Filesystem 1K-blocks Used Available Use% Mounted on\n\
+                 /dev/fuse 1000 600 400 60% /storage/emulated\n",
+            )
+            .on(
+                "cat /proc/meminfo",
+                "MemTotal: 4000 kB\nMemAvailable: 1000 kB\n",
+            );
+        let health = get_device_health(&adb, "SER1");
+        assert_eq!(
+            health.battery,
+            Some(BatteryStatus {
+                level: 64,
+                charging: true
+            })
+        );
+        assert_eq!(health.storage_free_bytes, Some(400 * 1024));
+        assert_eq!(health.ram_used_bytes, Some(3000 * 1024));
+        assert!(health.errors.is_empty());
+    }
+
+    #[test]
+    fn test_get_device_health_reports_missing_parts() {
+        let adb = MockAdb::new()
+            .fail(
+                "dumpsys battery",
+                AppError::AdbExecutionFailed("no battery service".into()),
+            )
+            .on("df -k /sdcard", "")
+            .on(
+                "cat /proc/meminfo",
+                "MemTotal: 4000 kB\nMemAvailable: 1000 kB\n",
+            );
+        let health = get_device_health(&adb, "SER1");
+        assert_eq!(health.battery, None);
+        assert_eq!(health.storage_total_bytes, None);
+        assert_eq!(health.ram_total_bytes, Some(4000 * 1024));
+        assert_eq!(health.errors.len(), 2);
+        assert!(health.errors[0].starts_with("Battery:"));
+    }
+}
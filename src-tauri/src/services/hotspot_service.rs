@@ -0,0 +1,331 @@
+//! Hotspot connection assistant for wireless ADB
+//!
+//! The usual reason `adb connect` fails is that the computer and the device
+//! sit on different networks (guest Wi-Fi, client isolation, mobile data).
+//! This checks for that and, where the device allows it, turns on the device's
+//! hotspot so the computer can join it and connect over it.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{HotspotStart, NetworkMatch, WirelessNetworkCheck};
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+use crate::services::wireless_service::TCPIP_PORT;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::Duration;
+
+/// Interface a device uses to join Wi-Fi networks
+const WIFI_INTERFACE: &str = "wlan0";
+
+/// Interfaces vendors put the hotspot (soft AP) on
+const HOTSPOT_INTERFACES: &[&str] = &["ap0", "swlan0", "softap0", "wlan1", "wlan2"];
+
+/// `adb connect` attempts while adbd restarts in TCP mode
+const CONNECT_ATTEMPTS: u32 = 3;
+const CONNECT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// An IPv4 address assigned to a device interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceAddress {
+    pub interface: String,
+    pub address: Ipv4Addr,
+    pub prefix: u8,
+}
+
+impl InterfaceAddress {
+    fn contains(&self, other: Ipv4Addr) -> bool {
+        same_subnet(self.address, other, self.prefix)
+    }
+}
+
+/// Parse `ip -o -4 addr show`
+///
+/// Example output:
+/// ```text
+/// 1: lo    inet 127.0.0.1/8 scope host lo\       valid_lft forever preferred_lft forever
+/// 30: wlan0    inet 192.168.1.23/24 brd 192.168.1.255 scope global wlan0\       valid_lft forever preferred_lft forever
+/// ```
+pub fn parse_ip_addr(output: &str) -> Vec<InterfaceAddress> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let interface = fields.next()?;
+            if fields.next()? != "inet" {
+                return None;
+            }
+            let (address, prefix) = fields.next()?.split_once('/')?;
+            Some(InterfaceAddress {
+                interface: interface.to_string(),
+                address: address.parse().ok()?,
+                prefix: prefix.parse().ok().filter(|p| *p <= 32)?,
+            })
+        })
+        .collect()
+}
+
+/// Whether two addresses share a subnet of the given prefix length
+pub fn same_subnet(a: Ipv4Addr, b: Ipv4Addr, prefix: u8) -> bool {
+    let mask = u32::MAX
+        .checked_shl(32 - u32::from(prefix.min(32)))
+        .unwrap_or(0);
+    u32::from(a) & mask == u32::from(b) & mask
+}
+
+/// Address this computer sends from to reach `target`. Connecting a UDP
+/// socket only looks up the route; nothing is sent.
+fn route_address(target: Ipv4Addr) -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect((target, TCPIP_PORT)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(address) => Some(address),
+        IpAddr::V6(_) => None,
+    }
+}
+
+/// Check whether this computer and the device share a network
+pub fn check_network(adb: &dyn AdbRunner, serial: &str) -> Result<WirelessNetworkCheck, AppError> {
+    let output = adb.run_idempotent(&["-s", serial, "shell", "ip", "-o", "-4", "addr", "show"])?;
+    Ok(assess_network(
+        serial,
+        &parse_ip_addr(&output),
+        route_address,
+    ))
+}
+
+/// Compare a device's addresses with the routes this computer would take to them
+pub fn assess_network(
+    serial: &str,
+    addresses: &[InterfaceAddress],
+    route: impl Fn(Ipv4Addr) -> Option<Ipv4Addr>,
+) -> WirelessNetworkCheck {
+    let wifi = addresses.iter().find(|a| a.interface == WIFI_INTERFACE);
+    let hotspot = addresses
+        .iter()
+        .find(|a| HOTSPOT_INTERFACES.contains(&a.interface.as_str()));
+
+    let host = wifi.or(hotspot).and_then(|a| route(a.address));
+    let network = match wifi {
+        None => NetworkMatch::DeviceOffWifi,
+        Some(wifi) if host.is_some_and(|h| wifi.contains(h)) => NetworkMatch::SameNetwork,
+        Some(_) => NetworkMatch::DifferentNetwork,
+    };
+    let host_on_hotspot = hotspot.is_some_and(|a| route(a.address).is_some_and(|h| a.contains(h)));
+
+    let cidr = |a: &InterfaceAddress| format!("{}/{}", a.address, a.prefix);
+    WirelessNetworkCheck {
+        device_serial: serial.to_string(),
+        device_address: wifi.map(cidr),
+        hotspot_address: hotspot.map(cidr),
+        host_address: host.map(|h| h.to_string()),
+        network,
+        host_on_hotspot,
+    }
+}
+
+/// Turn on the device's hotspot with the given name and WPA2 passphrase.
+/// Many builds only allow this for root; then the hotspot settings screen is
+/// opened so the user can switch it on by hand.
+pub fn start_hotspot(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    ssid: &str,
+    passphrase: &str,
+) -> Result<HotspotStart, AppError> {
+    if ssid.is_empty() || ssid.len() > 32 || ssid.chars().any(char::is_control) {
+        return Err(AppError::AdbExecutionFailed(
+            "Hotspot name must be 1 to 32 bytes".to_string(),
+        ));
+    }
+    if !(8..=63).contains(&passphrase.len())
+        || !passphrase.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+    {
+        return Err(AppError::AdbExecutionFailed(
+            "Hotspot password must be 8 to 63 printable ASCII characters".to_string(),
+        ));
+    }
+
+    let ssid = quote_remote_path(ssid);
+    let passphrase = quote_remote_path(passphrase);
+    let result = adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "cmd",
+        "wifi",
+        "start-softap",
+        &ssid,
+        "wpa2",
+        &passphrase,
+    ]);
+
+    let refusal = match result {
+        Ok(output) if !is_refusal(&output) => {
+            return Ok(HotspotStart {
+                started: true,
+                settings_opened: false,
+                message: output.trim().to_string(),
+            })
+        }
+        Ok(output) => output.trim().to_string(),
+        Err(e) => e.to_string(),
+    };
+
+    let settings_opened = adb
+        .run(&[
+            "-s",
+            serial,
+            "shell",
+            "am",
+            "start",
+            "-a",
+            "android.settings.TETHER_SETTINGS",
+        ])
+        .is_ok_and(|output| !is_refusal(&output));
+
+    Ok(HotspotStart {
+        started: false,
+        settings_opened,
+        message: refusal,
+    })
+}
+
+/// Turn the device's hotspot off again
+pub fn stop_hotspot(adb: &dyn AdbRunner, serial: &str) -> Result<String, AppError> {
+    adb.run(&["-s", serial, "shell", "cmd", "wifi", "stop-softap"])
+}
+
+/// Connect to the device through its hotspot once this computer has joined
+/// it. A USB device is switched to TCP mode first. Returns the new `ip:port`.
+pub fn connect_over_hotspot(adb: &dyn AdbRunner, serial: &str) -> Result<String, AppError> {
+    let check = check_network(adb, serial)?;
+    let Some(hotspot) = check.hotspot_address else {
+        return Err(AppError::AdbExecutionFailed(format!(
+            "The hotspot of {} is off",
+            serial
+        )));
+    };
+    if !check.host_on_hotspot {
+        return Err(AppError::AdbExecutionFailed(
+            "Join this computer to the device's hotspot first".to_string(),
+        ));
+    }
+
+    let port = match serial.parse::<SocketAddr>() {
+        Ok(address) => address.port(),
+        Err(_) => {
+            adb.run(&["-s", serial, "tcpip", &TCPIP_PORT.to_string()])?;
+            TCPIP_PORT
+        }
+    };
+    let address = hotspot.split('/').next().unwrap_or(&hotspot);
+    let endpoint = format!("{}:{}", address, port);
+
+    let mut last = String::new();
+    for attempt in 1..=CONNECT_ATTEMPTS {
+        last = adb.run(&["connect", &endpoint])?;
+        if last.contains("connected to") {
+            return Ok(endpoint);
+        }
+        if attempt < CONNECT_ATTEMPTS {
+            std::thread::sleep(CONNECT_RETRY_DELAY);
+        }
+    }
+    Err(AppError::AdbExecutionFailed(last.trim().to_string()))
+}
+
+/// Shell tools often report failure on stdout with a zero exit code
+fn is_refusal(output: &str) -> bool {
+    let output = output.to_lowercase();
+    [
+        "error",
+        "exception",
+        "not supported",
+        "permission",
+        "unknown command",
+        "failed",
+    ]
+    .iter()
+    .any(|word| output.contains(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const IP_ADDR: &str = "1: lo    inet 127.0.0.1/8 scope host lo\\       valid_lft forever preferred_lft forever\n30: wlan0    inet 192.168.1.23/24 brd 192.168.1.255 scope global wlan0\\       valid_lft forever preferred_lft forever\n";
+
+    fn address(interface: &str, address: &str, prefix: u8) -> InterfaceAddress {
+        InterfaceAddress {
+            interface: interface.to_string(),
+            address: address.parse().unwrap(),
+            prefix,
+        }
+    }
+
+    #[test]
+    fn test_parse_ip_addr() {
+        assert_eq!(
+            parse_ip_addr(IP_ADDR),
+            vec![
+                address("lo", "127.0.0.1", 8),
+                address("wlan0", "192.168.1.23", 24),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_same_subnet() {
+        let a: Ipv4Addr = "192.168.1.23".parse().unwrap();
+        assert!(same_subnet(a, "192.168.1.200".parse().unwrap(), 24));
+        assert!(!same_subnet(a, "192.168.2.23".parse().unwrap(), 24));
+        assert!(same_subnet(a, "10.0.0.1".parse().unwrap(), 0));
+    }
+
+    #[test]
+    fn test_assess_network() {
+        let wifi = [address("wlan0", "192.168.1.23", 24)];
+        let same = assess_network("SER1", &wifi, |_| "192.168.1.40".parse().ok());
+        assert_eq!(same.network, NetworkMatch::SameNetwork);
+        assert_eq!(same.device_address.as_deref(), Some("192.168.1.23/24"));
+
+        let other = assess_network("SER1", &wifi, |_| "10.20.0.5".parse().ok());
+        assert_eq!(other.network, NetworkMatch::DifferentNetwork);
+
+        let hotspot = [address("swlan0", "192.168.43.1", 24)];
+        let joined = assess_network("SER1", &hotspot, |_| "192.168.43.77".parse().ok());
+        assert_eq!(joined.network, NetworkMatch::DeviceOffWifi);
+        assert!(joined.host_on_hotspot);
+        assert_eq!(joined.hotspot_address.as_deref(), Some("192.168.43.1/24"));
+    }
+
+    #[test]
+    fn test_start_hotspot_quotes_arguments() {
+        let adb = MockAdb::new().on("start-softap", "Soft AP is started\n");
+        let result = start_hotspot(&adb, "SER1", "Gesu's AP", "correct horse").unwrap();
+        assert!(result.started);
+        assert_eq!(
+            adb.calls(),
+            vec!["-s SER1 shell cmd wifi start-softap 'Gesu'\\''s AP' wpa2 'correct horse'"]
+        );
+
+        assert!(start_hotspot(&adb, "SER1", "AP", "short").is_err());
+    }
+
+    #[test]
+    fn test_start_hotspot_falls_back_to_settings() {
+        let adb = MockAdb::new()
+            .on(
+                "start-softap",
+                "Exception occurred while executing 'start-softap': SecurityException\n",
+            )
+            .on(
+                "TETHER_SETTINGS",
+                "Starting: Intent { act=android.settings.TETHER_SETTINGS }\n",
+            );
+        let result = start_hotspot(&adb, "SER1", "Gesu AP", "correct horse").unwrap();
+        assert!(!result.started);
+        assert!(result.settings_opened);
+        assert!(result.message.contains("SecurityException"));
+    }
+}
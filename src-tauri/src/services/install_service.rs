@@ -0,0 +1,205 @@
+//! Package installation and drop routing
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    ConflictPolicy, DropAnalysis, DropFileKind, DropRouteReport, InstallResult, TransferStatus,
+};
+use crate::services::adb_service::{self, AdbRunner};
+use crate::services::drop_service;
+use crate::services::progress_service::ProgressSink;
+use crate::services::transfer_service::{self, TransferState};
+use std::path::Path;
+
+/// Install (or update, keeping data) an APK on a device
+pub fn install_apk(adb: &dyn AdbRunner, serial: &str, apk_path: &str) -> InstallResult {
+    let outcome = match drop_service::classify(Path::new(apk_path)) {
+        DropFileKind::Apk => adb
+            .run_transfer(&["-s", serial, "install", "-r", apk_path])
+            .map_err(|e| e.to_string())
+            .and_then(|out| parse_install_output(&out)),
+        DropFileKind::SplitApk => Err(
+            "Split APK bundles aren't supported yet; extract the .apk files and drop those"
+                .to_string(),
+        ),
+        _ => Err("Not an APK file".to_string()),
+    };
+
+    InstallResult {
+        serial: serial.to_string(),
+        path: apk_path.to_string(),
+        success: outcome.is_ok(),
+        message: outcome.unwrap_or_else(failure_reason),
+    }
+}
+
+/// `adb install` prints "Success" or "Failure [REASON]"; it may still exit 0 on failure
+pub fn parse_install_output(output: &str) -> Result<String, String> {
+    if output.lines().any(|l| l.trim() == "Success") {
+        Ok("Success".to_string())
+    } else {
+        Err(output.trim().to_string())
+    }
+}
+
+/// Reduce adb's install error text to the `INSTALL_FAILED_*` style code when present
+fn failure_reason(message: String) -> String {
+    message
+        .split(|c: char| c == '[' || c == ']' || c.is_whitespace() || c == ':')
+        .find(|token| token.starts_with("INSTALL_"))
+        .map(|code| code.to_string())
+        .unwrap_or(message)
+}
+
+/// Pick the devices a drop goes to: the requested ones, or the only ready device
+pub fn resolve_targets(adb: &dyn AdbRunner, requested: &[String]) -> Result<Vec<String>, AppError> {
+    if !requested.is_empty() {
+        return Ok(requested.to_vec());
+    }
+
+    let ready = adb_service::ready_serials(adb)?;
+    match ready.len() {
+        0 => Err(AppError::DeviceNotFound(
+            "No ready device connected".to_string(),
+        )),
+        1 => Ok(ready),
+        n => Err(AppError::DeviceNotFound(format!(
+            "{} devices connected; choose which ones to send to",
+            n
+        ))),
+    }
+}
+
+/// Route an analyzed drop: install APKs, copy OBBs to their package folder, push the rest
+pub fn route_drop(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serials: &[String],
+    analysis: &DropAnalysis,
+    default_dest: &str,
+    progress: ProgressSink,
+) -> DropRouteReport {
+    let mut installs = Vec::new();
+    let mut transfers = Vec::new();
+
+    let by_kind = |pred: fn(DropFileKind) -> bool| -> Vec<String> {
+        analysis
+            .files
+            .iter()
+            .filter(|f| pred(f.kind))
+            .map(|f| f.path.clone())
+            .collect()
+    };
+    let apks = by_kind(|k| matches!(k, DropFileKind::Apk | DropFileKind::SplitApk));
+    let obbs = by_kind(|k| k == DropFileKind::Obb);
+    let others = by_kind(|k| {
+        !matches!(
+            k,
+            DropFileKind::Apk | DropFileKind::SplitApk | DropFileKind::Obb
+        )
+    });
+
+    let push_dest = analysis
+        .suggested_dest
+        .as_deref()
+        .filter(|_| obbs.is_empty())
+        .unwrap_or(default_dest);
+
+    for serial in serials {
+        for apk in &apks {
+            installs.push(install_apk(adb, serial, apk));
+        }
+
+        // Each OBB goes under Android/obb/<package>; unrecognized names go with the rest
+        let mut unrouted = others.clone();
+        for obb in &obbs {
+            let package = Path::new(obb)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(drop_service::obb_package);
+            match package {
+                Some(package) => transfers.extend(transfer_service::push_files(
+                    state,
+                    adb,
+                    serial,
+                    std::slice::from_ref(obb),
+                    &format!("Android/obb/{}", package),
+                    false,
+                    ConflictPolicy::Overwrite,
+                    progress,
+                )),
+                None => unrouted.push(obb.clone()),
+            }
+        }
+
+        if !unrouted.is_empty() {
+            transfers.extend(transfer_service::push_files(
+                state,
+                adb,
+                serial,
+                &unrouted,
+                push_dest,
+                false,
+                ConflictPolicy::Overwrite,
+                progress,
+            ));
+        }
+    }
+
+    let succeeded = installs.iter().filter(|i| i.success).count()
+        + transfers
+            .iter()
+            .filter(|t| t.status == TransferStatus::Complete)
+            .count();
+    let failed = installs.len() + transfers.len() - succeeded;
+
+    DropRouteReport {
+        serials: serials.to_vec(),
+        installs,
+        transfers,
+        succeeded,
+        failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parse_install_output() {
+        assert!(parse_install_output("Performing Streamed Install\nSuccess\n").is_ok());
+        assert!(parse_install_output("Failure [INSTALL_FAILED_VERSION_DOWNGRADE]").is_err());
+    }
+
+    #[test]
+    fn test_install_failure_reason() {
+        let adb = MockAdb::new().fail(
+            "install",
+            AppError::AdbExecutionFailed(
+                "ADB command failed: adb: failed to install a.apk: Failure [INSTALL_FAILED_OLDER_SDK: Requires newer sdk]"
+                    .to_string(),
+            ),
+        );
+
+        let result = install_apk(&adb, "SER1", "C:/a.apk");
+        assert!(!result.success);
+        assert_eq!(result.message, "INSTALL_FAILED_OLDER_SDK");
+    }
+
+    #[test]
+    fn test_resolve_targets() {
+        let two = MockAdb::new().on(
+            "devices",
+            "List of devices attached\nAAA\tdevice\nBBB\tdevice\n",
+        );
+        assert!(resolve_targets(&two, &[]).is_err());
+        assert_eq!(
+            resolve_targets(&two, &["BBB".to_string()]).unwrap(),
+            vec!["BBB".to_string()]
+        );
+
+        let one = MockAdb::new().on("devices", "List of devices attached\nAAA\tdevice\n");
+        assert_eq!(resolve_targets(&one, &[]).unwrap(), vec!["AAA".to_string()]);
+    }
+}
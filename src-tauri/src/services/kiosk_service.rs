@@ -0,0 +1,171 @@
+//! Kiosk/demo mode: pin one app on the device with screen pinning so visitors
+//! can't leave it, while it is mirrored fullscreen from the PC
+
+use crate::domain::errors::AppError;
+use crate::domain::models::MirrorOptions;
+use crate::services::adb_service::AdbRunner;
+
+/// Java package names: dot-separated identifiers, at least two segments
+pub fn is_valid_package_name(package: &str) -> bool {
+    let segments: Vec<&str> = package.split('.').collect();
+    segments.len() >= 2
+        && segments.iter().all(|segment| {
+            segment.starts_with(|c: char| c.is_ascii_alphabetic())
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// Launch an app and pin its task; returns the pinned task id
+pub fn pin_app(adb: &dyn AdbRunner, serial: &str, package: &str) -> Result<u32, AppError> {
+    if !is_valid_package_name(package) {
+        return Err(AppError::MirrorError(format!(
+            "Invalid package name: {}",
+            package
+        )));
+    }
+
+    let component = resolve_launcher(adb, serial, package)?;
+    adb.run(&["-s", serial, "shell", "am", "start", "-W", "-n", &component])?;
+
+    let recents = adb.run(&["-s", serial, "shell", "dumpsys", "activity", "recents"])?;
+    let task_id = parse_recent_task_id(&recents, package).ok_or_else(|| {
+        AppError::MirrorError(format!("Could not find the task of {} to pin", package))
+    })?;
+
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "am",
+        "task",
+        "lock",
+        &task_id.to_string(),
+    ])?;
+    Ok(task_id)
+}
+
+/// Leave screen pinning
+pub fn unpin(adb: &dyn AdbRunner, serial: &str) -> Result<String, AppError> {
+    adb.run(&["-s", serial, "shell", "am", "task", "lock", "stop"])
+}
+
+/// Options for a kiosk mirror on top of the user's defaults
+pub fn kiosk_options(base: &MirrorOptions) -> MirrorOptions {
+    MirrorOptions {
+        fullscreen: true,
+        stay_awake: true,
+        ..base.clone()
+    }
+}
+
+/// Launcher activity of a package, e.g. `com.example/.MainActivity`
+fn resolve_launcher(adb: &dyn AdbRunner, serial: &str, package: &str) -> Result<String, AppError> {
+    let output = adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "cmd",
+        "package",
+        "resolve-activity",
+        "--brief",
+        "-c",
+        "android.intent.category.LAUNCHER",
+        package,
+    ])?;
+
+    output
+        .lines()
+        .map(str::trim)
+        .rfind(|line| line.starts_with(package) && line.contains('/'))
+        .map(str::to_string)
+        .ok_or_else(|| {
+            AppError::MirrorError(format!(
+                "{} has no launcher activity on the device",
+                package
+            ))
+        })
+}
+
+/// Id of the most recent task of `package` in `dumpsys activity recents`
+///
+/// Example lines (Android 12 and Android 9):
+/// ```text
+///   * Recent #0: Task{4a0b8c1 #231 type=standard A=10163:com.example.app U=0 visible=true}
+///   * Recent #0: TaskRecord{3c1d5f2 #86 A=com.example.app U=0 StackId=4 sz=1}
+/// ```
+pub fn parse_recent_task_id(output: &str, package: &str) -> Option<u32> {
+    output
+        .lines()
+        .filter(|line| line.contains("Recent #"))
+        .find_map(|line| {
+            let mut id = None;
+            let mut affinity = None;
+            for token in line.split_whitespace() {
+                if let Some(n) = token.strip_prefix('#') {
+                    id = id.or_else(|| n.parse::<u32>().ok());
+                } else if let Some(a) = token.strip_prefix("A=") {
+                    affinity = a.rsplit(':').next();
+                }
+            }
+            (affinity == Some(package)).then_some(id).flatten()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const RECENTS: &str = "ACTIVITY MANAGER RECENT TASKS (dumpsys activity recents)\n  Recent tasks:\n  * Recent #0: Task{4a0b8c1 #231 type=standard A=10163:com.example.demo U=0 visible=true}\n  * Recent #1: Task{1f2e3d4 #12 type=home A=10020:com.android.launcher3 U=0}\n";
+
+    #[test]
+    fn test_is_valid_package_name() {
+        assert!(is_valid_package_name("com.example.demo"));
+        assert!(is_valid_package_name("org.app_2.x"));
+        assert!(!is_valid_package_name("demo"));
+        assert!(!is_valid_package_name("com.example;reboot"));
+        assert!(!is_valid_package_name("com..example"));
+        assert!(!is_valid_package_name("com.1example"));
+    }
+
+    #[test]
+    fn test_parse_recent_task_id() {
+        assert_eq!(parse_recent_task_id(RECENTS, "com.example.demo"), Some(231));
+        assert_eq!(
+            parse_recent_task_id(RECENTS, "com.android.launcher3"),
+            Some(12)
+        );
+        assert_eq!(
+            parse_recent_task_id(
+                "  * Recent #0: TaskRecord{3c1d5f2 #86 A=com.example.demo U=0 StackId=4 sz=1}",
+                "com.example.demo"
+            ),
+            Some(86)
+        );
+        assert_eq!(parse_recent_task_id(RECENTS, "com.other"), None);
+    }
+
+    #[test]
+    fn test_pin_app() {
+        let adb = MockAdb::new()
+            .on(
+                "resolve-activity",
+                "priority=0 preferredOrder=0 match=0x108000\ncom.example.demo/.MainActivity\n",
+            )
+            .on("am start", "Status: ok\n")
+            .on("dumpsys activity recents", RECENTS)
+            .on("am task lock", "");
+
+        assert_eq!(pin_app(&adb, "SER1", "com.example.demo").unwrap(), 231);
+        let calls = adb.calls();
+        assert_eq!(
+            calls[1],
+            "-s SER1 shell am start -W -n com.example.demo/.MainActivity"
+        );
+        assert_eq!(calls[3], "-s SER1 shell am task lock 231");
+
+        assert!(pin_app(&adb, "SER1", "com.x; reboot").is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Wi-Fi link preflight: measures a wireless adb connection before mirroring
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{LinkQuality, MirrorOptions};
+use crate::services::adb_service::AdbRunner;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// Bytes streamed from the device for the throughput sample (2 MiB)
+const SAMPLE_BLOCK_SIZE: u64 = 64 * 1024;
+const SAMPLE_BLOCKS: u64 = 32;
+
+/// Round trips timed for latency; the fastest one is used
+const LATENCY_PINGS: usize = 3;
+
+/// scrcpy's default video bitrate
+pub const DEFAULT_BIT_RATE_MBPS: u32 = 8;
+
+/// Share of the measured throughput the video stream may use, leaving room
+/// for audio, control messages and bursts
+const BIT_RATE_HEADROOM: f64 = 0.5;
+
+/// Round trips slower than this make mirroring feel laggy regardless of bitrate
+const MAX_GOOD_LATENCY_MS: u64 = 100;
+
+/// Whether a serial is a network (`adb connect` or wireless debugging) device
+pub fn is_wireless_serial(serial: &str) -> bool {
+    serial.parse::<SocketAddr>().is_ok() || serial.contains("._adb-tls-connect._tcp")
+}
+
+/// Time a few round trips and a bulk transfer from the device
+pub fn measure_link(adb: &dyn AdbRunner, device_serial: &str) -> Result<LinkQuality, AppError> {
+    let mut latency = Duration::MAX;
+    for _ in 0..LATENCY_PINGS {
+        let started = Instant::now();
+        adb.run(&["-s", device_serial, "shell", "echo"])?;
+        latency = latency.min(started.elapsed());
+    }
+
+    let dd = format!(
+        "dd if=/dev/zero bs={} count={} 2>/dev/null",
+        SAMPLE_BLOCK_SIZE, SAMPLE_BLOCKS
+    );
+    let started = Instant::now();
+    let output = adb.run(&["-s", device_serial, "exec-out", &dd])?;
+    let elapsed = started.elapsed();
+
+    Ok(assess(
+        output.len() as u64,
+        elapsed.saturating_sub(latency),
+        latency,
+    ))
+}
+
+/// Turn a timed transfer into a quality verdict and a bitrate that fits it
+pub fn assess(bytes: u64, transfer: Duration, latency: Duration) -> LinkQuality {
+    // Guard against a zero-length timing on very fast links
+    let secs = transfer.as_secs_f64().max(0.001);
+    let throughput_kbps = (bytes as f64 * 8.0 / 1000.0 / secs) as u64;
+    let latency_ms = latency.as_millis() as u64;
+
+    let fitting = (throughput_kbps as f64 / 1000.0 * BIT_RATE_HEADROOM) as u32;
+    let recommended_bit_rate_mbps = fitting.clamp(1, DEFAULT_BIT_RATE_MBPS);
+
+    LinkQuality {
+        latency_ms,
+        throughput_kbps,
+        weak: recommended_bit_rate_mbps < DEFAULT_BIT_RATE_MBPS || latency_ms > MAX_GOOD_LATENCY_MS,
+        recommended_bit_rate_mbps,
+    }
+}
+
+/// Options to mirror with over a link; lowers the bitrate on weak links when
+/// the user allowed it, and never raises a bitrate they chose
+pub fn adapt_options(options: &MirrorOptions, link: &LinkQuality) -> MirrorOptions {
+    let mut adapted = options.clone();
+    if options.adapt_to_link && link.weak {
+        let current = options.bit_rate_mbps.unwrap_or(DEFAULT_BIT_RATE_MBPS);
+        adapted.bit_rate_mbps = Some(current.min(link.recommended_bit_rate_mbps));
+    }
+    adapted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_is_wireless_serial() {
+        assert!(is_wireless_serial("192.168.1.5:5555"));
+        assert!(is_wireless_serial("[fe80::1]:5555"));
+        assert!(is_wireless_serial(
+            "adb-R58M123-AbCdEf._adb-tls-connect._tcp"
+        ));
+        assert!(!is_wireless_serial("R58M123"));
+        assert!(!is_wireless_serial("emulator-5554"));
+    }
+
+    #[test]
+    fn test_assess() {
+        // 2 MB in 100 ms = 160 Mbit/s, plenty for the default bitrate
+        let fast = assess(
+            2_000_000,
+            Duration::from_millis(100),
+            Duration::from_millis(5),
+        );
+        assert_eq!(fast.throughput_kbps, 160_000);
+        assert_eq!(fast.recommended_bit_rate_mbps, DEFAULT_BIT_RATE_MBPS);
+        assert!(!fast.weak);
+
+        // 2 MB in 2 s = 8 Mbit/s, so the video gets half of that
+        let slow = assess(2_000_000, Duration::from_secs(2), Duration::from_millis(20));
+        assert_eq!(slow.recommended_bit_rate_mbps, 4);
+        assert!(slow.weak);
+
+        // Fast but laggy
+        let laggy = assess(
+            2_000_000,
+            Duration::from_millis(100),
+            Duration::from_millis(250),
+        );
+        assert!(laggy.weak);
+
+        // Never below 1 Mbit/s
+        let awful = assess(10_000, Duration::from_secs(5), Duration::from_millis(5));
+        assert_eq!(awful.recommended_bit_rate_mbps, 1);
+    }
+
+    #[test]
+    fn test_adapt_options() {
+        let weak = assess(2_000_000, Duration::from_secs(2), Duration::from_millis(20));
+
+        let untouched = adapt_options(&MirrorOptions::default(), &weak);
+        assert_eq!(untouched.bit_rate_mbps, None);
+
+        let adapt = MirrorOptions {
+            adapt_to_link: true,
+            ..MirrorOptions::default()
+        };
+        assert_eq!(adapt_options(&adapt, &weak).bit_rate_mbps, Some(4));
+
+        let already_low = MirrorOptions {
+            adapt_to_link: true,
+            bit_rate_mbps: Some(2),
+            ..MirrorOptions::default()
+        };
+        assert_eq!(adapt_options(&already_low, &weak).bit_rate_mbps, Some(2));
+    }
+
+    #[test]
+    fn test_measure_link_streams_from_device() {
+        let adb = MockAdb::new()
+            .on("shell echo", "\n")
+            .on("exec-out dd", &"\0".repeat(4096));
+
+        let link = measure_link(&adb, "192.168.1.5:5555").unwrap();
+        assert!(link.throughput_kbps > 0);
+
+        let calls = adb.calls();
+        assert_eq!(calls.len(), LATENCY_PINGS + 1);
+        assert!(calls[LATENCY_PINGS]
+            .ends_with("exec-out dd if=/dev/zero bs=65536 count=32 2>/dev/null"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Local names for pulled device files
+//!
+//! Android allows names Windows doesn't: `:` or `?` in them, trailing dots,
+//! or device names like `CON` and `aux.jpg`. On Windows such names are
+//! escaped, and the result reports the original. Paths past the Win32 limit
+//! are handed to adb in the `\\?\` form; `std::fs` already does that itself.
+
+use std::path::{Path, PathBuf};
+
+/// Longest path the plain Win32 calls take; `CreateDirectoryW` stops at
+/// 248, below `MAX_PATH`
+const WIN32_PATH_LIMIT: usize = 248;
+
+/// Names Windows reserves for devices, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// The name a device file gets on this computer: escaped on Windows,
+/// unchanged elsewhere
+pub fn local_file_name(name: &str) -> String {
+    if cfg!(target_os = "windows") {
+        windows_safe_name(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// `name` with the characters Windows rejects replaced by `_`, trailing dots
+/// and spaces replaced, and reserved device names suffixed with `_`
+/// (`CON.txt` becomes `CON_.txt`)
+pub fn windows_safe_name(name: &str) -> String {
+    let mut safe: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let kept = safe.trim_end_matches(['.', ' ']).len();
+    let trailing = safe.len() - kept;
+    safe.truncate(kept);
+    safe.push_str(&"_".repeat(trailing));
+    if safe.is_empty() {
+        return "_".to_string();
+    }
+
+    let stem_end = safe.find('.').unwrap_or(safe.len());
+    let stem = safe[..stem_end].trim_end();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        safe.insert(stem_end, '_');
+    }
+    safe
+}
+
+/// The `\\?\` form of an absolute Windows path too long for the Win32
+/// calls, or `None` when the path can be used as it is
+pub fn extended_length_path(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.encode_utf16().count() < WIN32_PATH_LIMIT {
+        return None;
+    }
+    // The prefix turns off separator normalisation, so `/` must go first
+    let path = path.replace('/', r"\");
+    if let Some(share) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{}", share));
+    }
+    let bytes = path.as_bytes();
+    let absolute =
+        bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\';
+    absolute.then(|| format!(r"\\?\{}", path))
+}
+
+/// A local path as handed to an external program such as adb, which can't
+/// reach past the Win32 limit without the `\\?\` prefix
+pub fn for_external_tool(path: &Path) -> PathBuf {
+    if !cfg!(target_os = "windows") {
+        return path.to_path_buf();
+    }
+    extended_length_path(&path.to_string_lossy())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_safe_name() {
+        assert_eq!(windows_safe_name("IMG_0001.jpg"), "IMG_0001.jpg");
+        assert_eq!(windows_safe_name("12:30 note?.txt"), "12_30 note_.txt");
+        assert_eq!(windows_safe_name("CON"), "CON_");
+        assert_eq!(windows_safe_name("aux.jpg"), "aux_.jpg");
+        assert_eq!(windows_safe_name("Com1.tar.gz"), "Com1_.tar.gz");
+        assert_eq!(windows_safe_name("console.log"), "console.log");
+        assert_eq!(windows_safe_name("draft. "), "draft__");
+        assert_eq!(windows_safe_name(".."), "__");
+    }
+
+    #[test]
+    fn test_extended_length_path() {
+        assert_eq!(extended_length_path(r"C:\Pulls\a.jpg"), None);
+
+        let long_dir = "d".repeat(250);
+        assert_eq!(
+            extended_length_path(&format!("C:\\Pulls/{}/a.jpg", long_dir)),
+            Some(format!("\\\\?\\C:\\Pulls\\{}\\a.jpg", long_dir))
+        );
+        assert_eq!(
+            extended_length_path(&format!("\\\\nas\\photos\\{}", long_dir)),
+            Some(format!("\\\\?\\UNC\\nas\\photos\\{}", long_dir))
+        );
+        let prefixed = format!("\\\\?\\C:\\{}", long_dir);
+        assert_eq!(extended_length_path(&prefixed), None);
+        assert_eq!(
+            extended_length_path(&format!("relative\\{}", long_dir)),
+            None
+        );
+    }
+}
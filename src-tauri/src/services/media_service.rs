@@ -1,10 +1,27 @@
 //! Media service for browsing and transferring media from Android devices via ADB
 
 use crate::domain::errors::AppError;
-use crate::domain::models::{FolderInfo, MediaFilter, MediaItem, MediaTransferResult, MediaType};
-use crate::services::adb_service::run_adb_command;
-use std::os::windows::process::CommandExt;
-use std::path::Path;
+use crate::domain::models::{
+    AffectedItem, BatchKind, ConflictPolicy, DestructiveOpReport, FolderInfo, MediaFilter,
+    MediaItem, MediaSelection, MediaTransferResult, MediaType, PulledFile,
+};
+use crate::services::adb_service::AdbRunner;
+use crate::services::conflict_service::{self, Resolution};
+use crate::services::device_thumbnail_service;
+use crate::services::hash_service;
+use crate::services::local_path_service;
+use crate::services::media_time_service;
+use crate::services::motion_photo_service;
+use crate::services::privacy_service;
+use crate::services::process_service::{self, TOOL_CHECK_TIMEOUT};
+use crate::services::progress_service::{BatchTracker, ProgressSink};
+use crate::services::retry_service;
+use crate::services::storage_service;
+use crate::services::thumbnail_cache_service;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Common media folder paths on Android devices
 const MEDIA_FOLDERS: &[&str] = &[
@@ -18,13 +35,17 @@ const MEDIA_FOLDERS: &[&str] = &[
 ];
 
 /// Image file extensions (case-insensitive matching)
-const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "heic", "heif"];
+pub(crate) const IMAGE_EXTENSIONS: &[&str] =
+    &["jpg", "jpeg", "png", "gif", "webp", "bmp", "heic", "heif"];
 
 /// Video file extensions (case-insensitive matching)
-const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "avi", "mov", "webm", "3gp", "m4v"];
+pub(crate) const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "avi", "mov", "webm", "3gp", "m4v"];
+
+/// Max time ffmpeg may spend extracting a single thumbnail frame
+const FFMPEG_FRAME_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Helper to quote paths for use in adb shell
-fn quote_remote_path(path: &str) -> String {
+pub(crate) fn quote_remote_path(path: &str) -> String {
     // Single quote the path and escape any single quotes inside
     // '/sdcard/It's Me' -> '/sdcard/It'\''s Me'
     format!("'{}'", path.replace('\'', "'\\''"))
@@ -32,9 +53,9 @@ fn quote_remote_path(path: &str) -> String {
 
 /// Detect the best default root folder for media browsing
 /// Priority: SD card > Internal storage (/storage/emulated/0) > /sdcard
-pub fn get_default_media_root(adb_path: &str, serial: &str) -> Result<String, AppError> {
+pub fn get_default_media_root(adb: &dyn AdbRunner, serial: &str) -> Result<String, AppError> {
     // Try to list /storage/ directory
-    let output = run_adb_command(adb_path, &["-s", serial, "shell", "ls", "/storage/"]);
+    let output = adb.run_idempotent(&["-s", serial, "shell", "ls", "/storage/"]);
 
     if let Ok(storage_list) = output {
         // Look for SD card (non-emulated storage)
@@ -50,7 +71,7 @@ pub fn get_default_media_root(adb_path: &str, serial: &str) -> Result<String, Ap
             let sd_path = format!("/storage/{}", line);
 
             // Verify it's accessible and has media folders
-            let verify = run_adb_command(adb_path, &["-s", serial, "shell", "ls", &sd_path]);
+            let verify = adb.run_idempotent(&["-s", serial, "shell", "ls", &sd_path]);
 
             if verify.is_ok() {
                 // SD card is accessible, use it
@@ -61,8 +82,7 @@ pub fn get_default_media_root(adb_path: &str, serial: &str) -> Result<String, Ap
 
     // No SD card found, try internal storage
     let internal_storage = "/storage/emulated/0";
-    let verify_internal =
-        run_adb_command(adb_path, &["-s", serial, "shell", "ls", internal_storage]);
+    let verify_internal = adb.run_idempotent(&["-s", serial, "shell", "ls", internal_storage]);
 
     if verify_internal.is_ok() {
         return Ok(internal_storage.to_string());
@@ -74,24 +94,21 @@ pub fn get_default_media_root(adb_path: &str, serial: &str) -> Result<String, Ap
 
 /// List folders at a given path on the device
 pub fn list_folders(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     serial: &str,
     path: Option<&str>,
 ) -> Result<Vec<FolderInfo>, AppError> {
     let base_path = path.unwrap_or("/sdcard");
     let quoted_path = quote_remote_path(base_path);
 
-    // Use ls -la to get directory listing
-    let output = run_adb_command(
-        adb_path,
-        &["-s", serial, "shell", "ls", "-la", &quoted_path],
-    )?;
+    // Use ls -la to get directory listing; exec-out passes the names through
+    // byte for byte, without the shell's line-ending conversion
+    let output = adb.run_idempotent(&["-s", serial, "exec-out", "ls", "-la", &quoted_path])?;
 
     let mut folders = Vec::new();
 
     for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
+        if line.trim().is_empty() {
             continue;
         }
         // "total" line often appears at top
@@ -104,19 +121,16 @@ pub fn list_folders(
         }
 
         // Parse ls -la output: drwxrwxrwx ... name
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 8 {
+        let Some((parts, name)) = split_ls_line(line, 7) else {
             continue;
-        }
+        };
 
         // First character 'd' indicates directory
         let permissions = parts[0];
         if !permissions.starts_with('d') {
             continue;
         }
-
-        // Last part is the name (may contain spaces, so rejoin)
-        let name = parts[7..].join(" ");
+        let name = name.to_string();
 
         // Skip . and .. and hidden folders
         if name == "." || name == ".." || name.starts_with('.') {
@@ -129,10 +143,7 @@ pub fn list_folders(
             format!("{}/{}", base_path, name)
         };
 
-        // Check if it's a known media folder
-        let is_media_folder = MEDIA_FOLDERS
-            .iter()
-            .any(|mf| name.eq_ignore_ascii_case(mf) || folder_path.contains(mf));
+        let is_media_folder = is_media_folder(&name, &folder_path);
 
         folders.push(FolderInfo {
             name,
@@ -152,196 +163,647 @@ pub fn list_folders(
     Ok(folders)
 }
 
-/// List media files in a folder
+/// Whether a folder is a known media folder, shown first when browsing
+pub(crate) fn is_media_folder(name: &str, path: &str) -> bool {
+    MEDIA_FOLDERS
+        .iter()
+        .any(|mf| name.eq_ignore_ascii_case(mf) || path.contains(mf))
+}
+
+/// List media files in a folder, newest first. `date_taken` is ISO-8601 UTC:
+/// MediaStore's capture time when it has one, else the file's modification
+/// time.
 pub fn list_media_files(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     serial: &str,
     path: &str,
     filter: MediaFilter,
 ) -> Result<Vec<MediaItem>, AppError> {
-    let quoted_path = quote_remote_path(path);
-    // Use ls -la to get file listing (more reliable than find on Android shell)
-    let output = run_adb_command(
-        adb_path,
-        &["-s", serial, "shell", "ls", "-la", &quoted_path],
-    )?;
+    let root = path.trim_end_matches('/');
+    // stat gives sizes and epoch times whatever the device's locale, and
+    // exec-out keeps names with emoji or CJK characters intact
+    let command = format!(
+        "find {}/ -maxdepth 1 -type f -exec stat -c '%s %Y %n' {{}} +",
+        quote_remote_path(root)
+    );
+    let output = adb.run_idempotent(&["-s", serial, "exec-out", &command])?;
+    let taken = media_time_service::date_taken_in(adb, serial, root);
+    let timezone = media_time_service::device_utc_offset(adb, serial);
 
     let mut items = Vec::new();
 
     for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("total") {
+        // Parse stat output: 12345 1706603400 /sdcard/DCIM/Camera/filename.jpg
+        let mut parts = line.trim_end_matches('\r').splitn(3, ' ');
+        let (Some(size), Some(modified), Some(file_path)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
             continue;
-        }
-
-        // Parse ls -la output: -rw-rw---- 1 u0_a123 u0_a123 12345 2024-01-30 10:30 filename.jpg
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 7 {
-            continue;
-        }
-
-        // First character '-' indicates regular file, 'd' is directory
-        let permissions = parts[0];
-        if !permissions.starts_with('-') {
-            continue;
-        }
-
-        // Parse size (position 4)
-        let size_bytes: u64 = parts[4].parse().unwrap_or(0);
-
-        // Date is at position 5, time at 6
-        let date_part = parts.get(5).unwrap_or(&"");
-        let time_part = parts.get(6).unwrap_or(&"");
-        let date_taken = if !date_part.is_empty() && !time_part.is_empty() {
-            Some(format!("{} {}", date_part, time_part))
-        } else {
-            None
         };
-
-        // Filename is everything after the time (may contain spaces)
-        let name = if parts.len() > 7 {
-            parts[7..].join(" ")
-        } else {
+        let Some(name) = file_path
+            .strip_prefix(root)
+            .and_then(|rest| rest.strip_prefix('/'))
+            // Some toolboxes keep the start point's trailing slash
+            .map(|rest| rest.trim_start_matches('/'))
+            .filter(|name| !name.is_empty() && !name.contains('/'))
+        else {
             continue;
         };
 
-        // Build full file path
-        let file_path = if path.ends_with('/') {
-            format!("{}{}", path, name)
-        } else {
-            format!("{}/{}", path, name)
-        };
-
-        // Get file extension
-        let extension = Path::new(&name)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        // Determine media type
-        let media_type = if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
-            Some(MediaType::Image)
-        } else if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
-            Some(MediaType::Video)
-        } else {
-            None
-        };
-
         // Skip non-media files
-        let media_type = match media_type {
+        let media_type = match media_type_of(name) {
             Some(t) => t,
             None => continue,
         };
 
         // Apply filter
-        match filter {
-            MediaFilter::Images if media_type != MediaType::Image => continue,
-            MediaFilter::Videos if media_type != MediaType::Video => continue,
-            _ => {}
+        if !filter.accepts(&media_type) {
+            continue;
         }
 
+        let date_taken = taken
+            .get(&media_time_service::storage_relative(file_path))
+            .copied()
+            .or_else(|| modified.parse().ok())
+            .and_then(media_time_service::iso_utc);
+
         items.push(MediaItem {
-            path: file_path,
-            name,
+            path: format!("{}/{}", root, name),
             media_type,
-            size_bytes,
+            size_bytes: size.parse().unwrap_or(0),
             width: None,
             height: None,
             duration_ms: None,
             date_taken,
+            timezone: timezone.clone(),
             thumbnail_url: None,
+            motion_photo: motion_photo_service::looks_like_motion_photo(name),
+            name: name.to_string(),
         });
     }
 
-    // Sort by date descending (newest first)
+    // Sort by date descending (newest first); ISO-8601 UTC sorts as text
     items.sort_by(|a, b| b.date_taken.cmp(&a.date_taken));
 
     Ok(items)
 }
 
-/// Pull a single file from device to local temp directory and return local path
+/// The first `count` columns of an `ls -l` line and the name after them.
+/// Columns are split on ASCII blanks only and the name is taken as is, so
+/// runs of spaces and Unicode spaces such as U+3000 in it survive.
+fn split_ls_line(line: &str, count: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut rest = line;
+    for _ in 0..count {
+        rest = rest.trim_start_matches([' ', '\t']);
+        let end = rest.find([' ', '\t'])?;
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    // One blank separates the time from the name; more belong to the name
+    let name = rest.strip_prefix([' ', '\t'])?;
+    (!name.is_empty()).then_some((fields, name))
+}
+
+/// Image or video, judged by the file extension
+pub fn media_type_of(name: &str) -> Option<MediaType> {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(MediaType::Image)
+    } else if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        Some(MediaType::Video)
+    } else {
+        None
+    }
+}
+
+/// Pull a single file from device to local temp directory and return local path.
+/// The local name is unique to the device and full remote path (see
+/// [`temp_pull_path`]), so same-named files never overwrite each other.
 pub fn pull_media_file(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     serial: &str,
     remote_path: &str,
     local_dest: &Path,
 ) -> Result<String, AppError> {
+    let local_path = temp_pull_path(local_dest, serial, remote_path)?;
+    pull_to(adb, serial, remote_path, &local_path)
+}
+
+/// Where [`pull_media_file`] puts a device file in `temp_dir`: its name behind
+/// a hash of the serial and full path, so `DCIM/IMG_0001.jpg` and
+/// `Download/IMG_0001.jpg` get different files and the extension is kept
+pub fn temp_pull_path(
+    temp_dir: &Path,
+    serial: &str,
+    remote_path: &str,
+) -> Result<PathBuf, AppError> {
     let file_name = Path::new(remote_path)
         .file_name()
         .and_then(|n| n.to_str())
         .ok_or_else(|| AppError::InvalidPath("Invalid remote path".to_string()))?;
 
-    let local_path = local_dest.join(file_name);
+    let mut hasher = Sha256::default();
+    hasher.update(serial.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(remote_path.as_bytes());
+    let digest = hash_service::hex(&hasher.finalize());
+    Ok(temp_dir.join(format!(
+        "{}_{}",
+        &digest[..16],
+        sanitize_filename(file_name)
+    )))
+}
+
+/// Pull a file to an exact local path
+pub(crate) fn pull_to(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    remote_path: &str,
+    local_path: &Path,
+) -> Result<String, AppError> {
     let local_path_str = local_path.to_string_lossy().to_string();
+    let adb_path = local_path_service::for_external_tool(local_path);
+    let adb_path = adb_path.to_string_lossy();
 
-    run_adb_command(
-        adb_path,
-        &["-s", serial, "pull", remote_path, &local_path_str],
-    )?;
+    retry_service::retry(&adb.retry_policy(), || {
+        adb.run_transfer(&["-s", serial, "pull", remote_path, &adb_path])
+    })?;
 
     Ok(local_path_str)
 }
 
+/// Modification time of a local file, in seconds since the Unix epoch
+fn local_mtime(path: &Path) -> Option<i64> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
 /// Pull multiple files from device
 pub fn pull_media_files_batch(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     serial: &str,
     remote_paths: &[String],
     local_dest: &Path,
-) -> Vec<MediaTransferResult> {
+    conflict: ConflictPolicy,
+    cached: CachedCopy,
+    progress: ProgressSink,
+) -> Result<Vec<MediaTransferResult>, AppError> {
+    // Sizes up front so the batch total is known before the first pull
+    let files: Vec<RemoteFile> = remote_paths
+        .iter()
+        .map(|p| RemoteFile {
+            path: p.clone(),
+            size_bytes: get_file_size(adb, serial, p).unwrap_or(0),
+        })
+        .collect();
+    pull_files(
+        adb,
+        serial,
+        &files,
+        |_| local_dest.to_path_buf(),
+        conflict,
+        cached,
+        progress,
+    )
+}
+
+/// A file on the device with its size
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteFile {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// A local copy of a device file known to match it (e.g. its preview),
+/// copied instead of pulling the file again
+pub type CachedCopy<'a> = &'a dyn Fn(&RemoteFile) -> Option<PathBuf>;
+
+/// Pull files as one tracked batch, each into the folder `local_dir` picks.
+/// Fails before the first file when the destination can't hold them all.
+pub fn pull_files(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    files: &[RemoteFile],
+    local_dir: impl Fn(&RemoteFile) -> PathBuf,
+    conflict: ConflictPolicy,
+    cached: CachedCopy,
+    progress: ProgressSink,
+) -> Result<Vec<MediaTransferResult>, AppError> {
+    if let Some(first) = files.first() {
+        let needed = files
+            .iter()
+            .map(|file| {
+                let target = Path::new(&file.path)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|name| local_dir(file).join(local_path_service::local_file_name(name)));
+                let existing = target
+                    .and_then(|t| std::fs::metadata(t).ok())
+                    .map(|m| m.len());
+                storage_service::pull_bytes_needed(file.size_bytes, existing, conflict)
+            })
+            .sum();
+        storage_service::ensure_space(&local_dir(first), needed)?;
+    }
+
+    let mut tracker = BatchTracker::new(
+        BatchKind::Pull,
+        files.len(),
+        files.iter().map(|f| f.size_bytes).sum(),
+        progress,
+    );
     let mut results = Vec::new();
 
-    for remote_path in remote_paths {
-        // file_name kept for potential future logging
-        let _file_name = Path::new(remote_path)
+    for file in files {
+        let file_name = Path::new(&file.path)
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
+        tracker.start_file(file_name);
+
+        let dir = local_dir(file);
+        let result = match std::fs::create_dir_all(&dir) {
+            Ok(()) => pull_with_policy(adb, serial, file, &dir, conflict, cached(file)),
+            Err(e) => MediaTransferResult {
+                source_path: file.path.clone(),
+                dest_path: None,
+                success: false,
+                error: Some(e.to_string()),
+                size_bytes: file.size_bytes,
+                skipped: false,
+                motion_video_path: None,
+                renamed_from: None,
+            },
+        };
+        let failure = (!result.success && !result.skipped)
+            .then(|| result.error.clone())
+            .flatten();
+        tracker.finish_file(&file.path, file.size_bytes, failure);
+        results.push(result);
+    }
 
-        // Get file size first
-        let size_bytes = get_file_size(adb_path, serial, remote_path).unwrap_or(0);
+    tracker.finish();
+    Ok(results)
+}
 
-        match pull_media_file(adb_path, serial, remote_path, local_dest) {
-            Ok(dest_path) => {
-                results.push(MediaTransferResult {
-                    source_path: remote_path.clone(),
-                    dest_path: Some(dest_path),
-                    success: true,
-                    error: None,
-                    size_bytes,
-                });
+/// Files in a device folder, optionally including subfolders. With a filter
+/// only media of that kind is listed; hidden files and folders are left out.
+pub fn list_folder_files(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    remote_dir: &str,
+    recursive: bool,
+    filter: Option<MediaFilter>,
+) -> Result<Vec<RemoteFile>, AppError> {
+    let root = remote_dir.trim_end_matches('/');
+    let mut command = format!("find {}/", quote_remote_path(root));
+    if !recursive {
+        command.push_str(" -maxdepth 1");
+    }
+    command.push_str(" -type f -exec stat -c '%s %n' {} +");
+
+    let output = adb.run_idempotent(&["-s", serial, "exec-out", &command])?;
+    Ok(parse_stat_listing(&output, root)
+        .into_iter()
+        .filter(|file| match &filter {
+            Some(filter) => media_type_of(&file.path).is_some_and(|t| filter.accepts(&t)),
+            None => true,
+        })
+        .collect())
+}
+
+/// Parse `stat -c '%s %n'` lines below `root`, skipping hidden entries
+///
+/// Example output:
+/// ```text
+/// 2048 /sdcard/DCIM/Camera/IMG_0001.jpg
+/// 512 /sdcard/DCIM/.thumbnails/1.jpg
+/// ```
+pub fn parse_stat_listing(output: &str, root: &str) -> Vec<RemoteFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (size, path) = line.trim_end_matches('\r').split_once(' ')?;
+            visible_relative(path, root)?;
+            Some(RemoteFile {
+                path: path.to_string(),
+                size_bytes: size.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// `path` relative to `root`, unless it's outside it or hidden below it
+pub(crate) fn visible_relative<'a>(path: &'a str, root: &str) -> Option<&'a str> {
+    let relative = path.strip_prefix(root)?.trim_start_matches('/');
+    if relative.is_empty() || relative.split('/').any(|part| part.starts_with('.')) {
+        return None;
+    }
+    Some(relative)
+}
+
+/// Pull a whole device folder into `local_dest/<folder name>`, keeping the
+/// subfolder layout, as one tracked batch. Files in the `hidden` private
+/// folders are left out.
+#[allow(clippy::too_many_arguments)]
+pub fn pull_folder(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    remote_dir: &str,
+    local_dest: &Path,
+    recursive: bool,
+    filter: Option<MediaFilter>,
+    hidden: &[String],
+    conflict: ConflictPolicy,
+    progress: ProgressSink,
+) -> Result<Vec<MediaTransferResult>, AppError> {
+    let root = remote_dir.trim_end_matches('/');
+    let mut files = list_folder_files(adb, serial, root, recursive, filter)?;
+    files.retain(|f| !privacy_service::is_private(&f.path, hidden));
+    let folder_name = Path::new(root)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("device");
+    let base = local_dest.join(local_path_service::local_file_name(folder_name));
+
+    pull_files(
+        adb,
+        serial,
+        &files,
+        |file| {
+            let relative = file.path[root.len()..].trim_start_matches('/');
+            match relative.rsplit_once('/') {
+                Some((subdir, _)) => subdir.split('/').fold(base.clone(), |dir, part| {
+                    dir.join(local_path_service::local_file_name(part))
+                }),
+                None => base.clone(),
             }
-            Err(e) => {
-                results.push(MediaTransferResult {
-                    source_path: remote_path.clone(),
-                    dest_path: None,
-                    success: false,
-                    error: Some(e.to_string()),
-                    size_bytes,
+        },
+        conflict,
+        &|_| None,
+        progress,
+    )
+}
+
+/// Format of a `since` time without a timezone
+const SINCE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Narrow a folder listing (newest first) down to what a selection asks for.
+/// `pulled` is the device's pull history; it's only consulted for
+/// `since_last_pull`.
+pub fn select_media(
+    items: Vec<MediaItem>,
+    selection: &MediaSelection,
+    pulled: &HashMap<String, PulledFile>,
+) -> Result<Vec<MediaItem>, AppError> {
+    let mut items = items;
+
+    if let Some(since) = &selection.since {
+        let cutoff = since_cutoff(since)
+            .ok_or_else(|| AppError::TransferError(format!("Unrecognised time \"{}\"", since)))?;
+        items.retain(|i| i.date_taken.as_deref() >= Some(cutoff.as_str()));
+    }
+
+    if selection.since_last_pull {
+        let was_pulled = |item: &MediaItem| {
+            pulled
+                .get(&item.path)
+                .is_some_and(|p| p.size_bytes == item.size_bytes)
+        };
+        // Items from the same second as the last pulled one are kept unless
+        // pulled themselves
+        let last = items
+            .iter()
+            .filter(|i| was_pulled(i))
+            .filter_map(|i| i.date_taken.clone())
+            .max();
+        items.retain(|i| !was_pulled(i) && i.date_taken >= last);
+    }
+
+    if let Some(newest) = selection.newest {
+        items.truncate(newest);
+    }
+
+    Ok(items)
+}
+
+/// A `since` value as ISO-8601 UTC, comparable with `date_taken`. Times
+/// without a timezone are taken as the computer's local time.
+fn since_cutoff(since: &str) -> Option<String> {
+    let since = since.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(since) {
+        return Some(media_time_service::format_utc(
+            &time.with_timezone(&chrono::Utc),
+        ));
+    }
+    let local = chrono::NaiveDateTime::parse_from_str(since, SINCE_TIME_FORMAT)
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    let time = local.and_local_timezone(chrono::Local).earliest()?;
+    Some(media_time_service::format_utc(
+        &time.with_timezone(&chrono::Utc),
+    ))
+}
+
+fn pull_with_policy(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    file: &RemoteFile,
+    local_dest: &Path,
+    conflict: ConflictPolicy,
+    cached: Option<PathBuf>,
+) -> MediaTransferResult {
+    let (remote_path, size_bytes) = (file.path.as_str(), file.size_bytes);
+    let file_name = Path::new(remote_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown");
+    let local_name = local_path_service::local_file_name(file_name);
+    let renamed_from = (local_name != file_name).then(|| file_name.to_string());
+
+    let target = local_dest.join(&local_name);
+    let existing = target.exists().then(|| local_mtime(&target));
+    let source_mtime = if conflict == ConflictPolicy::KeepNewer && existing.is_some() {
+        get_file_mtime(adb, serial, remote_path)
+    } else {
+        None
+    };
+
+    let local_path = match conflict_service::resolve(
+        conflict,
+        &target.to_string_lossy(),
+        existing,
+        source_mtime,
+        |candidate| Path::new(candidate).exists(),
+    ) {
+        Resolution::Write(path) => PathBuf::from(path),
+        Resolution::Skip(reason) => {
+            return MediaTransferResult {
+                source_path: remote_path.to_string(),
+                dest_path: Some(target.to_string_lossy().to_string()),
+                success: false,
+                error: Some(reason),
+                size_bytes,
+                skipped: true,
+                motion_video_path: None,
+                renamed_from,
+            };
+        }
+    };
+
+    let copied = cached.is_some_and(|source| std::fs::copy(source, &local_path).is_ok());
+    let transferred = if copied {
+        Ok(local_path.to_string_lossy().to_string())
+    } else {
+        pull_to(adb, serial, remote_path, &local_path)
+    };
+
+    match transferred {
+        Ok(dest_path) => MediaTransferResult {
+            source_path: remote_path.to_string(),
+            dest_path: Some(dest_path),
+            success: true,
+            error: None,
+            size_bytes,
+            skipped: false,
+            motion_video_path: None,
+            renamed_from,
+        },
+        Err(e) => MediaTransferResult {
+            source_path: remote_path.to_string(),
+            dest_path: None,
+            success: false,
+            error: Some(e.to_string()),
+            size_bytes,
+            skipped: false,
+            motion_video_path: None,
+            renamed_from: None,
+        },
+    }
+}
+
+/// Delete media files from the device
+/// With `dry_run`, only sizes are looked up and nothing is removed
+pub fn delete_media_files(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    remote_paths: &[String],
+    dry_run: bool,
+) -> DestructiveOpReport {
+    let mut items = Vec::new();
+
+    for remote_path in remote_paths {
+        let quoted_path = quote_remote_path(remote_path);
+
+        // A failed stat means the file is gone or unreadable; never try to remove it
+        let size_bytes = match adb
+            .run_idempotent(&["-s", serial, "shell", "stat", "-c", "%s", &quoted_path])
+            .ok()
+            .and_then(|out| out.trim().parse::<u64>().ok())
+        {
+            Some(size) => size,
+            None => {
+                items.push(AffectedItem {
+                    path: remote_path.clone(),
+                    size_bytes: 0,
+                    error: Some("File not found on device".to_string()),
                 });
+                continue;
             }
-        }
+        };
+
+        let error = if dry_run {
+            None
+        } else {
+            match adb.run(&["-s", serial, "shell", "rm", "-f", &quoted_path]) {
+                Ok(_) => {
+                    // Drop the MediaStore row so galleries don't show a dead entry
+                    let where_clause = format!("_data='{}'", quote_sql_value(remote_path));
+                    let _ = adb.run(&[
+                        "-s",
+                        serial,
+                        "shell",
+                        "content",
+                        "delete",
+                        "--uri",
+                        "content://media/external/file",
+                        "--where",
+                        &quote_remote_path(&where_clause),
+                    ]);
+                    None
+                }
+                Err(e) => Some(e.to_string()),
+            }
+        };
+
+        items.push(AffectedItem {
+            path: remote_path.clone(),
+            size_bytes,
+            error,
+        });
     }
 
-    results
+    DestructiveOpReport::new(dry_run, items)
 }
 
 /// Get file size on device
-fn get_file_size(adb_path: &str, serial: &str, path: &str) -> Result<u64, AppError> {
-    let output = run_adb_command(adb_path, &["-s", serial, "shell", "stat", "-c", "%s", path])?;
+pub fn get_file_size(adb: &dyn AdbRunner, serial: &str, path: &str) -> Result<u64, AppError> {
+    let output = adb.run_idempotent(&["-s", serial, "shell", "stat", "-c", "%s", path])?;
 
     output.trim().parse().map_err(|_| {
         AppError::AdbExecutionFailed(format!("Failed to parse file size for {}", path))
     })
 }
 
-/// Generate thumbnail for a media file
-/// Returns base64-encoded thumbnail data
+/// Modification time of a device file, in seconds since the Unix epoch
+fn get_file_mtime(adb: &dyn AdbRunner, serial: &str, path: &str) -> Option<i64> {
+    adb.run_idempotent(&[
+        "-s",
+        serial,
+        "shell",
+        "stat",
+        "-c",
+        "%Y",
+        &quote_remote_path(path),
+    ])
+    .ok()
+    .and_then(|out| out.trim().parse().ok())
+}
+
+/// Size and modification time of a device file
+fn get_file_stat(adb: &dyn AdbRunner, serial: &str, path: &str) -> Option<(u64, i64)> {
+    let output = adb
+        .run_idempotent(&[
+            "-s",
+            serial,
+            "shell",
+            "stat",
+            "-c",
+            "'%s %Y'",
+            &quote_remote_path(path),
+        ])
+        .ok()?;
+    let (size, modified) = output.trim().split_once(' ')?;
+    Some((size.parse().ok()?, modified.parse().ok()?))
+}
+
 /// Generate thumbnail for a media file
 /// Returns base64-encoded thumbnail data
 pub fn get_thumbnail(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     ffmpeg_path: Option<&String>,
     serial: &str,
     remote_path: &str,
@@ -352,9 +814,16 @@ pub fn get_thumbnail(
         .and_then(|n| n.to_str())
         .ok_or_else(|| AppError::InvalidPath("Invalid remote path".to_string()))?;
 
-    // Create unique thumbnail filename to check cache
-    let thumb_name = format!("thumb_{}.jpg", sanitize_filename(file_name));
-    let thumb_path = temp_dir.join(&thumb_name);
+    // Cached by path, size and modification time, so an edited photo gets a
+    // new thumbnail. Without them the thumbnail is still the path's own.
+    let (size_bytes, modified) = get_file_stat(adb, serial, remote_path).unwrap_or((0, 0));
+    let thumb_path = thumbnail_cache_service::thumbnail_path(
+        temp_dir,
+        serial,
+        remote_path,
+        size_bytes,
+        modified,
+    );
 
     if thumb_path.exists() {
         // Return cached thumbnail (check size > 0)
@@ -395,17 +864,17 @@ pub fn get_thumbnail(
 
     let mut media_id = None;
     for path in &paths_to_try {
-        if let Some(id) = get_media_store_id(adb_path, serial, path, is_video) {
+        if let Some(id) = get_media_store_id(adb, serial, path, is_video) {
             media_id = Some(id);
             break;
         }
     }
 
     // If we found an ID, try to get the thumbnail
-    if let Some(id) = media_id {
+    if let Some(id) = &media_id {
         // Strategy A: Check if a generated thumbnail path exists in DB
-        if let Some(thumb_remote_path) = get_thumbnail_path(adb_path, serial, &id, is_video) {
-            let _ = pull_media_file(adb_path, serial, &thumb_remote_path, temp_dir);
+        if let Some(thumb_remote_path) = get_thumbnail_path(adb, serial, id, is_video) {
+            let _ = pull_to(adb, serial, &thumb_remote_path, &thumb_path);
         }
 
         // Verify Strategy A
@@ -415,45 +884,59 @@ pub fn get_thumbnail(
             }
         }
 
-        // Strategy B: Content Read
-        let thumb_uri = if is_video {
-            format!("content://media/external/video/media/{}/thumbnail", id)
-        } else {
-            format!("content://media/external/images/thumbnails/{}", id)
-        };
+        // Strategy B: Content Read (videos are made on the device below)
+        if !is_video {
+            let thumb_uri = format!("content://media/external/images/thumbnails/{}", id);
 
-        // Use a temp file on the DEVICE to capture the output
-        let device_temp = format!("/data/local/tmp/gesu_thumb_{}.jpg", id);
+            // Use a temp file on the DEVICE to capture the output
+            let device_temp = format!("/data/local/tmp/gesu_thumb_{}.jpg", id);
 
-        // Run content read > device_temp
-        let cmd = format!("content read --uri {} > {}", thumb_uri, device_temp);
-        let _ = run_adb_command(adb_path, &["-s", serial, "shell", &cmd]);
+            // Run content read > device_temp
+            let cmd = format!("content read --uri {} > {}", thumb_uri, device_temp);
+            let _ = adb.run(&["-s", serial, "shell", &cmd]);
 
-        // Now pull this temp file to our specific cache path
-        let local_path_str = thumb_path.to_string_lossy().to_string();
-        let pull_result = run_adb_command(
-            adb_path,
-            &["-s", serial, "pull", &device_temp, &local_path_str],
-        );
+            // Now pull this temp file to our specific cache path
+            let local_path_str = thumb_path.to_string_lossy().to_string();
+            let pull_result =
+                adb.run_transfer(&["-s", serial, "pull", &device_temp, &local_path_str]);
 
-        // Cleanup device temp
-        let _ = run_adb_command(adb_path, &["-s", serial, "shell", "rm", &device_temp]);
+            // Cleanup device temp
+            let _ = adb.run(&["-s", serial, "shell", "rm", &device_temp]);
 
-        if pull_result.is_ok() {
-            if let Ok(m) = std::fs::metadata(&thumb_path) {
-                if m.len() > 0 {
-                    return read_file_as_base64(&thumb_path);
+            if pull_result.is_ok() {
+                if let Ok(m) = std::fs::metadata(&thumb_path) {
+                    if m.len() > 0 {
+                        return read_file_as_base64(&thumb_path);
+                    }
                 }
             }
         }
     }
 
+    // Videos: grab the frame on the device and pull only the small JPEG
+    if is_video
+        && device_thumbnail_service::generate_video_thumbnail(
+            adb,
+            serial,
+            remote_path,
+            &thumb_path,
+            media_id.as_deref(),
+            || {
+                paths_to_try
+                    .iter()
+                    .find_map(|path| get_media_store_id(adb, serial, path, true))
+            },
+        )
+    {
+        return read_file_as_base64(&thumb_path);
+    }
+
     // Fallback: If original is an image, pull it and resize locally
     // This is expensive but guaranteed to work if file exists
     // Fallback: If original is an image, pull it and resize locally
     if is_image {
         // ... (existing image logic) ...
-        match pull_media_file(adb_path, serial, remote_path, temp_dir) {
+        match pull_media_file(adb, serial, remote_path, temp_dir) {
             Ok(pulled_path) => {
                 let pulled_path = Path::new(&pulled_path);
                 if let Ok(img) = image::open(pulled_path) {
@@ -468,17 +951,17 @@ pub fn get_thumbnail(
             Err(_) => {}
         }
     } else if is_video {
-        // Fallback: If original is a video, pull and use ffmpeg
+        // Last resort: pull the whole video and use ffmpeg here
         // Check if ffmpeg is available
         let ffmpeg_bin = ffmpeg_path.map(|s| s.as_str()).unwrap_or("ffmpeg");
 
-        match std::process::Command::new(ffmpeg_bin)
-            .arg("-version")
-            .output()
-        {
-            Ok(_) => {
+        let mut version_cmd = std::process::Command::new(ffmpeg_bin);
+        version_cmd.arg("-version");
+
+        match process_service::output_hidden(&mut version_cmd, TOOL_CHECK_TIMEOUT) {
+            Ok(Some(_)) => {
                 // Pull video to temp
-                match pull_media_file(adb_path, serial, remote_path, temp_dir) {
+                match pull_media_file(adb, serial, remote_path, temp_dir) {
                     Ok(pulled_video) => {
                         // Generate thumbnail using ffmpeg
                         // ffmpeg -i <video> -ss 00:00:01.000 -vframes 1 -vf scale=320:-1 <thumb>
@@ -497,17 +980,13 @@ pub fn get_thumbnail(
                             thumb_path.to_str().unwrap(),
                         ]);
 
-                        // Apply Windows-specific creation flags to hide console window
-                        #[cfg(target_os = "windows")]
-                        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-
-                        let status = cmd.status();
+                        let output = process_service::output_hidden(&mut cmd, FFMPEG_FRAME_TIMEOUT);
 
                         // Cleanup video
                         let _ = std::fs::remove_file(pulled_video);
 
-                        if let Ok(s) = status {
-                            if s.success() {
+                        if let Ok(Some(o)) = output {
+                            if o.status.success() {
                                 return read_file_as_base64(&thumb_path);
                             }
                         }
@@ -515,8 +994,8 @@ pub fn get_thumbnail(
                     Err(_) => {}
                 }
             }
-            Err(_) => {
-                // ffmpeg not found, ignore
+            _ => {
+                // ffmpeg not found or hung, ignore
             }
         }
     }
@@ -535,7 +1014,7 @@ fn quote_sql_value(value: &str) -> String {
 
 /// Get MediaStore ID for a file
 fn get_media_store_id(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     serial: &str,
     remote_path: &str,
     is_video: bool,
@@ -554,9 +1033,8 @@ fn get_media_store_id(
 
     // Query by display name instead of exact path
     // This avoids issues with /sdcard vs /storage/emulated/0
-    let output = run_adb_command(
-        adb_path,
-        &[
+    let output = adb
+        .run_idempotent(&[
             "-s",
             serial,
             "shell",
@@ -568,9 +1046,8 @@ fn get_media_store_id(
             "_id:_data",
             "--where",
             &format!("_display_name='{}'", sql_name),
-        ],
-    )
-    .ok()?;
+        ])
+        .ok()?;
 
     find_id_matching_path(&output, remote_path)
 }
@@ -622,7 +1099,7 @@ fn normalize_path(path: &str) -> String {
 
 /// Get thumbnail path from MediaStore ID
 fn get_thumbnail_path(
-    adb_path: &str,
+    adb: &dyn AdbRunner,
     serial: &str,
     media_id: &str,
     is_video: bool,
@@ -633,9 +1110,8 @@ fn get_thumbnail_path(
         ("content://media/external/images/thumbnails", "image_id")
     };
 
-    let output = run_adb_command(
-        adb_path,
-        &[
+    let output = adb
+        .run_idempotent(&[
             "-s",
             serial,
             "shell",
@@ -647,9 +1123,8 @@ fn get_thumbnail_path(
             "_data",
             "--where",
             &format!("{}={}", id_col, media_id),
-        ],
-    )
-    .ok()?;
+        ])
+        .ok()?;
 
     parse_data_path(&output)
 }
@@ -673,18 +1148,18 @@ fn parse_data_path(output: &str) -> Option<String> {
 
 /// Read file and encode as base64 data URL
 pub fn read_file_as_base64(path: &Path) -> Result<String, AppError> {
-    use base64::{engine::general_purpose::STANDARD, Engine as _};
-
     let data = std::fs::read(path)
         .map_err(|e| AppError::IoError(format!("Failed to read file: {}", e)))?;
 
-    let extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("jpg")
-        .to_lowercase();
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+    Ok(image_data_url(&data, extension))
+}
+
+/// Encode image bytes as a base64 data URL, with the MIME type from the extension
+pub fn image_data_url(data: &[u8], extension: &str) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
 
-    let mime_type = match extension.as_str() {
+    let mime_type = match extension.to_lowercase().as_str() {
         "jpg" | "jpeg" => "image/jpeg",
         "png" => "image/png",
         "gif" => "image/gif",
@@ -692,8 +1167,8 @@ pub fn read_file_as_base64(path: &Path) -> Result<String, AppError> {
         _ => "image/jpeg",
     };
 
-    let base64_data = STANDARD.encode(&data);
-    Ok(format!("data:{};base64,{}", mime_type, base64_data))
+    let base64_data = STANDARD.encode(data);
+    format!("data:{};base64,{}", mime_type, base64_data)
 }
 
 /// Sanitize filename for use in temp directory
@@ -712,6 +1187,199 @@ fn sanitize_filename(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parse_stat_listing() {
+        let output = "2048 /sdcard/DCIM/Camera/IMG_0001.jpg\r\n512 /sdcard/DCIM/.thumbnails/1.jpg\n100 /sdcard/DCIM/My Trip/clip 1.mp4\n";
+        assert_eq!(
+            parse_stat_listing(output, "/sdcard/DCIM"),
+            vec![
+                RemoteFile {
+                    path: "/sdcard/DCIM/Camera/IMG_0001.jpg".to_string(),
+                    size_bytes: 2048,
+                },
+                RemoteFile {
+                    path: "/sdcard/DCIM/My Trip/clip 1.mp4".to_string(),
+                    size_bytes: 100,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pull_files_copies_cached_preview() {
+        let adb = MockAdb::new().on("pull", "1 file pulled");
+        let dir = std::env::temp_dir().join(format!("gesu_cached_pull_{}", std::process::id()));
+        let preview = dir.join("preview.mp4");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&preview, b"video").unwrap();
+        let files = vec![
+            RemoteFile {
+                path: "/sdcard/DCIM/a.mp4".to_string(),
+                size_bytes: 5,
+            },
+            RemoteFile {
+                path: "/sdcard/DCIM/b.mp4".to_string(),
+                size_bytes: 9,
+            },
+        ];
+
+        let results = pull_files(
+            &adb,
+            "SER1",
+            &files,
+            |_| dir.join("out"),
+            ConflictPolicy::Overwrite,
+            &|file| (file.path == "/sdcard/DCIM/a.mp4").then(|| preview.clone()),
+            &|_| {},
+        )
+        .unwrap();
+        let copied = std::fs::read(dir.join("out").join("a.mp4")).ok();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(results.iter().all(|r| r.success));
+        assert_eq!(copied.as_deref(), Some(&b"video"[..]));
+        let pulls: Vec<String> = adb
+            .calls()
+            .into_iter()
+            .filter(|c| c.contains("pull"))
+            .collect();
+        assert_eq!(pulls.len(), 1);
+        assert!(pulls[0].contains("/sdcard/DCIM/b.mp4"));
+    }
+
+    #[test]
+    fn test_pull_folder_keeps_layout_and_filters() {
+        let adb = MockAdb::new()
+            .on(
+                "find",
+                "2048 /sdcard/DCIM/Camera/a.jpg\n100 /sdcard/DCIM/b.mp4\n10 /sdcard/DCIM/notes.txt\n",
+            )
+            .on("pull", "1 file pulled");
+        let dest = std::env::temp_dir().join("gesu_pull_folder_test");
+
+        let results = pull_folder(
+            &adb,
+            "SER1",
+            "/sdcard/DCIM/",
+            &dest,
+            true,
+            Some(MediaFilter::All),
+            &[],
+            ConflictPolicy::Overwrite,
+            &|_| {},
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.success));
+        assert_eq!(
+            results[0].dest_path.as_deref(),
+            Some(
+                dest.join("DCIM")
+                    .join("Camera")
+                    .join("a.jpg")
+                    .to_str()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            results[1].dest_path.as_deref(),
+            Some(dest.join("DCIM").join("b.mp4").to_str().unwrap())
+        );
+        assert!(adb.calls()[0].contains("find '/sdcard/DCIM'/ -type f"));
+        std::fs::remove_dir_all(&dest).ok();
+    }
+
+    #[test]
+    fn test_select_media() {
+        let item = |name: &str, date: &str| MediaItem {
+            path: format!("/sdcard/DCIM/Camera/{}", name),
+            name: name.to_string(),
+            media_type: MediaType::Image,
+            size_bytes: 100,
+            width: None,
+            height: None,
+            duration_ms: None,
+            date_taken: Some(date.to_string()),
+            timezone: None,
+            thumbnail_url: None,
+            motion_photo: false,
+        };
+        // Newest first, as list_media_files returns them
+        let items = vec![
+            item("d.jpg", "2024-02-03T09:00:00Z"),
+            item("c.jpg", "2024-02-01T12:00:00Z"),
+            item("b.jpg", "2024-02-01T12:00:00Z"),
+            item("a.jpg", "2024-01-20T08:00:00Z"),
+        ];
+        let names = |selected: Vec<MediaItem>| -> Vec<String> {
+            selected.into_iter().map(|i| i.name).collect()
+        };
+        let none = HashMap::new();
+
+        let newest = MediaSelection {
+            newest: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(select_media(items.clone(), &newest, &none).unwrap()),
+            vec!["d.jpg", "c.jpg"]
+        );
+
+        let since = MediaSelection {
+            since: Some("2024-02-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(select_media(items.clone(), &since, &none).unwrap()),
+            vec!["d.jpg", "c.jpg", "b.jpg"]
+        );
+        // 19:00 at UTC+7 is 12:00 UTC
+        let since_offset = MediaSelection {
+            since: Some("2024-02-01T19:00:01+07:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(select_media(items.clone(), &since_offset, &none).unwrap()),
+            vec!["d.jpg"]
+        );
+        let bad = MediaSelection {
+            since: Some("last tuesday".to_string()),
+            ..Default::default()
+        };
+        assert!(select_media(items.clone(), &bad, &none).is_err());
+
+        let pulled: HashMap<String, PulledFile> = [("b.jpg", 100), ("a.jpg", 100)]
+            .into_iter()
+            .map(|(name, size_bytes)| {
+                let remote_path = format!("/sdcard/DCIM/Camera/{}", name);
+                let file = PulledFile {
+                    remote_path: remote_path.clone(),
+                    size_bytes,
+                    sha256: None,
+                    local_path: format!("/tmp/{}", name),
+                    pulled_at: "2024-02-02T00:00:00Z".to_string(),
+                    local_exists: true,
+                };
+                (remote_path, file)
+            })
+            .collect();
+        let since_last_pull = MediaSelection {
+            since_last_pull: true,
+            ..Default::default()
+        };
+        // c.jpg shares b.jpg's time but was never pulled
+        assert_eq!(
+            names(select_media(items.clone(), &since_last_pull, &pulled).unwrap()),
+            vec!["d.jpg", "c.jpg"]
+        );
+        assert_eq!(
+            names(select_media(items, &since_last_pull, &none).unwrap()).len(),
+            4
+        );
+    }
 
     #[test]
     fn test_sanitize_filename() {
@@ -741,4 +1409,152 @@ mod tests {
         // Mismatch
         assert_eq!(find_id_matching_path(output, "/sdcard/Other/IMG.jpg"), None);
     }
+
+    #[test]
+    fn test_pull_media_file_names_by_device_and_folder() {
+        let temp = Path::new("/tmp/previews");
+        let camera = temp_pull_path(temp, "SER1", "/sdcard/DCIM/IMG_0001.jpg").unwrap();
+        let download = temp_pull_path(temp, "SER1", "/sdcard/Download/IMG_0001.jpg").unwrap();
+        let other_device = temp_pull_path(temp, "SER2", "/sdcard/DCIM/IMG_0001.jpg").unwrap();
+        assert_ne!(camera, download);
+        assert_ne!(camera, other_device);
+        assert_eq!(camera.extension().and_then(|e| e.to_str()), Some("jpg"));
+        assert!(camera
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.ends_with("_IMG_0001.jpg")));
+
+        let adb = MockAdb::new().on("pull", "1 file pulled\n");
+        let pulled = pull_media_file(&adb, "SER1", "/sdcard/Download/IMG_0001.jpg", temp).unwrap();
+        assert_eq!(Path::new(&pulled), download);
+        assert!(adb.calls()[0].ends_with(&*download.to_string_lossy()));
+        assert!(temp_pull_path(temp, "SER1", "/").is_err());
+    }
+
+    #[test]
+    fn test_parse_data_path_after_crlf_normalization() {
+        // adb on Windows ended this line with \r\n, which used to end up in the path
+        let output = crate::services::adb_service::normalize_output(
+            b"Row: 0 _data=/storage/emulated/0/DCIM/.thumbnails/123.jpg\r\n",
+        );
+        assert_eq!(
+            parse_data_path(&output).as_deref(),
+            Some("/storage/emulated/0/DCIM/.thumbnails/123.jpg")
+        );
+    }
+
+    #[test]
+    fn test_list_media_files_parses_and_filters() {
+        let adb = MockAdb::new()
+            .on(
+                "find",
+                "2048 1706610600 /sdcard/DCIM/IMG 001.jpg\n\
+9000 1706688900 /sdcard/DCIM//VID_002.mp4\n\
+10 1706688960 /sdcard/DCIM/notes.txt\n",
+            )
+            .on(
+                "content query",
+                "Row: 0 _data=/storage/emulated/0/DCIM/IMG 001.jpg, datetaken=1706777940000\n",
+            )
+            .on("date +%z", "+0700\r\n");
+
+        let items = list_media_files(&adb, "SER1", "/sdcard/DCIM", MediaFilter::All).unwrap();
+        assert_eq!(items.len(), 2);
+        // Newest first, with the EXIF time beating the file time
+        assert_eq!(items[0].path, "/sdcard/DCIM/IMG 001.jpg");
+        assert_eq!(items[0].size_bytes, 2048);
+        assert_eq!(items[0].date_taken.as_deref(), Some("2024-02-01T08:59:00Z"));
+        assert_eq!(items[0].timezone.as_deref(), Some("+07:00"));
+        assert_eq!(items[1].name, "VID_002.mp4");
+        assert_eq!(items[1].media_type, MediaType::Video);
+        assert_eq!(items[1].date_taken.as_deref(), Some("2024-01-31T08:15:00Z"));
+
+        let images = list_media_files(&adb, "SER1", "/sdcard/DCIM", MediaFilter::Images).unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].media_type, MediaType::Image);
+    }
+
+    #[test]
+    fn test_list_media_files_keeps_unicode_names() {
+        let adb = MockAdb::new().on(
+            "exec-out find",
+            "2048 1706610600 /sdcard/DCIM/🎉 誕生日\u{3000}パーティー.jpg\n\
+9000 1706688900 /sdcard/DCIM/Café  2 spaces.mp4\n",
+        );
+
+        let items = list_media_files(&adb, "SER1", "/sdcard/DCIM", MediaFilter::All).unwrap();
+        let names: Vec<_> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Café  2 spaces.mp4", "🎉 誕生日\u{3000}パーティー.jpg"]
+        );
+        assert_eq!(
+            items[1].path,
+            "/sdcard/DCIM/🎉 誕生日\u{3000}パーティー.jpg"
+        );
+        assert_eq!(items[1].size_bytes, 2048);
+    }
+
+    #[test]
+    fn test_split_ls_line() {
+        let line = "-rw-rw---- 1 u0_a1 u0_a1   512 2024-01-30 07:00  leading space.jpg";
+        let (fields, name) = split_ls_line(line, 7).unwrap();
+        assert_eq!(fields[4], "512");
+        assert_eq!(name, " leading space.jpg");
+        assert_eq!(split_ls_line("total 24", 7), None);
+    }
+
+    #[test]
+    fn test_list_folders_puts_media_folders_first() {
+        let adb = MockAdb::new().on(
+            "ls -la",
+            "total 16\n\
+drwxrwx--x 2 root sdcard_rw 4096 2024-01-29 09:00 Alarms\n\
+drwxrwx--x 2 root sdcard_rw 4096 2024-01-29 09:00 DCIM\n\
+drwxrwx--x 2 root sdcard_rw 4096 2024-01-29 09:00 .hidden\n\
+-rw-rw---- 1 root sdcard_rw 12 2024-01-29 09:00 file.txt\n",
+        );
+
+        let folders = list_folders(&adb, "SER1", Some("/sdcard")).unwrap();
+        let names: Vec<_> = folders.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["DCIM", "Alarms"]);
+        assert!(folders[0].is_media_folder);
+        assert_eq!(folders[0].path, "/sdcard/DCIM");
+    }
+
+    #[test]
+    fn test_delete_media_files_dry_run_does_not_remove() {
+        let adb = MockAdb::new()
+            .on("stat -c %s '/sdcard/DCIM/a.jpg'", "1000\n")
+            .on("stat -c %s '/sdcard/DCIM/b.mp4'", "2500\n")
+            .fail(
+                "stat -c %s '/sdcard/DCIM/gone.jpg'",
+                AppError::AdbExecutionFailed("No such file".to_string()),
+            );
+        let paths = vec![
+            "/sdcard/DCIM/a.jpg".to_string(),
+            "/sdcard/DCIM/b.mp4".to_string(),
+            "/sdcard/DCIM/gone.jpg".to_string(),
+        ];
+
+        let report = delete_media_files(&adb, "SER1", &paths, true);
+        assert!(report.dry_run);
+        assert_eq!(report.total_bytes, 3500);
+        assert!(report.items[2].error.is_some());
+        assert!(adb.calls().iter().all(|c| !c.contains("rm")));
+    }
+
+    #[test]
+    fn test_delete_media_files_removes() {
+        let adb = MockAdb::new()
+            .on("stat -c %s", "1000\n")
+            .on("rm -f '/sdcard/DCIM/a.jpg'", "")
+            .on("content delete", "");
+
+        let report = delete_media_files(&adb, "SER1", &["/sdcard/DCIM/a.jpg".to_string()], false);
+        assert!(!report.dry_run);
+        assert_eq!(report.total_bytes, 1000);
+        assert!(report.items[0].error.is_none());
+        assert!(adb.calls().iter().any(|c| c.contains("rm -f")));
+    }
 }
@@ -0,0 +1,146 @@
+//! When device media was taken, as UTC instants
+//!
+//! `ls` prints times in the device's local time and, depending on the
+//! toolbox, its locale. Listings therefore use epoch seconds from `stat`,
+//! and MediaStore's `datetaken` (epoch milliseconds, read from EXIF by the
+//! device) wins when it has one. Times go to the UI as ISO-8601 UTC, which
+//! also sorts correctly as text; the device's UTC offset is sent alongside
+//! so they can be shown in the device's time.
+
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use std::collections::HashMap;
+
+/// ISO-8601 UTC time (`2024-02-01T08:59:00Z`) of epoch seconds
+pub fn iso_utc(epoch_secs: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(epoch_secs, 0).map(|time| format_utc(&time))
+}
+
+/// ISO-8601 in UTC with whole seconds, the form `date_taken` uses
+pub fn format_utc(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// The device's current UTC offset as `+07:00`
+pub fn device_utc_offset(adb: &dyn AdbRunner, serial: &str) -> Option<String> {
+    let output = adb
+        .run_idempotent(&["-s", serial, "shell", "date", "+%z"])
+        .ok()?;
+    parse_utc_offset(&output).map(|offset| offset.to_string())
+}
+
+/// Parse `date +%z` output (`+0700`, `-0330`)
+pub fn parse_utc_offset(output: &str) -> Option<FixedOffset> {
+    let value = output.trim();
+    let (sign, digits) = match (value.strip_prefix('+'), value.strip_prefix('-')) {
+        (Some(digits), _) => (1, digits),
+        (_, Some(digits)) => (-1, digits),
+        _ => return None,
+    };
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// MediaStore `datetaken` of the media directly in `dir`, in epoch seconds,
+/// by path below the storage root (see [`storage_relative`]). Empty when
+/// MediaStore can't be queried; the file times are used then.
+pub fn date_taken_in(adb: &dyn AdbRunner, serial: &str, dir: &str) -> HashMap<String, i64> {
+    // Direct children only, not everything below the folder
+    let relative = storage_relative(dir).replace('\'', "''");
+    let pattern = format!(
+        "_data LIKE '%/{0}/%' AND _data NOT LIKE '%/{0}/%/%'",
+        relative
+    );
+    let command = format!(
+        "content query --uri content://media/external/file --projection _data:datetaken --where {}",
+        quote_remote_path(&pattern)
+    );
+    adb.run_idempotent(&["-s", serial, "exec-out", &command])
+        .map(|output| parse_date_taken(&output))
+        .unwrap_or_default()
+}
+
+/// Parse `content query` rows of `_data` and `datetaken`. Rows without a
+/// time (`datetaken=NULL`) are left out.
+///
+/// Example output:
+/// ```text
+/// Row: 0 _data=/storage/emulated/0/DCIM/Camera/IMG, 1.jpg, datetaken=1706777940000
+/// ```
+pub fn parse_date_taken(output: &str) -> HashMap<String, i64> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end_matches('\r');
+            // datetaken is projected last, so a `, ` in the name can't confuse it
+            let (data, millis) = line.rsplit_once(", datetaken=")?;
+            let (_, path) = data.split_once("_data=")?;
+            let millis: i64 = millis.trim().parse().ok()?;
+            Some((storage_relative(path), millis.div_euclid(1000)))
+        })
+        .collect()
+}
+
+/// A device path without its storage root, so `/sdcard/DCIM/a.jpg` and
+/// `/storage/emulated/0/DCIM/a.jpg` compare equal
+pub fn storage_relative(path: &str) -> String {
+    let path = path.trim_end_matches('/');
+    ["/sdcard", "/storage/emulated/0", "/storage/self/primary"]
+        .iter()
+        .find_map(|root| {
+            path.strip_prefix(root)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .unwrap_or(path)
+        .trim_start_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_iso_utc_and_offsets() {
+        assert_eq!(
+            iso_utc(1_706_777_940).as_deref(),
+            Some("2024-02-01T08:59:00Z")
+        );
+        assert_eq!(
+            parse_utc_offset("+0700\n").map(|o| o.local_minus_utc()),
+            Some(7 * 3600)
+        );
+        assert_eq!(
+            parse_utc_offset("-0330").map(|o| o.to_string()).as_deref(),
+            Some("-03:30")
+        );
+        assert_eq!(parse_utc_offset("WIB"), None);
+        assert_eq!(parse_utc_offset(""), None);
+    }
+
+    #[test]
+    fn test_date_taken_by_storage_path() {
+        let adb = MockAdb::new().on(
+            "content query",
+            "Row: 0 _data=/storage/emulated/0/DCIM/Camera/IMG, 1.jpg, datetaken=1706777940000\n\
+             Row: 1 _data=/storage/emulated/0/DCIM/Camera/clip.mp4, datetaken=NULL\n",
+        );
+
+        let taken = date_taken_in(&adb, "SER1", "/sdcard/DCIM/Camera");
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken.get("DCIM/Camera/IMG, 1.jpg"), Some(&1_706_777_940));
+        assert!(adb.calls()[0].contains("NOT LIKE '\\''%/DCIM/Camera/%/%'\\''"));
+        assert_eq!(storage_relative("/sdcard/DCIM/a.jpg"), "DCIM/a.jpg");
+        assert_eq!(storage_relative("/sdcard2/a.jpg"), "sdcard2/a.jpg");
+        assert_eq!(
+            storage_relative("/storage/1234-ABCD/DCIM"),
+            "storage/1234-ABCD/DCIM"
+        );
+    }
+}
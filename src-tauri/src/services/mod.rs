@@ -1,5 +1,73 @@
 pub mod adb_service;
+pub mod apk_service;
+pub mod app_icon_service;
+pub mod app_list_service;
+pub mod apple_device_service;
+pub mod archive_service;
+pub mod autotune_service;
+pub mod battery_service;
+pub mod bloatware_service;
+pub mod bluetooth_service;
+pub mod clipboard_service;
+pub mod clock_service;
+pub mod compare_service;
+pub mod confirmation_service;
+pub mod conflict_service;
+pub mod default_apps_service;
+pub mod destination_service;
+pub mod device_process_service;
+pub mod device_queue_service;
+pub mod device_settings_service;
+pub mod device_thumbnail_service;
+pub mod device_watch_service;
+pub mod drop_service;
+pub mod exif_service;
+pub mod group_service;
+pub mod hash_service;
+pub mod health_service;
+pub mod hotspot_service;
+pub mod install_service;
+pub mod kiosk_service;
+pub mod link_service;
+pub mod local_path_service;
 pub mod media_service;
+pub mod media_time_service;
+pub mod motion_photo_service;
+pub mod mtp_service;
+pub mod network_usage_service;
+pub mod notification_service;
+pub mod offload_service;
+pub mod onboarding_service;
+pub mod perf_service;
+pub mod photo_group_service;
+pub mod platform_integration_service;
+pub mod power_service;
+pub mod preview_cache_service;
+pub mod privacy_service;
+pub mod probe_service;
+pub mod process_service;
+pub mod progress_service;
+pub mod provisioning_service;
+pub mod pull_history_service;
+pub mod quick_share_service;
+pub mod read_only_service;
+pub mod recording_service;
+pub mod retry_service;
+pub mod scheduler_service;
 pub mod scrcpy_service;
+pub mod screenshot_service;
 pub mod settings_service;
+pub mod settings_watch_service;
+pub mod shell_integration_service;
+pub mod state_file_service;
+pub mod storage_service;
+pub mod sync_service;
+pub mod thumbnail_cache_service;
+pub mod thumbnail_warmup_service;
+pub mod timeline_service;
 pub mod transfer_service;
+pub mod troubleshoot_service;
+pub mod update_service;
+pub mod watch_service;
+pub mod wireless_service;
+pub mod zip_service;
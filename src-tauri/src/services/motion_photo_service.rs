@@ -0,0 +1,218 @@
+//! Motion photos: JPEGs with a short video appended
+//!
+//! Pixel phones mark them in XMP (`MicroVideoOffset`, or a `MotionPhoto`
+//! container item with the video's length); Samsung appends the video after a
+//! `MotionPhoto_Data` marker, followed by its own SEF trailer. Either way the
+//! video is a plain MP4 that can be cut out of the pulled file.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::MediaTransferResult;
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Samsung's marker in front of the embedded video
+const SAMSUNG_MARKER: &[u8] = b"MotionPhoto_Data";
+
+/// Start of Samsung's trailer after the video
+const SAMSUNG_TRAILER: &[u8] = b"SEFH";
+
+/// Files searched per adb call when detecting on the device
+const DETECT_CHUNK: usize = 50;
+
+/// Whether the file name says it's a motion photo (Pixel `*.MP.jpg`, older
+/// Google `MVIMG_*`). Samsung names them like any other photo.
+pub fn looks_like_motion_photo(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    upper.contains(".MP.") || upper.starts_with("MVIMG_")
+}
+
+/// Which of the device's JPEGs carry a motion photo marker. The search runs
+/// on the device, so only file names come back over adb.
+pub fn detect(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    paths: &[String],
+) -> Result<Vec<String>, AppError> {
+    let mut found = Vec::new();
+    for chunk in paths.chunks(DETECT_CHUNK) {
+        let quoted: Vec<String> = chunk.iter().map(|p| quote_remote_path(p)).collect();
+        // grep exits 1 when nothing matches
+        let command = format!(
+            "grep -l -e MotionPhoto -e MicroVideo {} 2>/dev/null; true",
+            quoted.join(" ")
+        );
+        let output = adb.run_idempotent(&["-s", serial, "shell", &command])?;
+        found.extend(
+            output
+                .lines()
+                .map(|line| line.trim_end_matches('\r'))
+                .filter(|line| chunk.iter().any(|p| p == line))
+                .map(str::to_string),
+        );
+    }
+    Ok(found)
+}
+
+/// Byte range of the embedded MP4, if the file is a motion photo
+pub fn video_range(data: &[u8]) -> Option<Range<usize>> {
+    let from_xmp = xmp_video_length(data).and_then(|len| data.len().checked_sub(len));
+    let from_marker = find(data, SAMSUNG_MARKER).map(|at| at + SAMSUNG_MARKER.len());
+
+    // Some Samsung files carry the XMP too, with the SEF trailer after the
+    // video; only trust an offset that lands on an MP4 header
+    let start = [from_xmp, from_marker]
+        .into_iter()
+        .flatten()
+        .find(|&start| data.get(start + 4..start + 8) == Some(b"ftyp"))?;
+    let end = rfind(data, SAMSUNG_TRAILER)
+        .filter(|&at| at > start)
+        .unwrap_or(data.len());
+    Some(start..end)
+}
+
+/// Write the video of a pulled motion photo next to it as `<name>.mp4`.
+/// `Ok(None)` if the file has no video.
+pub fn extract_video(photo: &Path) -> Result<Option<PathBuf>, AppError> {
+    let data = std::fs::read(photo)?;
+    let Some(range) = video_range(&data) else {
+        return Ok(None);
+    };
+    let video = photo.with_extension("mp4");
+    std::fs::write(&video, &data[range])?;
+    Ok(Some(video))
+}
+
+/// Extract the videos of pulled JPEGs and note them in the results. Best
+/// effort: a file that can't be read stays a plain photo.
+pub fn extract_pulled(results: &mut [MediaTransferResult]) {
+    for result in results.iter_mut().filter(|r| r.success && !r.skipped) {
+        let Some(dest) = result.dest_path.as_deref().map(PathBuf::from) else {
+            continue;
+        };
+        let is_jpeg = dest
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"));
+        if !is_jpeg {
+            continue;
+        }
+        // The photo itself was pulled; an unreadable video part is left in it
+        if let Ok(video) = extract_video(&dest) {
+            result.motion_video_path = video.map(|v| v.to_string_lossy().to_string());
+        }
+    }
+}
+
+/// Video length from Google's XMP, counted from the end of the file
+fn xmp_video_length(data: &[u8]) -> Option<usize> {
+    // Older format: offset of the video from the end of the file
+    if let Some(offset) = number_after(data, b"MicroVideoOffset=\"") {
+        return Some(offset);
+    }
+    // Current format: the length attribute of the MotionPhoto container item
+    let item = find(data, b"Semantic=\"MotionPhoto\"")?;
+    let open = rfind(&data[..item], b"<")?;
+    let close = item + find(&data[item..], b">")?;
+    number_after(&data[open..close], b"Length=\"")
+}
+
+fn number_after(data: &[u8], prefix: &[u8]) -> Option<usize> {
+    let at = find(data, prefix)? + prefix.len();
+    let digits = data[at..].iter().take_while(|b| b.is_ascii_digit()).count();
+    std::str::from_utf8(&data[at..at + digits])
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).rposition(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const JPEG: &[u8] = b"\xFF\xD8 jpeg data \xFF\xD9";
+    const MP4: &[u8] = b"\0\0\0\x18ftypmp42 video data";
+
+    #[test]
+    fn test_video_range_google_and_samsung() {
+        let old_google = [
+            JPEG,
+            format!("<x GCamera:MicroVideoOffset=\"{}\"/>", MP4.len()).as_bytes(),
+            MP4,
+        ]
+        .concat();
+        assert_eq!(&old_google[video_range(&old_google).unwrap()], MP4);
+
+        let item = format!(
+            "<Container:Item Item:Mime=\"video/mp4\" Item:Semantic=\"MotionPhoto\" Item:Length=\"{}\"/>",
+            MP4.len()
+        );
+        let new_google = [
+            b"<Container:Item Item:Semantic=\"Primary\" Item:Length=\"0\"/>".as_slice(),
+            item.as_bytes(),
+            JPEG,
+            MP4,
+        ]
+        .concat();
+        assert_eq!(&new_google[video_range(&new_google).unwrap()], MP4);
+
+        let samsung = [JPEG, SAMSUNG_MARKER, MP4, b"SEFH trailer SEFT"].concat();
+        assert_eq!(&samsung[video_range(&samsung).unwrap()], MP4);
+
+        assert_eq!(video_range(JPEG), None);
+        assert!(looks_like_motion_photo("PXL_20240201_085900.MP.jpg"));
+        assert!(!looks_like_motion_photo("PXL_20240201_085900.jpg"));
+    }
+
+    #[test]
+    fn test_extract_pulled_writes_video_next_to_photo() {
+        let dir = std::env::temp_dir().join("gesu-motion-photo-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let photo = dir.join("IMG_1.jpg");
+        std::fs::write(&photo, [JPEG, SAMSUNG_MARKER, MP4].concat()).unwrap();
+
+        let mut results = vec![MediaTransferResult {
+            source_path: "/sdcard/DCIM/Camera/IMG_1.jpg".to_string(),
+            dest_path: Some(photo.to_string_lossy().to_string()),
+            success: true,
+            error: None,
+            size_bytes: 0,
+            skipped: false,
+            motion_video_path: None,
+            renamed_from: None,
+        }];
+        extract_pulled(&mut results);
+
+        let video = dir.join("IMG_1.mp4");
+        assert_eq!(
+            results[0].motion_video_path.as_deref(),
+            Some(video.to_str().unwrap())
+        );
+        assert_eq!(std::fs::read(&video).unwrap(), MP4);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_detect_keeps_only_requested_paths() {
+        let adb = MockAdb::new().on("grep -l", "/sdcard/DCIM/Camera/a.jpg\r\ngrep: warning\n");
+        let paths = vec![
+            "/sdcard/DCIM/Camera/a.jpg".to_string(),
+            "/sdcard/DCIM/Camera/b.jpg".to_string(),
+        ];
+        assert_eq!(
+            detect(&adb, "SER1", &paths).unwrap(),
+            vec!["/sdcard/DCIM/Camera/a.jpg"]
+        );
+        assert!(adb.calls()[0].contains("'/sdcard/DCIM/Camera/b.jpg' 2>/dev/null; true"));
+    }
+}
@@ -0,0 +1,416 @@
+//! Read-only MTP fallback for devices without USB debugging
+//!
+//! A phone that is unauthorized or has USB debugging off still shows up in
+//! Windows as a portable device (WPD) when it's set to file transfer. The
+//! shell's "This PC" namespace exposes those devices, so they're browsed and
+//! copied from through `Shell.Application` in a fixed PowerShell script;
+//! device names and paths reach it as environment variables, never as part
+//! of the script. Paths are `<storage>/<folder>/...` as Explorer shows them,
+//! e.g. `Internal shared storage/DCIM/Camera`. Nothing is ever written to or
+//! deleted from the device.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{FolderInfo, MediaFilter, MediaItem, MediaTransferResult};
+use crate::services::{media_service, media_time_service, motion_photo_service};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Listing a folder with thousands of photos over MTP is slow
+const LIST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Copying one file
+const PULL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Folder in the destination that copies land in first; a file is moved out
+/// only once it's complete, so a cut-off copy never passes for a pulled file
+const PARTIAL_DIR: &str = ".gesu-partial";
+
+/// Shared start of the scripts: find the device in "This PC" (shell folder
+/// 17) and walk down `GESU_MTP_PATH` into `$folder`
+const NAVIGATE: &str = r#"
+$ErrorActionPreference = 'Stop'
+[Console]::OutputEncoding = [Text.Encoding]::UTF8
+function Get-EntryName($item) {
+    $name = $item.ExtendedProperty('System.FileName')
+    if ($name) { $name } else { $item.Name }
+}
+$shell = New-Object -ComObject Shell.Application
+$device = $shell.Namespace(17).Items() |
+    Where-Object { $_.IsFolder -and -not $_.IsFileSystem -and $_.Name -eq $env:GESU_MTP_DEVICE } |
+    Select-Object -First 1
+if (-not $device) { exit 2 }
+$folder = $device.GetFolder
+foreach ($segment in ($env:GESU_MTP_PATH -split '/' | Where-Object { $_ })) {
+    $next = $folder.Items() |
+        Where-Object { $_.IsFolder -and (Get-EntryName $_) -eq $segment } |
+        Select-Object -First 1
+    if (-not $next) { exit 3 }
+    $folder = $next.GetFolder
+}
+"#;
+
+/// List the entries of `$folder` as JSON
+const LIST: &str = r#"
+$entries = @($folder.Items() | ForEach-Object {
+    [pscustomobject]@{
+        name = Get-EntryName $_
+        folder = [bool]$_.IsFolder
+        size = [int64]$_.Size
+        modified = ([DateTimeOffset]$_.ModifyDate).ToUnixTimeSeconds()
+    }
+})
+ConvertTo-Json -InputObject $entries -Compress
+"#;
+
+/// Copy `GESU_MTP_NAME` from `$folder` into `GESU_MTP_DEST` and wait for the
+/// copy to be complete; the shell copies in the background
+const PULL: &str = r#"
+$item = $folder.Items() |
+    Where-Object { -not $_.IsFolder -and (Get-EntryName $_) -eq $env:GESU_MTP_NAME } |
+    Select-Object -First 1
+if (-not $item) { exit 3 }
+$target = Join-Path $env:GESU_MTP_DEST $env:GESU_MTP_NAME
+$size = [int64]$item.Size
+# No progress window, no questions, no error dialogs
+$shell.Namespace($env:GESU_MTP_DEST).CopyHere($item, 4 + 16 + 512 + 1024)
+$deadline = (Get-Date).AddSeconds(590)
+while (-not (Test-Path -LiteralPath $target) -or (Get-Item -LiteralPath $target).Length -lt $size) {
+    if ((Get-Date) -gt $deadline) { exit 4 }
+    Start-Sleep -Milliseconds 200
+}
+"#;
+
+/// Names of the portable devices in "This PC"
+const DEVICES: &str = r#"
+$ErrorActionPreference = 'Stop'
+[Console]::OutputEncoding = [Text.Encoding]::UTF8
+$shell = New-Object -ComObject Shell.Application
+$names = @($shell.Namespace(17).Items() |
+    Where-Object { $_.IsFolder -and -not $_.IsFileSystem } |
+    ForEach-Object { $_.Name })
+ConvertTo-Json -InputObject $names -Compress
+"#;
+
+/// An entry of a device folder as the list script reports it
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct MtpEntry {
+    pub name: String,
+    pub folder: bool,
+    pub size: u64,
+    /// Modification time, Unix seconds
+    pub modified: i64,
+}
+
+/// Clean up a device path: `/`-separated names without empty, `.` or `..`
+/// parts
+pub fn normalize_path(path: &str) -> Result<String, AppError> {
+    let segments: Vec<&str> = path.split(['/', '\\']).filter(|s| !s.is_empty()).collect();
+    if segments.iter().any(|s| *s == "." || *s == "..") {
+        return Err(AppError::InvalidPath(format!(
+            "Invalid device path: {}",
+            path
+        )));
+    }
+    Ok(segments.join("/"))
+}
+
+/// Path below the storage, e.g. `DCIM/Camera` for
+/// `Internal shared storage/DCIM/Camera`, for the private folder check
+pub fn storage_relative(path: &str) -> &str {
+    path.split_once('/').map_or("", |(_, below)| below)
+}
+
+/// Parse the list script's JSON
+pub fn parse_entries(json: &str) -> Result<Vec<MtpEntry>, AppError> {
+    serde_json::from_str(json.trim())
+        .map_err(|e| AppError::IoError(format!("Unexpected MTP listing: {}", e)))
+}
+
+fn child_path(base: &str, name: &str) -> String {
+    if base.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base, name)
+    }
+}
+
+/// Subfolders of `base`, media folders first like the adb browser
+pub fn to_folders(entries: &[MtpEntry], base: &str) -> Vec<FolderInfo> {
+    let mut folders: Vec<FolderInfo> = entries
+        .iter()
+        .filter(|e| e.folder && !e.name.starts_with('.'))
+        .map(|e| {
+            let path = child_path(base, &e.name);
+            FolderInfo {
+                is_media_folder: media_service::is_media_folder(&e.name, &path),
+                name: e.name.clone(),
+                path,
+                item_count: None,
+            }
+        })
+        .collect();
+    folders.sort_by(|a, b| {
+        b.is_media_folder
+            .cmp(&a.is_media_folder)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    folders
+}
+
+/// Images and videos in `base`, newest first. MTP doesn't give the capture
+/// time, so `date_taken` is the modification time.
+pub fn to_media(entries: &[MtpEntry], base: &str, filter: MediaFilter) -> Vec<MediaItem> {
+    let mut items: Vec<MediaItem> = entries
+        .iter()
+        .filter(|e| !e.folder)
+        .filter_map(|e| {
+            let media_type = media_service::media_type_of(&e.name)?;
+            filter.accepts(&media_type).then(|| MediaItem {
+                path: child_path(base, &e.name),
+                name: e.name.clone(),
+                media_type,
+                size_bytes: e.size,
+                width: None,
+                height: None,
+                duration_ms: None,
+                date_taken: media_time_service::iso_utc(e.modified),
+                timezone: None,
+                thumbnail_url: None,
+                motion_photo: motion_photo_service::looks_like_motion_photo(&e.name),
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| b.date_taken.cmp(&a.date_taken));
+    items
+}
+
+/// Names of the portable devices Windows sees
+pub fn list_devices() -> Result<Vec<String>, AppError> {
+    let output = run_script(DEVICES, &[], LIST_TIMEOUT)?;
+    serde_json::from_str(output.trim())
+        .map_err(|e| AppError::IoError(format!("Unexpected MTP device list: {}", e)))
+}
+
+/// Entries of a device folder; the empty path lists the device's storages
+fn list(device: &str, path: &str) -> Result<Vec<MtpEntry>, AppError> {
+    let script = format!("{}{}", NAVIGATE, LIST);
+    let output = run_script(
+        &script,
+        &[("GESU_MTP_DEVICE", device), ("GESU_MTP_PATH", path)],
+        LIST_TIMEOUT,
+    )?;
+    parse_entries(&output)
+}
+
+/// Folders in a device folder
+pub fn list_folders(device: &str, path: Option<&str>) -> Result<Vec<FolderInfo>, AppError> {
+    let base = normalize_path(path.unwrap_or(""))?;
+    Ok(to_folders(&list(device, &base)?, &base))
+}
+
+/// Images and videos in a device folder
+pub fn list_media(
+    device: &str,
+    path: &str,
+    filter: MediaFilter,
+) -> Result<Vec<MediaItem>, AppError> {
+    let base = normalize_path(path)?;
+    Ok(to_media(&list(device, &base)?, &base, filter))
+}
+
+/// Copy device files into `dest`. A file that exists there already is
+/// skipped; the shell can't copy under another name.
+pub fn pull_files(device: &str, paths: &[String], dest: &Path) -> Vec<MediaTransferResult> {
+    paths
+        .iter()
+        .map(|path| {
+            let mut result = MediaTransferResult {
+                source_path: path.clone(),
+                dest_path: None,
+                success: false,
+                error: None,
+                size_bytes: 0,
+                skipped: false,
+                motion_video_path: None,
+                renamed_from: None,
+            };
+            match pull_file(device, path, dest) {
+                Ok(Pulled::Copied(target, size)) => {
+                    result.success = true;
+                    result.dest_path = Some(target);
+                    result.size_bytes = size;
+                }
+                Ok(Pulled::Exists(target)) => {
+                    result.skipped = true;
+                    result.dest_path = Some(target);
+                    result.error = Some("A file with this name already exists".to_string());
+                }
+                Err(e) => result.error = Some(e.to_string()),
+            }
+            result
+        })
+        .collect()
+}
+
+enum Pulled {
+    Copied(String, u64),
+    Exists(String),
+}
+
+fn pull_file(device: &str, path: &str, dest: &Path) -> Result<Pulled, AppError> {
+    let path = normalize_path(path)?;
+    let (folder, name) = path
+        .rsplit_once('/')
+        .ok_or_else(|| AppError::InvalidPath(format!("Not a file on the device: {}", path)))?;
+    let target = dest.join(name);
+    let target_str = target.to_string_lossy().to_string();
+    if target.exists() {
+        return Ok(Pulled::Exists(target_str));
+    }
+
+    let staging = dest.join(PARTIAL_DIR);
+    std::fs::create_dir_all(&staging)?;
+    let partial = staging.join(name);
+    let _ = std::fs::remove_file(&partial);
+
+    let script = format!("{}{}", NAVIGATE, PULL);
+    let copied = run_script(
+        &script,
+        &[
+            ("GESU_MTP_DEVICE", device),
+            ("GESU_MTP_PATH", folder),
+            ("GESU_MTP_NAME", name),
+            ("GESU_MTP_DEST", &staging.to_string_lossy()),
+        ],
+        PULL_TIMEOUT,
+    )
+    .and_then(|_| Ok(std::fs::rename(&partial, &target)?));
+    if copied.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    let _ = std::fs::remove_dir(&staging);
+    copied?;
+
+    let size = std::fs::metadata(&target)?.len();
+    Ok(Pulled::Copied(target_str, size))
+}
+
+#[cfg(target_os = "windows")]
+fn run_script(script: &str, env: &[(&str, &str)], timeout: Duration) -> Result<String, AppError> {
+    use crate::services::process_service;
+    use std::process::Command;
+
+    // Exit codes of the scripts: device not connected, path not on the
+    // device, copy not finished in time
+    const EXIT_NO_DEVICE: i32 = 2;
+    const EXIT_NO_PATH: i32 = 3;
+    const EXIT_COPY_TIMEOUT: i32 = 4;
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+    let output = process_service::output_hidden(&mut cmd, timeout)?.ok_or_else(|| {
+        AppError::Timeout("The device didn't answer over MTP in time".to_string())
+    })?;
+
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        Some(EXIT_NO_DEVICE) => Err(AppError::DeviceNotFound(
+            "Device not found. Unlock it and choose File transfer in its USB options.".to_string(),
+        )),
+        Some(EXIT_NO_PATH) => Err(AppError::InvalidPath("Not found on the device".to_string())),
+        Some(EXIT_COPY_TIMEOUT) => Err(AppError::Timeout(
+            "Copying from the device took too long".to_string(),
+        )),
+        _ => Err(AppError::IoError(format!(
+            "MTP access failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run_script(
+    _script: &str,
+    _env: &[(&str, &str)],
+    _timeout: Duration,
+) -> Result<String, AppError> {
+    Err(AppError::IoError(
+        "Browsing without USB debugging (MTP) is only available on Windows".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::MediaType;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path("/Internal shared storage//DCIM/").unwrap(),
+            "Internal shared storage/DCIM"
+        );
+        assert_eq!(
+            normalize_path("Internal shared storage\\DCIM").unwrap(),
+            "Internal shared storage/DCIM"
+        );
+        assert_eq!(normalize_path("").unwrap(), "");
+        assert!(normalize_path("Internal shared storage/../x").is_err());
+        assert_eq!(
+            storage_relative("Internal shared storage/DCIM/Camera"),
+            "DCIM/Camera"
+        );
+        assert_eq!(storage_relative("Internal shared storage"), "");
+    }
+
+    #[test]
+    fn test_listing() {
+        let entries = parse_entries(
+            r#"[{"name":"DCIM","folder":true,"size":0,"modified":1706778000},
+                {"name":"Alarms","folder":true,"size":0,"modified":1706778000},
+                {"name":".thumbnails","folder":true,"size":0,"modified":1706778000},
+                {"name":"IMG_0001.jpg","folder":false,"size":2048,"modified":1706778000},
+                {"name":"PXL_0002.MP.jpg","folder":false,"size":4096,"modified":1706781600},
+                {"name":"notes.txt","folder":false,"size":12,"modified":1706778000}]"#,
+        )
+        .unwrap();
+
+        let folders = to_folders(&entries, "Internal shared storage");
+        assert_eq!(folders.len(), 2);
+        assert_eq!(folders[0].name, "DCIM");
+        assert!(folders[0].is_media_folder);
+        assert!(!folders[1].is_media_folder);
+        assert_eq!(folders[0].path, "Internal shared storage/DCIM");
+
+        let media = to_media(&entries, "Internal shared storage/DCIM", MediaFilter::All);
+        assert_eq!(media.len(), 2);
+        assert_eq!(media[0].name, "PXL_0002.MP.jpg");
+        assert!(media[0].motion_photo);
+        assert_eq!(media[1].date_taken.as_deref(), Some("2024-02-01T09:00:00Z"));
+        assert_eq!(media[1].media_type, MediaType::Image);
+        assert!(to_media(&entries, "x", MediaFilter::Videos).is_empty());
+        assert!(parse_entries("not json").is_err());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_failed_pull_leaves_nothing_behind() {
+        let dest = std::env::temp_dir().join(format!("gesu_mtp_pull_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dest);
+        std::fs::create_dir_all(&dest).unwrap();
+
+        let results = pull_files(
+            "Pixel 7",
+            &["Internal shared storage/DCIM/Camera/IMG_0001.jpg".to_string()],
+            &dest,
+        );
+        assert!(!results[0].success);
+        assert!(!results[0].skipped);
+        assert_eq!(std::fs::read_dir(&dest).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+}
@@ -0,0 +1,287 @@
+//! Per-app network traffic from `dumpsys netstats`, exportable as CSV
+
+use crate::domain::errors::AppError;
+use crate::domain::models::NetworkUsage;
+use crate::services::adb_service::AdbRunner;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Traffic per app, heaviest first
+pub fn get_network_usage(adb: &dyn AdbRunner, serial: &str) -> Result<Vec<NetworkUsage>, AppError> {
+    let stats = adb.run_idempotent(&["-s", serial, "shell", "dumpsys", "netstats", "detail"])?;
+    let packages = adb.run_idempotent(&["-s", serial, "shell", "pm", "list", "packages", "-U"])?;
+    Ok(build_usage(
+        parse_uid_stats(&stats),
+        &parse_package_uids(&packages),
+    ))
+}
+
+/// Bytes counted for one uid: `[rx, tx, mobile rx, mobile tx]`
+pub type UidTotals = [u64; 4];
+
+/// Sum the `Uid stats:` section of `dumpsys netstats detail` per uid
+///
+/// Example section:
+/// ```text
+/// Uid stats:
+///   Pending bytes: 1044
+///   Complete history:
+///   ident=[{type=MOBILE, ratType=COMBINED, metered=true, defaultNetwork=true}] uid=10123 set=DEFAULT tag=0x0
+///     NetworkStatsHistory: bucketDuration=7200
+///       st=1697000400 rb=52144 rp=60 tb=8120 tp=41 op=0
+/// Uid tag stats:
+/// ```
+/// Tagged entries (`Uid tag stats:`, `tag` other than `0x0`) are subsets of the
+/// untagged ones and are skipped so nothing is counted twice.
+pub fn parse_uid_stats(output: &str) -> BTreeMap<i32, UidTotals> {
+    let mut totals: BTreeMap<i32, UidTotals> = BTreeMap::new();
+    let mut in_section = false;
+    // Uid and network type of the entry whose buckets follow
+    let mut current: Option<(i32, bool)> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(' ') && trimmed.ends_with(':') {
+            in_section = trimmed.eq_ignore_ascii_case("uid stats:");
+            current = None;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        if trimmed.starts_with("ident=") {
+            current = parse_ident(trimmed);
+        } else if trimmed.starts_with("st=") {
+            let Some((uid, mobile)) = current else {
+                continue;
+            };
+            let rx = bucket_field(trimmed, "rb=");
+            let tx = bucket_field(trimmed, "tb=");
+            let entry = totals.entry(uid).or_default();
+            entry[0] += rx;
+            entry[1] += tx;
+            if mobile {
+                entry[2] += rx;
+                entry[3] += tx;
+            }
+        }
+    }
+
+    totals
+}
+
+/// Uid and whether it's mobile traffic, for untagged entries only
+fn parse_ident(line: &str) -> Option<(i32, bool)> {
+    let field = |name: &str| {
+        line.split_whitespace()
+            .find_map(|part| part.strip_prefix(name))
+            .map(|value| value.trim_end_matches(','))
+    };
+    if field("tag=")? != "0x0" {
+        return None;
+    }
+    let uid = field("uid=")?.parse().ok()?;
+
+    // Older releases print the ConnectivityManager constant, 0 being mobile
+    let network_type = line.split_once("type=")?.1;
+    let mobile = network_type.starts_with("MOBILE") || network_type.starts_with("0,");
+    Some((uid, mobile))
+}
+
+fn bucket_field(line: &str, name: &str) -> u64 {
+    line.split_whitespace()
+        .find_map(|part| part.strip_prefix(name))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Packages per uid from `pm list packages -U`
+///
+/// Example output:
+/// ```text
+/// package:com.android.chrome uid:10123
+/// package:com.android.settings uid:1000
+/// ```
+pub fn parse_package_uids(output: &str) -> HashMap<i32, Vec<String>> {
+    let mut uids: HashMap<i32, Vec<String>> = HashMap::new();
+    for line in output.lines() {
+        let Some((package, uid)) = line
+            .trim()
+            .strip_prefix("package:")
+            .and_then(|rest| rest.split_once(" uid:"))
+        else {
+            continue;
+        };
+        // Shared uids are listed comma-separated on some releases
+        for uid in uid.split(',').filter_map(|u| u.trim().parse().ok()) {
+            uids.entry(uid).or_default().push(package.to_string());
+        }
+    }
+    for packages in uids.values_mut() {
+        packages.sort();
+    }
+    uids
+}
+
+/// Attach package names to uid totals, heaviest users first
+pub fn build_usage(
+    totals: BTreeMap<i32, UidTotals>,
+    packages: &HashMap<i32, Vec<String>>,
+) -> Vec<NetworkUsage> {
+    let mut usage: Vec<NetworkUsage> = totals
+        .into_iter()
+        .map(
+            |(uid, [rx_bytes, tx_bytes, mobile_rx_bytes, mobile_tx_bytes])| {
+                let packages = packages.get(&uid).cloned().unwrap_or_default();
+                let label = if packages.is_empty() {
+                    uid_label(uid)
+                } else {
+                    packages.join(", ")
+                };
+                NetworkUsage {
+                    uid,
+                    packages,
+                    label,
+                    rx_bytes,
+                    tx_bytes,
+                    mobile_rx_bytes,
+                    mobile_tx_bytes,
+                }
+            },
+        )
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.rx_bytes + u.tx_bytes));
+    usage
+}
+
+/// Name of a uid no installed package owns
+fn uid_label(uid: i32) -> String {
+    match uid {
+        -5 => "Tethering".to_string(),
+        -4 => "Removed apps".to_string(),
+        0 => "root".to_string(),
+        1000 => "system".to_string(),
+        1013 => "media".to_string(),
+        1051 => "DNS resolver".to_string(),
+        _ => format!("uid {}", uid),
+    }
+}
+
+/// Write usage as CSV, one row per uid
+pub fn export_csv(usage: &[NetworkUsage], path: &Path) -> Result<(), AppError> {
+    std::fs::write(path, to_csv(usage))?;
+    Ok(())
+}
+
+/// CSV with a header row; packages sharing a uid are separated by `;`
+pub fn to_csv(usage: &[NetworkUsage]) -> String {
+    let mut csv =
+        String::from("uid,label,packages,rx_bytes,tx_bytes,mobile_rx_bytes,mobile_tx_bytes\n");
+    for u in usage {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            u.uid,
+            csv_field(&u.label),
+            csv_field(&u.packages.join(";")),
+            u.rx_bytes,
+            u.tx_bytes,
+            u.mobile_rx_bytes,
+            u.mobile_tx_bytes
+        ));
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const NETSTATS: &str = "\
+Xt stats:
+  Pending bytes: 0
+  ident=[{type=WIFI, ratType=COMBINED, metered=false}] uid=-1 set=ALL tag=0x0
+    NetworkStatsHistory: bucketDuration=3600
+      st=1697000400 rb=999999 rp=1 tb=999999 tp=1 op=0
+Uid stats:
+  Pending bytes: 1044
+  Complete history:
+  ident=[{type=MOBILE, ratType=COMBINED, metered=true, defaultNetwork=true}] uid=10123 set=DEFAULT tag=0x0
+    NetworkStatsHistory: bucketDuration=7200
+      st=1697000400 rb=50000 rp=60 tb=8000 tp=41 op=0
+      st=1697007600 rb=2000 rp=3 tb=120 tp=1 op=0
+  ident=[{type=WIFI, ratType=COMBINED, networkId=\"home\", metered=false}] uid=10123 set=FOREGROUND tag=0x0
+    NetworkStatsHistory: bucketDuration=7200
+      st=1697000400 rb=300000 rp=200 tb=40000 tp=90 op=0
+  ident=[{type=WIFI, ratType=COMBINED, metered=false}] uid=1000 set=DEFAULT tag=0x0
+    NetworkStatsHistory: bucketDuration=7200
+      st=1697000400 rb=700 rp=5 tb=300 tp=4 op=0
+  ident=[{type=MOBILE, ratType=COMBINED, metered=true}] uid=10123 set=DEFAULT tag=0xffffff01
+    NetworkStatsHistory: bucketDuration=7200
+      st=1697000400 rb=40000 rp=50 tb=7000 tp=30 op=0
+Uid tag stats:
+  ident=[{type=MOBILE, ratType=COMBINED, metered=true}] uid=10123 set=DEFAULT tag=0x0
+    NetworkStatsHistory: bucketDuration=7200
+      st=1697000400 rb=77777 rp=50 tb=7000 tp=30 op=0
+";
+
+    #[test]
+    fn test_parse_uid_stats() {
+        let totals = parse_uid_stats(NETSTATS);
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[&10123], [352_000, 48_120, 52_000, 8_120]);
+        assert_eq!(totals[&1000], [700, 300, 0, 0]);
+    }
+
+    #[test]
+    fn test_parse_package_uids() {
+        let uids = parse_package_uids(
+            "package:com.android.chrome uid:10123\npackage:com.android.settings uid:1000\npackage:com.android.providers.settings uid:1000\n",
+        );
+        assert_eq!(uids[&10123], vec!["com.android.chrome"]);
+        assert_eq!(
+            uids[&1000],
+            vec!["com.android.providers.settings", "com.android.settings"]
+        );
+    }
+
+    #[test]
+    fn test_get_network_usage() {
+        let adb = MockAdb::new()
+            .on("netstats", NETSTATS)
+            .on("list packages -U", "package:com.android.chrome uid:10123\n");
+
+        let usage = get_network_usage(&adb, "SER1").unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].label, "com.android.chrome");
+        assert_eq!(usage[0].mobile_rx_bytes, 52_000);
+        assert_eq!(usage[1].label, "system");
+        assert!(usage[1].packages.is_empty());
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let usage = vec![NetworkUsage {
+            uid: 1000,
+            packages: vec!["android".to_string(), "com.android.settings".to_string()],
+            label: "android, com.android.settings".to_string(),
+            rx_bytes: 700,
+            tx_bytes: 300,
+            mobile_rx_bytes: 0,
+            mobile_tx_bytes: 0,
+        }];
+        assert_eq!(
+            to_csv(&usage),
+            "uid,label,packages,rx_bytes,tx_bytes,mobile_rx_bytes,mobile_tx_bytes\n1000,\"android, com.android.settings\",android;com.android.settings,700,300,0,0\n"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! Notifications posted on the device itself, so whoever holds the phone
+//! can see what the bridge is doing with it
+
+use crate::domain::errors::AppError;
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+
+/// Tag shared by all bridge notifications; a new one replaces the previous
+const NOTIFICATION_TAG: &str = "gesu-bridge";
+
+const NOTIFICATION_TITLE: &str = "Gesu Bridge";
+
+/// Shown when a mirror session starts controlling the device
+pub const MIRROR_STARTED_TEXT: &str = "This phone is being mirrored and controlled from a computer";
+
+/// Post a notification from the shell (`cmd notification`, Android 9+)
+pub fn post_notification(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    text: &str,
+) -> Result<String, AppError> {
+    let title = quote_remote_path(NOTIFICATION_TITLE);
+    let text = quote_remote_path(text);
+    adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "cmd",
+        "notification",
+        "post",
+        "-t",
+        &title,
+        NOTIFICATION_TAG,
+        &text,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_post_notification_quotes_text() {
+        let adb = MockAdb::new().on("cmd notification post", "");
+        post_notification(&adb, "SER1", "It's on; rm -rf /").unwrap();
+
+        assert_eq!(
+            adb.calls(),
+            vec![
+                "-s SER1 shell cmd notification post -t 'Gesu Bridge' gesu-bridge 'It'\\''s on; rm -rf /'"
+            ]
+        );
+    }
+}
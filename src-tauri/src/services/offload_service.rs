@@ -0,0 +1,251 @@
+//! Camera offload planning
+//!
+//! Scans the DCIM folder of every storage volume, groups the media by month
+//! and works out which files are already on this computer, so the UI can walk
+//! the user through pulling the rest. Nothing is pulled here.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{CameraOffloadPlan, OffloadMonth, OffloadVolume};
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::{media_type_of, quote_remote_path, visible_relative};
+use crate::services::privacy_service;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Internal shared storage
+const INTERNAL_VOLUME: &str = "/storage/emulated/0";
+
+/// A camera file with its modification time
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraFile {
+    pub path: String,
+    pub size_bytes: u64,
+    /// `YYYY-MM-DD HH:MM:SS`, by the device's clock
+    pub modified: String,
+}
+
+impl CameraFile {
+    /// `YYYY-MM` of the modification time
+    pub fn month(&self) -> &str {
+        self.modified.get(..7).unwrap_or("unknown")
+    }
+}
+
+/// Internal storage followed by any SD cards or USB drives
+pub fn storage_volumes(adb: &dyn AdbRunner, serial: &str) -> Vec<String> {
+    let mut volumes = vec![INTERNAL_VOLUME.to_string()];
+    if let Ok(output) = adb.run_idempotent(&["-s", serial, "shell", "ls", "/storage/"]) {
+        volumes.extend(
+            output
+                .lines()
+                .map(str::trim)
+                .filter(|name| !name.is_empty() && !name.contains("emulated") && *name != "self")
+                .map(|name| format!("/storage/{}", name)),
+        );
+    }
+    volumes
+}
+
+/// Media files below a volume's DCIM folder; none if it has no DCIM
+pub fn list_camera_files(adb: &dyn AdbRunner, serial: &str, dcim_path: &str) -> Vec<CameraFile> {
+    let command = format!(
+        "find {}/ -type f -exec stat -c '%y %s %n' {{}} +",
+        quote_remote_path(dcim_path)
+    );
+    adb.run_idempotent(&["-s", serial, "shell", &command])
+        .map(|output| parse_dated_listing(&output, dcim_path))
+        .unwrap_or_default()
+}
+
+/// Parse `stat -c '%y %s %n'` lines below `root`, keeping visible media files
+///
+/// Example output:
+/// ```text
+/// 2024-01-30 10:30:05.123456789 +0700 3145728 /sdcard/DCIM/Camera/IMG_0001.jpg
+/// ```
+pub fn parse_dated_listing(output: &str, root: &str) -> Vec<CameraFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_end_matches('\r').splitn(5, ' ');
+            let (date, time, _zone, size, path) = (
+                parts.next()?,
+                parts.next()?,
+                parts.next()?,
+                parts.next()?,
+                parts.next()?,
+            );
+            visible_relative(path, root)?;
+            media_type_of(path)?;
+            Some(CameraFile {
+                path: path.to_string(),
+                size_bytes: size.parse().ok()?,
+                modified: format!("{} {}", date, time.split('.').next().unwrap_or(time)),
+            })
+        })
+        .collect()
+}
+
+/// Whether `dir` holds a file with the device file's name and size, as a
+/// flat pull into that folder leaves it
+pub fn copy_in_folder(dir: &Path, remote_path: &str, size_bytes: u64) -> bool {
+    Path::new(remote_path)
+        .file_name()
+        .and_then(|name| std::fs::metadata(dir.join(name)).ok())
+        .is_some_and(|meta| meta.len() == size_bytes)
+}
+
+/// Group scanned volumes into a plan; `backed_up` says whether a file is
+/// already on this computer
+pub fn build_plan(
+    serial: &str,
+    scanned: Vec<(String, Vec<CameraFile>)>,
+    backed_up: impl Fn(&CameraFile) -> bool,
+) -> CameraOffloadPlan {
+    let mut volumes = Vec::new();
+    let mut months: BTreeMap<String, (OffloadMonth, Vec<CameraFile>)> = BTreeMap::new();
+
+    for (root, files) in scanned {
+        if files.is_empty() {
+            continue;
+        }
+        volumes.push(OffloadVolume {
+            dcim_path: format!("{}/DCIM", root),
+            root,
+            file_count: files.len(),
+            total_bytes: files.iter().map(|f| f.size_bytes).sum(),
+        });
+
+        for file in files {
+            let (month, pending) = months.entry(file.month().to_string()).or_insert_with(|| {
+                (
+                    OffloadMonth {
+                        month: file.month().to_string(),
+                        file_count: 0,
+                        total_bytes: 0,
+                        backed_up_count: 0,
+                        backed_up_bytes: 0,
+                        pending_paths: Vec::new(),
+                    },
+                    Vec::new(),
+                )
+            });
+            month.file_count += 1;
+            month.total_bytes += file.size_bytes;
+            if backed_up(&file) {
+                month.backed_up_count += 1;
+                month.backed_up_bytes += file.size_bytes;
+            } else {
+                pending.push(file);
+            }
+        }
+    }
+
+    let months: Vec<OffloadMonth> = months
+        .into_values()
+        .rev()
+        .map(|(mut month, mut pending)| {
+            pending.sort_by(|a, b| b.modified.cmp(&a.modified));
+            month.pending_paths = pending.into_iter().map(|f| f.path).collect();
+            month
+        })
+        .collect();
+
+    let total_count = months.iter().map(|m| m.file_count).sum();
+    let total_bytes = months.iter().map(|m| m.total_bytes).sum();
+    let pending_count = months.iter().map(|m| m.pending_paths.len()).sum();
+    let pending_bytes = months
+        .iter()
+        .map(|m| m.total_bytes - m.backed_up_bytes)
+        .sum();
+
+    CameraOffloadPlan {
+        device_serial: serial.to_string(),
+        volumes,
+        months,
+        total_count,
+        total_bytes,
+        pending_count,
+        pending_bytes,
+    }
+}
+
+/// Scan DCIM on every volume and plan an offload, leaving out files in the
+/// `hidden` private folders
+pub fn plan_camera_offload(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    hidden: &[String],
+    backed_up: impl Fn(&CameraFile) -> bool,
+) -> Result<CameraOffloadPlan, AppError> {
+    let scanned = storage_volumes(adb, serial)
+        .into_iter()
+        .map(|root| {
+            let mut files = list_camera_files(adb, serial, &format!("{}/DCIM", root));
+            files.retain(|f| !privacy_service::is_private(&f.path, hidden));
+            (root, files)
+        })
+        .collect();
+    Ok(build_plan(serial, scanned, backed_up))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_parse_dated_listing() {
+        let output = "2024-01-30 10:30:05.123456789 +0700 2048 /storage/emulated/0/DCIM/Camera/IMG 1.jpg\r\n\
+            2024-01-30 10:31:00.000000000 +0700 512 /storage/emulated/0/DCIM/.thumbnails/1.jpg\n\
+            2024-01-30 10:32:00.000000000 +0700 9 /storage/emulated/0/DCIM/Camera/notes.txt\n";
+        assert_eq!(
+            parse_dated_listing(output, "/storage/emulated/0/DCIM"),
+            vec![CameraFile {
+                path: "/storage/emulated/0/DCIM/Camera/IMG 1.jpg".to_string(),
+                size_bytes: 2048,
+                modified: "2024-01-30 10:30:05".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_groups_by_month_across_volumes() {
+        let adb = MockAdb::new()
+            .on("ls /storage/", "emulated\nself\n1234-ABCD\n")
+            .on(
+                "'/storage/emulated/0/DCIM'",
+                "2024-01-30 10:30:05.0 +0700 100 /storage/emulated/0/DCIM/Camera/a.jpg\n\
+                 2024-02-02 08:00:00.0 +0700 300 /storage/emulated/0/DCIM/Camera/b.mp4\n",
+            )
+            .on(
+                "'/storage/1234-ABCD/DCIM'",
+                "2024-02-01 12:00:00.0 +0700 200 /storage/1234-ABCD/DCIM/Camera/c.jpg\n",
+            );
+
+        let plan = plan_camera_offload(&adb, "SER1", &[], |f| f.path.ends_with("a.jpg")).unwrap();
+
+        assert_eq!(plan.volumes.len(), 2);
+        assert_eq!(plan.volumes[1].dcim_path, "/storage/1234-ABCD/DCIM");
+        assert_eq!(plan.total_count, 3);
+        assert_eq!(plan.total_bytes, 600);
+        assert_eq!(plan.pending_count, 2);
+        assert_eq!(plan.pending_bytes, 500);
+
+        let months: Vec<_> = plan.months.iter().map(|m| m.month.as_str()).collect();
+        assert_eq!(months, vec!["2024-02", "2024-01"]);
+        assert_eq!(
+            plan.months[0].pending_paths,
+            vec![
+                "/storage/emulated/0/DCIM/Camera/b.mp4",
+                "/storage/1234-ABCD/DCIM/Camera/c.jpg"
+            ]
+        );
+        assert_eq!(plan.months[1].backed_up_count, 1);
+        assert!(plan.months[1].pending_paths.is_empty());
+
+        let hidden = vec!["DCIM/Camera".to_string()];
+        let plan = plan_camera_offload(&adb, "SER1", &hidden, |_| false).unwrap();
+        assert_eq!(plan.total_count, 0);
+    }
+}
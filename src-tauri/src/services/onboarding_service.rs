@@ -0,0 +1,138 @@
+//! First-run setup steps, each backed by a real check
+//!
+//! The steps are: adb found and working, a device authorized (listed as
+//! ready, which only happens once the phone accepted this computer's key)
+//! and a first transfer that copied files. A step is completed once its check
+//! passes and stays done after that, remembered in the settings.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    DeviceEventKind, OnboardingState, OnboardingStep, OnboardingStepStatus,
+};
+use crate::services::adb_service::{self, AdbCli};
+use crate::services::{settings_service, timeline_service};
+use tauri::AppHandle;
+
+/// The steps in the order the wizard walks through them
+pub const STEPS: [OnboardingStep; 3] = [
+    OnboardingStep::ToolsConfigured,
+    OnboardingStep::DeviceAuthorized,
+    OnboardingStep::FirstTransfer,
+];
+
+/// Progress from the steps completed before and what the checks say now
+pub fn evaluate(
+    completed: &[OnboardingStep],
+    passes: impl Fn(OnboardingStep) -> bool,
+) -> OnboardingState {
+    let steps: Vec<OnboardingStepStatus> = STEPS
+        .iter()
+        .map(|&step| OnboardingStepStatus {
+            step,
+            completed: completed.contains(&step),
+            ready: completed.contains(&step) || passes(step),
+        })
+        .collect();
+    let next_step = steps.iter().find(|s| !s.completed).map(|s| s.step);
+    OnboardingState {
+        steps,
+        next_step,
+        finished: next_step.is_none(),
+    }
+}
+
+/// Setup progress, running the checks of steps not completed yet
+pub fn get_state(app: &AppHandle) -> Result<OnboardingState, AppError> {
+    let settings = settings_service::load_settings(app)?;
+    let completed = settings.onboarding_completed;
+    Ok(evaluate(&completed, |step| {
+        !completed.contains(&step) && check(app, step).is_ok()
+    }))
+}
+
+/// Mark a step completed if its check passes; fails with the reason otherwise
+pub fn complete_step(app: &AppHandle, step: OnboardingStep) -> Result<OnboardingState, AppError> {
+    check(app, step)?;
+    let mut settings = settings_service::load_settings(app)?;
+    if !settings.onboarding_completed.contains(&step) {
+        settings.onboarding_completed.push(step);
+        settings_service::save_settings(app, &settings)?;
+    }
+    get_state(app)
+}
+
+/// The real check behind a step
+fn check(app: &AppHandle, step: OnboardingStep) -> Result<(), AppError> {
+    match step {
+        OnboardingStep::ToolsConfigured => {
+            let settings = settings_service::get_settings_with_detection(app)?;
+            if settings.adb_available {
+                Ok(())
+            } else {
+                Err(AppError::AdbNotFound(
+                    "ADB not found. Configure it in Settings.".to_string(),
+                ))
+            }
+        }
+        OnboardingStep::DeviceAuthorized => {
+            let settings = settings_service::get_settings_with_detection(app)?;
+            let adb = AdbCli::from_settings(&settings).ok_or_else(|| {
+                AppError::AdbNotFound("ADB not found. Configure it in Settings.".to_string())
+            })?;
+            if adb_service::ready_serials(&adb)?.is_empty() {
+                Err(AppError::DeviceNotFound(
+                    "No authorized device connected yet".to_string(),
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        OnboardingStep::FirstTransfer => {
+            let copied = timeline_service::any_event(
+                app,
+                &[
+                    DeviceEventKind::FilesPulled,
+                    DeviceEventKind::FilesPushed,
+                    DeviceEventKind::Synced,
+                ],
+            )?;
+            if copied {
+                Ok(())
+            } else {
+                Err(AppError::TransferError(
+                    "No files have been copied yet".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_walks_the_steps_in_order() {
+        let fresh = evaluate(&[], |step| step == OnboardingStep::ToolsConfigured);
+        assert_eq!(fresh.next_step, Some(OnboardingStep::ToolsConfigured));
+        assert!(fresh.steps[0].ready && !fresh.steps[0].completed);
+        assert!(!fresh.steps[1].ready);
+        assert!(!fresh.finished);
+
+        // A completed step stays done even when its check fails now, e.g.
+        // with the phone unplugged
+        let later = evaluate(
+            &[
+                OnboardingStep::ToolsConfigured,
+                OnboardingStep::DeviceAuthorized,
+            ],
+            |_| false,
+        );
+        assert!(later.steps[1].completed && later.steps[1].ready);
+        assert_eq!(later.next_step, Some(OnboardingStep::FirstTransfer));
+
+        let done = evaluate(&STEPS, |_| false);
+        assert_eq!(done.next_step, None);
+        assert!(done.finished);
+    }
+}
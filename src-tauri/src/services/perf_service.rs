@@ -0,0 +1,440 @@
+//! Live performance stream: CPU, memory, battery current and FPS once a second
+//!
+//! A stream runs only between `start_perf_stream` and `stop_perf_stream` (or
+//! until the device goes away) and reads nothing but `/proc`, sysfs and the
+//! SurfaceFlinger frame counter.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::PerfSample;
+use crate::services::adb_service::{AdbCli, AdbRunner};
+use crate::services::kiosk_service;
+use crate::state::write_lock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Time between samples
+const TICK: Duration = Duration::from_secs(1);
+
+/// Consecutive failed samples after which the device is considered gone
+const MAX_FAILURES: u32 = 5;
+
+/// Event emitted with a [`PerfSample`] payload every tick
+pub const PERF_SAMPLE_EVENT: &str = "perf-sample";
+
+/// Event emitted with the device serial when a stream ends on its own
+pub const PERF_STOPPED_EVENT: &str = "perf-stopped";
+
+/// Running perf streams, keyed by device serial
+#[derive(Default)]
+pub struct PerfState {
+    streams: RwLock<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// Start sampling a device, or one app on it, replacing any stream already
+/// running for that device
+pub fn start_stream(
+    app: AppHandle,
+    state: &PerfState,
+    adb: AdbCli,
+    serial: String,
+    package: Option<String>,
+) -> Result<(), AppError> {
+    if let Some(package) = &package {
+        if !kiosk_service::is_valid_package_name(package) {
+            return Err(AppError::AdbExecutionFailed(format!(
+                "Invalid package name: {}",
+                package
+            )));
+        }
+    }
+
+    let running = Arc::new(AtomicBool::new(true));
+    if let Some(previous) = write_lock(&state.streams).insert(serial.clone(), running.clone()) {
+        previous.store(false, Ordering::Relaxed);
+    }
+
+    std::thread::spawn(move || {
+        let mut sampler = PerfSampler::new(&serial, package);
+        let mut failures = 0;
+        while running.load(Ordering::Relaxed) {
+            let started = Instant::now();
+            match sampler.sample(&adb, started) {
+                Ok(sample) => {
+                    failures = 0;
+                    let _ = app.emit(PERF_SAMPLE_EVENT, &sample);
+                }
+                Err(_) => {
+                    failures += 1;
+                    if failures >= MAX_FAILURES {
+                        break;
+                    }
+                }
+            }
+            std::thread::sleep(TICK.saturating_sub(started.elapsed()));
+        }
+
+        // Only clean up if this stream wasn't replaced or stopped in the meantime
+        let state = app.state::<PerfState>();
+        let mut streams = write_lock(&state.streams);
+        if streams
+            .get(&serial)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &running))
+        {
+            streams.remove(&serial);
+            drop(streams);
+            let _ = app.emit(PERF_STOPPED_EVENT, &serial);
+        }
+    });
+
+    Ok(())
+}
+
+/// Stop a device's perf stream; returns whether one was running
+pub fn stop_stream(state: &PerfState, serial: &str) -> bool {
+    match write_lock(&state.streams).remove(serial) {
+        Some(running) => {
+            running.store(false, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Keeps the previous counters so each sample can report rates
+pub struct PerfSampler {
+    serial: String,
+    package: Option<String>,
+    pid: Option<u32>,
+    /// `(total, busy)` jiffies at the previous sample
+    last_cpu: Option<(u64, u64)>,
+    /// SurfaceFlinger frame counter at the previous sample
+    last_frames: Option<(u32, Instant)>,
+}
+
+impl PerfSampler {
+    pub fn new(serial: &str, package: Option<String>) -> Self {
+        Self {
+            serial: serial.to_string(),
+            package,
+            pid: None,
+            last_cpu: None,
+            last_frames: None,
+        }
+    }
+
+    /// Take one sample. Rates (CPU, FPS) need a previous sample, so they're
+    /// `None` the first time. Fails only if the device can't be reached at all.
+    pub fn sample(&mut self, adb: &dyn AdbRunner, now: Instant) -> Result<PerfSample, AppError> {
+        // Owned, since reading the app's process updates the sampler
+        let serial = self.serial.clone();
+        let serial = serial.as_str();
+        let system = adb.run(&["-s", serial, "shell", "cat", "/proc/stat", "/proc/meminfo"])?;
+        let cpu_total = parse_cpu_jiffies(&system);
+
+        let (busy, mem_kb) = match self.package.clone() {
+            Some(package) => match self.read_process(adb, &package) {
+                Some(stats) => (Some(stats.jiffies), stats.rss_kb),
+                None => (None, None),
+            },
+            None => (cpu_total.map(|(_, busy)| busy), parse_mem_used_kb(&system)),
+        };
+
+        let cpu_percent = match (cpu_total, busy) {
+            (Some((total, _)), Some(busy)) => {
+                let percent = self.last_cpu.and_then(|(last_total, last_busy)| {
+                    cpu_percent(total.checked_sub(last_total)?, busy.checked_sub(last_busy)?)
+                });
+                self.last_cpu = Some((total, busy));
+                percent
+            }
+            _ => {
+                self.last_cpu = None;
+                None
+            }
+        };
+
+        let battery_current_ma = adb
+            .run(&[
+                "-s",
+                serial,
+                "shell",
+                "cat",
+                "/sys/class/power_supply/battery/current_now",
+            ])
+            .ok()
+            .and_then(|output| parse_current_ma(&output));
+
+        let fps = match adb
+            .run(&[
+                "-s",
+                serial,
+                "shell",
+                "service",
+                "call",
+                "SurfaceFlinger",
+                "1013",
+            ])
+            .ok()
+            .and_then(|output| parse_frame_counter(&output))
+        {
+            Some(frames) => {
+                let fps = self.last_frames.and_then(|(last, at)| {
+                    let secs = now.checked_duration_since(at)?.as_secs_f32();
+                    (secs > 0.0).then(|| frames.wrapping_sub(last) as f32 / secs)
+                });
+                self.last_frames = Some((frames, now));
+                fps
+            }
+            None => None,
+        };
+
+        Ok(PerfSample {
+            device_serial: self.serial.clone(),
+            package: self.package.clone(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            cpu_percent,
+            mem_kb,
+            battery_current_ma,
+            fps,
+        })
+    }
+
+    /// CPU time and memory of the app's main process. The pid is looked up
+    /// again whenever the process can't be read, so a restarted app is picked up.
+    fn read_process(&mut self, adb: &dyn AdbRunner, package: &str) -> Option<ProcessStats> {
+        let serial = self.serial.as_str();
+        let pid = match self.pid {
+            Some(pid) => pid,
+            None => {
+                let output = adb.run(&["-s", serial, "shell", "pidof", package]).ok()?;
+                output.split_whitespace().next()?.parse().ok()?
+            }
+        };
+
+        let stat_path = format!("/proc/{}/stat", pid);
+        let status_path = format!("/proc/{}/status", pid);
+        let stats = adb
+            .run(&["-s", serial, "shell", "cat", &stat_path, &status_path])
+            .ok()
+            .and_then(|output| parse_process_stats(&output));
+        self.pid = stats.as_ref().map(|_| pid);
+        stats
+    }
+}
+
+/// CPU time and resident memory of one process
+#[derive(Debug, PartialEq)]
+pub struct ProcessStats {
+    /// utime + stime in clock ticks
+    pub jiffies: u64,
+    pub rss_kb: Option<u64>,
+}
+
+/// `(total, busy)` jiffies from the aggregate `cpu` line of `/proc/stat`
+///
+/// Example line:
+/// ```text
+/// cpu  84213 1532 60211 1422050 3311 0 1620 0 0 0
+/// ```
+/// (user nice system idle iowait irq softirq steal guest guest_nice)
+pub fn parse_cpu_jiffies(output: &str) -> Option<(u64, u64)> {
+    let line = output.lines().find(|line| line.starts_with("cpu "))?;
+    let values: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .map(|v| v.parse().ok())
+        .collect::<Option<_>>()?;
+    if values.len() < 5 {
+        return None;
+    }
+    // Guest time is already counted in user time
+    let total: u64 = values.iter().sum();
+    let idle = values[3] + values[4];
+    Some((total, total - idle))
+}
+
+/// Memory in use on the device from `/proc/meminfo`, in KiB
+pub fn parse_mem_used_kb(output: &str) -> Option<u64> {
+    let field = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.split_whitespace().next()?.parse::<u64>().ok())
+    };
+    Some(field("MemTotal:")?.saturating_sub(field("MemAvailable:")?))
+}
+
+/// Parse `cat /proc/<pid>/stat /proc/<pid>/status`
+///
+/// The stat line's second field is the process name in parentheses and may
+/// itself contain spaces, so fields are counted from the closing parenthesis;
+/// utime and stime are fields 14 and 15.
+pub fn parse_process_stats(output: &str) -> Option<ProcessStats> {
+    let (_, fields) = output.lines().next()?.rsplit_once(')')?;
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+
+    let rss_kb = output
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|rest| rest.split_whitespace().next()?.parse().ok());
+
+    Some(ProcessStats {
+        jiffies: utime + stime,
+        rss_kb,
+    })
+}
+
+/// Busy share of elapsed CPU time, in percent
+pub fn cpu_percent(total_delta: u64, busy_delta: u64) -> Option<f32> {
+    (total_delta > 0).then(|| (busy_delta as f32 * 100.0 / total_delta as f32).min(100.0))
+}
+
+/// Battery current from sysfs `current_now`. Most kernels report µA, a few
+/// report mA; anything that small in µA would be a rounding error, so small
+/// values are taken as mA already.
+pub fn parse_current_ma(output: &str) -> Option<i32> {
+    let value: i64 = output.trim().parse().ok()?;
+    let ma = if value.abs() >= 10_000 {
+        value / 1000
+    } else {
+        value
+    };
+    i32::try_from(ma).ok()
+}
+
+/// Frame counter from `service call SurfaceFlinger 1013`
+///
+/// Example output:
+/// ```text
+/// Result: Parcel(0004e3a1    '....')
+/// ```
+pub fn parse_frame_counter(output: &str) -> Option<u32> {
+    let (_, rest) = output.split_once("Parcel(")?;
+    let word = rest.split_whitespace().next()?;
+    u32::from_str_radix(word.trim_end_matches(')'), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const PROC_STAT: &str =
+        "cpu  100 0 100 700 100 0 0 0 0 0\ncpu0 50 0 50 350 50 0 0 0 0 0\nintr 12345\n";
+    const PROC_STAT_LATER: &str =
+        "cpu  150 0 150 800 100 0 0 0 0 0\ncpu0 75 0 75 400 50 0 0 0 0 0\nintr 12400\n";
+    const MEMINFO: &str =
+        "MemTotal:        7812344 kB\nMemFree:          301212 kB\nMemAvailable:    3212344 kB\n";
+
+    #[test]
+    fn test_parse_cpu_jiffies() {
+        assert_eq!(parse_cpu_jiffies(PROC_STAT), Some((1000, 200)));
+        assert_eq!(parse_cpu_jiffies("intr 1\n"), None);
+    }
+
+    #[test]
+    fn test_parse_mem_used_kb() {
+        assert_eq!(parse_mem_used_kb(MEMINFO), Some(4_600_000));
+        assert_eq!(parse_mem_used_kb("MemTotal: 100 kB\n"), None);
+    }
+
+    #[test]
+    fn test_parse_process_stats() {
+        let output = "4321 (com.example.game (main)) S 612 612 0 0 -1 1077952832 90123 0 12 0 1500 250 0 0 10 -10 88 0 4711 15784468480 53124 18446744073709551615\nName:\tcom.example.game\nVmPeak:\t 9001000 kB\nVmRSS:\t  212496 kB\n";
+        assert_eq!(
+            parse_process_stats(output),
+            Some(ProcessStats {
+                jiffies: 1750,
+                rss_kb: Some(212_496),
+            })
+        );
+        assert_eq!(parse_process_stats("cat: /proc/1/stat: No such file"), None);
+    }
+
+    #[test]
+    fn test_parse_current_ma() {
+        assert_eq!(parse_current_ma("-452000\n"), Some(-452));
+        assert_eq!(parse_current_ma("380\n"), Some(380));
+        assert_eq!(parse_current_ma("n/a"), None);
+    }
+
+    #[test]
+    fn test_parse_frame_counter() {
+        assert_eq!(
+            parse_frame_counter("Result: Parcel(0004e3a1    '....')\n"),
+            Some(0x4e3a1)
+        );
+        assert_eq!(parse_frame_counter("Result: Parcel(0000003c)"), Some(60));
+        assert_eq!(parse_frame_counter("service: not found"), None);
+    }
+
+    #[test]
+    fn test_device_sampler_reports_rates_from_second_sample() {
+        let first = MockAdb::new()
+            .on(
+                "/proc/stat /proc/meminfo",
+                &format!("{}{}", PROC_STAT, MEMINFO),
+            )
+            .on("current_now", "-452000\n")
+            .on("SurfaceFlinger", "Result: Parcel(00000100    '....')\n");
+        let second = MockAdb::new()
+            .on(
+                "/proc/stat /proc/meminfo",
+                &format!("{}{}", PROC_STAT_LATER, MEMINFO),
+            )
+            .on("current_now", "-450000\n")
+            .on("SurfaceFlinger", "Result: Parcel(0000013c    '....')\n");
+
+        let start = Instant::now();
+        let mut sampler = PerfSampler::new("SER1", None);
+        let sample = sampler.sample(&first, start).unwrap();
+        assert_eq!(sample.cpu_percent, None);
+        assert_eq!(sample.fps, None);
+        assert_eq!(sample.mem_kb, Some(4_600_000));
+        assert_eq!(sample.battery_current_ma, Some(-452));
+
+        let sample = sampler.sample(&second, start + TICK).unwrap();
+        // 100 of the 200 jiffies since the first sample were busy
+        assert_eq!(sample.cpu_percent, Some(50.0));
+        assert_eq!(sample.fps, Some(60.0));
+    }
+
+    #[test]
+    fn test_app_sampler_looks_up_pid_once() {
+        let adb = MockAdb::new()
+            .on("pidof", "4321\n")
+            .on(
+                "/proc/4321/stat",
+                "4321 (com.example.game) S 612 612 0 0 -1 0 0 0 0 0 1500 250 0\nVmRSS:\t  212496 kB\n",
+            )
+            .on("/proc/stat", PROC_STAT)
+            .on("current_now", "")
+            .on("SurfaceFlinger", "");
+
+        let mut sampler = PerfSampler::new("SER1", Some("com.example.game".to_string()));
+        let now = Instant::now();
+        let sample = sampler.sample(&adb, now).unwrap();
+        assert_eq!(sample.mem_kb, Some(212_496));
+        assert_eq!(sample.battery_current_ma, None);
+        sampler.sample(&adb, now + TICK).unwrap();
+
+        let pidof_calls = adb.calls().iter().filter(|c| c.contains("pidof")).count();
+        assert_eq!(pidof_calls, 1);
+    }
+
+    #[test]
+    fn test_sampler_fails_when_device_is_gone() {
+        let adb = MockAdb::new().fail(
+            "",
+            AppError::AdbExecutionFailed("device 'SER1' not found".to_string()),
+        );
+        let mut sampler = PerfSampler::new("SER1", None);
+        assert!(sampler.sample(&adb, Instant::now()).is_err());
+    }
+}
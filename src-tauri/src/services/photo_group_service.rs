@@ -0,0 +1,316 @@
+//! Burst and near-duplicate photo groups
+//!
+//! Bursts are found from file names (Pixel `..._BURST<id>...`, Samsung
+//! `<date>_<time>_001`) and from shots taken within [`BURST_GAP_SECS`] of each
+//! other. Near-duplicates compare a difference hash of each thumbnail, so only
+//! thumbnails come over adb. The user can then pull one shot per group.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{MediaGroup, MediaGroupKind, MediaType};
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::{media_type_of, quote_remote_path, visible_relative};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Shots this close together (seconds) count as one burst
+pub const BURST_GAP_SECS: i64 = 2;
+
+/// Hashes differing in at most this many of 64 bits count as the same picture
+pub const SIMILAR_MAX_DISTANCE: u32 = 6;
+
+/// Hashes the thumbnail of a device photo by path
+pub type ThumbnailHash<'a> = &'a dyn Fn(&str) -> Option<u64>;
+
+/// A photo with its modification time
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedPhoto {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Unix seconds
+    pub modified: i64,
+}
+
+impl TimedPhoto {
+    fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Photos directly in a device folder, oldest first
+pub fn list_photos(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    dir: &str,
+) -> Result<Vec<TimedPhoto>, AppError> {
+    let root = dir.trim_end_matches('/');
+    let command = format!(
+        "find {}/ -maxdepth 1 -type f -exec stat -c '%Y %s %n' {{}} +",
+        quote_remote_path(root)
+    );
+    let output = adb.run_idempotent(&["-s", serial, "shell", &command])?;
+    Ok(parse_timed_listing(&output, root))
+}
+
+/// Parse `stat -c '%Y %s %n'` lines, keeping visible images, oldest first
+pub fn parse_timed_listing(output: &str, root: &str) -> Vec<TimedPhoto> {
+    let mut photos: Vec<TimedPhoto> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_end_matches('\r').splitn(3, ' ');
+            let (modified, size, path) = (parts.next()?, parts.next()?, parts.next()?);
+            visible_relative(path, root)?;
+            if media_type_of(path)? != MediaType::Image {
+                return None;
+            }
+            Some(TimedPhoto {
+                path: path.to_string(),
+                size_bytes: size.parse().ok()?,
+                modified: modified.parse().ok()?,
+            })
+        })
+        .collect();
+    photos.sort_by(|a, b| a.modified.cmp(&b.modified).then(a.path.cmp(&b.path)));
+    photos
+}
+
+/// What ties a file to its burst by name: the Pixel burst id, or a Samsung
+/// `YYYYMMDD_HHMMSS` stem followed by a `_NNN` sequence number
+pub fn burst_key(name: &str) -> Option<String> {
+    let upper = name.to_uppercase();
+    if let Some(at) = upper.find("_BURST") {
+        let id: String = upper[at + 6..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        return (!id.is_empty()).then_some(id);
+    }
+
+    let stem = upper
+        .rsplit_once('.')
+        .map_or(upper.as_str(), |(stem, _)| stem);
+    let (base, sequence) = stem.rsplit_once('_')?;
+    let is_digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    let (date, time) = base.split_once('_')?;
+    (is_digits(sequence, 3) && is_digits(date, 8) && is_digits(time, 6)).then(|| base.to_string())
+}
+
+/// 64-bit difference hash of a 9x8 grayscale image (row-major): each bit says
+/// whether a pixel is brighter than its right neighbour
+pub fn dhash(pixels: &[u8]) -> Option<u64> {
+    if pixels.len() != 9 * 8 {
+        return None;
+    }
+    let mut hash = 0u64;
+    for row in 0..8 {
+        for col in 0..8 {
+            let at = row * 9 + col;
+            hash = (hash << 1) | u64::from(pixels[at] > pixels[at + 1]);
+        }
+    }
+    Some(hash)
+}
+
+/// Difference hash of a local image, e.g. a cached thumbnail
+pub fn image_hash(path: &Path) -> Option<u64> {
+    let gray = image::open(path)
+        .ok()?
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    dhash(gray.as_raw())
+}
+
+/// Burst groups among photos sorted oldest first, as indices
+pub fn find_bursts(photos: &[TimedPhoto]) -> Vec<Vec<usize>> {
+    let mut sets = DisjointSets::new(photos.len());
+    for i in 1..photos.len() {
+        if photos[i].modified - photos[i - 1].modified <= BURST_GAP_SECS {
+            sets.union(i - 1, i);
+        }
+    }
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    for (i, photo) in photos.iter().enumerate() {
+        if let Some(key) = burst_key(photo.name()) {
+            let first = *by_key.entry(key).or_insert(i);
+            sets.union(first, i);
+        }
+    }
+    sets.groups()
+}
+
+/// Near-duplicate groups among `(index, hash)` pairs, as indices
+pub fn find_similar(hashes: &[(usize, u64)]) -> Vec<Vec<usize>> {
+    let mut sets = DisjointSets::new(hashes.len());
+    for i in 0..hashes.len() {
+        for j in i + 1..hashes.len() {
+            if (hashes[i].1 ^ hashes[j].1).count_ones() <= SIMILAR_MAX_DISTANCE {
+                sets.union(i, j);
+            }
+        }
+    }
+    sets.groups()
+        .into_iter()
+        .map(|group| group.into_iter().map(|i| hashes[i].0).collect())
+        .collect()
+}
+
+/// Group the photos of a device folder. Near-duplicates are only looked for
+/// when `hash` is given; it hashes a photo's thumbnail by device path.
+pub fn group_photos(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    dir: &str,
+    hash: Option<ThumbnailHash>,
+) -> Result<Vec<MediaGroup>, AppError> {
+    let photos = list_photos(adb, serial, dir)?;
+    let bursts = find_bursts(&photos);
+    let mut groups: Vec<MediaGroup> = bursts
+        .iter()
+        .map(|members| media_group(&photos, members, MediaGroupKind::Burst))
+        .collect();
+
+    if let Some(hash) = hash {
+        let in_burst: Vec<usize> = bursts.into_iter().flatten().collect();
+        let hashes: Vec<(usize, u64)> = (0..photos.len())
+            .filter(|i| !in_burst.contains(i))
+            .filter_map(|i| Some((i, hash(&photos[i].path)?)))
+            .collect();
+        groups.extend(
+            find_similar(&hashes)
+                .iter()
+                .map(|members| media_group(&photos, members, MediaGroupKind::Similar)),
+        );
+    }
+
+    Ok(groups)
+}
+
+fn media_group(photos: &[TimedPhoto], members: &[usize], kind: MediaGroupKind) -> MediaGroup {
+    let members: Vec<&TimedPhoto> = members.iter().map(|&i| &photos[i]).collect();
+    let best = members
+        .iter()
+        .find(|p| p.name().to_uppercase().contains("COVER"))
+        .or_else(|| members.iter().max_by_key(|p| p.size_bytes))
+        .map(|p| p.path.clone())
+        .unwrap_or_default();
+    MediaGroup {
+        kind,
+        paths: members.iter().map(|p| p.path.clone()).collect(),
+        best_path: best,
+        total_bytes: members.iter().map(|p| p.size_bytes).sum(),
+    }
+}
+
+/// Union-find over indices
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        self.parent[i] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            // Lowest index as root keeps groups in their original order
+            self.parent[a.max(b)] = a.min(b);
+        }
+    }
+
+    /// Sets with more than one member, each in index order
+    fn groups(mut self) -> Vec<Vec<usize>> {
+        let mut by_root: Vec<Vec<usize>> = vec![Vec::new(); self.parent.len()];
+        for i in 0..self.parent.len() {
+            let root = self.find(i);
+            by_root[root].push(i);
+        }
+        by_root.into_iter().filter(|g| g.len() > 1).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_burst_key() {
+        assert_eq!(
+            burst_key("00001IMG_00001_BURST20240201085900123.jpg").as_deref(),
+            Some("20240201085900123")
+        );
+        assert_eq!(
+            burst_key("20240201_085900_003.jpg").as_deref(),
+            Some("20240201_085900")
+        );
+        assert_eq!(burst_key("PXL_20240201_085900123.jpg"), None);
+        assert_eq!(burst_key("IMG_001.jpg"), None);
+    }
+
+    #[test]
+    fn test_dhash_and_similar() {
+        let gradient: Vec<u8> = (0..72).map(|i| (i % 9 * 20) as u8).collect();
+        assert_eq!(dhash(&gradient), Some(0));
+        let reversed: Vec<u8> = (0..72).map(|i| (200 - i % 9 * 20) as u8).collect();
+        assert_eq!(dhash(&reversed), Some(u64::MAX));
+        assert_eq!(dhash(&[0; 10]), None);
+
+        let groups = find_similar(&[(3, 0b1011), (5, u64::MAX), (7, 0b0011), (9, 0)]);
+        assert_eq!(groups, vec![vec![3, 7, 9]]);
+    }
+
+    #[test]
+    fn test_group_photos_by_time_name_and_hash() {
+        let adb = MockAdb::new().on(
+            "stat -c",
+            "1000 4000 /sdcard/DCIM/Camera/a.jpg\n\
+             1001 5000 /sdcard/DCIM/Camera/b.jpg\n\
+             1003 3000 /sdcard/DCIM/Camera/c.jpg\n\
+             2000 100 /sdcard/DCIM/Camera/20240201_085900_001.jpg\n\
+             2100 200 /sdcard/DCIM/Camera/20240201_085900_002.jpg\n\
+             3000 700 /sdcard/DCIM/Camera/d.jpg\n\
+             4000 800 /sdcard/DCIM/Camera/e.jpg\n\
+             5000 900 /sdcard/DCIM/Camera/f.jpg\n\
+             6000 50 /sdcard/DCIM/Camera/clip.mp4\n",
+        );
+
+        let hash = |path: &str| match path {
+            "/sdcard/DCIM/Camera/d.jpg" | "/sdcard/DCIM/Camera/f.jpg" => Some(0xFF),
+            _ => Some(0xFF00),
+        };
+        let groups = group_photos(&adb, "SER1", "/sdcard/DCIM/Camera", Some(&hash)).unwrap();
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].kind, MediaGroupKind::Burst);
+        assert_eq!(groups[0].paths.len(), 3);
+        assert_eq!(groups[0].best_path, "/sdcard/DCIM/Camera/b.jpg");
+        assert_eq!(groups[0].total_bytes, 12000);
+        assert_eq!(
+            groups[1].paths,
+            vec![
+                "/sdcard/DCIM/Camera/20240201_085900_001.jpg",
+                "/sdcard/DCIM/Camera/20240201_085900_002.jpg"
+            ]
+        );
+        assert_eq!(groups[2].kind, MediaGroupKind::Similar);
+        assert_eq!(
+            groups[2].paths,
+            vec!["/sdcard/DCIM/Camera/d.jpg", "/sdcard/DCIM/Camera/f.jpg"]
+        );
+
+        let without_hash = group_photos(&adb, "SER1", "/sdcard/DCIM/Camera", None).unwrap();
+        assert_eq!(without_hash.len(), 2);
+    }
+}
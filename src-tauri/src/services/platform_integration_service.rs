@@ -0,0 +1,107 @@
+//! Desktop integration beyond the app window
+//!
+//! Transfer progress is shown on the app's taskbar button: the Windows
+//! taskbar progress bar (ITaskbarList3, through Tauri), the macOS dock icon
+//! and Unity launchers on Linux. Running batches are combined into one bar,
+//! which clears when the last one finishes.
+//!
+//! Jump list tasks are not added: they need the Windows COM bindings as a new
+//! dependency and the app to act on launch arguments.
+
+use crate::domain::models::BatchProgress;
+use crate::state::write_lock;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+/// Percentage done per running batch
+#[derive(Default)]
+pub struct TaskbarState {
+    batches: RwLock<HashMap<String, u64>>,
+}
+
+/// Percentage done of one batch, in bytes, or in files when sizes are unknown
+fn batch_percent(progress: &BatchProgress) -> u64 {
+    let (done, total) = if progress.total_bytes > 0 {
+        (progress.completed_bytes, progress.total_bytes)
+    } else {
+        (progress.completed_files as u64, progress.total_files as u64)
+    };
+    (done.min(total) * 100).checked_div(total).unwrap_or(0)
+}
+
+/// Update the running batches with `progress`; the average percentage done
+/// across them, or `None` once none are running
+pub fn track(batches: &mut HashMap<String, u64>, progress: &BatchProgress) -> Option<u64> {
+    if progress.finished {
+        batches.remove(&progress.batch_id);
+    } else {
+        batches.insert(progress.batch_id.clone(), batch_percent(progress));
+    }
+
+    if batches.is_empty() {
+        return None;
+    }
+    Some(batches.values().sum::<u64>() / batches.len() as u64)
+}
+
+/// Show a batch update on the taskbar button of every app window
+pub fn show_batch_progress(app: &AppHandle, progress: &BatchProgress) {
+    let Some(state) = app.try_state::<TaskbarState>() else {
+        return;
+    };
+    let percent = track(&mut write_lock(&state.batches), progress);
+
+    for window in app.webview_windows().values() {
+        let bar = match percent {
+            Some(percent) => ProgressBarState {
+                status: Some(ProgressBarStatus::Normal),
+                progress: Some(percent),
+            },
+            None => ProgressBarState {
+                status: Some(ProgressBarStatus::None),
+                progress: None,
+            },
+        };
+        let _ = window.set_progress_bar(bar);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::BatchKind;
+
+    fn progress(id: &str, done: u64, total: u64, finished: bool) -> BatchProgress {
+        BatchProgress {
+            batch_id: id.to_string(),
+            kind: BatchKind::Pull,
+            total_files: 2,
+            completed_files: 1,
+            total_bytes: total,
+            completed_bytes: done,
+            current_file: None,
+            failures: Vec::new(),
+            finished,
+        }
+    }
+
+    #[test]
+    fn test_track_combines_running_batches() {
+        let mut batches = HashMap::new();
+        assert_eq!(
+            track(&mut batches, &progress("a", 25, 100, false)),
+            Some(25)
+        );
+        assert_eq!(track(&mut batches, &progress("b", 0, 300, false)), Some(12));
+        // Sizes unknown: counted in files, one of two done
+        assert_eq!(track(&mut batches, &progress("c", 0, 0, false)), Some(25));
+        assert_eq!(
+            track(&mut batches, &progress("a", 100, 100, true)),
+            Some(25)
+        );
+        assert_eq!(track(&mut batches, &progress("c", 0, 0, true)), Some(0));
+        assert_eq!(track(&mut batches, &progress("b", 300, 300, true)), None);
+    }
+}
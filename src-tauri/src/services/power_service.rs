@@ -0,0 +1,177 @@
+//! Keeping the computer awake while transfers, recordings and backups run
+//!
+//! Each long operation holds a [`SleepGuard`]. The first guard blocks system
+//! sleep and display-off, and dropping the last one lets the computer sleep
+//! again. Windows uses `SetThreadExecutionState` on a thread kept for the
+//! purpose (the request belongs to the calling thread), macOS a `caffeinate`
+//! process and Linux a `systemd-inhibit` lock. The helper processes exit with
+//! the app, so a crash never leaves the computer awake.
+
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// Shown by `systemd-inhibit --list` as the reason for the lock
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const INHIBIT_REASON: &str = "Transferring, recording or backing up an Android device";
+
+/// Number of running operations that need the computer awake
+#[derive(Debug, Default)]
+pub struct Holds {
+    count: usize,
+}
+
+impl Holds {
+    /// Count one more operation; true when it is the first
+    pub fn acquire(&mut self) -> bool {
+        self.count += 1;
+        self.count == 1
+    }
+
+    /// Count one operation as done; true when it was the last
+    pub fn release(&mut self) -> bool {
+        self.count = self.count.saturating_sub(1);
+        self.count == 0
+    }
+}
+
+#[derive(Default)]
+struct PowerState {
+    holds: Holds,
+    inhibitor: Option<Inhibitor>,
+}
+
+/// Keeps the computer awake while one operation runs; dropping it ends the hold
+#[must_use = "the computer may sleep as soon as the guard is dropped"]
+pub struct SleepGuard {
+    _private: (),
+}
+
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        let mut power = power().lock().unwrap_or_else(PoisonError::into_inner);
+        if power.holds.release() {
+            power.inhibitor = None;
+        }
+    }
+}
+
+/// Block system sleep until the returned guard is dropped. Failing to start
+/// the platform's inhibitor is not an error: the operation still runs.
+pub fn keep_awake() -> SleepGuard {
+    let mut power = power().lock().unwrap_or_else(PoisonError::into_inner);
+    if power.holds.acquire() {
+        power.inhibitor = Inhibitor::start().ok();
+    }
+    SleepGuard { _private: () }
+}
+
+/// Guards are taken from commands and background threads alike, so the
+/// count lives for the whole process
+fn power() -> &'static Mutex<PowerState> {
+    static POWER: OnceLock<Mutex<PowerState>> = OnceLock::new();
+    POWER.get_or_init(Mutex::default)
+}
+
+/// The platform's sleep block; released when dropped
+#[cfg(target_os = "windows")]
+struct Inhibitor {
+    /// Dropping it wakes the thread to clear the request
+    _stop: std::sync::mpsc::Sender<()>,
+}
+
+#[cfg(target_os = "windows")]
+impl Inhibitor {
+    fn start() -> std::io::Result<Self> {
+        const ES_CONTINUOUS: u32 = 0x8000_0000;
+        const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+        const ES_DISPLAY_REQUIRED: u32 = 0x0000_0002;
+
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn SetThreadExecutionState(flags: u32) -> u32;
+        }
+
+        let (stop, stopped) = std::sync::mpsc::channel::<()>();
+        std::thread::Builder::new()
+            .name("keep-awake".to_string())
+            .spawn(move || {
+                // SAFETY: takes flags only and has no memory effects
+                unsafe {
+                    SetThreadExecutionState(
+                        ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+                    )
+                };
+                // Returns once the sender is dropped
+                let _ = stopped.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            })?;
+        Ok(Self { _stop: stop })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+struct Inhibitor {
+    child: std::process::Child,
+}
+
+#[cfg(not(target_os = "windows"))]
+impl Inhibitor {
+    fn start() -> std::io::Result<Self> {
+        let pid = std::process::id().to_string();
+
+        // -d display, -i idle, -s system sleep; -w exits with the app
+        #[cfg(target_os = "macos")]
+        let mut cmd = {
+            let mut cmd = std::process::Command::new("caffeinate");
+            cmd.args(["-d", "-i", "-s", "-w", &pid]);
+            cmd
+        };
+
+        // The lock is held while `tail` runs, and `tail` exits with the app
+        #[cfg(not(target_os = "macos"))]
+        let mut cmd = {
+            let mut cmd = std::process::Command::new("systemd-inhibit");
+            cmd.args([
+                "--what=sleep:idle",
+                "--who=GesuBridge",
+                &format!("--why={}", INHIBIT_REASON),
+                "--mode=block",
+                "tail",
+                &format!("--pid={}", pid),
+                "-f",
+                "/dev/null",
+            ]);
+            cmd
+        };
+
+        cmd.stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        let child = crate::services::process_service::spawn_hidden(&mut cmd)?;
+        Ok(Self { child })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holds_start_and_end_with_the_queue() {
+        let mut holds = Holds::default();
+        assert!(holds.acquire());
+        assert!(!holds.acquire());
+        assert!(!holds.release());
+        assert!(holds.release());
+        // An extra release doesn't underflow
+        assert!(holds.release());
+        assert!(holds.acquire());
+    }
+}
@@ -0,0 +1,243 @@
+//! Files pulled for preview, so a later pull can copy them (or a save can
+//! move them) instead of downloading them again
+//!
+//! Previews are cached below `previews/<serial>/` mirroring the device path.
+//! Which ones are current is kept for the app session; a cached copy is only
+//! used while its size still matches the file on the device.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{ConflictPolicy, MediaTransferResult};
+use crate::services::conflict_service::{self, Resolution};
+use crate::services::local_path_service;
+use crate::state::{read_lock, write_lock};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+/// Cached previews by device serial and device path
+#[derive(Default)]
+pub struct PreviewCacheState {
+    previews: RwLock<HashMap<(String, String), PathBuf>>,
+}
+
+/// Where the preview of a device file is cached
+pub fn cache_path(
+    preview_dir: &Path,
+    serial: &str,
+    remote_path: &str,
+) -> Result<PathBuf, AppError> {
+    let mut path = preview_dir.join(sanitize_serial(serial));
+    let mut named = false;
+    for component in Path::new(remote_path).components() {
+        match component {
+            Component::Normal(part) => {
+                path.push(local_path_service::local_file_name(&part.to_string_lossy()));
+                named = true;
+            }
+            Component::RootDir | Component::CurDir => {}
+            _ => {
+                return Err(AppError::InvalidPath(format!(
+                    "Invalid remote path: {}",
+                    remote_path
+                )))
+            }
+        }
+    }
+    if !named {
+        return Err(AppError::InvalidPath("Invalid remote path".to_string()));
+    }
+    Ok(path)
+}
+
+/// Remember that a device file's preview is at `local_path`
+pub fn record(state: &PreviewCacheState, serial: &str, remote_path: &str, local_path: &Path) {
+    write_lock(&state.previews).insert(
+        (serial.to_string(), remote_path.to_string()),
+        local_path.to_path_buf(),
+    );
+}
+
+/// The cached preview of a device file, if it's still there with the size
+/// the device reports
+pub fn cached_copy(
+    state: &PreviewCacheState,
+    serial: &str,
+    remote_path: &str,
+    size_bytes: u64,
+) -> Option<PathBuf> {
+    let previews = read_lock(&state.previews);
+    let local = previews.get(&(serial.to_string(), remote_path.to_string()))?;
+    (size_bytes > 0 && std::fs::metadata(local).is_ok_and(|m| m.len() == size_bytes))
+        .then(|| local.clone())
+}
+
+/// Forget a device file's preview, e.g. once it was moved out of the cache
+pub fn forget(state: &PreviewCacheState, serial: &str, remote_path: &str) {
+    write_lock(&state.previews).remove(&(serial.to_string(), remote_path.to_string()));
+}
+
+/// Move a device file's preview into `dest_dir` under the device file's
+/// name, so keeping a previewed file needs no second transfer. The device
+/// time isn't known here, so `KeepNewer` leaves an existing file alone.
+pub fn save_as(
+    state: &PreviewCacheState,
+    serial: &str,
+    remote_path: &str,
+    dest_dir: &Path,
+    conflict: ConflictPolicy,
+) -> Result<MediaTransferResult, AppError> {
+    let cached = read_lock(&state.previews)
+        .get(&(serial.to_string(), remote_path.to_string()))
+        .filter(|local| local.is_file())
+        .cloned()
+        .ok_or_else(|| AppError::TransferError(format!("{} hasn't been previewed", remote_path)))?;
+    let file_name = Path::new(remote_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::InvalidPath("Invalid remote path".to_string()))?;
+    let local_name = local_path_service::local_file_name(file_name);
+    let renamed_from = (local_name != file_name).then(|| file_name.to_string());
+    let size_bytes = std::fs::metadata(&cached)?.len();
+
+    std::fs::create_dir_all(dest_dir)?;
+    let target = dest_dir.join(&local_name);
+    let existing = target.exists().then_some(None);
+    let local_path = match conflict_service::resolve(
+        conflict,
+        &target.to_string_lossy(),
+        existing,
+        None,
+        |candidate| Path::new(candidate).exists(),
+    ) {
+        Resolution::Write(path) => PathBuf::from(path),
+        Resolution::Skip(reason) => {
+            return Ok(MediaTransferResult {
+                source_path: remote_path.to_string(),
+                dest_path: Some(target.to_string_lossy().to_string()),
+                success: false,
+                error: Some(reason),
+                size_bytes,
+                skipped: true,
+                motion_video_path: None,
+                renamed_from: renamed_from.clone(),
+            })
+        }
+    };
+
+    // A rename fails across drives; copy and remove the cached file then
+    if std::fs::rename(&cached, &local_path).is_err() {
+        std::fs::copy(&cached, &local_path)?;
+        let _ = std::fs::remove_file(&cached);
+    }
+    forget(state, serial, remote_path);
+
+    Ok(MediaTransferResult {
+        source_path: remote_path.to_string(),
+        dest_path: Some(local_path.to_string_lossy().to_string()),
+        success: true,
+        error: None,
+        size_bytes,
+        skipped: false,
+        motion_video_path: None,
+        renamed_from,
+    })
+}
+
+/// Serials of network devices contain `:`, which Windows doesn't allow in names
+fn sanitize_serial(serial: &str) -> String {
+    serial
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_path_mirrors_device_path() {
+        let dir = Path::new("/cache/previews");
+        assert_eq!(
+            cache_path(dir, "192.168.1.5:5555", "/sdcard/DCIM/Camera/a b.mp4").unwrap(),
+            dir.join("192.168.1.5_5555/sdcard/DCIM/Camera/a b.mp4")
+        );
+        assert!(cache_path(dir, "SER1", "/sdcard/../../etc/passwd").is_err());
+        assert!(cache_path(dir, "SER1", "/").is_err());
+    }
+
+    #[test]
+    fn test_cached_copy_needs_matching_size() {
+        let dir = std::env::temp_dir().join(format!("gesu_preview_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let local = dir.join("a.mp4");
+        std::fs::write(&local, b"video").unwrap();
+
+        let state = PreviewCacheState::default();
+        record(&state, "SER1", "/sdcard/a.mp4", &local);
+        let hit = cached_copy(&state, "SER1", "/sdcard/a.mp4", 5);
+        let changed = cached_copy(&state, "SER1", "/sdcard/a.mp4", 6);
+        let other_device = cached_copy(&state, "SER2", "/sdcard/a.mp4", 5);
+        forget(&state, "SER1", "/sdcard/a.mp4");
+        let forgotten = cached_copy(&state, "SER1", "/sdcard/a.mp4", 5);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(hit, Some(local));
+        assert_eq!(changed, None);
+        assert_eq!(other_device, None);
+        assert_eq!(forgotten, None);
+    }
+
+    #[test]
+    fn test_save_as_moves_with_device_name() {
+        let dir = std::env::temp_dir().join(format!("gesu_save_preview_{}", std::process::id()));
+        let cached = dir.join("cache").join("a.mp4");
+        let dest = dir.join("dest");
+        std::fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::write(&cached, b"video").unwrap();
+        std::fs::write(dest.join("a.mp4"), b"older").unwrap();
+
+        let state = PreviewCacheState::default();
+        record(&state, "SER1", "/sdcard/DCIM/a.mp4", &cached);
+        let skipped = save_as(
+            &state,
+            "SER1",
+            "/sdcard/DCIM/a.mp4",
+            &dest,
+            ConflictPolicy::Skip,
+        )
+        .unwrap();
+        let saved = save_as(
+            &state,
+            "SER1",
+            "/sdcard/DCIM/a.mp4",
+            &dest,
+            ConflictPolicy::Rename,
+        )
+        .unwrap();
+        let again = save_as(
+            &state,
+            "SER1",
+            "/sdcard/DCIM/a.mp4",
+            &dest,
+            ConflictPolicy::Rename,
+        );
+        let moved = std::fs::read(dest.join("a (1).mp4")).ok();
+        let cache_left = cached.exists();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(skipped.skipped);
+        assert!(saved.success);
+        assert_eq!(saved.size_bytes, 5);
+        assert_eq!(moved.as_deref(), Some(&b"video"[..]));
+        assert!(!cache_left);
+        assert!(again.is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Private folders: left out of listings and pulls unless asked for
+//!
+//! Folders are kept relative to the storage volume (`Pictures/Banking`), so
+//! one entry covers internal storage and SD cards, and are matched without
+//! case like Android's shared storage.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::MediaTransferResult;
+
+/// Turn a user-entered folder into its stored form, e.g.
+/// `/sdcard/Pictures/Banking/` -> `Pictures/Banking`
+pub fn normalize_folder(dir: &str) -> Result<String, AppError> {
+    let dir = dir.trim().replace('\\', "/");
+    let relative = volume_relative(&dir).trim_matches('/');
+    if relative.is_empty() || relative.split('/').any(|part| part == "..") {
+        return Err(AppError::InvalidPath(format!(
+            "Invalid private folder: {}",
+            dir
+        )));
+    }
+    Ok(relative.to_string())
+}
+
+/// Whether a device path is in (or is) one of the private folders
+pub fn is_private(path: &str, private_folders: &[String]) -> bool {
+    let relative = volume_relative(path).trim_matches('/').to_lowercase();
+    private_folders.iter().any(|folder| {
+        let folder = folder.to_lowercase();
+        relative == folder
+            || relative
+                .strip_prefix(&folder)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// The folders to hide for a request: none when private items were asked for
+pub fn hidden(private_folders: &[String], include_private: Option<bool>) -> &[String] {
+    if include_private.unwrap_or(false) {
+        &[]
+    } else {
+        private_folders
+    }
+}
+
+/// Result for a file left out of a pull because it's private
+pub fn skipped_pull(path: &str) -> MediaTransferResult {
+    MediaTransferResult {
+        source_path: path.to_string(),
+        dest_path: None,
+        success: false,
+        error: Some("In a private folder; include private folders to pull it".to_string()),
+        size_bytes: 0,
+        skipped: true,
+        motion_video_path: None,
+        renamed_from: None,
+    }
+}
+
+/// Path below its storage volume: `/sdcard/`, `/storage/emulated/<user>/`
+/// or `/storage/<volume>/`
+fn volume_relative(path: &str) -> &str {
+    if let Some(rest) = path.strip_prefix("/sdcard/") {
+        return rest;
+    }
+    if let Some(rest) = path.strip_prefix("/storage/") {
+        let rest = rest.strip_prefix("emulated/").unwrap_or(rest);
+        return rest.split_once('/').map_or("", |(_, below)| below);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_folder() {
+        assert_eq!(
+            normalize_folder("/sdcard/Pictures/Banking/").unwrap(),
+            "Pictures/Banking"
+        );
+        assert_eq!(
+            normalize_folder("/storage/1234-ABCD/DCIM/Private").unwrap(),
+            "DCIM/Private"
+        );
+        assert_eq!(
+            normalize_folder("Pictures/Banking").unwrap(),
+            "Pictures/Banking"
+        );
+        assert!(normalize_folder("/sdcard/").is_err());
+        assert!(normalize_folder("Pictures/../Android").is_err());
+    }
+
+    #[test]
+    fn test_is_private_across_volumes() {
+        let folders = vec![
+            "Pictures/Banking".to_string(),
+            "Android/media/com.whatsapp/WhatsApp/Media/WhatsApp Images/Sent".to_string(),
+        ];
+        for path in [
+            "/sdcard/Pictures/Banking",
+            "/sdcard/Pictures/Banking/shot.png",
+            "/storage/emulated/0/pictures/banking/a/b.png",
+            "/storage/1234-ABCD/Pictures/Banking/c.jpg",
+            "/sdcard/Android/media/com.whatsapp/WhatsApp/Media/WhatsApp Images/Sent/IMG-1.jpg",
+        ] {
+            assert!(is_private(path, &folders), "{}", path);
+        }
+        for path in [
+            "/sdcard/Pictures",
+            "/sdcard/Pictures/BankingOld/a.png",
+            "/sdcard/DCIM/Camera/a.jpg",
+            "/sdcard/Android/media/com.whatsapp/WhatsApp/Media/WhatsApp Images/IMG-2.jpg",
+        ] {
+            assert!(!is_private(path, &folders), "{}", path);
+        }
+        assert!(hidden(&folders, Some(true)).is_empty());
+        assert_eq!(hidden(&folders, None).len(), 2);
+    }
+}
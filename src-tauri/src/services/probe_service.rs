@@ -0,0 +1,234 @@
+//! ffprobe service for reading media metadata
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ProbeResult;
+use crate::services::process_service;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Max time ffprobe may spend reading a single file
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run ffprobe on a local file and return parsed metadata
+pub fn probe_file(ffprobe_path: &str, file_path: &Path) -> Result<ProbeResult, AppError> {
+    if !file_path.exists() {
+        return Err(AppError::InvalidPath(format!(
+            "File not found: {}",
+            file_path.display()
+        )));
+    }
+
+    let mut cmd = Command::new(ffprobe_path);
+    cmd.args([
+        "-v",
+        "quiet",
+        "-print_format",
+        "json",
+        "-show_format",
+        "-show_streams",
+    ])
+    .arg(file_path);
+
+    let output = process_service::output_hidden(&mut cmd, PROBE_TIMEOUT)
+        .map_err(|e| AppError::ProbeError(format!("Failed to execute ffprobe: {}", e)))?
+        .ok_or_else(|| {
+            AppError::Timeout(format!(
+                "ffprobe did not finish within {}s for {}",
+                PROBE_TIMEOUT.as_secs(),
+                file_path.display()
+            ))
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::ProbeError(format!(
+            "ffprobe failed for {}: {}",
+            file_path.display(),
+            stderr
+        )));
+    }
+
+    parse_probe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the JSON printed by `ffprobe -print_format json -show_format -show_streams`
+pub fn parse_probe_output(json: &str) -> Result<ProbeResult, AppError> {
+    let root: Value = serde_json::from_str(json)
+        .map_err(|e| AppError::ProbeError(format!("Invalid ffprobe output: {}", e)))?;
+
+    let mut result = ProbeResult::default();
+
+    if let Some(format) = root.get("format") {
+        result.format_name = str_field(format, "format_name");
+        result.duration_ms = seconds_to_ms(format.get("duration"));
+        result.bit_rate = number_field(format, "bit_rate");
+        result.creation_time = format
+            .get("tags")
+            .and_then(|t| str_field(t, "creation_time"));
+        if let Some((latitude, longitude)) = format
+            .get("tags")
+            .and_then(|t| str_field(t, "location"))
+            .and_then(|l| parse_iso6709(&l))
+        {
+            result.latitude = Some(latitude);
+            result.longitude = Some(longitude);
+        }
+    }
+
+    let streams = root
+        .get("streams")
+        .and_then(|s| s.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    for stream in &streams {
+        match stream.get("codec_type").and_then(|t| t.as_str()) {
+            Some("video") if result.video_codec.is_none() => {
+                result.video_codec = str_field(stream, "codec_name");
+                result.width = number_field(stream, "width").map(|w| w as u32);
+                result.height = number_field(stream, "height").map(|h| h as u32);
+                result.rotation = stream_rotation(stream);
+
+                // Still images report no container duration; fall back to the stream
+                if result.duration_ms.is_none() {
+                    result.duration_ms = seconds_to_ms(stream.get("duration"));
+                }
+            }
+            Some("audio") if result.audio_codec.is_none() => {
+                result.audio_codec = str_field(stream, "codec_name");
+            }
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// Read rotation from the legacy `rotate` tag or the display matrix side data
+fn stream_rotation(stream: &Value) -> Option<i32> {
+    if let Some(rotate) = stream
+        .get("tags")
+        .and_then(|t| str_field(t, "rotate"))
+        .and_then(|r| r.parse().ok())
+    {
+        return Some(rotate);
+    }
+
+    stream
+        .get("side_data_list")
+        .and_then(|s| s.as_array())?
+        .iter()
+        .find_map(|sd| sd.get("rotation").and_then(|r| r.as_i64()))
+        .map(|r| r as i32)
+}
+
+/// Latitude and longitude from an ISO 6709 string as Android writes it into
+/// videos, e.g. `+37.4219-122.0840/` (an altitude may follow)
+pub fn parse_iso6709(location: &str) -> Option<(f64, f64)> {
+    let location = location.trim().trim_end_matches('/');
+    let split = location
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '+' || c == '-')
+        .map(|(at, _)| at)?;
+    let (latitude, rest) = location.split_at(split);
+    let longitude_end = rest
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '+' || c == '-')
+        .map_or(rest.len(), |(at, _)| at);
+
+    let latitude: f64 = latitude.parse().ok()?;
+    let longitude: f64 = rest[..longitude_end].parse().ok()?;
+    ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude))
+        .then_some((latitude, longitude))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// ffprobe prints most numbers as strings; accept both forms
+fn number_field(value: &Value, key: &str) -> Option<u64> {
+    let field = value.get(key)?;
+    field
+        .as_u64()
+        .or_else(|| field.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn seconds_to_ms(value: Option<&Value>) -> Option<u64> {
+    let value = value?;
+    let seconds = value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse::<f64>().ok()))?;
+
+    if seconds.is_finite() && seconds >= 0.0 {
+        Some((seconds * 1000.0).round() as u64)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probe_output() {
+        let json = r#"{
+            "streams": [
+                {
+                    "codec_type": "video",
+                    "codec_name": "h264",
+                    "width": 1920,
+                    "height": 1080,
+                    "tags": { "rotate": "90" }
+                },
+                { "codec_type": "audio", "codec_name": "aac" }
+            ],
+            "format": {
+                "format_name": "mov,mp4,m4a,3gp,3g2,mj2",
+                "duration": "12.345000",
+                "bit_rate": "17000000",
+                "tags": { "creation_time": "2024-01-30T10:30:00.000000Z" }
+            }
+        }"#;
+
+        let result = parse_probe_output(json).unwrap();
+        assert_eq!(result.video_codec, Some("h264".to_string()));
+        assert_eq!(result.audio_codec, Some("aac".to_string()));
+        assert_eq!(result.width, Some(1920));
+        assert_eq!(result.height, Some(1080));
+        assert_eq!(result.rotation, Some(90));
+        assert_eq!(result.duration_ms, Some(12345));
+        assert_eq!(result.bit_rate, Some(17_000_000));
+        assert_eq!(
+            result.creation_time,
+            Some("2024-01-30T10:30:00.000000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_iso6709() {
+        assert_eq!(
+            parse_iso6709("+37.4219-122.0840/"),
+            Some((37.4219, -122.084))
+        );
+        assert_eq!(
+            parse_iso6709("-33.8688+151.2093+012.000/"),
+            Some((-33.8688, 151.2093))
+        );
+        assert_eq!(parse_iso6709("+99.0000+000.0000/"), None);
+        assert_eq!(parse_iso6709("somewhere"), None);
+    }
+
+    #[test]
+    fn test_parse_probe_output_invalid() {
+        assert!(parse_probe_output("not json").is_err());
+    }
+}
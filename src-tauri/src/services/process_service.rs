@@ -0,0 +1,227 @@
+//! Process helpers shared by all tool invocations
+//!
+//! Every external tool is started through [`spawn_hidden`] or
+//! [`output_hidden`], so none of them flashes a console window on Windows.
+//! Runs to completion are counted per tool (see [`tool_metrics`]); long-lived
+//! processes such as scrcpy sessions are not.
+
+use crate::domain::models::ToolMetrics;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Keeps console tools such as adb and ffmpeg from flashing a window
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// How often a running process is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Timeout for quick `--version` style checks of external tools
+pub const TOOL_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Calls of one tool so far
+#[derive(Debug, Default, Clone, Copy)]
+struct ToolTally {
+    calls: u64,
+    failures: u64,
+    timeouts: u64,
+    total: Duration,
+    max: Duration,
+}
+
+/// Run a command to completion, killing it if it runs longer than `timeout`
+///
+/// Returns `Ok(None)` when the timeout elapsed. stdout/stderr are always captured.
+pub fn output_with_timeout(
+    cmd: &mut Command,
+    timeout: Duration,
+) -> std::io::Result<Option<Output>> {
+    let started = Instant::now();
+    let result = run_with_timeout(cmd, timeout);
+    let succeeded = matches!(&result, Ok(Some(output)) if output.status.success());
+    record_call(
+        &tool_name(cmd),
+        started.elapsed(),
+        succeeded,
+        matches!(result, Ok(None)),
+    );
+    result
+}
+
+/// Call counts, durations and failure rates per tool since the app started,
+/// by tool name
+pub fn tool_metrics() -> Vec<ToolMetrics> {
+    let tallies = tallies().lock().unwrap_or_else(PoisonError::into_inner);
+    let mut metrics: Vec<ToolMetrics> = tallies
+        .iter()
+        .map(|(tool, tally)| ToolMetrics {
+            tool: tool.clone(),
+            calls: tally.calls,
+            failures: tally.failures,
+            timeouts: tally.timeouts,
+            failure_rate: tally.failures as f64 / tally.calls.max(1) as f64,
+            total_ms: tally.total.as_millis() as u64,
+            average_ms: (tally.total.as_millis() / u128::from(tally.calls.max(1))) as u64,
+            max_ms: tally.max.as_millis() as u64,
+        })
+        .collect();
+    metrics.sort_by(|a, b| a.tool.cmp(&b.tool));
+    metrics
+}
+
+/// The name a command's calls are counted under: the program's file name
+/// without extension, so `C:\platform-tools\adb.exe` counts as `adb`
+pub fn tool_name(cmd: &Command) -> String {
+    let program = Path::new(cmd.get_program());
+    program
+        .file_stem()
+        .unwrap_or(program.as_os_str())
+        .to_string_lossy()
+        .to_lowercase()
+}
+
+fn record_call(tool: &str, elapsed: Duration, succeeded: bool, timed_out: bool) {
+    let mut tallies = tallies().lock().unwrap_or_else(PoisonError::into_inner);
+    let tally = tallies.entry(tool.to_string()).or_default();
+    tally.calls += 1;
+    tally.failures += u64::from(!succeeded);
+    tally.timeouts += u64::from(timed_out);
+    tally.total += elapsed;
+    tally.max = tally.max.max(elapsed);
+}
+
+/// Tools are run from commands and background threads alike, so the counts
+/// live for the whole process
+fn tallies() -> &'static Mutex<HashMap<String, ToolTally>> {
+    static TALLIES: OnceLock<Mutex<HashMap<String, ToolTally>>> = OnceLock::new();
+    TALLIES.get_or_init(Mutex::default)
+}
+
+fn run_with_timeout(cmd: &mut Command, timeout: Duration) -> std::io::Result<Option<Output>> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn()?;
+
+    // Drain pipes on threads so a chatty process can't block on a full pipe buffer
+    let stdout = child.stdout.take().map(read_to_end_in_background);
+    let stderr = child.stderr.take().map(read_to_end_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: join_reader(stdout),
+        stderr: join_reader(stderr),
+    }))
+}
+
+/// Start a command without a console window on Windows
+pub fn spawn_hidden(cmd: &mut Command) -> std::io::Result<Child> {
+    hide_window(cmd).spawn()
+}
+
+/// [`output_with_timeout`] without a console window on Windows
+pub fn output_hidden(cmd: &mut Command, timeout: Duration) -> std::io::Result<Option<Output>> {
+    output_with_timeout(hide_window(cmd), timeout)
+}
+
+fn hide_window(cmd: &mut Command) -> &mut Command {
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+fn read_to_end_in_background<R: Read + Send + 'static>(mut reader: R) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf);
+        buf
+    })
+}
+
+fn join_reader(handle: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    handle.and_then(|h| h.join().ok()).unwrap_or_default()
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_with_timeout_captures_output() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2"]);
+
+        let output = output_with_timeout(&mut cmd, Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+    }
+
+    #[test]
+    fn test_output_with_timeout_kills_hung_process() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+
+        let started = Instant::now();
+        let output = output_with_timeout(&mut cmd, Duration::from_millis(200)).unwrap();
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_hidden_helpers_run_the_command() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo hidden"]);
+        let output = output_hidden(&mut cmd, Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hidden\n");
+
+        let mut child = spawn_hidden(Command::new("true").stdout(Stdio::null())).unwrap();
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn test_tool_metrics_count_calls_and_failures() {
+        let mut cmd = Command::new("/bin/sh");
+        assert_eq!(tool_name(&cmd), "sh");
+        cmd.args(["-c", "exit 3"]);
+        let before = tool_metrics()
+            .into_iter()
+            .find(|m| m.tool == "sh")
+            .map_or(0, |m| m.failures);
+        output_with_timeout(&mut cmd, Duration::from_secs(5)).unwrap();
+
+        let sh = tool_metrics().into_iter().find(|m| m.tool == "sh").unwrap();
+        assert!(sh.calls >= 1);
+        assert!(sh.failures > before);
+        assert!(sh.failure_rate > 0.0 && sh.failure_rate <= 1.0);
+        assert!(sh.max_ms >= sh.average_ms);
+    }
+}
@@ -0,0 +1,102 @@
+//! Aggregate progress reporting for multi-file operations
+
+use crate::domain::models::{AffectedItem, BatchKind, BatchProgress};
+use crate::services::platform_integration_service;
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted with a [`BatchProgress`] payload
+pub const BATCH_PROGRESS_EVENT: &str = "batch-progress";
+
+/// Receives progress updates; services stay independent of Tauri
+pub type ProgressSink<'a> = &'a dyn Fn(&BatchProgress);
+
+/// Sink that forwards updates to the frontend as events and to the
+/// taskbar progress bar
+pub fn emit_progress(app: &AppHandle) -> impl Fn(&BatchProgress) + '_ {
+    move |progress| {
+        let _ = app.emit(BATCH_PROGRESS_EVENT, progress);
+        platform_integration_service::show_batch_progress(app, progress);
+    }
+}
+
+/// Tracks one batch and reports each change to a sink
+pub struct BatchTracker<'a> {
+    progress: BatchProgress,
+    sink: ProgressSink<'a>,
+}
+
+impl<'a> BatchTracker<'a> {
+    pub fn new(
+        kind: BatchKind,
+        total_files: usize,
+        total_bytes: u64,
+        sink: ProgressSink<'a>,
+    ) -> Self {
+        let progress = BatchProgress {
+            batch_id: format!("batch_{}", chrono::Utc::now().timestamp_millis()),
+            kind,
+            total_files,
+            completed_files: 0,
+            total_bytes,
+            completed_bytes: 0,
+            current_file: None,
+            failures: Vec::new(),
+            finished: false,
+        };
+        sink(&progress);
+        Self { progress, sink }
+    }
+
+    pub fn start_file(&mut self, name: &str) {
+        self.progress.current_file = Some(name.to_string());
+        (self.sink)(&self.progress);
+    }
+
+    /// Mark a file done; `error` records it among the failures
+    pub fn finish_file(&mut self, path: &str, size_bytes: u64, error: Option<String>) {
+        self.progress.completed_files += 1;
+        self.progress.completed_bytes += size_bytes;
+        if error.is_some() {
+            self.progress.failures.push(AffectedItem {
+                path: path.to_string(),
+                size_bytes,
+                error,
+            });
+        }
+        (self.sink)(&self.progress);
+    }
+
+    /// Emit the final update and return it
+    pub fn finish(mut self) -> BatchProgress {
+        self.progress.current_file = None;
+        self.progress.finished = true;
+        (self.sink)(&self.progress);
+        self.progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_tracker_reports_each_step() {
+        let seen = RefCell::new(Vec::new());
+        let sink = |p: &BatchProgress| seen.borrow_mut().push(p.clone());
+
+        let mut tracker = BatchTracker::new(BatchKind::Push, 2, 30, &sink);
+        tracker.start_file("a.txt");
+        tracker.finish_file("C:/a.txt", 10, None);
+        tracker.start_file("b.txt");
+        tracker.finish_file("C:/b.txt", 20, Some("device offline".to_string()));
+        let last = tracker.finish();
+
+        assert_eq!(seen.borrow().len(), 6);
+        assert_eq!(seen.borrow()[1].current_file.as_deref(), Some("a.txt"));
+        assert!(last.finished);
+        assert_eq!(last.completed_bytes, 30);
+        assert_eq!(last.completed_files, 2);
+        assert_eq!(last.failures.len(), 1);
+    }
+}
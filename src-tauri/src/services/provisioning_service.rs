@@ -0,0 +1,191 @@
+//! Apply provisioning profiles: install APKs, push files, set Android settings
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    BatchKind, ConflictPolicy, ProvisioningProfile, ProvisioningReport, ProvisioningStepKind,
+    ProvisioningStepResult, TransferStatus,
+};
+use crate::services::adb_service::AdbRunner;
+use crate::services::progress_service::{BatchTracker, ProgressSink};
+use crate::services::transfer_service::{self, TransferState};
+use crate::services::{device_settings_service, install_service};
+
+/// Run every step of a profile on one device. Steps run in order (installs,
+/// pushes, settings) and a failed step doesn't stop the rest.
+pub fn apply_profile(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+    profile: &ProvisioningProfile,
+    progress: ProgressSink,
+) -> ProvisioningReport {
+    let total = profile.apks.len() + profile.files.len() + profile.settings.len();
+    let mut tracker = BatchTracker::new(BatchKind::Provision, total, 0, progress);
+    let mut steps = Vec::with_capacity(total);
+
+    for apk in &profile.apks {
+        tracker.start_file(apk);
+        let result = install_service::install_apk(adb, serial, apk);
+        let outcome = if result.success {
+            Ok(result.message)
+        } else {
+            Err(result.message)
+        };
+        record(
+            &mut tracker,
+            &mut steps,
+            ProvisioningStepKind::Install,
+            apk.clone(),
+            outcome,
+        );
+    }
+
+    for file in &profile.files {
+        tracker.start_file(&file.local_path);
+        let outcome =
+            push(state, adb, serial, &file.local_path, &file.device_dir).map_err(|e| e.to_string());
+        record(
+            &mut tracker,
+            &mut steps,
+            ProvisioningStepKind::Push,
+            file.local_path.clone(),
+            outcome,
+        );
+    }
+
+    for setting in &profile.settings {
+        let target = format!("{}/{}", setting.namespace.as_str(), setting.key);
+        tracker.start_file(&target);
+        let outcome = device_settings_service::put_setting(adb, serial, setting)
+            .map(|_| format!("Set to {}", setting.value))
+            .map_err(|e| e.to_string());
+        record(
+            &mut tracker,
+            &mut steps,
+            ProvisioningStepKind::Setting,
+            target,
+            outcome,
+        );
+    }
+
+    tracker.finish();
+
+    let succeeded = steps.iter().filter(|s| s.success).count();
+    ProvisioningReport {
+        serial: serial.to_string(),
+        profile_id: profile.id.clone(),
+        profile_name: profile.name.clone(),
+        failed: steps.len() - succeeded,
+        succeeded,
+        steps,
+    }
+}
+
+fn record(
+    tracker: &mut BatchTracker,
+    steps: &mut Vec<ProvisioningStepResult>,
+    kind: ProvisioningStepKind,
+    target: String,
+    outcome: Result<String, String>,
+) {
+    let (success, message) = match outcome {
+        Ok(message) => (true, message),
+        Err(message) => (false, message),
+    };
+    tracker.finish_file(&target, 0, (!success).then(|| message.clone()));
+    steps.push(ProvisioningStepResult {
+        kind,
+        target,
+        success,
+        message,
+    });
+}
+
+fn push(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+    local_path: &str,
+    device_dir: &str,
+) -> Result<String, AppError> {
+    let dest_dir = transfer_service::normalize_device_dir(device_dir)?;
+    let item = transfer_service::push_file(
+        state,
+        adb,
+        serial,
+        local_path,
+        &dest_dir,
+        ConflictPolicy::Overwrite,
+    )?;
+
+    match item.status {
+        TransferStatus::Complete => Ok(format!("Pushed to {}", item.dest_path)),
+        _ => Err(AppError::TransferError(
+            item.error.unwrap_or_else(|| "Push failed".to_string()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{
+        BatchProgress, DeviceSetting, ProvisioningFile, SettingsNamespace,
+    };
+    use crate::services::adb_service::mock::MockAdb;
+    use std::cell::RefCell;
+
+    fn profile() -> ProvisioningProfile {
+        ProvisioningProfile {
+            id: "p1".to_string(),
+            name: "Kiosk".to_string(),
+            apks: vec!["C:/apps/kiosk.apk".to_string()],
+            files: vec![ProvisioningFile {
+                local_path: "C:/docs/welcome.pdf".to_string(),
+                device_dir: "/sdcard/Documents".to_string(),
+            }],
+            settings: vec![
+                DeviceSetting {
+                    namespace: SettingsNamespace::System,
+                    key: "screen_off_timeout".to_string(),
+                    value: "600000".to_string(),
+                },
+                DeviceSetting {
+                    namespace: SettingsNamespace::Global,
+                    key: "bad; reboot".to_string(),
+                    value: "1".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_apply_profile_reports_each_step() {
+        let adb = MockAdb::new()
+            .on("install", "Success")
+            .on("push", "1 file pushed")
+            .on("settings put", "");
+        let seen = RefCell::new(Vec::new());
+        let sink = |p: &BatchProgress| seen.borrow_mut().push(p.clone());
+
+        let report = apply_profile(&TransferState::default(), &adb, "SER1", &profile(), &sink);
+
+        assert_eq!(report.steps.len(), 4);
+        assert_eq!(report.succeeded, 3);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.steps[3].kind, ProvisioningStepKind::Setting);
+        assert!(!report.steps[3].success);
+
+        // The invalid key never reaches the device
+        let calls = adb.calls();
+        assert!(calls
+            .iter()
+            .any(|c| c == "-s SER1 shell settings put system screen_off_timeout '600000'"));
+        assert!(!calls.iter().any(|c| c.contains("reboot")));
+
+        let last = seen.borrow().last().cloned().unwrap();
+        assert!(last.finished);
+        assert_eq!(last.completed_files, 4);
+        assert_eq!(last.failures.len(), 1);
+    }
+}
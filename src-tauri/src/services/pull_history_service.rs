@@ -0,0 +1,197 @@
+//! Which device files were pulled to this computer, and where to
+//!
+//! Lets the media grid badge files that are already on the PC and lets pulls
+//! skip them. Kept in its own file next to the settings like the device
+//! timeline; recording is best effort and never fails the pull.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{MediaTransferResult, PulledFile};
+use crate::services::{hash_service, state_file_service};
+use crate::state::{read_lock, write_lock};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+
+const HISTORY_FILE: &str = "pull_history.json";
+
+/// Latest pull per device file, per device serial
+pub type PullHistory = HashMap<String, HashMap<String, PulledFile>>;
+
+/// History loaded from disk on first use
+#[derive(Default)]
+pub struct PullHistoryState {
+    history: RwLock<Option<PullHistory>>,
+}
+
+/// Add the successful pulls of a batch to a device's history
+pub fn add_pulls(
+    device: &mut HashMap<String, PulledFile>,
+    results: &[MediaTransferResult],
+    pulled_at: &str,
+    hash: impl Fn(&Path) -> Option<String>,
+) {
+    for result in results.iter().filter(|r| r.success && !r.skipped) {
+        let Some(local_path) = &result.dest_path else {
+            continue;
+        };
+        device.insert(
+            result.source_path.clone(),
+            PulledFile {
+                remote_path: result.source_path.clone(),
+                size_bytes: result.size_bytes,
+                sha256: hash(Path::new(local_path)),
+                local_path: local_path.clone(),
+                pulled_at: pulled_at.to_string(),
+                local_exists: true,
+            },
+        );
+    }
+}
+
+/// Whether a device file of this size was pulled before and the copy is still there
+pub fn already_pulled(device: &HashMap<String, PulledFile>, remote_path: &str, size: u64) -> bool {
+    device
+        .get(remote_path)
+        .is_some_and(|p| p.size_bytes == size && Path::new(&p.local_path).exists())
+}
+
+/// Record a batch of pulls for a device
+pub fn record_pulls(app: &AppHandle, serial: &str, results: &[MediaTransferResult]) {
+    let pulled_at = chrono::Utc::now().to_rfc3339();
+    // The pull itself succeeded; a lost history entry only means the files
+    // are offered again next time
+    let _ = with_history(app, |history| {
+        let device = history.entry(serial.to_string()).or_default();
+        add_pulls(device, results, &pulled_at, |path| {
+            hash_service::sha256_file(path).ok()
+        });
+    });
+}
+
+/// A device's pulled files, most recent first
+pub fn get_history(app: &AppHandle, serial: &str) -> Result<Vec<PulledFile>, AppError> {
+    let mut files: Vec<PulledFile> = device_history(app, serial)?.into_values().collect();
+    for file in &mut files {
+        file.local_exists = Path::new(&file.local_path).exists();
+    }
+    files.sort_by(|a, b| b.pulled_at.cmp(&a.pulled_at));
+    Ok(files)
+}
+
+/// A device's history keyed by device path
+pub fn device_history(
+    app: &AppHandle,
+    serial: &str,
+) -> Result<HashMap<String, PulledFile>, AppError> {
+    let state = app.state::<PullHistoryState>();
+    if let Some(history) = read_lock(&state.history).as_ref() {
+        return Ok(history.get(serial).cloned().unwrap_or_default());
+    }
+
+    let mut cached = write_lock(&state.history);
+    let history = match cached.as_ref() {
+        Some(history) => history,
+        None => cached.insert(load(app)?),
+    };
+    Ok(history.get(serial).cloned().unwrap_or_default())
+}
+
+/// Update the history and write it back to disk
+fn with_history(app: &AppHandle, update: impl FnOnce(&mut PullHistory)) -> Result<(), AppError> {
+    let state = app.state::<PullHistoryState>();
+    let mut cached = write_lock(&state.history);
+    let history = match cached.as_mut() {
+        Some(history) => history,
+        None => cached.insert(load(app)?),
+    };
+    update(history);
+    save(app, history)
+}
+
+fn history_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::SettingsError(format!("Failed to get config dir: {}", e)))?;
+    fs::create_dir_all(&config_dir)?;
+    Ok(config_dir.join(HISTORY_FILE))
+}
+
+fn load(app: &AppHandle) -> Result<PullHistory, AppError> {
+    let path = history_path(app)?;
+    Ok(state_file_service::load_json(app, &path)?.unwrap_or_else(PullHistory::new))
+}
+
+fn save(app: &AppHandle, history: &PullHistory) -> Result<(), AppError> {
+    let content = serde_json::to_string(history)
+        .map_err(|e| AppError::SettingsError(format!("Failed to serialize pull history: {}", e)))?;
+    state_file_service::write_atomic(&history_path(app)?, content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(
+        source: &str,
+        dest: Option<&str>,
+        success: bool,
+        skipped: bool,
+    ) -> MediaTransferResult {
+        MediaTransferResult {
+            source_path: source.to_string(),
+            dest_path: dest.map(str::to_string),
+            success,
+            error: None,
+            size_bytes: 2048,
+            skipped,
+            motion_video_path: None,
+            renamed_from: None,
+        }
+    }
+
+    #[test]
+    fn test_add_pulls_records_successes_only() {
+        let mut device = HashMap::new();
+        add_pulls(
+            &mut device,
+            &[
+                result("/sdcard/DCIM/a.jpg", Some("/home/u/a.jpg"), true, false),
+                result("/sdcard/DCIM/b.jpg", None, false, false),
+                result("/sdcard/DCIM/c.jpg", Some("/home/u/c.jpg"), false, true),
+            ],
+            "2026-10-16T09:00:00+00:00",
+            |_| Some("abc".to_string()),
+        );
+
+        assert_eq!(device.len(), 1);
+        let a = &device["/sdcard/DCIM/a.jpg"];
+        assert_eq!(a.local_path, "/home/u/a.jpg");
+        assert_eq!(a.sha256.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_already_pulled_needs_same_size_and_local_copy() {
+        let dir = std::env::temp_dir().join("gesu_pull_history_test");
+        fs::create_dir_all(&dir).unwrap();
+        let local = dir.join("a.jpg");
+        fs::write(&local, b"x").unwrap();
+
+        let mut device = HashMap::new();
+        add_pulls(
+            &mut device,
+            &[result("/sdcard/DCIM/a.jpg", local.to_str(), true, false)],
+            "2026-10-16T09:00:00+00:00",
+            |_| None,
+        );
+
+        assert!(already_pulled(&device, "/sdcard/DCIM/a.jpg", 2048));
+        assert!(!already_pulled(&device, "/sdcard/DCIM/a.jpg", 4096));
+        assert!(!already_pulled(&device, "/sdcard/DCIM/b.jpg", 2048));
+
+        fs::remove_file(&local).unwrap();
+        assert!(!already_pulled(&device, "/sdcard/DCIM/a.jpg", 2048));
+    }
+}
@@ -0,0 +1,182 @@
+//! Quick Share hand-offs: sending local files with Google's Quick Share for
+//! Windows when neither adb nor Bluetooth is convenient
+//!
+//! Quick Share is started with the files as arguments and the user picks
+//! the phone in its window. It doesn't report what happened and keeps running
+//! in the tray, so the files go straight to the history as handed off.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{QuickShareStatus, TransferItem, TransferStatus};
+use crate::services::bluetooth_service;
+use crate::services::transfer_service::{self, TransferState};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// Where Quick Share installs; the app kept the Nearby Share folder and
+/// executable names when it was renamed
+fn install_paths() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("C:/Program Files/Google/NearbyShare/nearby_share.exe"),
+        PathBuf::from("C:/Program Files (x86)/Google/NearbyShare/nearby_share.exe"),
+    ]
+}
+
+/// The first of `candidates` that exists
+pub fn find_installed(candidates: &[PathBuf]) -> Option<PathBuf> {
+    candidates.iter().find(|path| path.is_file()).cloned()
+}
+
+/// Whether Quick Share is installed, and where
+pub fn get_status() -> QuickShareStatus {
+    let path = find_installed(&install_paths());
+    QuickShareStatus {
+        installed: path.is_some(),
+        path: path.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// Local files to send with their sizes; every path must be an existing file
+pub fn files_to_send(paths: &[String]) -> Result<Vec<(PathBuf, u64)>, AppError> {
+    if paths.is_empty() {
+        return Err(AppError::InvalidPath("No files to send".to_string()));
+    }
+    paths
+        .iter()
+        .map(|path| {
+            let path = PathBuf::from(path);
+            match std::fs::metadata(&path) {
+                Ok(metadata) if metadata.is_file() => Ok((path, metadata.len())),
+                _ => Err(AppError::InvalidPath(format!(
+                    "Not a file: {}",
+                    path.display()
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Open Quick Share with `paths` preselected and record them in the
+/// transfer history. `device_name` is the phone the user means to send to,
+/// if known.
+pub fn send(
+    app: &AppHandle,
+    paths: &[String],
+    device_name: Option<String>,
+) -> Result<(), AppError> {
+    let files = files_to_send(paths)?;
+    let exe = find_installed(&install_paths()).ok_or_else(|| {
+        AppError::InvalidPath("Quick Share for Windows is not installed".to_string())
+    })?;
+
+    let exe = exe.to_string_lossy().to_string();
+    let args: Vec<String> = files
+        .iter()
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    bluetooth_service::spawn_handoff(&exe, &args, "Quick Share")?;
+
+    let items = handoff_items(
+        &transfer_service::generate_id(),
+        &files,
+        device_name.as_deref(),
+        chrono::Utc::now().to_rfc3339(),
+    );
+    transfer_service::record_external(&app.state::<TransferState>(), items);
+    Ok(())
+}
+
+/// History entries for files passed to Quick Share
+pub fn handoff_items(
+    id: &str,
+    files: &[(PathBuf, u64)],
+    device_name: Option<&str>,
+    started_at: String,
+) -> Vec<TransferItem> {
+    let device = device_name.unwrap_or("Quick Share");
+    files
+        .iter()
+        .enumerate()
+        .map(|(i, (path, size))| TransferItem {
+            id: format!("{}_{}", id, i),
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            source_path: path.to_string_lossy().to_string(),
+            dest_path: device.to_string(),
+            size_bytes: *size,
+            transferred_bytes: 0,
+            status: TransferStatus::HandedOff,
+            error: None,
+            started_at: started_at.clone(),
+            device_serial: device.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gesu_qs_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_find_installed() {
+        let dir = temp_dir("find");
+        let exe = dir.join("nearby_share.exe");
+        let candidates = vec![dir.join("missing.exe"), exe.clone()];
+        assert_eq!(find_installed(&candidates), None);
+
+        std::fs::write(&exe, b"MZ").unwrap();
+        assert_eq!(find_installed(&candidates), Some(exe));
+        // A folder of that name isn't the app
+        assert_eq!(find_installed(std::slice::from_ref(&dir)), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_files_to_send() {
+        let dir = temp_dir("files");
+        let photo = dir.join("IMG_0001.jpg");
+        std::fs::write(&photo, b"jpeg").unwrap();
+        let photo_path = photo.to_string_lossy().to_string();
+
+        assert_eq!(
+            files_to_send(std::slice::from_ref(&photo_path)).unwrap(),
+            vec![(photo.clone(), 4)]
+        );
+        assert!(files_to_send(&[]).is_err());
+        assert!(files_to_send(&[dir.to_string_lossy().to_string()]).is_err());
+        assert!(files_to_send(&[
+            photo_path,
+            dir.join("gone.jpg").to_string_lossy().to_string()
+        ])
+        .is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_handoff_items() {
+        let items = handoff_items(
+            "transfer_1",
+            &[
+                (PathBuf::from("/home/me/IMG_0001.jpg"), 2048),
+                (PathBuf::from("/home/me/notes.txt"), 12),
+            ],
+            Some("Pixel 7"),
+            "2026-10-16T10:00:00+00:00".to_string(),
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].file_name, "IMG_0001.jpg");
+        assert_eq!(items[0].dest_path, "Pixel 7");
+        assert_eq!(items[0].status, TransferStatus::HandedOff);
+        assert_eq!(items[1].id, "transfer_1_1");
+        assert_eq!(items[1].size_bytes, 12);
+    }
+}
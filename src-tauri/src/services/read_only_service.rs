@@ -0,0 +1,330 @@
+//! Read-only (guest) mode: which adb invocations change the device
+//!
+//! With `read_only_mode` on, [`AdbCli`](crate::services::adb_service::AdbCli)
+//! refuses anything listed here, so browsing, pulling and mirroring keep
+//! working on someone else's phone while pushes, deletes, installs, app
+//! launches, screen pinning and input fail with [`AppError::ReadOnlyMode`].
+
+use crate::domain::errors::AppError;
+
+/// Scratch folder the app writes its own temp files to and removes them from
+const DEVICE_TEMP_DIR: &str = "/data/local/tmp/";
+
+/// Top-level adb commands that change the device
+const MUTATING_COMMANDS: &[&str] = &[
+    "push",
+    "install",
+    "install-multiple",
+    "uninstall",
+    "sideload",
+    "reboot",
+    "tcpip",
+    "usb",
+    "root",
+    "remount",
+    "disable-verity",
+    "enable-verity",
+];
+
+/// Shell tools that change files, state or inject input whatever their arguments
+const MUTATING_TOOLS: &[&str] = &[
+    "mv", "cp", "mkdir", "touch", "chmod", "chown", "ln", "input", "monkey", "svc", "kill",
+    "killall", "setprop", "reboot",
+];
+
+/// Subcommand prefixes of `pm` / `cmd <service>` that only read
+const READ_VERBS: &[&str] = &[
+    "list", "path", "dump", "get", "query", "resolve", "has", "is", "status", "help",
+];
+
+/// Fail with [`AppError::ReadOnlyMode`] if the adb arguments would change the device
+pub fn check(args: &[&str]) -> Result<(), AppError> {
+    match mutating_operation(args) {
+        Some(operation) => Err(AppError::ReadOnlyMode(format!(
+            "\"{}\" is disabled in read-only mode",
+            operation
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The part of an adb invocation that would change the device, if any
+pub fn mutating_operation(args: &[&str]) -> Option<String> {
+    let args = match args {
+        ["-s", _, rest @ ..] => rest,
+        _ => args,
+    };
+    let (command, rest) = args.split_first()?;
+
+    if MUTATING_COMMANDS.contains(command) {
+        return Some(command.to_string());
+    }
+    // exec-out runs a shell command too, only without the shell's line-ending conversion
+    if *command != "shell" && *command != "exec-out" {
+        return None;
+    }
+
+    // Shell commands arrive either as separate arguments or as one string
+    let line = rest.join(" ");
+    shell_commands(&line)
+        .into_iter()
+        .find(|command| command.writes_file() || segment_mutates(&command.words))
+        .map(|command| command.to_string())
+}
+
+/// One command of a shell line, with its quoting removed
+#[derive(Debug, Default)]
+struct ShellCommand {
+    words: Vec<String>,
+    /// Files written with `>` or `>>`
+    redirects: Vec<String>,
+}
+
+impl ShellCommand {
+    /// Whether it writes a file other than the app's temp files and `/dev/null`
+    fn writes_file(&self) -> bool {
+        self.redirects
+            .iter()
+            .any(|target| target != "/dev/null" && !in_temp_dir(target))
+    }
+}
+
+impl std::fmt::Display for ShellCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.words.join(" "))?;
+        for target in &self.redirects {
+            write!(f, " > {}", target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a shell line into its commands the way the device shell would, so
+/// `'rm' '-rf' '/sdcard'` is seen as `rm -rf /sdcard` while a `;` or `>`
+/// inside quotes stays part of an argument
+fn shell_commands(line: &str) -> Vec<ShellCommand> {
+    let mut commands = Vec::new();
+    let mut command = ShellCommand::default();
+    let mut word: Option<String> = None;
+    let mut redirecting = false;
+
+    fn finish_word(word: &mut Option<String>, command: &mut ShellCommand, redirecting: &mut bool) {
+        if let Some(word) = word.take() {
+            if std::mem::take(redirecting) {
+                command.redirects.push(word);
+            } else {
+                command.words.push(word);
+            }
+        }
+    }
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            '>' => {
+                // A file descriptor number belongs to the operator (`2>`)
+                if word
+                    .as_ref()
+                    .is_some_and(|w| w.chars().all(|c| c.is_ascii_digit()))
+                {
+                    word = None;
+                }
+                finish_word(&mut word, &mut command, &mut redirecting);
+                chars.next_if_eq(&'>');
+                if chars.next_if_eq(&'&').is_some() {
+                    // `>&2` points at another descriptor, not a file
+                    while chars.next_if(|c| c.is_ascii_digit() || *c == '-').is_some() {}
+                } else {
+                    redirecting = true;
+                }
+            }
+            ';' | '|' | '&' | '\n' | '(' | ')' | '`' => {
+                finish_word(&mut word, &mut command, &mut redirecting);
+                redirecting = false;
+                commands.push(std::mem::take(&mut command));
+            }
+            c if c.is_whitespace() => finish_word(&mut word, &mut command, &mut redirecting),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    finish_word(&mut word, &mut command, &mut redirecting);
+    commands.push(command);
+    commands.retain(|command| !command.words.is_empty() || !command.redirects.is_empty());
+    commands
+}
+
+/// Whether a device path is inside the temp folder once `.` and `..` are
+/// resolved
+fn in_temp_dir(path: &str) -> bool {
+    if !path.starts_with('/') {
+        return false;
+    }
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    format!("/{}", parts.join("/")).starts_with(DEVICE_TEMP_DIR)
+}
+
+fn segment_mutates(words: &[String]) -> bool {
+    let Some((tool, rest)) = words.split_first() else {
+        return false;
+    };
+    let sub = rest.first().map_or("", String::as_str);
+
+    match tool.as_str() {
+        "rm" | "rmdir" => rest
+            .iter()
+            .filter(|arg| !arg.starts_with('-'))
+            .any(|path| !in_temp_dir(path)),
+        "pm" => !is_read_verb(sub),
+        "cmd" => !is_read_verb(rest.get(1).map_or("", String::as_str)),
+        "settings" => matches!(sub, "put" | "delete" | "reset"),
+        "content" => matches!(sub, "insert" | "update" | "delete"),
+        // `start*` launches apps and `task lock` pins the screen
+        "am" => {
+            sub.starts_with("start")
+                || matches!(
+                    sub,
+                    "task" | "force-stop" | "kill" | "kill-all" | "clear-debug-app"
+                )
+        }
+        tool => MUTATING_TOOLS.contains(&tool),
+    }
+}
+
+fn is_read_verb(verb: &str) -> bool {
+    !verb.is_empty() && READ_VERBS.iter().any(|read| verb.starts_with(read))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_are_allowed() {
+        for args in [
+            vec!["devices", "-l"],
+            vec!["-s", "SER1", "pull", "/sdcard/DCIM/a.jpg", "/tmp"],
+            vec!["-s", "SER1", "shell", "ls", "-la", "/sdcard"],
+            vec!["-s", "SER1", "exec-out", "ls", "-la", "'/sdcard/DCIM'"],
+            vec!["-s", "SER1", "shell", "pm", "list", "packages", "-U"],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "settings",
+                "get",
+                "global",
+                "adb_enabled",
+            ],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "cmd",
+                "package",
+                "get-app-links",
+                "x.y",
+            ],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "rm",
+                "-f",
+                "'/data/local/tmp/shot.png'",
+            ],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "content read --uri x > /data/local/tmp/t.jpg",
+            ],
+            vec![
+                "-s",
+                "SER1",
+                "exec-out",
+                "dd if=/dev/zero bs=65536 count=32 2>/dev/null",
+            ],
+            vec!["-s", "SER1", "shell", "'echo'", "'a; reboot > /sdcard/f'"],
+            vec!["-s", "SER1", "shell", "ls /sdcard 2>&1 | grep DCIM"],
+            vec!["-s", "SER1", "shell", "am", "get-current-user"],
+        ] {
+            assert_eq!(mutating_operation(&args), None, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_writes_are_refused() {
+        for args in [
+            vec!["-s", "SER1", "push", "a.jpg", "/sdcard/"],
+            vec!["-s", "SER1", "install", "-r", "app.apk"],
+            vec!["-s", "SER1", "tcpip", "5555"],
+            vec!["-s", "SER1", "shell", "rm", "-f", "'/sdcard/DCIM/a.jpg'"],
+            vec!["-s", "SER1", "shell", "input", "keyevent", "26"],
+            vec!["-s", "SER1", "exec-out", "rm -f '/sdcard/DCIM/a.jpg'"],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "pm",
+                "disable-user",
+                "--user",
+                "0",
+                "x.y",
+            ],
+            vec!["-s", "SER1", "shell", "settings", "put", "system", "k", "1"],
+            vec!["-s", "SER1", "shell", "cmd", "wifi", "start-softap", "'AP'"],
+            vec!["-s", "SER1", "shell", "ls /sdcard; rm -rf /sdcard/Music"],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "rm",
+                "'/data/local/tmp/../../sdcard/DCIM/x'",
+            ],
+            vec!["-s", "SER1", "shell", "echo x > /sdcard/f"],
+            vec!["-s", "SER1", "shell", "'rm'", "'-rf'", "'/sdcard/DCIM'"],
+            vec!["-s", "SER1", "shell", "\"pm\" uninstall x.y"],
+            vec!["-s", "SER1", "shell", "'echo' 'x' > '/sdcard/f'"],
+            vec!["-s", "SER1", "shell", "ls $(rm -rf /sdcard/Music)"],
+            vec!["-s", "SER1", "shell", "am", "task", "lock", "231"],
+            vec!["-s", "SER1", "shell", "am", "start", "-n", "x.y/.Main"],
+            vec!["-s", "SER1", "shell", "echo x >>/sdcard/f"],
+            vec![
+                "-s",
+                "SER1",
+                "shell",
+                "cat a > /data/local/tmp/../../sdcard/b",
+            ],
+        ] {
+            assert!(mutating_operation(&args).is_some(), "{:?}", args);
+        }
+
+        let err = check(&["-s", "SER1", "shell", "input", "tap", "1", "2"]).unwrap_err();
+        assert!(matches!(err, AppError::ReadOnlyMode(_)));
+        assert!(err.to_string().contains("input tap 1 2"));
+    }
+}
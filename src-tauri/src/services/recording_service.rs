@@ -0,0 +1,241 @@
+//! Library of screen, camera and microphone recordings made by scrcpy
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{Recording, RecordingKind};
+use crate::services::screenshot_service::file_safe_serial;
+use crate::services::{probe_service, process_service};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Folder created under the user's Videos folder
+const RECORDINGS_FOLDER: &str = "Gesu Bridge Recordings";
+
+/// Max time ffmpeg may spend remuxing one recording (stream copy, no re-encode)
+const REMUX_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Timestamp format used in recording file names
+const STAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// Where recordings are saved; created if missing
+pub fn recordings_dir() -> Result<PathBuf, AppError> {
+    let base = dirs::video_dir()
+        .or_else(dirs::download_dir)
+        .ok_or_else(|| AppError::IoError("Could not determine Videos folder".to_string()))?;
+    let dir = base.join(RECORDINGS_FOLDER);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Path for a new recording. Matroska (MKV, or MKA for audio) survives scrcpy
+/// being stopped abruptly, which would leave an MP4 without its index.
+pub fn new_recording_path(dir: &Path, kind: RecordingKind, serial: &str) -> PathBuf {
+    let stamp = chrono::Local::now().format(STAMP_FORMAT);
+    dir.join(format!(
+        "{}_{}_{}.{}",
+        kind_prefix(kind),
+        file_safe_serial(serial),
+        stamp,
+        kind_extension(kind)
+    ))
+}
+
+/// Recordings in `dir`, newest first. Durations are read with ffprobe when available.
+pub fn list_recordings(dir: &Path, ffprobe_path: Option<&str>) -> Result<Vec<Recording>, AppError> {
+    let mut recordings = Vec::new();
+
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((kind, device_serial)) = parse_recording_name(file_name) else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        let duration_ms = ffprobe_path
+            .and_then(|ffprobe| probe_service::probe_file(ffprobe, &path).ok())
+            .and_then(|probe| probe.duration_ms);
+        let created_at = metadata
+            .modified()
+            .ok()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+
+        recordings.push(Recording {
+            path: path.to_string_lossy().to_string(),
+            file_name: file_name.to_string(),
+            kind,
+            device_serial,
+            size_bytes: metadata.len(),
+            duration_ms,
+            created_at,
+        });
+    }
+
+    recordings.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(recordings)
+}
+
+/// Delete a recording; only files in the recordings folder can be deleted
+pub fn delete_recording(dir: &Path, path: &Path) -> Result<(), AppError> {
+    let path = check_in_library(dir, path)?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Copy an MKV recording's streams into an MP4 next to it and delete the MKV.
+/// Returns the MP4 path.
+pub fn remux_to_mp4(ffmpeg_path: &str, dir: &Path, path: &Path) -> Result<PathBuf, AppError> {
+    let path = check_in_library(dir, path)?;
+    if path.extension().and_then(|e| e.to_str()) != Some("mkv") {
+        return Err(AppError::InvalidPath(format!(
+            "Not an MKV recording: {}",
+            path.display()
+        )));
+    }
+    let output_path = path.with_extension("mp4");
+
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args(["-v", "error", "-y", "-i"])
+        .arg(&path)
+        .args(["-c", "copy", "-movflags", "+faststart"])
+        .arg(&output_path);
+
+    let output = process_service::output_hidden(&mut cmd, REMUX_TIMEOUT)
+        .map_err(|e| AppError::IoError(format!("Failed to execute ffmpeg: {}", e)))?
+        .ok_or_else(|| {
+            AppError::Timeout(format!(
+                "ffmpeg did not finish within {}s for {}",
+                REMUX_TIMEOUT.as_secs(),
+                path.display()
+            ))
+        })?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(AppError::IoError(format!(
+            "ffmpeg failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(output_path)
+}
+
+/// `screen_<serial>_<YYYYMMDD_HHMMSS>.mkv` -> (kind, serial as written in the name)
+pub fn parse_recording_name(file_name: &str) -> Option<(RecordingKind, String)> {
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if !matches!(extension, "mkv" | "mp4" | "mka") {
+        return None;
+    }
+
+    let (kind, rest) = [
+        RecordingKind::Screen,
+        RecordingKind::Camera,
+        RecordingKind::Audio,
+    ]
+    .into_iter()
+    .find_map(|kind| {
+        let rest = stem.strip_prefix(kind_prefix(kind))?.strip_prefix('_')?;
+        Some((kind, rest))
+    })?;
+
+    // The timestamp is a fixed 15 characters, preceded by '_'
+    let stamp_start = rest.len().checked_sub(15)?;
+    let serial = rest.get(..stamp_start)?.strip_suffix('_')?;
+    chrono::NaiveDateTime::parse_from_str(rest.get(stamp_start..)?, STAMP_FORMAT).ok()?;
+
+    (!serial.is_empty()).then(|| (kind, serial.to_string()))
+}
+
+fn kind_prefix(kind: RecordingKind) -> &'static str {
+    match kind {
+        RecordingKind::Screen => "screen",
+        RecordingKind::Camera => "camera",
+        RecordingKind::Audio => "audio",
+    }
+}
+
+fn kind_extension(kind: RecordingKind) -> &'static str {
+    match kind {
+        RecordingKind::Screen | RecordingKind::Camera => "mkv",
+        RecordingKind::Audio => "mka",
+    }
+}
+
+/// Resolve `path` and make sure it is a recording directly inside `dir`
+fn check_in_library(dir: &Path, path: &Path) -> Result<PathBuf, AppError> {
+    let not_recording = || AppError::InvalidPath(format!("Not a recording: {}", path.display()));
+
+    let dir = dir.canonicalize()?;
+    let path = path.canonicalize().map_err(|_| not_recording())?;
+    let is_recording = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(parse_recording_name)
+        .is_some();
+
+    if path.parent() == Some(dir.as_path()) && is_recording {
+        Ok(path)
+    } else {
+        Err(not_recording())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_recording_name() {
+        assert_eq!(
+            parse_recording_name("screen_R58M123_20240501_120000.mkv"),
+            Some((RecordingKind::Screen, "R58M123".to_string()))
+        );
+        assert_eq!(
+            parse_recording_name("camera_192_168_1_5_5555_20240501_120000.mp4"),
+            Some((RecordingKind::Camera, "192_168_1_5_5555".to_string()))
+        );
+        assert_eq!(
+            parse_recording_name("audio_R58M123_20240501_120000.mka"),
+            Some((RecordingKind::Audio, "R58M123".to_string()))
+        );
+        assert_eq!(
+            parse_recording_name("screen_R58M123_notastamp1234.mkv"),
+            None
+        );
+        assert_eq!(parse_recording_name("holiday.mkv"), None);
+        assert_eq!(
+            parse_recording_name("screen_R58M123_20240501_120000.txt"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_library_listing_and_delete() {
+        let dir = std::env::temp_dir().join(format!("gesu_rec_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let recording = new_recording_path(&dir, RecordingKind::Screen, "emulator-5554");
+        std::fs::write(&recording, b"data").unwrap();
+        let other = dir.join("notes.txt");
+        std::fs::write(&other, b"x").unwrap();
+
+        let listed = list_recordings(&dir, None).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].device_serial, "emulator_5554");
+        assert_eq!(listed[0].size_bytes, 4);
+
+        // Only recordings inside the library can be deleted
+        assert!(delete_recording(&dir, &other).is_err());
+        delete_recording(&dir, &recording).unwrap();
+        assert!(!recording.exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,142 @@
+//! Retry policy with exponential backoff for transient adb failures
+
+use crate::domain::errors::AppError;
+use std::time::Duration;
+
+/// Error fragments adb prints while a device is (re)negotiating USB
+const TRANSIENT_PATTERNS: &[&str] = &[
+    "device offline",
+    "device still authorizing",
+    "no devices/emulators found",
+    "error: closed",
+    "connection closed",
+    "protocol fault",
+    "connection reset",
+    "broken pipe",
+];
+
+/// How often and how patiently to retry an idempotent operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one (1 = no retries)
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(300),
+            max_delay: Duration::from_secs(3),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given retry (1-based), doubling each time up to `max_delay`
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+/// Whether an error is worth retrying (device briefly unavailable, not a real failure)
+pub fn is_transient(err: &AppError) -> bool {
+    match err {
+        AppError::AdbExecutionFailed(msg) => {
+            let msg = msg.to_lowercase();
+            TRANSIENT_PATTERNS.iter().any(|p| msg.contains(p))
+        }
+        _ => false,
+    }
+}
+
+/// Run `op`, retrying transient failures according to `policy`
+pub fn retry<T>(
+    policy: &RetryPolicy,
+    mut op: impl FnMut() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(e) if attempt < policy.max_attempts && is_transient(&e) => {
+                std::thread::sleep(policy.delay_for(attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_delay_backs_off_exponentially() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay_for(1), Duration::from_millis(300));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(600));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(1200));
+        assert_eq!(policy.delay_for(10), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_retry_recovers_from_transient_failure() {
+        let mut calls = 0;
+        let result = retry(&instant_policy(3), || {
+            calls += 1;
+            if calls < 3 {
+                Err(AppError::AdbExecutionFailed(
+                    "ADB command failed: error: device offline".to_string(),
+                ))
+            } else {
+                Ok("ok")
+            }
+        });
+
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_retry_gives_up_on_permanent_failure() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry(&instant_policy(5), || {
+            calls += 1;
+            Err(AppError::AdbExecutionFailed(
+                "ADB command failed: No such file or directory".to_string(),
+            ))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_is_transient_matches_adb_messages_only() {
+        let failed =
+            |msg: &str| AppError::AdbExecutionFailed(format!("ADB command failed: {}", msg));
+        assert!(is_transient(&failed("adb: error: closed")));
+        assert!(is_transient(&failed("error: connection closed")));
+        assert!(is_transient(&failed(
+            "protocol fault (couldn't read status): connection reset by peer"
+        )));
+        // A folder name that happens to contain "closed"
+        assert!(!is_transient(&failed(
+            "failed to stat remote object '/sdcard/Disclosed/a.jpg': No such file or directory"
+        )));
+    }
+}
@@ -0,0 +1,249 @@
+//! In-app scheduler for sync/backup jobs
+//!
+//! Runs only while the app is open; there is no background service on the
+//! computer or the device.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{DeviceEventKind, SyncJob, SyncJobRun};
+use crate::services::adb_service::{self, AdbCli, AdbRunner};
+use crate::services::{power_service, settings_service, sync_service, timeline_service};
+use crate::state::{read_lock, write_lock};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often the scheduler checks for due jobs
+const TICK: Duration = Duration::from_secs(60);
+
+/// Runs kept per job
+const HISTORY_LIMIT: usize = 20;
+
+/// Event emitted with a [`SyncJobRun`] payload when a scheduled run fails
+pub const SYNC_JOB_FAILED_EVENT: &str = "sync-job-failed";
+
+/// Managed state: run history per job and jobs currently running
+#[derive(Default)]
+pub struct SchedulerState {
+    history: RwLock<HashMap<String, Vec<SyncJobRun>>>,
+    running: RwLock<HashSet<String>>,
+}
+
+/// Whether a job should run now
+pub fn is_due(job: &SyncJob, now: DateTime<Utc>) -> bool {
+    if !job.enabled {
+        return false;
+    }
+
+    match job
+        .last_run_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+    {
+        Some(last) => {
+            now.signed_duration_since(last.with_timezone(&Utc))
+                >= chrono::Duration::minutes(job.interval_minutes.max(1) as i64)
+        }
+        None => true,
+    }
+}
+
+/// Jobs that are due and whose device is connected and ready
+pub fn due_jobs(jobs: &[SyncJob], ready_serials: &[String], now: DateTime<Utc>) -> Vec<SyncJob> {
+    jobs.iter()
+        .filter(|j| ready_serials.contains(&j.device_serial) && is_due(j, now))
+        .cloned()
+        .collect()
+}
+
+/// Run a job once and record the outcome in its history
+pub fn run_job(state: &SchedulerState, adb: &dyn AdbRunner, job: &SyncJob) -> SyncJobRun {
+    // Skip if a previous run of this job is still going
+    if !write_lock(&state.running).insert(job.id.clone()) {
+        let now = Utc::now().to_rfc3339();
+        return SyncJobRun {
+            job_id: job.id.clone(),
+            job_name: job.name.clone(),
+            started_at: now.clone(),
+            finished_at: now,
+            success: false,
+            transferred: 0,
+            failed: 0,
+            error: Some("Job is already running".to_string()),
+        };
+    }
+
+    let started_at = Utc::now().to_rfc3339();
+    let result = sync_service::run_sync(
+        adb,
+        &job.device_serial,
+        &job.local_root,
+        &job.remote_root,
+        job.direction,
+        job.compare_hash,
+        job.conflict,
+    );
+    write_lock(&state.running).remove(&job.id);
+
+    let (transferred, failed, error) = match result {
+        Ok(report) => (report.transferred, report.failures.len(), None),
+        Err(e) => (0, 0, Some(e.to_string())),
+    };
+
+    let run = SyncJobRun {
+        job_id: job.id.clone(),
+        job_name: job.name.clone(),
+        started_at,
+        finished_at: Utc::now().to_rfc3339(),
+        success: error.is_none() && failed == 0,
+        transferred,
+        failed,
+        error,
+    };
+
+    let mut history = write_lock(&state.history);
+    let runs = history.entry(job.id.clone()).or_default();
+    runs.insert(0, run.clone());
+    runs.truncate(HISTORY_LIMIT);
+
+    run
+}
+
+/// Past runs of a job, newest first
+pub fn get_history(state: &SchedulerState, job_id: &str) -> Vec<SyncJobRun> {
+    read_lock(&state.history)
+        .get(job_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Drop the history of a deleted job
+pub fn clear_history(state: &SchedulerState, job_id: &str) {
+    write_lock(&state.history).remove(job_id);
+}
+
+/// Run a job, persist its last run time, and notify the UI on failure
+pub fn run_and_record(app: &AppHandle, adb: &dyn AdbRunner, job: &SyncJob) -> SyncJobRun {
+    let awake = power_service::keep_awake();
+    let run = run_job(&app.state::<SchedulerState>(), adb, job);
+    drop(awake);
+
+    // Reload so edits made while the job ran are not overwritten
+    if let Ok(mut settings) = settings_service::load_settings(app) {
+        if let Some(saved) = settings.sync_jobs.iter_mut().find(|j| j.id == job.id) {
+            saved.last_run_at = Some(run.started_at.clone());
+            let _ = settings_service::save_settings(app, &settings);
+        }
+    }
+
+    if run.error.is_none() {
+        timeline_service::record(
+            app,
+            &job.device_serial,
+            DeviceEventKind::Synced,
+            Some(format!(
+                "{}: {}",
+                job.name,
+                timeline_service::files_detail(
+                    run.transferred,
+                    run.transferred + run.failed,
+                    &job.remote_root
+                )
+            )),
+        );
+    }
+    if !run.success {
+        let _ = app.emit(SYNC_JOB_FAILED_EVENT, &run);
+    }
+
+    run
+}
+
+/// Start the scheduler thread; it lives as long as the app
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK);
+        let _ = tick(&app);
+    });
+}
+
+fn tick(app: &AppHandle) -> Result<(), AppError> {
+    let settings = settings_service::load_settings(app)?;
+
+    // Don't touch adb at all unless something could run
+    let now = Utc::now();
+    if !settings.sync_jobs.iter().any(|j| is_due(j, now)) {
+        return Ok(());
+    }
+
+    let settings = settings_service::get_settings_with_detection(app)?;
+    let Some(adb) = AdbCli::from_settings(&settings).map(AdbCli::background) else {
+        return Ok(());
+    };
+
+    let ready = adb_service::ready_serials(&adb)?;
+
+    for job in due_jobs(&settings.sync_jobs, &ready, now) {
+        run_and_record(app, &adb, &job);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::SyncDirection;
+    use crate::services::adb_service::mock::MockAdb;
+
+    fn job(last_run_at: Option<&str>) -> SyncJob {
+        SyncJob {
+            id: "job1".to_string(),
+            name: "Music".to_string(),
+            device_serial: "SER1".to_string(),
+            local_root: "/definitely/missing/folder".to_string(),
+            remote_root: "/sdcard/Music".to_string(),
+            direction: SyncDirection::ToDevice,
+            compare_hash: false,
+            conflict: Default::default(),
+            interval_minutes: 60,
+            enabled: true,
+            last_run_at: last_run_at.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(is_due(&job(None), now));
+        assert!(is_due(&job(Some("2024-05-01T10:59:00Z")), now));
+        assert!(!is_due(&job(Some("2024-05-01T11:30:00Z")), now));
+
+        let mut disabled = job(None);
+        disabled.enabled = false;
+        assert!(!is_due(&disabled, now));
+    }
+
+    #[test]
+    fn test_due_jobs_requires_ready_device() {
+        let now = Utc::now();
+        let jobs = vec![job(None)];
+
+        assert!(due_jobs(&jobs, &[], now).is_empty());
+        assert_eq!(due_jobs(&jobs, &["SER1".to_string()], now).len(), 1);
+    }
+
+    #[test]
+    fn test_failed_run_recorded_in_history() {
+        let state = SchedulerState::default();
+        let run = run_job(&state, &MockAdb::new(), &job(None));
+
+        assert!(!run.success);
+        assert!(run.error.is_some());
+        assert_eq!(get_history(&state, "job1").len(), 1);
+    }
+}
@@ -1,47 +1,115 @@
 //! scrcpy service for screen mirroring and camera mirroring
 
 use crate::domain::errors::AppError;
-use crate::domain::models::MirrorSession;
-use std::collections::HashMap;
-use std::process::{Child, Command};
-use std::sync::Mutex;
+use crate::domain::models::{
+    CameraFacing, CameraOptions, CameraOrientation, CropRegion, DeviceSetting, DisplaySize,
+    MirrorOptions, MirrorSession, RecordingKind, SettingsNamespace,
+};
+use crate::services::adb_service::AdbRunner;
+use crate::services::autotune_service::{self, SharedHealth};
+use crate::services::device_settings_service::{self, SavedSetting};
+use crate::services::link_service;
+use crate::services::power_service::{self, SleepGuard};
+use crate::services::process_service;
+use crate::services::recording_service;
+use crate::services::retry_service::RetryPolicy;
+use crate::state::write_lock;
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
+/// Android setting holding the screen timeout, in milliseconds
+const SCREEN_TIMEOUT_KEY: &str = "screen_off_timeout";
 
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// Width of the picture-in-picture camera window; scrcpy keeps the aspect ratio
+const PIP_WINDOW_WIDTH: u32 = 360;
 
-/// Global state for active screen mirror sessions
-static ACTIVE_SESSIONS: Mutex<Option<HashMap<String, Child>>> = Mutex::new(None);
+/// Distance of the picture-in-picture window from the top-left screen corner
+const PIP_WINDOW_MARGIN: u32 = 16;
 
-/// Global state for active camera mirror sessions
-static CAMERA_SESSIONS: Mutex<Option<HashMap<String, Child>>> = Mutex::new(None);
+/// How often the watchdog looks for camera sessions to relaunch
+const WATCHDOG_TICK: Duration = Duration::from_secs(1);
 
-fn ensure_sessions_map() {
-    let mut sessions = ACTIVE_SESSIONS.lock().unwrap();
-    if sessions.is_none() {
-        *sessions = Some(HashMap::new());
-    }
+/// Backoff between camera relaunches; `max_attempts` is the number of
+/// relaunches in a row before the session is given up
+const CAMERA_RESTART_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 8,
+    initial_delay: Duration::from_secs(2),
+    max_delay: Duration::from_secs(30),
+};
+
+/// A camera session that ran this long counts as healthy, resetting the backoff
+const CAMERA_STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// How much of an auto-tuned stream's output is judged at a time
+const TUNE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Managed state for active screen and camera mirror sessions
+#[derive(Default)]
+pub struct MirrorState {
+    screen: RwLock<HashMap<String, SessionHandle>>,
+    camera: RwLock<HashMap<String, SessionHandle>>,
+    audio: RwLock<HashMap<String, SessionHandle>>,
+}
+
+/// A running scrcpy process together with the metadata it was started with
+struct SessionHandle {
+    child: Child,
+    session: MirrorSession,
+    /// Device settings to put back when the app stops the session. scrcpy
+    /// restores these itself on a clean exit, but not when it is killed.
+    restore: Vec<SavedSetting>,
+    /// Set for camera sessions the watchdog relaunches when scrcpy exits
+    restart: Option<CameraRestart>,
+    /// Set for screen sessions the watchdog restarts at lower quality when struggling
+    tune: Option<AutoTune>,
+    /// Held while the session records, so the computer doesn't sleep mid-recording
+    awake: Option<SleepGuard>,
+}
+
+/// Watchdog bookkeeping for an auto-tuned screen session
+struct AutoTune {
+    scrcpy_path: String,
+    health: SharedHealth,
+    window_start: Instant,
 }
 
-fn ensure_camera_sessions_map() {
-    let mut sessions = CAMERA_SESSIONS.lock().unwrap();
-    if sessions.is_none() {
-        *sessions = Some(HashMap::new());
+/// Watchdog bookkeeping for an auto-restarting camera session
+struct CameraRestart {
+    scrcpy_path: String,
+    /// Relaunches since the session last ran long enough to count as healthy
+    restarts: u32,
+    started: Instant,
+    exited_at: Option<Instant>,
+}
+
+impl SessionHandle {
+    /// Whether scrcpy has exited and the watchdog won't bring it back
+    fn is_finished(&mut self) -> bool {
+        let exited = !matches!(self.child.try_wait(), Ok(None));
+        let restarting = self
+            .restart
+            .as_ref()
+            .is_some_and(|r| r.restarts < CAMERA_RESTART_POLICY.max_attempts);
+        exited && !restarting
     }
 }
 
 /// Start a screen mirror session for a device
 pub fn start_mirror(
+    state: &MirrorState,
     scrcpy_path: &str,
+    adb: &dyn AdbRunner,
     device_serial: &str,
     screen_off: bool,
+    options: &MirrorOptions,
+    record_to: Option<&Path>,
 ) -> Result<MirrorSession, AppError> {
-    ensure_sessions_map();
-
-    let mut sessions = ACTIVE_SESSIONS.lock().unwrap();
-    let sessions_map = sessions.as_mut().unwrap();
+    let mut sessions_map = write_lock(&state.screen);
 
     if sessions_map.contains_key(device_serial) {
         return Err(AppError::MirrorError(format!(
@@ -50,44 +118,305 @@ pub fn start_mirror(
         )));
     }
 
+    if let Some(crop) = options.crop {
+        // An unknown display size isn't fatal; scrcpy reports bad crops itself
+        if let Ok(display) = get_display_size(adb, device_serial) {
+            check_crop(&crop, &display)?;
+        }
+    }
+
+    // Measure wireless links first so a weak one can be warned about or
+    // mirrored at a lower bitrate; a failed measurement doesn't block mirroring
+    let link_quality = if link_service::is_wireless_serial(device_serial) {
+        link_service::measure_link(adb, device_serial).ok()
+    } else {
+        None
+    };
+    let options = match &link_quality {
+        Some(link) => link_service::adapt_options(options, link),
+        None => options.clone(),
+    };
+    let options = &options;
+
+    let restore = settings_to_restore(adb, device_serial, options);
+    apply_screen_timeout(adb, device_serial, options)?;
+
+    let (child, health) =
+        match spawn_mirror(scrcpy_path, device_serial, screen_off, options, record_to) {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                restore_settings(adb, device_serial, &restore);
+                return Err(e);
+            }
+        };
+
+    let session = MirrorSession {
+        device_serial: device_serial.to_string(),
+        process_id: child.id(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        screen_off,
+        camera_options: None,
+        mirror_options: Some(options.clone()),
+        recording_path: record_to.map(|p| p.to_string_lossy().to_string()),
+        link_quality,
+    };
+
+    sessions_map.insert(
+        device_serial.to_string(),
+        SessionHandle {
+            child,
+            session: session.clone(),
+            restore,
+            restart: None,
+            tune: health.map(|health| AutoTune {
+                scrcpy_path: scrcpy_path.to_string(),
+                health,
+                window_start: Instant::now(),
+            }),
+            awake: record_to.map(|_| power_service::keep_awake()),
+        },
+    );
+
+    Ok(session)
+}
+
+/// Launch scrcpy for a screen mirror. With auto-tuning on, its output is
+/// read into the returned health counters.
+fn spawn_mirror(
+    scrcpy_path: &str,
+    device_serial: &str,
+    screen_off: bool,
+    options: &MirrorOptions,
+    record_to: Option<&Path>,
+) -> Result<(Child, Option<SharedHealth>), AppError> {
     let mut cmd = Command::new(scrcpy_path);
-    cmd.args(["-s", device_serial]);
+    cmd.args(mirror_args(device_serial, screen_off, options));
+    if let Some(path) = record_to {
+        cmd.arg(record_arg(path));
+    }
+    if options.auto_tune {
+        cmd.arg("--print-fps");
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    let mut child = process_service::spawn_hidden(&mut cmd)
+        .map_err(|e| AppError::MirrorError(format!("Failed to start scrcpy: {}", e)))?;
+
+    let health = options.auto_tune.then(|| {
+        let health = SharedHealth::default();
+        if let Some(stdout) = child.stdout.take() {
+            autotune_service::watch_output(stdout, Arc::clone(&health));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            autotune_service::watch_output(stderr, Arc::clone(&health));
+        }
+        health
+    });
+
+    Ok((child, health))
+}
+
+/// Device settings the session's options will change, with their current values
+fn settings_to_restore(
+    adb: &dyn AdbRunner,
+    device_serial: &str,
+    options: &MirrorOptions,
+) -> Vec<SavedSetting> {
+    let mut keys = Vec::new();
+    if options.show_touches {
+        keys.push((SettingsNamespace::System, "show_touches"));
+    }
+    if options.stay_awake {
+        keys.push((SettingsNamespace::Global, "stay_on_while_plugged_in"));
+    }
+    if options.screen_timeout_secs.is_some() {
+        keys.push((SettingsNamespace::System, SCREEN_TIMEOUT_KEY));
+    }
+
+    keys.into_iter()
+        .filter_map(|(namespace, key)| {
+            device_settings_service::save_setting(adb, device_serial, namespace, key).ok()
+        })
+        .collect()
+}
+
+/// Raise the screen timeout for the session; the old value is in the restore list
+fn apply_screen_timeout(
+    adb: &dyn AdbRunner,
+    device_serial: &str,
+    options: &MirrorOptions,
+) -> Result<(), AppError> {
+    let Some(secs) = options.screen_timeout_secs else {
+        return Ok(());
+    };
+
+    device_settings_service::put_setting(
+        adb,
+        device_serial,
+        &DeviceSetting {
+            namespace: SettingsNamespace::System,
+            key: SCREEN_TIMEOUT_KEY.to_string(),
+            value: (secs.max(1) as u64 * 1000).to_string(),
+        },
+    )?;
+    Ok(())
+}
+
+fn restore_settings(adb: &dyn AdbRunner, device_serial: &str, saved: &[SavedSetting]) {
+    for setting in saved {
+        let _ = device_settings_service::restore_setting(adb, device_serial, setting);
+    }
+}
+
+/// scrcpy arguments for a screen mirror session
+fn mirror_args(device_serial: &str, screen_off: bool, options: &MirrorOptions) -> Vec<String> {
+    let mut args = vec!["-s".to_string(), device_serial.to_string()];
 
     if screen_off {
-        cmd.arg("--turn-screen-off");
+        args.push("--turn-screen-off".to_string());
     }
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    if !options.shortcut_mod.is_empty() {
+        let mods: Vec<&str> = options.shortcut_mod.iter().map(|m| m.as_str()).collect();
+        args.push(format!("--shortcut-mod={}", mods.join(",")));
+    }
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| AppError::MirrorError(format!("Failed to start scrcpy: {}", e)))?;
+    if options.show_touches {
+        args.push("--show-touches".to_string());
+    }
 
-    let process_id = child.id();
-    let started_at = chrono::Utc::now().to_rfc3339();
+    if options.stay_awake {
+        args.push("--stay-awake".to_string());
+    }
 
-    sessions_map.insert(device_serial.to_string(), child);
+    if let Some(crop) = options.crop {
+        args.push(format!(
+            "--crop={}:{}:{}:{}",
+            crop.width, crop.height, crop.x, crop.y
+        ));
+    }
 
-    Ok(MirrorSession {
-        device_serial: device_serial.to_string(),
-        process_id,
-        started_at,
+    if let Some(mbps) = options.bit_rate_mbps {
+        args.push(format!("--video-bit-rate={}M", mbps));
+    }
+
+    if let Some(max_size) = options.max_size {
+        args.push(format!("--max-size={}", max_size));
+    }
+
+    if options.power_off_on_close {
+        args.push("--power-off-on-close".to_string());
+    }
+
+    if let Some(title) = &options.window_title {
+        args.push(format!("--window-title={}", title));
+    }
+
+    if options.fullscreen {
+        args.push("--fullscreen".to_string());
+    }
+
+    if options.no_control {
+        args.push("--no-control".to_string());
+    }
+
+    args
+}
+
+/// Options for a "headless phone" session on top of the user's defaults:
+/// keep the device awake, power it off when the window closes and label the
+/// window so it's clear the phone screen is off
+pub fn headless_options(base: &MirrorOptions, device_serial: &str) -> MirrorOptions {
+    MirrorOptions {
+        stay_awake: true,
+        power_off_on_close: true,
+        window_title: Some(format!("{} (screen off)", device_serial)),
+        ..base.clone()
+    }
+}
+
+/// Options for read-only mode: view only, and nothing that changes device
+/// settings or powers the device off
+pub fn read_only_options(base: &MirrorOptions) -> MirrorOptions {
+    MirrorOptions {
+        no_control: true,
+        show_touches: false,
+        stay_awake: false,
+        screen_timeout_secs: None,
+        power_off_on_close: false,
+        ..base.clone()
+    }
+}
+
+/// `--record` argument; scrcpy picks the container from the extension
+fn record_arg(path: &Path) -> OsString {
+    let mut arg = OsString::from("--record=");
+    arg.push(path);
+    arg
+}
+
+/// Display resolution of a device, for choosing a crop region
+pub fn get_display_size(adb: &dyn AdbRunner, device_serial: &str) -> Result<DisplaySize, AppError> {
+    let output = adb.run_idempotent(&["-s", device_serial, "shell", "wm", "size"])?;
+    parse_wm_size(&output).ok_or_else(|| {
+        AppError::AdbExecutionFailed(format!("Unexpected `wm size` output: {}", output.trim()))
     })
 }
 
-/// Stop a screen mirror session
-pub fn stop_mirror(device_serial: &str) -> Result<(), AppError> {
-    ensure_sessions_map();
+/// Parse `wm size`; an override size (set by the user or a tool) wins over
+/// the physical one because it is what the screen actually renders
+pub fn parse_wm_size(output: &str) -> Option<DisplaySize> {
+    let size_of = |prefix: &str| {
+        output.lines().find_map(|line| {
+            let (w, h) = line.trim().strip_prefix(prefix)?.trim().split_once('x')?;
+            Some(DisplaySize {
+                width: w.trim().parse().ok()?,
+                height: h.trim().parse().ok()?,
+            })
+        })
+    };
+
+    size_of("Override size:").or_else(|| size_of("Physical size:"))
+}
+
+/// Reject crop regions that are empty or reach outside the display
+fn check_crop(crop: &CropRegion, display: &DisplaySize) -> Result<(), AppError> {
+    let fits = crop.width > 0
+        && crop.height > 0
+        && crop.x as u64 + crop.width as u64 <= display.width as u64
+        && crop.y as u64 + crop.height as u64 <= display.height as u64;
+
+    if fits {
+        Ok(())
+    } else {
+        Err(AppError::MirrorError(format!(
+            "Crop {}x{} at {},{} doesn't fit the {}x{} display",
+            crop.width, crop.height, crop.x, crop.y, display.width, display.height
+        )))
+    }
+}
 
-    let mut sessions = ACTIVE_SESSIONS.lock().unwrap();
-    let sessions_map = sessions.as_mut().unwrap();
+/// Stop a screen mirror session, restoring device settings it changed
+/// when `adb` is available. Returns the stopped session.
+pub fn stop_mirror(
+    state: &MirrorState,
+    adb: Option<&dyn AdbRunner>,
+    device_serial: &str,
+) -> Result<MirrorSession, AppError> {
+    let mut sessions_map = write_lock(&state.screen);
 
-    if let Some(mut child) = sessions_map.remove(device_serial) {
-        child
+    if let Some(mut handle) = sessions_map.remove(device_serial) {
+        handle
+            .child
             .kill()
             .map_err(|e| AppError::MirrorError(format!("Failed to stop scrcpy: {}", e)))?;
-        Ok(())
+        // Reap the process so a recording file is closed before it is used
+        let _ = handle.child.wait();
+
+        if let Some(adb) = adb {
+            restore_settings(adb, device_serial, &handle.restore);
+        }
+        Ok(handle.session)
     } else {
         Err(AppError::MirrorError(format!(
             "No active mirror session for device {}",
@@ -96,17 +425,21 @@ pub fn stop_mirror(device_serial: &str) -> Result<(), AppError> {
     }
 }
 
-/// Get all active screen mirror sessions
-pub fn get_active_sessions() -> Vec<MirrorSession> {
-    ensure_sessions_map();
+/// Whether a screen mirror session is running for a device
+pub fn is_mirroring(state: &MirrorState, device_serial: &str) -> bool {
+    get_active_sessions(state)
+        .iter()
+        .any(|session| session.device_serial == device_serial)
+}
 
-    let mut sessions = ACTIVE_SESSIONS.lock().unwrap();
-    let sessions_map = sessions.as_mut().unwrap();
+/// Get all active screen mirror sessions
+pub fn get_active_sessions(state: &MirrorState) -> Vec<MirrorSession> {
+    let mut sessions_map = write_lock(&state.screen);
 
     // Clean up exited processes
     let to_remove: Vec<_> = sessions_map
         .iter_mut()
-        .filter_map(|(serial, child)| match child.try_wait() {
+        .filter_map(|(serial, handle)| match handle.child.try_wait() {
             Ok(Some(_)) | Err(_) => Some(serial.clone()),
             _ => None,
         })
@@ -117,12 +450,8 @@ pub fn get_active_sessions() -> Vec<MirrorSession> {
     }
 
     sessions_map
-        .iter()
-        .map(|(serial, child)| MirrorSession {
-            device_serial: serial.clone(),
-            process_id: child.id(),
-            started_at: String::new(),
-        })
+        .values()
+        .map(|handle| handle.session.clone())
         .collect()
 }
 
@@ -132,17 +461,13 @@ pub fn get_active_sessions() -> Vec<MirrorSession> {
 
 /// Start a camera mirror session for a device
 pub fn start_camera_mirror(
+    state: &MirrorState,
     scrcpy_path: &str,
     device_serial: &str,
-    camera_facing: &str, // "front" or "back"
-    camera_size: &str,   // e.g., "1920x1080"
-    no_audio: bool,      // disable audio forwarding
-    orientation: &str,   // "portrait" or "landscape"
+    options: &CameraOptions,
+    record_to: Option<&Path>,
 ) -> Result<MirrorSession, AppError> {
-    ensure_camera_sessions_map();
-
-    let mut sessions = CAMERA_SESSIONS.lock().unwrap();
-    let sessions_map = sessions.as_mut().unwrap();
+    let mut sessions_map = write_lock(&state.camera);
 
     if sessions_map.contains_key(device_serial) {
         return Err(AppError::MirrorError(format!(
@@ -151,62 +476,252 @@ pub fn start_camera_mirror(
         )));
     }
 
+    let child = spawn_camera(scrcpy_path, device_serial, options, record_to)?;
+
+    let session = MirrorSession {
+        device_serial: device_serial.to_string(),
+        process_id: child.id(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        screen_off: false,
+        camera_options: Some(options.clone()),
+        mirror_options: None,
+        recording_path: record_to.map(|p| p.to_string_lossy().to_string()),
+        link_quality: None,
+    };
+
+    let restart = options.auto_restart.then(|| CameraRestart {
+        scrcpy_path: scrcpy_path.to_string(),
+        restarts: 0,
+        started: Instant::now(),
+        exited_at: None,
+    });
+
+    sessions_map.insert(
+        device_serial.to_string(),
+        SessionHandle {
+            child,
+            session: session.clone(),
+            restore: Vec::new(),
+            restart,
+            tune: None,
+            awake: record_to.map(|_| power_service::keep_awake()),
+        },
+    );
+
+    Ok(session)
+}
+
+fn spawn_camera(
+    scrcpy_path: &str,
+    device_serial: &str,
+    options: &CameraOptions,
+    record_to: Option<&Path>,
+) -> Result<Child, AppError> {
     let mut cmd = Command::new(scrcpy_path);
-    cmd.args(["-s", device_serial]);
-    cmd.arg("--video-source=camera");
-    cmd.arg(format!("--camera-facing={}", camera_facing));
+    cmd.args(camera_args(device_serial, options));
+    if let Some(path) = record_to {
+        cmd.arg(record_arg(path));
+    }
+
+    process_service::spawn_hidden(&mut cmd)
+        .map_err(|e| AppError::MirrorError(format!("Failed to start camera: {}", e)))
+}
+
+/// scrcpy arguments for a camera mirror session
+fn camera_args(device_serial: &str, options: &CameraOptions) -> Vec<String> {
+    let mut args = vec![
+        "-s".to_string(),
+        device_serial.to_string(),
+        "--video-source=camera".to_string(),
+        format!("--camera-facing={}", options.facing.as_str()),
+    ];
 
-    if !camera_size.is_empty() {
-        cmd.arg(format!("--camera-size={}", camera_size));
+    if !options.resolution.is_empty() {
+        args.push(format!("--camera-size={}", options.resolution));
     }
 
-    if no_audio {
-        cmd.arg("--no-audio");
+    if options.no_audio {
+        args.push("--no-audio".to_string());
     }
 
     // Camera sensors naturally output landscape, orientation depends on:
     // - Portrait + Back camera: 90° rotation
     // - Portrait + Front camera: 270° rotation (front sensor is flipped)
     // - Landscape: no rotation (natural camera orientation)
-    if orientation == "portrait" {
-        if camera_facing == "front" {
-            cmd.arg("--orientation=270");
+    if options.orientation == CameraOrientation::Portrait {
+        if options.facing == CameraFacing::Front {
+            args.push("--orientation=270".to_string());
         } else {
-            cmd.arg("--orientation=90");
+            args.push("--orientation=90".to_string());
         }
     }
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    if options.pip {
+        args.push("--always-on-top".to_string());
+        args.push(format!("--window-width={}", PIP_WINDOW_WIDTH));
+        args.push(format!("--window-x={}", PIP_WINDOW_MARGIN));
+        args.push(format!("--window-y={}", PIP_WINDOW_MARGIN));
+    }
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| AppError::MirrorError(format!("Failed to start camera: {}", e)))?;
+    args
+}
 
-    let process_id = child.id();
-    let started_at = chrono::Utc::now().to_rfc3339();
+/// Start the session watchdog thread; it lives as long as the app
+pub fn start_watchdog(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WATCHDOG_TICK);
+        let state = app.state::<MirrorState>();
+        restart_exited_cameras(&state);
+        retune_struggling_mirrors(&state);
+        release_finished_recordings(&state);
+    });
+}
 
-    sessions_map.insert(device_serial.to_string(), child);
+/// Let the computer sleep again once a recording session has ended on its
+/// own; stopped sessions release their hold when they are removed
+fn release_finished_recordings(state: &MirrorState) {
+    for sessions in [&state.screen, &state.camera, &state.audio] {
+        for handle in write_lock(sessions).values_mut() {
+            if handle.awake.is_some() && handle.is_finished() {
+                handle.awake = None;
+            }
+        }
+    }
+}
 
-    Ok(MirrorSession {
-        device_serial: device_serial.to_string(),
-        process_id,
-        started_at,
-    })
+/// Restart auto-tuned screen sessions whose stream is struggling at the next
+/// lower bitrate or resolution. Device settings changed at start stay in
+/// place and are still restored on stop.
+fn retune_struggling_mirrors(state: &MirrorState) {
+    let mut sessions_map = write_lock(&state.screen);
+    let now = Instant::now();
+
+    for (serial, handle) in sessions_map.iter_mut() {
+        let Some(tune) = handle.tune.as_mut() else {
+            continue;
+        };
+        if now.duration_since(tune.window_start) < TUNE_WINDOW
+            || !matches!(handle.child.try_wait(), Ok(None))
+        {
+            continue;
+        }
+
+        let window = std::mem::take(&mut *write_lock(&tune.health));
+        tune.window_start = now;
+        if !autotune_service::is_struggling(&window) {
+            continue;
+        }
+        let Some(lowered) = handle
+            .session
+            .mirror_options
+            .as_ref()
+            .and_then(autotune_service::lower_quality)
+        else {
+            continue;
+        };
+
+        let _ = handle.child.kill();
+        let _ = handle.child.wait();
+
+        let record_to = next_recording_path(&handle.session, RecordingKind::Screen);
+        // If it fails, the session is reaped as exited on the next listing
+        if let Ok((child, health)) = spawn_mirror(
+            &tune.scrcpy_path,
+            serial,
+            handle.session.screen_off,
+            &lowered,
+            record_to.as_deref(),
+        ) {
+            handle.child = child;
+            handle.session.process_id = handle.child.id();
+            handle.session.mirror_options = Some(lowered);
+            handle.session.recording_path = record_to.map(|p| p.to_string_lossy().to_string());
+            tune.health = health.unwrap_or_default();
+        }
+    }
+}
+
+/// Fresh file next to a session's recording, so a relaunch doesn't overwrite it
+fn next_recording_path(session: &MirrorSession, kind: RecordingKind) -> Option<PathBuf> {
+    let dir = Path::new(session.recording_path.as_ref()?).parent()?;
+    Some(recording_service::new_recording_path(
+        dir,
+        kind,
+        &session.device_serial,
+    ))
 }
 
-/// Stop a camera mirror session
-pub fn stop_camera_mirror(device_serial: &str) -> Result<(), AppError> {
-    ensure_camera_sessions_map();
+/// Relaunch auto-restart camera sessions whose scrcpy exited, typically
+/// because another app took the camera. Backs off between attempts and
+/// drops sessions that keep failing.
+fn restart_exited_cameras(state: &MirrorState) {
+    let mut sessions_map = write_lock(&state.camera);
+    let now = Instant::now();
+    let mut given_up = Vec::new();
+
+    for (serial, handle) in sessions_map.iter_mut() {
+        let Some(restart) = handle.restart.as_mut() else {
+            continue;
+        };
+        if matches!(handle.child.try_wait(), Ok(None)) {
+            continue;
+        }
+
+        let exited_at = match restart.exited_at {
+            Some(at) => at,
+            None => {
+                if now.duration_since(restart.started) >= CAMERA_STABLE_AFTER {
+                    restart.restarts = 0;
+                }
+                restart.exited_at = Some(now);
+                now
+            }
+        };
+
+        if restart.restarts >= CAMERA_RESTART_POLICY.max_attempts {
+            given_up.push(serial.clone());
+            continue;
+        }
+        if now.duration_since(exited_at) < CAMERA_RESTART_POLICY.delay_for(restart.restarts + 1) {
+            continue;
+        }
+
+        restart.restarts += 1;
+        restart.exited_at = Some(now);
+
+        let options = handle.session.camera_options.clone().unwrap_or_default();
+        let record_to = next_recording_path(&handle.session, RecordingKind::Camera);
+
+        if let Ok(child) =
+            spawn_camera(&restart.scrcpy_path, serial, &options, record_to.as_deref())
+        {
+            handle.child = child;
+            handle.session.process_id = handle.child.id();
+            handle.session.recording_path = record_to.map(|p| p.to_string_lossy().to_string());
+            restart.started = now;
+            restart.exited_at = None;
+        }
+    }
+
+    for serial in given_up {
+        sessions_map.remove(&serial);
+    }
+}
 
-    let mut sessions = CAMERA_SESSIONS.lock().unwrap();
-    let sessions_map = sessions.as_mut().unwrap();
+/// Stop a camera mirror session and return it
+pub fn stop_camera_mirror(
+    state: &MirrorState,
+    device_serial: &str,
+) -> Result<MirrorSession, AppError> {
+    let mut sessions_map = write_lock(&state.camera);
 
-    if let Some(mut child) = sessions_map.remove(device_serial) {
-        child
+    if let Some(mut handle) = sessions_map.remove(device_serial) {
+        handle
+            .child
             .kill()
             .map_err(|e| AppError::MirrorError(format!("Failed to stop camera: {}", e)))?;
-        Ok(())
+        let _ = handle.child.wait();
+        Ok(handle.session)
     } else {
         Err(AppError::MirrorError(format!(
             "No active camera session for device {}",
@@ -216,16 +731,146 @@ pub fn stop_camera_mirror(device_serial: &str) -> Result<(), AppError> {
 }
 
 /// Get all active camera sessions
-pub fn get_camera_sessions() -> Vec<MirrorSession> {
-    ensure_camera_sessions_map();
+pub fn get_camera_sessions(state: &MirrorState) -> Vec<MirrorSession> {
+    let mut sessions_map = write_lock(&state.camera);
 
-    let mut sessions = CAMERA_SESSIONS.lock().unwrap();
-    let sessions_map = sessions.as_mut().unwrap();
+    // Clean up exited processes, keeping those the watchdog will relaunch
+    let to_remove: Vec<_> = sessions_map
+        .iter_mut()
+        .filter_map(|(serial, handle)| handle.is_finished().then(|| serial.clone()))
+        .collect();
+
+    for serial in to_remove {
+        sessions_map.remove(&serial);
+    }
+
+    sessions_map
+        .values()
+        .map(|handle| handle.session.clone())
+        .collect()
+}
+
+/// Serials of devices with any active screen, camera or audio session
+pub fn active_serials(state: &MirrorState) -> BTreeSet<String> {
+    get_active_sessions(state)
+        .into_iter()
+        .chain(get_camera_sessions(state))
+        .chain(get_audio_sessions(state))
+        .map(|session| session.device_serial)
+        .collect()
+}
+
+/// Stop every session of a device and return how many were running
+pub fn stop_device_sessions(
+    state: &MirrorState,
+    adb: &dyn AdbRunner,
+    device_serial: &str,
+) -> usize {
+    [
+        stop_mirror(state, Some(adb), device_serial).is_ok(),
+        stop_camera_mirror(state, device_serial).is_ok(),
+        stop_audio_capture(state, device_serial).is_ok(),
+    ]
+    .into_iter()
+    .filter(|&stopped| stopped)
+    .count()
+}
+
+// ============================================
+// Audio Capture Functions
+// ============================================
+
+/// Start recording a device's microphone to `record_to` without mirroring.
+/// scrcpy supports audio capture on Android 11 and later.
+pub fn start_audio_capture(
+    state: &MirrorState,
+    scrcpy_path: &str,
+    device_serial: &str,
+    record_to: &Path,
+) -> Result<MirrorSession, AppError> {
+    let mut sessions_map = write_lock(&state.audio);
+
+    if sessions_map.contains_key(device_serial) {
+        return Err(AppError::MirrorError(format!(
+            "Audio capture already active for device {}",
+            device_serial
+        )));
+    }
+
+    let mut cmd = Command::new(scrcpy_path);
+    cmd.args(audio_capture_args(device_serial));
+    cmd.arg(record_arg(record_to));
+
+    let child = process_service::spawn_hidden(&mut cmd)
+        .map_err(|e| AppError::MirrorError(format!("Failed to start audio capture: {}", e)))?;
+
+    let session = MirrorSession {
+        device_serial: device_serial.to_string(),
+        process_id: child.id(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        screen_off: false,
+        camera_options: None,
+        mirror_options: None,
+        recording_path: Some(record_to.to_string_lossy().to_string()),
+        link_quality: None,
+    };
+
+    sessions_map.insert(
+        device_serial.to_string(),
+        SessionHandle {
+            child,
+            session: session.clone(),
+            restore: Vec::new(),
+            restart: None,
+            tune: None,
+            awake: Some(power_service::keep_awake()),
+        },
+    );
+
+    Ok(session)
+}
+
+/// scrcpy arguments for recording the microphone only, with no window or playback
+fn audio_capture_args(device_serial: &str) -> Vec<String> {
+    vec![
+        "-s".to_string(),
+        device_serial.to_string(),
+        "--no-video".to_string(),
+        "--audio-source=mic".to_string(),
+        "--no-playback".to_string(),
+    ]
+}
+
+/// Stop an audio capture and return it
+pub fn stop_audio_capture(
+    state: &MirrorState,
+    device_serial: &str,
+) -> Result<MirrorSession, AppError> {
+    let mut sessions_map = write_lock(&state.audio);
+
+    if let Some(mut handle) = sessions_map.remove(device_serial) {
+        handle
+            .child
+            .kill()
+            .map_err(|e| AppError::MirrorError(format!("Failed to stop audio capture: {}", e)))?;
+        let _ = handle.child.wait();
+        Ok(handle.session)
+    } else {
+        Err(AppError::MirrorError(format!(
+            "No active audio capture for device {}",
+            device_serial
+        )))
+    }
+}
+
+/// Get all active audio captures
+pub fn get_audio_sessions(state: &MirrorState) -> Vec<MirrorSession> {
+    let mut sessions_map = write_lock(&state.audio);
 
     // Clean up exited processes
     let to_remove: Vec<_> = sessions_map
         .iter_mut()
-        .filter_map(|(serial, child)| match child.try_wait() {
+        .filter_map(|(serial, handle)| match handle.child.try_wait() {
             Ok(Some(_)) | Err(_) => Some(serial.clone()),
             _ => None,
         })
@@ -236,11 +881,213 @@ pub fn get_camera_sessions() -> Vec<MirrorSession> {
     }
 
     sessions_map
-        .iter()
-        .map(|(serial, child)| MirrorSession {
-            device_serial: serial.clone(),
-            process_id: child.id(),
-            started_at: String::new(),
-        })
+        .values()
+        .map(|handle| handle.session.clone())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::ShortcutMod;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_mirror_args() {
+        assert_eq!(
+            mirror_args("SER1", false, &MirrorOptions::default()),
+            vec!["-s", "SER1"]
+        );
+
+        let options = MirrorOptions {
+            shortcut_mod: vec![ShortcutMod::Lctrl, ShortcutMod::Rctrl],
+            show_touches: true,
+            stay_awake: true,
+            screen_timeout_secs: Some(600),
+            crop: Some(CropRegion {
+                width: 1080,
+                height: 1200,
+                x: 0,
+                y: 120,
+            }),
+            record: true,
+            bit_rate_mbps: Some(4),
+            adapt_to_link: true,
+            max_size: Some(1280),
+            auto_tune: true,
+            power_off_on_close: true,
+            window_title: Some("Phone".to_string()),
+            fullscreen: true,
+            no_control: true,
+        };
+        assert_eq!(
+            mirror_args("SER1", true, &options),
+            vec![
+                "-s",
+                "SER1",
+                "--turn-screen-off",
+                "--shortcut-mod=lctrl,rctrl",
+                "--show-touches",
+                "--stay-awake",
+                "--crop=1080:1200:0:120",
+                "--video-bit-rate=4M",
+                "--max-size=1280",
+                "--power-off-on-close",
+                "--window-title=Phone",
+                "--fullscreen",
+                "--no-control"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_headless_options() {
+        let base = MirrorOptions {
+            shortcut_mod: vec![ShortcutMod::Lalt],
+            ..MirrorOptions::default()
+        };
+        let options = headless_options(&base, "SER1");
+        assert!(options.stay_awake && options.power_off_on_close);
+        assert_eq!(options.shortcut_mod, base.shortcut_mod);
+        assert_eq!(
+            mirror_args("SER1", true, &options),
+            vec![
+                "-s",
+                "SER1",
+                "--turn-screen-off",
+                "--shortcut-mod=lalt",
+                "--stay-awake",
+                "--power-off-on-close",
+                "--window-title=SER1 (screen off)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_camera_args() {
+        let options = CameraOptions {
+            facing: CameraFacing::Front,
+            resolution: String::new(),
+            no_audio: true,
+            orientation: CameraOrientation::Portrait,
+            record: false,
+            pip: true,
+            auto_restart: true,
+        };
+        assert_eq!(
+            camera_args("SER1", &options),
+            vec![
+                "-s",
+                "SER1",
+                "--video-source=camera",
+                "--camera-facing=front",
+                "--no-audio",
+                "--orientation=270",
+                "--always-on-top",
+                "--window-width=360",
+                "--window-x=16",
+                "--window-y=16"
+            ]
+        );
+
+        let landscape = CameraOptions {
+            orientation: CameraOrientation::Landscape,
+            ..CameraOptions::default()
+        };
+        assert_eq!(
+            camera_args("SER1", &landscape),
+            vec![
+                "-s",
+                "SER1",
+                "--video-source=camera",
+                "--camera-facing=back",
+                "--camera-size=1280x720",
+                "--no-audio"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_audio_capture_args() {
+        assert_eq!(
+            audio_capture_args("SER1"),
+            vec![
+                "-s",
+                "SER1",
+                "--no-video",
+                "--audio-source=mic",
+                "--no-playback"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_to_restore() {
+        let adb = MockAdb::new().on("settings get system show_touches", "0");
+
+        assert!(settings_to_restore(&adb, "SER1", &MirrorOptions::default()).is_empty());
+
+        let options = MirrorOptions {
+            show_touches: true,
+            ..Default::default()
+        };
+        let saved = settings_to_restore(&adb, "SER1", &options);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].previous.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn test_apply_screen_timeout() {
+        let adb = MockAdb::new().on("settings put", "");
+
+        apply_screen_timeout(&adb, "SER1", &MirrorOptions::default()).unwrap();
+        assert!(adb.calls().is_empty());
+
+        let options = MirrorOptions {
+            screen_timeout_secs: Some(1800),
+            ..Default::default()
+        };
+        apply_screen_timeout(&adb, "SER1", &options).unwrap();
+        assert_eq!(
+            adb.calls(),
+            vec!["-s SER1 shell settings put system screen_off_timeout '1800000'"]
+        );
+    }
+
+    #[test]
+    fn test_parse_wm_size() {
+        assert_eq!(
+            parse_wm_size("Physical size: 1080x2400\n"),
+            Some(DisplaySize {
+                width: 1080,
+                height: 2400
+            })
+        );
+        assert_eq!(
+            parse_wm_size("Physical size: 1440x3120\nOverride size: 1080x2340\n"),
+            Some(DisplaySize {
+                width: 1080,
+                height: 2340
+            })
+        );
+        assert_eq!(parse_wm_size("error"), None);
+    }
+
+    #[test]
+    fn test_check_crop() {
+        let display = DisplaySize {
+            width: 1080,
+            height: 2400,
+        };
+        let crop = |width, height, x, y| CropRegion {
+            width,
+            height,
+            x,
+            y,
+        };
+
+        assert!(check_crop(&crop(1080, 2280, 0, 120), &display).is_ok());
+        assert!(check_crop(&crop(1080, 2400, 0, 1), &display).is_err());
+        assert!(check_crop(&crop(0, 100, 0, 0), &display).is_err());
+    }
+}
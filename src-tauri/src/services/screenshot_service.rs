@@ -0,0 +1,36 @@
+//! Device screenshots via `screencap`
+
+use crate::domain::errors::AppError;
+use crate::services::adb_service::AdbRunner;
+use crate::services::media_service::quote_remote_path;
+use std::path::Path;
+
+/// Capture the device screen to a PNG in `local_dir` and return its path
+pub fn capture(adb: &dyn AdbRunner, serial: &str, local_dir: &Path) -> Result<String, AppError> {
+    std::fs::create_dir_all(local_dir)?;
+
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let file_name = format!("screenshot_{}_{}.png", file_safe_serial(serial), stamp);
+    let local_path = local_dir.join(&file_name);
+    let local_str = local_path.to_string_lossy().to_string();
+
+    // adb shell output is text, so capture on the device and pull the file
+    let remote_path = format!("/data/local/tmp/{}", file_name);
+    let quoted = quote_remote_path(&remote_path);
+
+    let result = adb
+        .run(&["-s", serial, "shell", "screencap", "-p", &quoted])
+        .and_then(|_| adb.run_transfer(&["-s", serial, "pull", &remote_path, &local_str]));
+    let _ = adb.run(&["-s", serial, "shell", "rm", "-f", &quoted]);
+    result?;
+
+    Ok(local_str)
+}
+
+/// Serials of network devices contain ':' which Windows can't have in names
+pub(crate) fn file_safe_serial(serial: &str) -> String {
+    serial
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
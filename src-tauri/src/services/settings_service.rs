@@ -1,21 +1,50 @@
 //! Settings service for loading/saving app configuration
 
 use crate::domain::errors::AppError;
-use crate::domain::models::Settings;
+use crate::domain::models::{DeviceRecord, Settings};
+use crate::services::process_service::{self, TOOL_CHECK_TIMEOUT};
+use crate::services::{settings_watch_service, state_file_service};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
 use tauri::{AppHandle, Manager};
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
+const SETTINGS_FILE: &str = "settings.json";
 
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// An executable's path and modification time
+type ExecutableKey = (String, Option<SystemTime>);
 
-const SETTINGS_FILE: &str = "settings.json";
+/// FFmpeg executable and its version, so detection doesn't run
+/// `ffmpeg -version` on every call
+static FFMPEG_VERSION: Mutex<Option<(ExecutableKey, Option<String>)>> = Mutex::new(None);
+
+/// Platform executable name for a tool (`adb` -> `adb.exe` on Windows)
+fn exe_name(tool: &str) -> String {
+    format!("{}{}", tool, std::env::consts::EXE_SUFFIX)
+}
+
+/// Package-manager bin folders that GUI apps on macOS/Linux don't get on PATH
+#[cfg(not(target_os = "windows"))]
+fn unix_tool_paths(tool: &str) -> Vec<PathBuf> {
+    [
+        "/opt/homebrew/bin",
+        "/usr/local/bin",
+        "/usr/bin",
+        "/snap/bin",
+    ]
+    .iter()
+    .map(|dir| PathBuf::from(dir).join(tool))
+    .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn unix_tool_paths(_tool: &str) -> Vec<PathBuf> {
+    Vec::new()
+}
 
 /// Get the settings file path
-fn get_settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+pub fn get_settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let config_dir = app
         .path()
         .app_config_dir()
@@ -29,20 +58,13 @@ fn get_settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     Ok(config_dir.join(SETTINGS_FILE))
 }
 
-/// Load settings from disk
+/// Load settings from disk, recovering from a corrupt file (see
+/// [`state_file_service`])
 pub fn load_settings(app: &AppHandle) -> Result<Settings, AppError> {
     let path = get_settings_path(app)?;
 
-    if !path.exists() {
-        // Return default settings if file doesn't exist
-        return Ok(Settings::new());
-    }
-
-    let content = fs::read_to_string(&path)?;
-    let settings: Settings = serde_json::from_str(&content)
-        .map_err(|e| AppError::SettingsError(format!("Failed to parse settings: {}", e)))?;
-
-    Ok(settings)
+    // Default settings if the file doesn't exist
+    Ok(state_file_service::load_json(app, &path)?.unwrap_or_else(Settings::new))
 }
 
 /// Save settings to disk
@@ -52,7 +74,28 @@ pub fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), AppErro
     let content = serde_json::to_string_pretty(settings)
         .map_err(|e| AppError::SettingsError(format!("Failed to serialize settings: {}", e)))?;
 
-    fs::write(&path, content)?;
+    state_file_service::write_atomic(&path, content.as_bytes())?;
+    settings_watch_service::note_saved(&path);
+    Ok(())
+}
+
+/// Update a device's registry entry, saving only if it changed
+pub fn update_device_record(
+    app: &AppHandle,
+    serial: &str,
+    update: impl FnOnce(&mut DeviceRecord),
+) -> Result<(), AppError> {
+    let mut settings = load_settings(app)?;
+    let record = settings
+        .device_registry
+        .entry(serial.to_string())
+        .or_default();
+
+    let before = record.clone();
+    update(record);
+    if *record != before {
+        save_settings(app, &settings)?;
+    }
     Ok(())
 }
 
@@ -60,7 +103,7 @@ pub fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), AppErro
 pub fn detect_adb_path(app: &AppHandle) -> Option<String> {
     // First check bundled resources
     if let Ok(resource_dir) = app.path().resource_dir() {
-        let bundled_adb = resource_dir.join("resources/adb/adb.exe");
+        let bundled_adb = resource_dir.join("resources/adb").join(exe_name("adb"));
         if bundled_adb.exists() {
             return Some(bundled_adb.to_string_lossy().to_string());
         }
@@ -95,6 +138,11 @@ pub fn detect_adb_path(app: &AppHandle) -> Option<String> {
         }
     }
 
+    // Check package-manager locations on macOS/Linux
+    if let Some(path) = unix_tool_paths("adb").into_iter().find(|p| p.exists()) {
+        return Some(path.to_string_lossy().to_string());
+    }
+
     None
 }
 
@@ -109,19 +157,18 @@ pub fn validate_adb_path(path: &str) -> bool {
     let mut cmd = std::process::Command::new(&path);
     cmd.arg("version");
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
-
-    let output = cmd.output();
+    let output = process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT);
 
-    matches!(output, Ok(o) if o.status.success())
+    matches!(output, Ok(Some(o)) if o.status.success())
 }
 
 /// Auto-detect scrcpy path, checking bundled resources first
 pub fn detect_scrcpy_path(app: &AppHandle) -> Option<String> {
     // First check bundled resources
     if let Ok(resource_dir) = app.path().resource_dir() {
-        let bundled_scrcpy = resource_dir.join("resources/scrcpy/scrcpy.exe");
+        let bundled_scrcpy = resource_dir
+            .join("resources/scrcpy")
+            .join(exe_name("scrcpy"));
         if bundled_scrcpy.exists() {
             return Some(bundled_scrcpy.to_string_lossy().to_string());
         }
@@ -151,6 +198,11 @@ pub fn detect_scrcpy_path(app: &AppHandle) -> Option<String> {
         }
     }
 
+    // Check package-manager locations on macOS/Linux
+    if let Some(path) = unix_tool_paths("scrcpy").into_iter().find(|p| p.exists()) {
+        return Some(path.to_string_lossy().to_string());
+    }
+
     None
 }
 
@@ -165,19 +217,18 @@ pub fn validate_scrcpy_path(path: &str) -> bool {
     let mut cmd = std::process::Command::new(&path);
     cmd.arg("--version");
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    let output = process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT);
 
-    let output = cmd.output();
-
-    matches!(output, Ok(o) if o.status.success())
+    matches!(output, Ok(Some(o)) if o.status.success())
 }
 
 /// Auto-detect FFmpeg path, checking bundled resources first
 pub fn detect_ffmpeg_path(app: &AppHandle) -> Option<String> {
     // First check bundled resources
     if let Ok(resource_dir) = app.path().resource_dir() {
-        let bundled_ffmpeg = resource_dir.join("resources/ffmpeg/ffmpeg.exe");
+        let bundled_ffmpeg = resource_dir
+            .join("resources/ffmpeg")
+            .join(exe_name("ffmpeg"));
         if bundled_ffmpeg.exists() {
             return Some(bundled_ffmpeg.to_string_lossy().to_string());
         }
@@ -192,9 +243,15 @@ pub fn detect_ffmpeg_path(app: &AppHandle) -> Option<String> {
     let common_paths = [
         // User's custom location
         Some(PathBuf::from("C:/ffmpeg/bin/ffmpeg.exe")),
-        // WinGet location (generic guess, though IDs vary)
-        dirs::home_dir()
-            .map(|h| h.join("AppData/Local/Microsoft/WinGet/Packages/ffmpeg/bin/ffmpeg.exe")),
+        // WinGet exposes installed CLI tools through a Links folder
+        dirs::home_dir().map(|h| h.join("AppData/Local/Microsoft/WinGet/Links/ffmpeg.exe")),
+        // Chocolatey shim
+        Some(PathBuf::from("C:/ProgramData/chocolatey/bin/ffmpeg.exe")),
+        // Scoop shim and current app version
+        dirs::home_dir().map(|h| h.join("scoop/shims/ffmpeg.exe")),
+        dirs::home_dir().map(|h| h.join("scoop/apps/ffmpeg/current/bin/ffmpeg.exe")),
+        // Program Files
+        Some(PathBuf::from("C:/Program Files/ffmpeg/bin/ffmpeg.exe")),
     ];
 
     // Check common paths
@@ -206,6 +263,43 @@ pub fn detect_ffmpeg_path(app: &AppHandle) -> Option<String> {
         }
     }
 
+    // Check package-manager locations on macOS/Linux
+    if let Some(path) = unix_tool_paths("ffmpeg").into_iter().find(|p| p.exists()) {
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    // WinGet package folders carry a versioned ID (e.g. Gyan.FFmpeg_Microsoft.Winget.Source_xxx)
+    if let Some(path) = find_winget_ffmpeg() {
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    None
+}
+
+/// Scan WinGet package folders for an ffmpeg build
+/// Layout: Packages/<Publisher.FFmpeg_*>/<ffmpeg-x.y-build>/bin/ffmpeg.exe
+fn find_winget_ffmpeg() -> Option<PathBuf> {
+    let packages_dir = dirs::home_dir()?.join("AppData/Local/Microsoft/WinGet/Packages");
+
+    for package in fs::read_dir(packages_dir).ok()?.flatten() {
+        let package_name = package.file_name().to_string_lossy().to_lowercase();
+        if !package_name.contains("ffmpeg") {
+            continue;
+        }
+
+        let builds = match fs::read_dir(package.path()) {
+            Ok(builds) => builds,
+            Err(_) => continue,
+        };
+
+        for build in builds.flatten() {
+            let candidate = build.path().join("bin/ffmpeg.exe");
+            if candidate.exists() {
+                return Some(candidate);
+            }
+        }
+    }
+
     None
 }
 
@@ -220,12 +314,122 @@ pub fn validate_ffmpeg_path(path: &str) -> bool {
     let mut cmd = std::process::Command::new(&path);
     cmd.arg("-version");
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    let output = process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT);
+
+    matches!(output, Ok(Some(o)) if o.status.success())
+}
+
+/// Auto-detect ffprobe path, preferring the folder FFmpeg was resolved from
+pub fn detect_ffprobe_path(app: &AppHandle, ffmpeg_path: Option<&str>) -> Option<String> {
+    // First check bundled resources (shipped next to ffmpeg)
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        let bundled_ffprobe = resource_dir
+            .join("resources/ffmpeg")
+            .join(exe_name("ffprobe"));
+        if bundled_ffprobe.exists() {
+            return Some(bundled_ffprobe.to_string_lossy().to_string());
+        }
+    }
+
+    // ffprobe ships in the same bin folder as ffmpeg in every common build
+    if let Some(ffmpeg) = ffmpeg_path {
+        if let Some(dir) = PathBuf::from(ffmpeg).parent() {
+            let sibling = dir.join(exe_name("ffprobe"));
+            if sibling.exists() {
+                return Some(sibling.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    // Then check PATH using which
+    if let Ok(path) = which::which("ffprobe") {
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    // Check common locations on Windows
+    let common_paths = [
+        Some(PathBuf::from("C:/ffmpeg/bin/ffprobe.exe")),
+        dirs::home_dir().map(|h| h.join("AppData/Local/Microsoft/WinGet/Links/ffprobe.exe")),
+        Some(PathBuf::from("C:/ProgramData/chocolatey/bin/ffprobe.exe")),
+        dirs::home_dir().map(|h| h.join("scoop/shims/ffprobe.exe")),
+        Some(PathBuf::from("C:/Program Files/ffmpeg/bin/ffprobe.exe")),
+    ];
+
+    for path_opt in common_paths.iter() {
+        if let Some(path) = path_opt {
+            if path.exists() {
+                return Some(path.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    // Check package-manager locations on macOS/Linux
+    if let Some(path) = unix_tool_paths("ffprobe").into_iter().find(|p| p.exists()) {
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    None
+}
+
+/// Validate that a ffprobe path is valid and executable
+pub fn validate_ffprobe_path(path: &str) -> bool {
+    let path = PathBuf::from(path);
+    if !path.exists() {
+        return false;
+    }
+
+    // Try to run ffprobe -version to verify it works
+    let mut cmd = std::process::Command::new(&path);
+    cmd.arg("-version");
+
+    let output = process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT);
+
+    matches!(output, Ok(Some(o)) if o.status.success())
+}
+
+/// Get the FFmpeg version string by running `ffmpeg -version`
+pub fn get_ffmpeg_version(path: &str) -> Option<String> {
+    let mut cmd = std::process::Command::new(path);
+    cmd.arg("-version");
+
+    let output = process_service::output_hidden(&mut cmd, TOOL_CHECK_TIMEOUT).ok()??;
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_ffmpeg_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// [`get_ffmpeg_version`], run again only when the executable's path or
+/// modification time changed
+fn cached_ffmpeg_version(path: &str) -> Option<String> {
+    let key = (
+        path.to_string(),
+        fs::metadata(path).and_then(|m| m.modified()).ok(),
+    );
+    let mut cache = FFMPEG_VERSION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    match cache.as_ref() {
+        Some((cached, version)) if *cached == key => version.clone(),
+        _ => {
+            let version = get_ffmpeg_version(path);
+            *cache = Some((key, version.clone()));
+            version
+        }
+    }
+}
 
-    let output = cmd.output();
+/// Parse the version from the first line of `ffmpeg -version` output
+///
+/// Example: `ffmpeg version 6.1.1-full_build-www.gyan.dev Copyright (c) 2000-2023 ...`
+pub fn parse_ffmpeg_version(output: &str) -> Option<String> {
+    let first_line = output.lines().next()?.trim();
+    let rest = first_line
+        .strip_prefix("ffmpeg version ")
+        .or_else(|| first_line.strip_prefix("ffprobe version "))?;
 
-    matches!(output, Ok(o) if o.status.success())
+    rest.split_whitespace().next().map(|v| v.to_string())
 }
 
 /// Get settings with resolved ADB and scrcpy paths
@@ -277,8 +481,46 @@ pub fn get_settings_with_detection(app: &AppHandle) -> Result<Settings, AppError
         detect_ffmpeg_path(app)
     };
 
+    settings.ffmpeg_version = ffmpeg_resolved.as_deref().and_then(cached_ffmpeg_version);
     settings.ffmpeg_resolved_path = ffmpeg_resolved.clone();
     settings.ffmpeg_available = ffmpeg_resolved.is_some();
 
+    // Resolve ffprobe path
+    let ffprobe_resolved = if let Some(ref user_path) = settings.ffprobe_path {
+        // User specified a path, validate it
+        if validate_ffprobe_path(user_path) {
+            Some(user_path.clone())
+        } else {
+            None
+        }
+    } else {
+        // Try auto-detection (checks bundled, then next to FFmpeg)
+        detect_ffprobe_path(app, ffmpeg_resolved.as_deref())
+    };
+
+    settings.ffprobe_resolved_path = ffprobe_resolved.clone();
+    settings.ffprobe_available = ffprobe_resolved.is_some();
+
     Ok(settings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffmpeg_version() {
+        let output = "ffmpeg version 6.1.1-full_build-www.gyan.dev Copyright (c) 2000-2023 the FFmpeg developers\nbuilt with gcc 12.2.0";
+        assert_eq!(
+            parse_ffmpeg_version(output),
+            Some("6.1.1-full_build-www.gyan.dev".to_string())
+        );
+
+        assert_eq!(
+            parse_ffmpeg_version("ffmpeg version n7.0 Copyright"),
+            Some("n7.0".to_string())
+        );
+        assert_eq!(parse_ffmpeg_version("not ffmpeg output"), None);
+        assert_eq!(parse_ffmpeg_version(""), None);
+    }
+}
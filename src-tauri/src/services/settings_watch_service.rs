@@ -0,0 +1,81 @@
+//! Settings file watcher: picks up edits made to `settings.json` outside the
+//! app, e.g. by hand in a text editor
+//!
+//! Polls the file's size and modification time; saves made by the app itself
+//! are recorded and skipped. Every command loads the settings from disk, so
+//! an edit applies right away; the event lets the UI show it without a
+//! restart. Runs only while the app is open.
+
+use crate::services::settings_service;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+/// How often the settings file is checked
+const TICK: Duration = Duration::from_secs(2);
+
+/// Event emitted with the re-resolved [`Settings`](crate::domain::models::Settings)
+/// when the settings file was edited outside the app
+pub const SETTINGS_CHANGED_EVENT: &str = "settings://changed";
+
+/// Size and modification time of a file, `None` when it doesn't exist
+pub type Fingerprint = Option<(u64, SystemTime)>;
+
+/// The file as the app last saved it
+static LAST_SAVED: Mutex<Fingerprint> = Mutex::new(None);
+
+/// The file's current [`Fingerprint`]
+pub fn fingerprint(path: &Path) -> Fingerprint {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// Record the file as the app just saved it, so the watcher skips the change
+pub fn note_saved(path: &Path) {
+    *LAST_SAVED.lock().unwrap_or_else(PoisonError::into_inner) = fingerprint(path);
+}
+
+/// Whether the file changed since it was last seen, other than by the app
+pub fn edited_outside(seen: Fingerprint, now: Fingerprint, saved: Fingerprint) -> bool {
+    now != seen && now != saved
+}
+
+/// Start the watcher thread; it lives as long as the app
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let Ok(path) = settings_service::get_settings_path(&app) else {
+            return;
+        };
+        let mut seen = fingerprint(&path);
+        loop {
+            std::thread::sleep(TICK);
+            let now = fingerprint(&path);
+            let saved = *LAST_SAVED.lock().unwrap_or_else(PoisonError::into_inner);
+            if edited_outside(seen, now, saved) {
+                // A corrupt edit is set aside and reported by the load
+                if let Ok(settings) = settings_service::get_settings_with_detection(&app) {
+                    let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+                }
+            }
+            // Loading may have replaced a corrupt file with the backup
+            seen = fingerprint(&path);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edited_outside() {
+        let at = |secs| Some((120, SystemTime::UNIX_EPOCH + Duration::from_secs(secs)));
+        assert!(!edited_outside(at(1), at(1), None));
+        assert!(edited_outside(at(1), at(2), at(1)));
+        // Saved by the app
+        assert!(!edited_outside(at(1), at(2), at(2)));
+        // Deleted by hand: defaults from now on
+        assert!(edited_outside(at(1), None, at(1)));
+    }
+}
@@ -0,0 +1,72 @@
+//! Opening and revealing local files through the desktop (Explorer, Finder,
+//! the Linux file manager), so finished transfers can be acted on
+//!
+//! All three go through the opener plugin: revealing selects the file in its
+//! folder (`explorer /select`, `open -R`, or the FileManager1 D-Bus call),
+//! and a chosen app is launched as a program with the file as its argument,
+//! never through a shell.
+
+use crate::domain::errors::AppError;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+/// An existing local file or folder, as an absolute path
+pub fn existing_path(path: &str) -> Result<PathBuf, AppError> {
+    let path = Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err(AppError::InvalidPath(format!(
+            "Not an absolute path: {}",
+            path.display()
+        )));
+    }
+    if !path.exists() {
+        return Err(AppError::InvalidPath(format!(
+            "{} no longer exists",
+            path.display()
+        )));
+    }
+    Ok(path.to_path_buf())
+}
+
+/// The app to open a file with: an app name (`VLC` on macOS) or a program
+/// path. Empty means the default app.
+pub fn chosen_app(with_app: Option<&str>) -> Option<String> {
+    with_app
+        .map(str::trim)
+        .filter(|app| !app.is_empty())
+        .map(str::to_string)
+}
+
+/// Open a file or folder with its default app, or with `with_app`
+pub fn open(app: &AppHandle, path: &str, with_app: Option<&str>) -> Result<(), AppError> {
+    let path = existing_path(path)?;
+    app.opener()
+        .open_path(path.to_string_lossy(), chosen_app(with_app))
+        .map_err(|e| AppError::IoError(format!("Failed to open {}: {}", path.display(), e)))
+}
+
+/// Show a file selected in its folder in the system file manager
+pub fn reveal(app: &AppHandle, path: &str) -> Result<(), AppError> {
+    let path = existing_path(path)?;
+    app.opener()
+        .reveal_item_in_dir(&path)
+        .map_err(|e| AppError::IoError(format!("Failed to reveal {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_existing_path_and_chosen_app() {
+        let here = std::env::temp_dir();
+        assert_eq!(existing_path(&here.to_string_lossy()).unwrap(), here);
+        assert!(existing_path("relative/file.jpg").is_err());
+        assert!(existing_path(&here.join("gesu_missing_file.jpg").to_string_lossy()).is_err());
+
+        assert_eq!(chosen_app(Some(" VLC ")).as_deref(), Some("VLC"));
+        assert_eq!(chosen_app(Some("  ")), None);
+        assert_eq!(chosen_app(None), None);
+    }
+}
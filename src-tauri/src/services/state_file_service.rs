@@ -0,0 +1,186 @@
+//! Crash-safe state files: settings, device timeline, pull history
+//!
+//! A file is written to a temporary file next to it, flushed to disk and
+//! renamed over the original, so a crash mid-write leaves the old version
+//! instead of half a file. The version replaced is kept as `<name>.bak`.
+//! A file that doesn't parse anyway is set aside as `<name>.corrupt` and the
+//! backup (or the defaults) is used, with a [`SETTINGS_ERROR_EVENT`] instead
+//! of every later load failing.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::StateFileRecovery;
+use serde::de::DeserializeOwned;
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted with a [`StateFileRecovery`] payload when a state file
+/// didn't parse and was replaced
+pub const SETTINGS_ERROR_EVENT: &str = "settings-error";
+
+/// `<path><suffix>`, e.g. `settings.json.bak`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Backup of the version a write replaced
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, ".bak")
+}
+
+/// Where a file that didn't parse is set aside
+pub fn corrupt_path(path: &Path) -> PathBuf {
+    sibling(path, ".corrupt")
+}
+
+/// Replace a file's contents so that it's either the old or the new version,
+/// never a mix. The old version becomes the backup.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), AppError> {
+    // Unique per write: settings are saved from several threads
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let temp = sibling(
+        path,
+        &format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ),
+    );
+
+    let written = fs::File::create(&temp).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+
+    if path.exists() {
+        // A missing backup only matters if this write is later corrupted
+        let _ = fs::copy(path, backup_path(path));
+    }
+    fs::rename(&temp, path).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        AppError::from(e)
+    })
+}
+
+/// Load a file with `parse`. None when there's no file yet. When it doesn't
+/// parse, it's moved aside and the backup is restored in its place, or the
+/// file is left missing (so defaults are used) when the backup is bad too.
+pub fn load_with<T>(
+    path: &Path,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<(Option<T>, Option<StateFileRecovery>), AppError> {
+    if !path.exists() {
+        return Ok((None, None));
+    }
+    let error = match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| parse(&content))
+    {
+        Ok(value) => return Ok((Some(value), None)),
+        Err(e) => e,
+    };
+
+    let corrupt = corrupt_path(path);
+    fs::rename(path, &corrupt)?;
+
+    let restored = fs::read_to_string(backup_path(path))
+        .ok()
+        .and_then(|content| parse(&content).ok().map(|value| (value, content)));
+    if let Some((_, content)) = &restored {
+        write_atomic(path, content.as_bytes())?;
+    }
+
+    let recovery = StateFileRecovery {
+        file: path.to_string_lossy().to_string(),
+        error,
+        restored_from_backup: restored.is_some(),
+        corrupt_copy: corrupt.to_string_lossy().to_string(),
+    };
+    Ok((restored.map(|(value, _)| value), Some(recovery)))
+}
+
+/// Load a JSON state file, telling the user when it had to be recovered
+pub fn load_json<T: DeserializeOwned>(app: &AppHandle, path: &Path) -> Result<Option<T>, AppError> {
+    let (value, recovery) = load_with(path, |content| {
+        serde_json::from_str(content).map_err(|e| e.to_string())
+    })?;
+    if let Some(recovery) = recovery {
+        let _ = app.emit(SETTINGS_ERROR_EVENT, recovery);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_number(content: &str) -> Result<u32, String> {
+        content.trim().parse().map_err(|e| format!("{}", e))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gesu_state_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_atomic_keeps_a_backup() {
+        let dir = temp_dir("write");
+        let path = dir.join("settings.json");
+
+        write_atomic(&path, b"1").unwrap();
+        assert!(!backup_path(&path).exists());
+        write_atomic(&path, b"2").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "2");
+        assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "1");
+        // No temporary files left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_recovers_from_backup() {
+        let dir = temp_dir("recover");
+        let path = dir.join("settings.json");
+        assert_eq!(load_with(&path, parse_number).unwrap(), (None, None));
+
+        write_atomic(&path, b"1").unwrap();
+        write_atomic(&path, b"2").unwrap();
+        assert_eq!(load_with(&path, parse_number).unwrap().0, Some(2));
+
+        // Cut off mid-write
+        fs::write(&path, b"{\"adb_pa").unwrap();
+        let (value, recovery) = load_with(&path, parse_number).unwrap();
+        assert_eq!(value, Some(1));
+        let recovery = recovery.unwrap();
+        assert!(recovery.restored_from_backup);
+        assert_eq!(
+            fs::read_to_string(corrupt_path(&path)).unwrap(),
+            "{\"adb_pa"
+        );
+        // The restored backup is what later loads see
+        assert_eq!(load_with(&path, parse_number).unwrap(), (Some(1), None));
+
+        // Backup bad too: defaults from then on
+        fs::write(&path, b"x").unwrap();
+        fs::write(backup_path(&path), b"y").unwrap();
+        let (value, recovery) = load_with(&path, parse_number).unwrap();
+        assert_eq!(value, None);
+        assert!(!recovery.unwrap().restored_from_backup);
+        assert!(!path.exists());
+        assert_eq!(load_with(&path, parse_number).unwrap(), (None, None));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,159 @@
+//! Free space on the computer, checked before pulls, previews, recordings
+//! and backups so they fail up front instead of halfway through
+//!
+//! Free space comes from `GetDiskFreeSpaceExW` on Windows and `df -Pk`
+//! elsewhere. When it can't be read the operation goes ahead unchecked.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::ConflictPolicy;
+use std::path::Path;
+
+/// Space a recording needs to start: about a quarter hour of screen video at
+/// scrcpy's default 8 Mbps. How long it runs isn't known up front.
+pub const RECORDING_RESERVE: u64 = 1024 * 1024 * 1024;
+
+/// Bytes free for the current user on the volume holding `path`. The path
+/// may not exist yet; its nearest existing parent is measured.
+pub fn available_bytes(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    volume_free_bytes(existing)
+}
+
+/// How many bytes short `available` is of `needed`, if any
+pub fn shortfall(needed: u64, available: u64) -> Option<u64> {
+    needed.checked_sub(available).filter(|short| *short > 0)
+}
+
+/// Fail with `InsufficientLocalStorage` when `dir`'s volume can't hold
+/// `needed` more bytes
+pub fn ensure_space(dir: &Path, needed: u64) -> Result<(), AppError> {
+    if needed == 0 {
+        return Ok(());
+    }
+    let Some(available) = available_bytes(dir) else {
+        return Ok(());
+    };
+    match shortfall(needed, available) {
+        Some(short) => Err(AppError::InsufficientLocalStorage(format!(
+            "{} needs {} but only {} is free; free up {} ({} bytes)",
+            dir.display(),
+            format_bytes(needed),
+            format_bytes(available),
+            format_bytes(short),
+            short
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Bytes a pull adds to the destination. Files left alone take nothing, and
+/// an overwritten file frees the space of the one it replaces.
+pub fn pull_bytes_needed(size_bytes: u64, existing: Option<u64>, conflict: ConflictPolicy) -> u64 {
+    match (existing, conflict) {
+        (None, _) | (Some(_), ConflictPolicy::Rename) => size_bytes,
+        (Some(_), ConflictPolicy::Skip) => 0,
+        (Some(len), ConflictPolicy::Overwrite | ConflictPolicy::KeepNewer) => {
+            size_bytes.saturating_sub(len)
+        }
+    }
+}
+
+/// Size for messages, in binary units with one decimal (`1.5 GB`)
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn volume_free_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory: *const u16,
+            free_to_caller: *mut u64,
+            total: *mut u64,
+            total_free: *mut u64,
+        ) -> i32;
+    }
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut free = 0u64;
+    // SAFETY: `wide` is NUL-terminated and outlives the call; the unused
+    // totals may be null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut free,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(free)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn volume_free_bytes(path: &Path) -> Option<u64> {
+    use crate::services::process_service;
+
+    // -P keeps each volume on one line, with the available column fourth;
+    // a hung network mount must not hold up the operation
+    let mut cmd = std::process::Command::new("df");
+    cmd.arg("-Pk").arg(path);
+    let output =
+        process_service::output_hidden(&mut cmd, process_service::TOOL_CHECK_TIMEOUT).ok()??;
+    if !output.status.success() {
+        return None;
+    }
+    crate::services::compare_service::parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfall_and_message() {
+        assert_eq!(shortfall(100, 40), Some(60));
+        assert_eq!(shortfall(100, 100), None);
+        assert_eq!(shortfall(10, 40), None);
+
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(40 * 1024 * 1024 * 1024), "40.0 GB");
+
+        // No volume has this much room, so the exact shortfall is reported
+        let err = ensure_space(&std::env::temp_dir(), u64::MAX).unwrap_err();
+        assert_eq!(err.kind(), "InsufficientLocalStorage");
+        assert!(ensure_space(&std::env::temp_dir(), 0).is_ok());
+    }
+
+    #[test]
+    fn test_pull_bytes_needed_follows_conflict_policy() {
+        assert_eq!(pull_bytes_needed(100, None, ConflictPolicy::Skip), 100);
+        assert_eq!(pull_bytes_needed(100, Some(30), ConflictPolicy::Skip), 0);
+        assert_eq!(
+            pull_bytes_needed(100, Some(30), ConflictPolicy::Rename),
+            100
+        );
+        assert_eq!(
+            pull_bytes_needed(100, Some(30), ConflictPolicy::Overwrite),
+            70
+        );
+        assert_eq!(
+            pull_bytes_needed(10, Some(30), ConflictPolicy::KeepNewer),
+            0
+        );
+    }
+}
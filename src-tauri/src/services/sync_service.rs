@@ -0,0 +1,509 @@
+//! Incremental folder sync between a local folder and a device folder
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    AffectedItem, ConflictPolicy, SyncAction, SyncActionKind, SyncDirection, SyncPlan, SyncReport,
+};
+use crate::services::adb_service::AdbRunner;
+use crate::services::conflict_service::{self, Resolution};
+use crate::services::hash_service;
+use crate::services::media_service::quote_remote_path;
+use crate::services::storage_service;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Modification times this close are treated as equal (FAT/exFAT store 2s resolution)
+const MTIME_TOLERANCE_SECS: i64 = 2;
+
+/// Size and modification time of one file in a tree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileEntry {
+    pub size: u64,
+    /// Seconds since the Unix epoch
+    pub mtime: i64,
+}
+
+/// Files under a root, keyed by `/`-separated relative path
+pub type FileTree = BTreeMap<String, FileEntry>;
+
+/// Walk a local folder recursively (symlinks are not followed)
+pub fn list_local_tree(root: &Path) -> Result<FileTree, AppError> {
+    let mut tree = FileTree::new();
+    if root.exists() {
+        walk_local(root, "", &mut tree)?;
+    }
+    Ok(tree)
+}
+
+fn walk_local(dir: &Path, prefix: &str, tree: &mut FileTree) -> Result<(), AppError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.path().symlink_metadata()?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+
+        if metadata.is_dir() {
+            walk_local(&entry.path(), &relative, tree)?;
+        } else if metadata.is_file() {
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            tree.insert(
+                relative,
+                FileEntry {
+                    size: metadata.len(),
+                    mtime,
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// List files under a device folder with one `find`/`stat` call
+pub fn list_remote_tree(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    root: &str,
+) -> Result<FileTree, AppError> {
+    // A missing root is an empty tree, not an error
+    let script = format!(
+        "find {} -type f -exec stat -c '%s|%Y|%n' {{}} + 2>/dev/null; true",
+        quote_remote_path(root)
+    );
+    let output = adb.run_idempotent(&["-s", serial, "shell", &script])?;
+    Ok(parse_stat_listing(&output, root))
+}
+
+/// Parse `size|mtime|path` lines printed by `stat -c '%s|%Y|%n'`
+pub fn parse_stat_listing(output: &str, root: &str) -> FileTree {
+    let prefix = format!("{}/", root.trim_end_matches('/'));
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_end_matches('\r').splitn(3, '|');
+            let size = parts.next()?.trim().parse().ok()?;
+            let mtime = parts.next()?.trim().parse().ok()?;
+            let relative = parts.next()?.strip_prefix(&prefix)?;
+            Some((relative.to_string(), FileEntry { size, mtime }))
+        })
+        .collect()
+}
+
+/// Result of comparing two trees
+#[derive(Debug, Default)]
+pub struct TreeDiff {
+    pub actions: Vec<SyncAction>,
+    /// Files present on both sides that are already identical
+    pub unchanged: usize,
+    /// Differing files left alone because of the conflict policy
+    pub skipped_conflicts: usize,
+}
+
+/// Compare two trees and decide which files to copy.
+///
+/// `content_equal` is consulted only when sizes match but times differ, so a
+/// hash comparison runs for as few files as possible. `conflict` applies to
+/// files that exist on both sides and differ. Nothing is ever deleted.
+pub fn diff_trees(
+    local: &FileTree,
+    remote: &FileTree,
+    direction: SyncDirection,
+    conflict: ConflictPolicy,
+    mut content_equal: impl FnMut(&str) -> bool,
+) -> TreeDiff {
+    let paths: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let mut diff = TreeDiff::default();
+
+    for path in paths {
+        let action = match (local.get(path), remote.get(path)) {
+            (Some(l), None) if direction != SyncDirection::FromDevice => {
+                Some((SyncActionKind::Push, "missing on device", l.size, None))
+            }
+            (None, Some(r)) if direction != SyncDirection::ToDevice => {
+                Some((SyncActionKind::Pull, "missing locally", r.size, None))
+            }
+            (Some(l), Some(r)) => {
+                let reason = if l.size != r.size {
+                    Some("size differs")
+                } else if (l.mtime - r.mtime).abs() <= MTIME_TOLERANCE_SECS || content_equal(path) {
+                    None
+                } else {
+                    Some("modified time differs")
+                };
+
+                match reason {
+                    None => {
+                        diff.unchanged += 1;
+                        None
+                    }
+                    Some(reason) => {
+                        let push = match direction {
+                            SyncDirection::ToDevice => true,
+                            SyncDirection::FromDevice => false,
+                            SyncDirection::TwoWay => l.mtime >= r.mtime,
+                        };
+                        let (kind, source, dest, dest_tree) = if push {
+                            (SyncActionKind::Push, l, r, remote)
+                        } else {
+                            (SyncActionKind::Pull, r, l, local)
+                        };
+
+                        let target = match conflict {
+                            ConflictPolicy::Overwrite => Some(None),
+                            ConflictPolicy::Skip => None,
+                            ConflictPolicy::KeepNewer => {
+                                (source.mtime > dest.mtime).then_some(None)
+                            }
+                            ConflictPolicy::Rename => match conflict_service::resolve(
+                                conflict,
+                                path,
+                                Some(Some(dest.mtime)),
+                                Some(source.mtime),
+                                |candidate| dest_tree.contains_key(candidate),
+                            ) {
+                                Resolution::Write(renamed) => Some(Some(renamed)),
+                                Resolution::Skip(_) => None,
+                            },
+                        };
+
+                        match target {
+                            Some(target) => Some((kind, reason, source.size, target)),
+                            None => {
+                                diff.skipped_conflicts += 1;
+                                None
+                            }
+                        }
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        if let Some((kind, reason, size_bytes, target_path)) = action {
+            diff.actions.push(SyncAction {
+                relative_path: path.clone(),
+                kind,
+                reason: reason.to_string(),
+                size_bytes,
+                target_path,
+            });
+        }
+    }
+
+    diff
+}
+
+/// Compute the sync plan without transferring anything
+pub fn plan_sync(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    local_root: &str,
+    remote_root: &str,
+    direction: SyncDirection,
+    compare_hash: bool,
+    conflict: ConflictPolicy,
+) -> Result<SyncPlan, AppError> {
+    if !remote_root.starts_with('/') {
+        return Err(AppError::InvalidPath(format!(
+            "Device folder must be an absolute path: {}",
+            remote_root
+        )));
+    }
+
+    let local_path = Path::new(local_root);
+    if direction != SyncDirection::FromDevice && !local_path.is_dir() {
+        return Err(AppError::InvalidPath(format!(
+            "Local folder not found: {}",
+            local_root
+        )));
+    }
+
+    let local = list_local_tree(local_path)?;
+    let remote = list_remote_tree(adb, serial, remote_root)?;
+
+    let diff = diff_trees(&local, &remote, direction, conflict, |relative| {
+        compare_hash && hashes_match(adb, serial, local_root, remote_root, relative)
+    });
+    let total_bytes = diff.actions.iter().map(|a| a.size_bytes).sum();
+
+    Ok(SyncPlan {
+        local_root: local_root.to_string(),
+        remote_root: remote_root.to_string(),
+        direction,
+        actions: diff.actions,
+        unchanged: diff.unchanged,
+        skipped_conflicts: diff.skipped_conflicts,
+        total_bytes,
+    })
+}
+
+/// Plan and then execute a sync, continuing past individual failures
+pub fn run_sync(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    local_root: &str,
+    remote_root: &str,
+    direction: SyncDirection,
+    compare_hash: bool,
+    conflict: ConflictPolicy,
+) -> Result<SyncReport, AppError> {
+    let plan = plan_sync(
+        adb,
+        serial,
+        local_root,
+        remote_root,
+        direction,
+        compare_hash,
+        conflict,
+    )?;
+    let pull_bytes = plan
+        .actions
+        .iter()
+        .filter(|a| a.kind == SyncActionKind::Pull)
+        .map(|a| a.size_bytes)
+        .sum();
+    storage_service::ensure_space(Path::new(local_root), pull_bytes)?;
+    Ok(execute_plan(adb, serial, plan))
+}
+
+/// Execute a previously computed plan
+pub fn execute_plan(adb: &dyn AdbRunner, serial: &str, plan: SyncPlan) -> SyncReport {
+    let mut transferred = 0;
+    let mut failures = Vec::new();
+
+    for action in &plan.actions {
+        // A renamed target only applies to the side being written
+        let dest_relative = action
+            .target_path
+            .as_deref()
+            .unwrap_or(&action.relative_path);
+        let (local_relative, remote_relative) = match action.kind {
+            SyncActionKind::Push => (action.relative_path.as_str(), dest_relative),
+            SyncActionKind::Pull => (dest_relative, action.relative_path.as_str()),
+        };
+
+        let local = local_path(&plan.local_root, local_relative);
+        let local_str = local.to_string_lossy().to_string();
+        let remote = remote_path(&plan.remote_root, remote_relative);
+
+        let result = match action.kind {
+            // adb push creates missing parent folders on the device
+            SyncActionKind::Push => adb.run_transfer(&["-s", serial, "push", &local_str, &remote]),
+            // -a keeps the device mtime so the next sync sees the file as unchanged
+            SyncActionKind::Pull => local
+                .parent()
+                .map(std::fs::create_dir_all)
+                .transpose()
+                .map_err(AppError::from)
+                .and_then(|_| adb.run_transfer(&["-s", serial, "pull", "-a", &remote, &local_str])),
+        };
+
+        match result {
+            Ok(_) => transferred += 1,
+            Err(e) => failures.push(AffectedItem {
+                path: action.relative_path.clone(),
+                size_bytes: action.size_bytes,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    SyncReport {
+        plan,
+        transferred,
+        failures,
+    }
+}
+
+fn local_path(root: &str, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .fold(PathBuf::from(root), |path, part| path.join(part))
+}
+
+fn remote_path(root: &str, relative: &str) -> String {
+    format!("{}/{}", root.trim_end_matches('/'), relative)
+}
+
+fn hashes_match(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    local_root: &str,
+    remote_root: &str,
+    relative: &str,
+) -> bool {
+    let local = hash_service::sha256_file(&local_path(local_root, relative)).ok();
+    let remote = adb
+        .run_idempotent(&[
+            "-s",
+            serial,
+            "shell",
+            "sha256sum",
+            &quote_remote_path(&remote_path(remote_root, relative)),
+        ])
+        .ok()
+        .and_then(|out| out.split_whitespace().next().map(|h| h.to_lowercase()));
+
+    matches!((local, remote), (Some(l), Some(r)) if l == r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(entries: &[(&str, u64, i64)]) -> FileTree {
+        entries
+            .iter()
+            .map(|(p, size, mtime)| {
+                (
+                    p.to_string(),
+                    FileEntry {
+                        size: *size,
+                        mtime: *mtime,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_stat_listing() {
+        let output = "1024|1700000000|/sdcard/Music/a.mp3\r\n\
+                      2048|1700000100|/sdcard/Music/Album/b|c.mp3\n\
+                      garbage line\n";
+        let parsed = parse_stat_listing(output, "/sdcard/Music/");
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            parsed["a.mp3"],
+            FileEntry {
+                size: 1024,
+                mtime: 1700000000
+            }
+        );
+        assert_eq!(parsed["Album/b|c.mp3"].size, 2048);
+    }
+
+    #[test]
+    fn test_diff_to_device() {
+        let local = tree(&[
+            ("new.mp3", 10, 100),
+            ("same.mp3", 5, 100),
+            ("big.mp3", 9, 100),
+        ]);
+        let remote = tree(&[
+            ("same.mp3", 5, 101),
+            ("big.mp3", 7, 100),
+            ("only.mp3", 3, 1),
+        ]);
+
+        let diff = diff_trees(
+            &local,
+            &remote,
+            SyncDirection::ToDevice,
+            ConflictPolicy::Overwrite,
+            |_| false,
+        );
+
+        assert_eq!(diff.unchanged, 1);
+        let paths: Vec<_> = diff
+            .actions
+            .iter()
+            .map(|a| a.relative_path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["big.mp3", "new.mp3"]);
+        assert!(diff.actions.iter().all(|a| a.kind == SyncActionKind::Push));
+    }
+
+    #[test]
+    fn test_diff_two_way_newer_wins() {
+        let local = tree(&[("a.txt", 5, 500), ("local_only.txt", 1, 1)]);
+        let remote = tree(&[("a.txt", 5, 900), ("remote_only.txt", 2, 2)]);
+
+        let diff = diff_trees(
+            &local,
+            &remote,
+            SyncDirection::TwoWay,
+            ConflictPolicy::Overwrite,
+            |_| false,
+        );
+
+        let find = |p: &str| {
+            diff.actions
+                .iter()
+                .find(|a| a.relative_path == p)
+                .unwrap()
+                .kind
+        };
+        assert_eq!(find("a.txt"), SyncActionKind::Pull);
+        assert_eq!(find("local_only.txt"), SyncActionKind::Push);
+        assert_eq!(find("remote_only.txt"), SyncActionKind::Pull);
+    }
+
+    #[test]
+    fn test_diff_hash_match_skips_touched_file() {
+        let local = tree(&[("a.txt", 5, 500)]);
+        let remote = tree(&[("a.txt", 5, 900)]);
+
+        let diff = diff_trees(
+            &local,
+            &remote,
+            SyncDirection::ToDevice,
+            ConflictPolicy::Overwrite,
+            |_| true,
+        );
+
+        assert!(diff.actions.is_empty());
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn test_diff_conflict_policies() {
+        let local = tree(&[("a.txt", 5, 100), ("b.txt", 9, 900)]);
+        let remote = tree(&[("a.txt", 7, 500), ("b.txt", 3, 100), ("b (1).txt", 1, 1)]);
+
+        let skip = diff_trees(
+            &local,
+            &remote,
+            SyncDirection::ToDevice,
+            ConflictPolicy::Skip,
+            |_| false,
+        );
+        assert!(skip.actions.is_empty());
+        assert_eq!(skip.skipped_conflicts, 2);
+
+        // Only b.txt is newer locally
+        let newer = diff_trees(
+            &local,
+            &remote,
+            SyncDirection::ToDevice,
+            ConflictPolicy::KeepNewer,
+            |_| false,
+        );
+        assert_eq!(newer.actions.len(), 1);
+        assert_eq!(newer.actions[0].relative_path, "b.txt");
+
+        let rename = diff_trees(
+            &local,
+            &remote,
+            SyncDirection::ToDevice,
+            ConflictPolicy::Rename,
+            |_| false,
+        );
+        let b = rename
+            .actions
+            .iter()
+            .find(|a| a.relative_path == "b.txt")
+            .unwrap();
+        assert_eq!(b.target_path.as_deref(), Some("b (2).txt"));
+    }
+}
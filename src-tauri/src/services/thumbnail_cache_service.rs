@@ -0,0 +1,184 @@
+//! Where thumbnails are cached, keyed by device, path, size and modification
+//! time
+//!
+//! A thumbnail's file name is a hash of all four, so an edited or replaced
+//! photo gets a new one and same-named files in different folders never
+//! share one. `index.json` in the thumbnail folder records the current
+//! thumbnail of each device file; a superseded one is deleted when its
+//! replacement is keyed.
+
+use crate::services::hash_service;
+use crate::services::state_file_service;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+const INDEX_FILE: &str = "index.json";
+
+/// The current thumbnail of one device file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThumbnailEntry {
+    /// File name in the thumbnail folder
+    pub file: String,
+    pub size_bytes: u64,
+    /// Modification time on the device, in seconds since the Unix epoch
+    pub modified: i64,
+}
+
+/// Entries per device file, per device serial
+type ThumbnailIndex = HashMap<String, HashMap<String, ThumbnailEntry>>;
+
+/// File name of the thumbnail for this version of a device file
+pub fn thumbnail_file_name(
+    serial: &str,
+    remote_path: &str,
+    size_bytes: u64,
+    modified: i64,
+) -> String {
+    let mut hasher = Sha256::default();
+    for part in [
+        serial.as_bytes(),
+        remote_path.as_bytes(),
+        &size_bytes.to_le_bytes(),
+        &modified.to_le_bytes(),
+    ] {
+        hasher.update(part);
+        hasher.update(&[0]);
+    }
+    format!("thumb_{}.jpg", &hash_service::hex(&hasher.finalize())[..32])
+}
+
+/// Where the thumbnail of this version of a device file goes. Records it as
+/// the file's current thumbnail and deletes the one it replaces.
+pub fn thumbnail_path(
+    thumb_dir: &Path,
+    serial: &str,
+    remote_path: &str,
+    size_bytes: u64,
+    modified: i64,
+) -> PathBuf {
+    let entry = ThumbnailEntry {
+        file: thumbnail_file_name(serial, remote_path, size_bytes, modified),
+        size_bytes,
+        modified,
+    };
+    let path = thumb_dir.join(&entry.file);
+
+    with_index(thumb_dir, |index| {
+        let device = index.entry(serial.to_string()).or_default();
+        if device.get(remote_path) == Some(&entry) {
+            return false;
+        }
+        if let Some(stale) = device.insert(remote_path.to_string(), entry) {
+            let _ = std::fs::remove_file(thumb_dir.join(stale.file));
+        }
+        true
+    });
+    path
+}
+
+/// The cached thumbnail of a device file and the version it was made from,
+/// if one was made and is still on disk
+pub fn cached_thumbnail(
+    thumb_dir: &Path,
+    serial: &str,
+    remote_path: &str,
+) -> Option<(PathBuf, ThumbnailEntry)> {
+    let mut found = None;
+    with_index(thumb_dir, |index| {
+        found = index
+            .get(serial)
+            .and_then(|device| device.get(remote_path))
+            .cloned();
+        false
+    });
+    let entry = found?;
+    let path = thumb_dir.join(&entry.file);
+    std::fs::metadata(&path)
+        .is_ok_and(|m| m.len() > 0)
+        .then_some((path, entry))
+}
+
+/// Run `f` on the index of `thumb_dir`, saving it when `f` returns true. The
+/// grid and the warm-up make thumbnails at the same time, so the index is
+/// loaded once and shared.
+fn with_index(thumb_dir: &Path, f: impl FnOnce(&mut ThumbnailIndex) -> bool) {
+    static INDEX: OnceLock<Mutex<Option<(PathBuf, ThumbnailIndex)>>> = OnceLock::new();
+    let mut loaded = INDEX
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    if loaded.as_ref().is_none_or(|(dir, _)| dir != thumb_dir) {
+        let index = std::fs::read_to_string(thumb_dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        *loaded = Some((thumb_dir.to_path_buf(), index));
+    }
+    let Some((_, index)) = loaded.as_mut() else {
+        return;
+    };
+
+    if f(index) {
+        // Unsaved entries are rebuilt the next time they're requested
+        let _ = serde_json::to_string(index)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                state_file_service::write_atomic(&thumb_dir.join(INDEX_FILE), json.as_bytes())
+                    .map_err(|e| e.to_string())
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_names_follow_path_size_and_mtime() {
+        let name = thumbnail_file_name("SER1", "/sdcard/DCIM/IMG_0001.jpg", 100, 1_700_000_000);
+        assert_ne!(
+            name,
+            thumbnail_file_name("SER1", "/sdcard/Download/IMG_0001.jpg", 100, 1_700_000_000)
+        );
+        assert_ne!(
+            name,
+            thumbnail_file_name("SER1", "/sdcard/DCIM/IMG_0001.jpg", 120, 1_700_000_000)
+        );
+        assert_ne!(
+            name,
+            thumbnail_file_name("SER1", "/sdcard/DCIM/IMG_0001.jpg", 100, 1_700_000_060)
+        );
+        assert_eq!(
+            name,
+            thumbnail_file_name("SER1", "/sdcard/DCIM/IMG_0001.jpg", 100, 1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn test_edited_file_replaces_its_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("gesu_thumb_index_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let remote = "/sdcard/DCIM/IMG_0001.jpg";
+
+        let first = thumbnail_path(&dir, "SER1", remote, 100, 1_700_000_000);
+        assert!(cached_thumbnail(&dir, "SER1", remote).is_none());
+        std::fs::write(&first, b"jpeg").unwrap();
+        let (cached, entry) = cached_thumbnail(&dir, "SER1", remote).unwrap();
+        assert_eq!(cached, first);
+        assert_eq!(entry.size_bytes, 100);
+
+        // Edited on the device: new thumbnail, the old one is removed
+        let second = thumbnail_path(&dir, "SER1", remote, 120, 1_700_000_060);
+        assert_ne!(second, first);
+        assert!(!first.exists());
+        assert!(cached_thumbnail(&dir, "SER1", remote).is_none());
+
+        let index = std::fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(index.contains(&second.file_name().unwrap().to_string_lossy().to_string()));
+    }
+}
@@ -0,0 +1,169 @@
+//! Thumbnail warm-up: makes the thumbnails of a folder's first items in the
+//! background once it's listed, so the grid is filled before the user
+//! scrolls. Uses background-priority adb and stops when another folder is
+//! opened.
+
+use crate::domain::models::MediaItem;
+use crate::services::adb_service::AdbRunner;
+use crate::services::{media_service, thumbnail_cache_service};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter};
+
+/// Most thumbnails warmed per folder
+pub const MAX_WARMUP_COUNT: usize = 200;
+
+/// Event emitted with the device path of each thumbnail made
+pub const THUMBNAIL_WARMED_EVENT: &str = "thumbnail-warmed";
+
+/// Bumped by each warm-up so the one before it stops
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Paths of the first `count` items without a cached thumbnail of their
+/// current size. The modification time is checked when the thumbnail is
+/// asked for.
+pub fn warmup_targets(
+    items: &[MediaItem],
+    count: usize,
+    thumb_dir: &Path,
+    serial: &str,
+) -> Vec<String> {
+    items
+        .iter()
+        .take(count.min(MAX_WARMUP_COUNT))
+        .filter(|item| {
+            thumbnail_cache_service::cached_thumbnail(thumb_dir, serial, &item.path)
+                .is_none_or(|(_, entry)| entry.size_bytes != item.size_bytes)
+        })
+        .map(|item| item.path.clone())
+        .collect()
+}
+
+/// Make thumbnails for `paths` in order while `current` holds, calling
+/// `warmed` for each one made; returns how many were made
+pub fn warm(
+    adb: &dyn AdbRunner,
+    ffmpeg_path: Option<&String>,
+    serial: &str,
+    paths: &[String],
+    thumb_dir: &Path,
+    current: impl Fn() -> bool,
+    warmed: impl Fn(&str),
+) -> usize {
+    let mut made = 0;
+    for path in paths {
+        if !current() {
+            break;
+        }
+        if media_service::get_thumbnail(adb, ffmpeg_path, serial, path, thumb_dir).is_ok() {
+            warmed(path);
+            made += 1;
+        }
+    }
+    made
+}
+
+/// Warm `paths` on a thread, replacing any warm-up still running
+pub fn start<A: AdbRunner + Send + 'static>(
+    app: AppHandle,
+    adb: A,
+    ffmpeg_path: Option<String>,
+    serial: String,
+    paths: Vec<String>,
+    thumb_dir: PathBuf,
+) {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if paths.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        warm(
+            &adb,
+            ffmpeg_path.as_ref(),
+            &serial,
+            &paths,
+            &thumb_dir,
+            || GENERATION.load(Ordering::SeqCst) == generation,
+            |path| {
+                let _ = app.emit(THUMBNAIL_WARMED_EVENT, path);
+            },
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::MediaType;
+    use crate::services::adb_service::mock::MockAdb;
+    use std::cell::Cell;
+
+    fn item(name: &str) -> MediaItem {
+        MediaItem {
+            path: format!("/sdcard/DCIM/Camera/{}", name),
+            name: name.to_string(),
+            media_type: MediaType::Image,
+            size_bytes: 1,
+            width: None,
+            height: None,
+            duration_ms: None,
+            date_taken: None,
+            timezone: None,
+            thumbnail_url: None,
+            motion_photo: false,
+        }
+    }
+
+    #[test]
+    fn test_warmup_targets_skip_cached() {
+        let dir = std::env::temp_dir().join(format!("gesu_warmup_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let thumbnail = |name: &str, size: u64| {
+            let path = format!("/sdcard/DCIM/Camera/{}", name);
+            thumbnail_cache_service::thumbnail_path(&dir, "SER1", &path, size, 1_700_000_000)
+        };
+        std::fs::write(thumbnail("b.jpg", 1), b"jpeg").unwrap();
+        std::fs::write(thumbnail("c.jpg", 1), b"").unwrap();
+        // Made before the file on the device changed size
+        std::fs::write(thumbnail("d.jpg", 7), b"jpeg").unwrap();
+
+        let items = vec![item("a.jpg"), item("b.jpg"), item("c.jpg"), item("d.jpg")];
+        let targets = warmup_targets(&items, 4, &dir, "SER1");
+        let other_device = warmup_targets(&items, 4, &dir, "SER2");
+        let none = warmup_targets(&items, 0, &dir, "SER1");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            targets,
+            vec![
+                "/sdcard/DCIM/Camera/a.jpg",
+                "/sdcard/DCIM/Camera/c.jpg",
+                "/sdcard/DCIM/Camera/d.jpg"
+            ]
+        );
+        assert_eq!(other_device.len(), 4);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_warm_stops_when_replaced() {
+        let adb = MockAdb::new();
+        let paths = vec!["/sdcard/a.txt".to_string(), "/sdcard/b.txt".to_string()];
+        let checks = Cell::new(0);
+
+        let made = warm(
+            &adb,
+            None,
+            "SER1",
+            &paths,
+            &std::env::temp_dir(),
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() == 1
+            },
+            |_| {},
+        );
+        assert_eq!(made, 0);
+        assert_eq!(checks.get(), 2);
+    }
+}
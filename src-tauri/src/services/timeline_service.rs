@@ -0,0 +1,170 @@
+//! Per-device timeline of connects, mirror sessions and transfers
+//!
+//! Kept in its own file next to the settings so answering "when did I last
+//! back up this phone?" doesn't grow the settings file. Recording is best
+//! effort and never fails the operation being recorded.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{DeviceEvent, DeviceEventKind};
+use crate::services::state_file_service;
+use crate::state::{read_lock, write_lock};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+
+const TIMELINE_FILE: &str = "device_timeline.json";
+
+/// Events kept per device; older ones are dropped
+const EVENTS_PER_DEVICE: usize = 500;
+
+/// Events per device serial, newest first
+pub type Timeline = HashMap<String, Vec<DeviceEvent>>;
+
+/// Timeline loaded from disk on first use
+#[derive(Default)]
+pub struct TimelineState {
+    timeline: RwLock<Option<Timeline>>,
+}
+
+/// Add an event to the front of a device's timeline
+pub fn push_event(timeline: &mut Timeline, serial: &str, event: DeviceEvent) {
+    let events = timeline.entry(serial.to_string()).or_default();
+    events.insert(0, event);
+    events.truncate(EVENTS_PER_DEVICE);
+}
+
+/// Record an event for a device now
+pub fn record(app: &AppHandle, serial: &str, kind: DeviceEventKind, detail: Option<String>) {
+    let event = DeviceEvent {
+        kind,
+        at: chrono::Utc::now().to_rfc3339(),
+        detail,
+    };
+
+    let state = app.state::<TimelineState>();
+    let mut cached = write_lock(&state.timeline);
+    // The timeline is informational; a failed save never fails the action
+    let _ = match cached.as_mut() {
+        Some(timeline) => Ok(timeline),
+        None => load(app).map(|loaded| cached.insert(loaded)),
+    }
+    .and_then(|timeline| {
+        push_event(timeline, serial, event);
+        save(app, timeline)
+    });
+}
+
+/// A device's events, newest first
+pub fn get_timeline(app: &AppHandle, serial: &str) -> Result<Vec<DeviceEvent>, AppError> {
+    let state = app.state::<TimelineState>();
+    if let Some(timeline) = read_lock(&state.timeline).as_ref() {
+        return Ok(timeline.get(serial).cloned().unwrap_or_default());
+    }
+
+    let mut cached = write_lock(&state.timeline);
+    let timeline = match cached.as_ref() {
+        Some(timeline) => timeline,
+        None => cached.insert(load(app)?),
+    };
+    Ok(timeline.get(serial).cloned().unwrap_or_default())
+}
+
+/// Whether any device has an event of one of these kinds
+pub fn any_event(app: &AppHandle, kinds: &[DeviceEventKind]) -> Result<bool, AppError> {
+    let has = |timeline: &Timeline| {
+        timeline
+            .values()
+            .flatten()
+            .any(|event| kinds.contains(&event.kind))
+    };
+
+    let state = app.state::<TimelineState>();
+    if let Some(timeline) = read_lock(&state.timeline).as_ref() {
+        return Ok(has(timeline));
+    }
+
+    let mut cached = write_lock(&state.timeline);
+    let timeline = match cached.as_ref() {
+        Some(timeline) => timeline,
+        None => cached.insert(load(app)?),
+    };
+    Ok(has(timeline))
+}
+
+/// Summary for a batch of files copied to or from a device
+pub fn files_detail(copied: usize, total: usize, folder: &str) -> String {
+    if copied == total {
+        format!("{} file(s), {}", copied, folder)
+    } else {
+        format!("{} of {} file(s), {}", copied, total, folder)
+    }
+}
+
+fn timeline_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::SettingsError(format!("Failed to get config dir: {}", e)))?;
+    fs::create_dir_all(&config_dir)?;
+    Ok(config_dir.join(TIMELINE_FILE))
+}
+
+fn load(app: &AppHandle) -> Result<Timeline, AppError> {
+    let path = timeline_path(app)?;
+    Ok(state_file_service::load_json(app, &path)?.unwrap_or_else(Timeline::new))
+}
+
+fn save(app: &AppHandle, timeline: &Timeline) -> Result<(), AppError> {
+    let content = serde_json::to_string(timeline).map_err(|e| {
+        AppError::SettingsError(format!("Failed to serialize device timeline: {}", e))
+    })?;
+    state_file_service::write_atomic(&timeline_path(app)?, content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: DeviceEventKind) -> DeviceEvent {
+        DeviceEvent {
+            kind,
+            at: "2026-10-16T09:00:00+00:00".to_string(),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_push_event_keeps_newest_first() {
+        let mut timeline = Timeline::new();
+        push_event(&mut timeline, "SER1", event(DeviceEventKind::Connected));
+        push_event(&mut timeline, "SER1", event(DeviceEventKind::FilesPulled));
+        push_event(&mut timeline, "SER2", event(DeviceEventKind::Connected));
+
+        assert_eq!(timeline["SER1"][0].kind, DeviceEventKind::FilesPulled);
+        assert_eq!(timeline["SER1"].len(), 2);
+        assert_eq!(timeline["SER2"].len(), 1);
+    }
+
+    #[test]
+    fn test_push_event_caps_history() {
+        let mut timeline = Timeline::new();
+        for _ in 0..EVENTS_PER_DEVICE + 5 {
+            push_event(&mut timeline, "SER1", event(DeviceEventKind::Connected));
+        }
+        assert_eq!(timeline["SER1"].len(), EVENTS_PER_DEVICE);
+    }
+
+    #[test]
+    fn test_files_detail() {
+        assert_eq!(
+            files_detail(3, 3, "/sdcard/DCIM"),
+            "3 file(s), /sdcard/DCIM"
+        );
+        assert_eq!(
+            files_detail(2, 3, "/sdcard/DCIM"),
+            "2 of 3 file(s), /sdcard/DCIM"
+        );
+    }
+}
@@ -1,25 +1,27 @@
 //! Transfer service for file operations
 
 use crate::domain::errors::AppError;
-use crate::domain::models::{TransferItem, TransferStatus};
+use crate::domain::models::{BatchKind, ConflictPolicy, TransferItem, TransferStatus};
+use crate::services::adb_service::AdbRunner;
+use crate::services::archive_service;
+use crate::services::conflict_service::{self, Resolution};
+use crate::services::media_service::quote_remote_path;
+use crate::services::progress_service::{BatchTracker, ProgressSink};
+use crate::state::{read_lock, write_lock};
 use std::collections::HashMap;
 
 use std::path::Path;
-use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
+use std::process::Child;
+use std::sync::RwLock;
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-/// Global state for active transfers and history
-static TRANSFERS: Mutex<Option<TransferState>> = Mutex::new(None);
-
-struct TransferState {
-    active: HashMap<String, TransferHandle>,
-    history: Vec<TransferItem>,
+/// Managed state for active transfers and history
+#[derive(Default)]
+pub struct TransferState {
+    active: RwLock<HashMap<String, TransferHandle>>,
+    history: RwLock<Vec<TransferItem>>,
+    /// Interrupted items waiting for their device, oldest first. Kept apart
+    /// from the capped history so a large batch can't push them out.
+    interrupted: RwLock<Vec<TransferItem>>,
 }
 
 struct TransferHandle {
@@ -27,25 +29,21 @@ struct TransferHandle {
     process: Option<Child>,
 }
 
-/// Initialize the transfer state if needed
-fn ensure_transfer_state() {
-    let mut state = TRANSFERS.lock().unwrap();
-    if state.is_none() {
-        *state = Some(TransferState {
-            active: HashMap::new(),
-            history: Vec::new(),
-        });
-    }
-}
-
-/// Generate a unique transfer ID
-fn generate_id() -> String {
+/// Generate a unique transfer ID; the counter keeps IDs made within the
+/// same millisecond apart
+pub(crate) fn generate_id() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
     use std::time::{SystemTime, UNIX_EPOCH};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_millis();
-    format!("transfer_{}", timestamp)
+    format!(
+        "transfer_{}_{}",
+        timestamp,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
 }
 
 /// Get file size in bytes
@@ -53,133 +51,544 @@ fn get_file_size(path: &str) -> u64 {
     std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
 }
 
-/// Push a single file to device
+/// Turn a user-entered device folder into the path below `/sdcard` that
+/// transfers use, e.g. `/sdcard/Music/` -> `Music`
+pub fn normalize_device_dir(dir: &str) -> Result<String, AppError> {
+    // Trailing slash lets a bare "/sdcard" match the prefixes below
+    let trimmed = format!("{}/", dir.trim().replace('\\', "/"));
+    let relative = ["/sdcard/", "/storage/emulated/0/"]
+        .iter()
+        .find_map(|prefix| trimmed.strip_prefix(prefix))
+        .unwrap_or(&trimmed)
+        .trim_matches('/');
+
+    if relative.is_empty() || relative.split('/').any(|part| part == "..") {
+        return Err(AppError::InvalidPath(format!(
+            "Invalid device folder: {}",
+            dir
+        )));
+    }
+
+    Ok(relative.to_string())
+}
+
+/// Push several files, recording a failed item for any that can't be started
+#[allow(clippy::too_many_arguments)]
+pub fn push_files(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+    paths: &[String],
+    dest_dir: &str,
+    archive: bool,
+    conflict: ConflictPolicy,
+    progress: ProgressSink,
+) -> Vec<TransferItem> {
+    let total_bytes = paths.iter().map(|p| get_file_size(p)).sum();
+    let mut tracker = BatchTracker::new(BatchKind::Push, paths.len(), total_bytes, progress);
+
+    // Batch many small files into a single tar push when requested
+    if archive {
+        tracker.start_file(&format!("{} files (archive)", paths.len()));
+        let results = push_files_archived(state, adb, serial, paths, dest_dir, conflict);
+        for item in &results {
+            tracker.finish_file(&item.source_path, item.size_bytes, failure_of(item));
+        }
+        tracker.finish();
+        return results;
+    }
+
+    let mut results = Vec::new();
+    for path in paths {
+        tracker.start_file(&file_name_of(path));
+
+        let item = push_or_fail(state, adb, serial, path, dest_dir, conflict);
+        tracker.finish_file(path, get_file_size(path), failure_of(&item));
+        results.push(item);
+    }
+
+    tracker.finish();
+    results
+}
+
+/// Error of an item that failed or was interrupted (skips are not failures)
+fn failure_of(item: &TransferItem) -> Option<String> {
+    match item.status {
+        TransferStatus::Failed | TransferStatus::Interrupted => item.error.clone(),
+        _ => None,
+    }
+}
+
+/// Modification time of a local file, in seconds since the Unix epoch
+fn local_mtime(path: &str) -> Option<i64> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+/// `None` if nothing is at `path` on the device, otherwise its mtime if readable
+fn remote_mtime(adb: &dyn AdbRunner, serial: &str, path: &str) -> Option<Option<i64>> {
+    adb.run_idempotent(&[
+        "-s",
+        serial,
+        "shell",
+        "stat",
+        "-c",
+        "%Y",
+        &quote_remote_path(path),
+    ])
+    .ok()
+    .map(|out| out.trim().parse().ok())
+}
+
+fn file_name_of(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Push a file, turning an error that kept it from starting into a failed
+/// item so the rest of a batch continues and the failure is reported
+fn push_or_fail(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+    path: &str,
+    dest_dir: &str,
+    conflict: ConflictPolicy,
+) -> TransferItem {
+    push_file(state, adb, serial, path, dest_dir, conflict).unwrap_or_else(|e| TransferItem {
+        id: format!("error_{}", generate_id()),
+        file_name: file_name_of(path),
+        source_path: path.to_string(),
+        dest_path: format!("/sdcard/{}/", dest_dir),
+        size_bytes: 0,
+        transferred_bytes: 0,
+        status: TransferStatus::Failed,
+        error: Some(e.to_string()),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        device_serial: serial.to_string(),
+    })
+}
+
+/// Push a single file to device, applying `conflict` if the destination exists
 pub fn push_file(
-    adb_path: &str,
+    state: &TransferState,
+    adb: &dyn AdbRunner,
     serial: &str,
     source_path: &str,
     dest_dir: &str,
+    conflict: ConflictPolicy,
 ) -> Result<TransferItem, AppError> {
-    ensure_transfer_state();
-
     let file_name = Path::new(source_path)
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown")
         .to_string();
 
-    let dest_path = format!("/sdcard/{}/{}", dest_dir, file_name);
+    let mut dest_path = format!("/sdcard/{}/{}", dest_dir, file_name);
     let size_bytes = get_file_size(source_path);
 
-    let id = generate_id();
-    let started_at = chrono::Utc::now().to_rfc3339();
+    // Overwrite is adb's own behavior, so only probe the device for other policies
+    let mut skip_reason = None;
+    if conflict != ConflictPolicy::Overwrite {
+        match conflict_service::resolve(
+            conflict,
+            &dest_path,
+            remote_mtime(adb, serial, &dest_path),
+            local_mtime(source_path),
+            |candidate| remote_mtime(adb, serial, candidate).is_some(),
+        ) {
+            Resolution::Write(path) => dest_path = path,
+            Resolution::Skip(reason) => skip_reason = Some(reason),
+        }
+    }
 
     let mut item = TransferItem {
-        id: id.clone(),
-        file_name: file_name.clone(),
+        id: generate_id(),
+        file_name,
         source_path: source_path.to_string(),
-        dest_path: dest_path.clone(),
+        dest_path,
         size_bytes,
         transferred_bytes: 0,
         status: TransferStatus::Transferring,
         error: None,
-        started_at,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        device_serial: serial.to_string(),
     };
 
-    // Store in active transfers
-    {
-        let mut state = TRANSFERS.lock().unwrap();
-        let state = state.as_mut().unwrap();
-        state.active.insert(
-            id.clone(),
-            TransferHandle {
-                item: item.clone(),
-                process: None,
-            },
-        );
+    if let Some(reason) = skip_reason {
+        item.status = TransferStatus::Skipped;
+        item.error = Some(reason);
+        add_to_history(state, item.clone());
+        return Ok(item);
     }
 
-    // Run adb push synchronously (for simplicity in MVP)
-    let mut cmd = Command::new(adb_path);
-    cmd.args(["-s", serial, "push", source_path, &dest_path])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    Ok(run_push(state, adb, item))
+}
 
-    let output = cmd.output();
+/// Run `adb push` for an item, tracking it as active until it finishes
+fn run_push(state: &TransferState, adb: &dyn AdbRunner, mut item: TransferItem) -> TransferItem {
+    let id = item.id.clone();
+    item.status = TransferStatus::Transferring;
+    item.error = None;
 
-    let mut state = TRANSFERS.lock().unwrap();
-    let state = state.as_mut().unwrap();
+    // Store in active transfers
+    write_lock(&state.active).insert(
+        id.clone(),
+        TransferHandle {
+            item: item.clone(),
+            process: None,
+        },
+    );
 
-    match output {
-        Ok(result) => {
-            if result.status.success() {
-                item.status = TransferStatus::Complete;
-                item.transferred_bytes = size_bytes;
-            } else {
-                let stderr = String::from_utf8_lossy(&result.stderr);
-                item.status = TransferStatus::Failed;
-                item.error = Some(stderr.to_string());
-            }
+    // Run adb push synchronously (for simplicity in MVP)
+    match adb.run_transfer(&[
+        "-s",
+        &item.device_serial,
+        "push",
+        &item.source_path,
+        &item.dest_path,
+    ]) {
+        Ok(_) => {
+            item.status = TransferStatus::Complete;
+            item.transferred_bytes = item.size_bytes;
         }
         Err(e) => {
-            item.status = TransferStatus::Failed;
-            item.error = Some(format!("Failed to execute adb: {}", e));
+            // A vanished device is resumable; anything else is a real failure
+            item.status = if is_device_lost(&e) {
+                TransferStatus::Interrupted
+            } else {
+                TransferStatus::Failed
+            };
+            item.error = Some(e.to_string());
         }
     }
 
     // Move to history
-    state.active.remove(&id);
-    state.history.insert(0, item.clone());
+    write_lock(&state.active).remove(&id);
+    add_to_history(state, item.clone());
+
+    item
+}
 
-    // Keep only last 50 in history
-    if state.history.len() > 50 {
-        state.history.truncate(50);
+/// Whether a push failed because the device disconnected mid-transfer
+fn is_device_lost(err: &AppError) -> bool {
+    match err {
+        AppError::Timeout(_) => true,
+        AppError::AdbExecutionFailed(msg) => {
+            let msg = msg.to_lowercase();
+            names_missing_device(&msg)
+                || [
+                    "no devices",
+                    "device offline",
+                    "error: closed",
+                    "connection reset",
+                ]
+                .iter()
+                .any(|p| msg.contains(p))
+        }
+        _ => false,
     }
+}
 
-    Ok(item)
+/// adb's `device '<serial>' not found`
+fn names_missing_device(msg: &str) -> bool {
+    msg.split("device '").skip(1).any(|rest| {
+        rest.split_once('\'')
+            .is_some_and(|(_, after)| after.starts_with(" not found"))
+    })
 }
 
-/// Get all active transfers
-pub fn get_active_transfers() -> Vec<TransferItem> {
-    ensure_transfer_state();
+/// Whether any interrupted transfers are waiting for this device
+pub fn has_interrupted(state: &TransferState, serial: &str) -> bool {
+    read_lock(&state.interrupted)
+        .iter()
+        .any(|i| i.device_serial == serial)
+}
+
+/// Resume interrupted transfers for a device that has reappeared
+///
+/// Files whose remote size already matches the local size are marked complete
+/// without pushing again (the push finished but the disconnect hid the result).
+pub fn resume_interrupted(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+) -> Vec<TransferItem> {
+    // Take the items out first so concurrent resumes can't double-push
+    let pending: Vec<TransferItem> = {
+        let mut interrupted = write_lock(&state.interrupted);
+        let (pending, rest): (Vec<_>, Vec<_>) = interrupted
+            .drain(..)
+            .partition(|i| i.device_serial == serial);
+        *interrupted = rest;
+        pending
+    };
+    // Their history entries are replaced by the outcome of the resume
+    write_lock(&state.history)
+        .retain(|i| !(i.status == TransferStatus::Interrupted && i.device_serial == serial));
+
+    pending
+        .into_iter()
+        .map(|mut item| {
+            let quoted = quote_remote_path(&item.dest_path);
+            let remote_size = adb
+                .run_idempotent(&["-s", serial, "shell", "stat", "-c", "%s", &quoted])
+                .ok()
+                .and_then(|out| out.trim().parse::<u64>().ok());
+
+            if item.size_bytes > 0 && remote_size == Some(item.size_bytes) {
+                item.status = TransferStatus::Complete;
+                item.transferred_bytes = item.size_bytes;
+                item.error = None;
+                add_to_history(state, item.clone());
+                item
+            } else {
+                run_push(state, adb, item)
+            }
+        })
+        .collect()
+}
 
-    let state = TRANSFERS.lock().unwrap();
-    if let Some(s) = state.as_ref() {
-        s.active.values().map(|h| h.item.clone()).collect()
+/// Printed after a successful on-device extraction so failures can't pass silently
+const EXTRACT_OK_MARKER: &str = "GESU_EXTRACT_OK";
+
+/// Push many files as one tar archive and extract it on the device
+///
+/// Per-file adb overhead dominates when pushing thousands of small files. Falls
+/// back to individual pushes if the device has no `tar` or any archive step fails.
+pub fn push_files_archived(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+    source_paths: &[String],
+    dest_dir: &str,
+    conflict: ConflictPolicy,
+) -> Vec<TransferItem> {
+    let dest_root = format!("/sdcard/{}", dest_dir);
+
+    // tar overwrites on extract; other policies need the per-file checks
+    if conflict != ConflictPolicy::Overwrite {
+        let all: Vec<&String> = source_paths.iter().collect();
+        return push_each(state, adb, serial, &all, dest_dir, conflict);
+    }
+
+    // Only flat, regular files with ustar-compatible names go in the archive.
+    // Files sharing a name would overwrite each other in it, so those are
+    // pushed one by one.
+    let mut name_counts: HashMap<&str, usize> = HashMap::new();
+    for path in source_paths {
+        let name = Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+        *name_counts.entry(name).or_default() += 1;
+    }
+    let (batch, single): (Vec<&String>, Vec<&String>) = source_paths.iter().partition(|p| {
+        let path = Path::new(p.as_str());
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        path.is_file()
+            && name_counts[name] == 1
+            && archive_service::fits_in_archive(name, get_file_size(p))
+    });
+
+    let mut results = Vec::new();
+
+    if batch.len() > 1 && device_has_tar(adb, serial) {
+        match push_archive(adb, serial, &batch, &dest_root) {
+            Ok(()) => {
+                for source_path in &batch {
+                    let file_name = file_name_of(source_path);
+                    let size_bytes = get_file_size(source_path);
+                    let item = TransferItem {
+                        id: generate_id(),
+                        dest_path: format!("{}/{}", dest_root, file_name),
+                        file_name,
+                        source_path: source_path.to_string(),
+                        size_bytes,
+                        transferred_bytes: size_bytes,
+                        status: TransferStatus::Complete,
+                        error: None,
+                        started_at: chrono::Utc::now().to_rfc3339(),
+                        device_serial: serial.to_string(),
+                    };
+                    add_to_history(state, item.clone());
+                    results.push(item);
+                }
+            }
+            // Fall back to pushing the files one by one
+            Err(_) => {
+                results.extend(push_each(state, adb, serial, &batch, dest_dir, conflict));
+            }
+        }
     } else {
-        Vec::new()
+        results.extend(push_each(state, adb, serial, &batch, dest_dir, conflict));
     }
+
+    results.extend(push_each(state, adb, serial, &single, dest_dir, conflict));
+    results
 }
 
-/// Get transfer history
-pub fn get_transfer_history() -> Vec<TransferItem> {
-    ensure_transfer_state();
+fn push_each(
+    state: &TransferState,
+    adb: &dyn AdbRunner,
+    serial: &str,
+    paths: &[&String],
+    dest_dir: &str,
+    conflict: ConflictPolicy,
+) -> Vec<TransferItem> {
+    paths
+        .iter()
+        .map(|p| push_or_fail(state, adb, serial, p, dest_dir, conflict))
+        .collect()
+}
 
-    let state = TRANSFERS.lock().unwrap();
-    if let Some(s) = state.as_ref() {
-        s.history.clone()
-    } else {
-        Vec::new()
+/// Whether the device shell provides `tar` (toybox ships it on most modern devices)
+fn device_has_tar(adb: &dyn AdbRunner, serial: &str) -> bool {
+    adb.run_idempotent(&["-s", serial, "shell", "command", "-v", "tar"])
+        .map(|out| !out.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Build the archive locally, push it, extract it, and clean up both copies
+fn push_archive(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    paths: &[&String],
+    dest_root: &str,
+) -> Result<(), AppError> {
+    let id = generate_id();
+    let local_archive = std::env::temp_dir().join(format!("gesu_bridge_{}.tar", id));
+    let remote_archive = format!("/data/local/tmp/gesu_bridge_{}.tar", id);
+
+    let entries: Vec<(&Path, &str)> = paths
+        .iter()
+        .map(|p| {
+            let path = Path::new(p.as_str());
+            (
+                path,
+                path.file_name().and_then(|n| n.to_str()).unwrap_or(""),
+            )
+        })
+        .collect();
+
+    let result = archive_service::write_tar(&entries, &local_archive)
+        .map_err(|e| AppError::TransferError(format!("Failed to build archive: {}", e)))
+        .and_then(|_| {
+            adb.run_transfer(&[
+                "-s",
+                serial,
+                "push",
+                &local_archive.to_string_lossy(),
+                &remote_archive,
+            ])
+        })
+        .and_then(|_| {
+            let script = format!(
+                "mkdir -p {dest} && tar -xf {archive} -C {dest} && echo {marker}",
+                dest = quote_remote_path(dest_root),
+                archive = quote_remote_path(&remote_archive),
+                marker = EXTRACT_OK_MARKER,
+            );
+            adb.run(&["-s", serial, "shell", &script])
+        })
+        .and_then(|out| {
+            if out.contains(EXTRACT_OK_MARKER) {
+                Ok(())
+            } else {
+                Err(AppError::TransferError(format!(
+                    "Extraction failed on device: {}",
+                    out.trim()
+                )))
+            }
+        });
+
+    // Clean up regardless of outcome
+    let _ = std::fs::remove_file(&local_archive);
+    let _ = adb.run(&[
+        "-s",
+        serial,
+        "shell",
+        "rm",
+        "-f",
+        &quote_remote_path(&remote_archive),
+    ]);
+
+    result
+}
+
+/// Record a finished transfer, keeping only the last 50 (interrupted ones
+/// are also kept until they're resumed)
+fn add_to_history(state: &TransferState, item: TransferItem) {
+    if item.status == TransferStatus::Interrupted {
+        write_lock(&state.interrupted).push(item.clone());
+    }
+
+    let mut history = write_lock(&state.history);
+    history.insert(0, item);
+
+    if history.len() > 50 {
+        history.truncate(50);
     }
 }
 
-/// Cancel a transfer (mark as cancelled)
-pub fn cancel_transfer(id: &str) -> Result<(), AppError> {
-    ensure_transfer_state();
+/// Show a transfer another program runs (e.g. the Bluetooth wizard) as active
+pub fn start_external(state: &TransferState, item: TransferItem) {
+    write_lock(&state.active).insert(
+        item.id.clone(),
+        TransferHandle {
+            item,
+            process: None,
+        },
+    );
+}
+
+/// Move an external transfer to the history as `items` (one per file, when
+/// the files are known). Nothing is recorded if it was cancelled meanwhile.
+pub fn finish_external(state: &TransferState, id: &str, items: Vec<TransferItem>) {
+    if write_lock(&state.active).remove(id).is_none() {
+        return;
+    }
+    record_external(state, items);
+}
+
+/// Add transfers another program took over straight to the history, for
+/// hand-offs that can't be followed while they run
+pub fn record_external(state: &TransferState, items: Vec<TransferItem>) {
+    for item in items.into_iter().rev() {
+        add_to_history(state, item);
+    }
+}
+
+/// Get all active transfers
+pub fn get_active_transfers(state: &TransferState) -> Vec<TransferItem> {
+    read_lock(&state.active)
+        .values()
+        .map(|h| h.item.clone())
+        .collect()
+}
+
+/// Get transfer history
+pub fn get_transfer_history(state: &TransferState) -> Vec<TransferItem> {
+    read_lock(&state.history).clone()
+}
 
-    let mut state = TRANSFERS.lock().unwrap();
-    let state = state.as_mut().unwrap();
+/// Cancel a transfer (mark as cancelled)
+pub fn cancel_transfer(state: &TransferState, id: &str) -> Result<(), AppError> {
+    let removed = write_lock(&state.active).remove(id);
 
-    if let Some(mut handle) = state.active.remove(id) {
+    if let Some(mut handle) = removed {
         // Kill process if running
         if let Some(ref mut process) = handle.process {
             let _ = process.kill();
         }
         handle.item.status = TransferStatus::Cancelled;
-        state.history.insert(0, handle.item);
+        add_to_history(state, handle.item);
         Ok(())
     } else {
         Err(AppError::TransferError(format!(
@@ -188,3 +597,360 @@ pub fn cancel_transfer(id: &str) -> Result<(), AppError> {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    fn finished_item(id: &str) -> TransferItem {
+        TransferItem {
+            id: id.to_string(),
+            file_name: "a.txt".to_string(),
+            source_path: "C:/a.txt".to_string(),
+            dest_path: "/sdcard/Download/a.txt".to_string(),
+            size_bytes: 1,
+            transferred_bytes: 1,
+            status: TransferStatus::Complete,
+            error: None,
+            started_at: String::new(),
+            device_serial: "SER1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_history_is_capped_newest_first() {
+        let state = TransferState::default();
+        for i in 0..60 {
+            add_to_history(&state, finished_item(&format!("t{}", i)));
+        }
+
+        let history = get_transfer_history(&state);
+        assert_eq!(history.len(), 50);
+        assert_eq!(history[0].id, "t59");
+    }
+
+    #[test]
+    fn test_cancel_unknown_transfer() {
+        let state = TransferState::default();
+        assert!(cancel_transfer(&state, "missing").is_err());
+        assert!(get_active_transfers(&state).is_empty());
+    }
+
+    #[test]
+    fn test_push_file_success_moves_to_history() {
+        let state = TransferState::default();
+        let adb = MockAdb::new().on("push", "1 file pushed, 0 skipped.");
+
+        let item = push_file(
+            &state,
+            &adb,
+            "SER1",
+            "C:/missing/photo.jpg",
+            "Download",
+            ConflictPolicy::Overwrite,
+        )
+        .unwrap();
+        assert_eq!(item.status, TransferStatus::Complete);
+        assert_eq!(item.dest_path, "/sdcard/Download/photo.jpg");
+        assert_eq!(
+            adb.calls(),
+            vec!["-s SER1 push C:/missing/photo.jpg /sdcard/Download/photo.jpg"]
+        );
+
+        assert!(get_active_transfers(&state).is_empty());
+        assert_eq!(get_transfer_history(&state).len(), 1);
+    }
+
+    #[test]
+    fn test_push_file_failure_records_error() {
+        let state = TransferState::default();
+        let adb = MockAdb::new().fail(
+            "push",
+            AppError::AdbExecutionFailed("remote couldn't create file".to_string()),
+        );
+
+        let item = push_file(
+            &state,
+            &adb,
+            "SER1",
+            "C:/a.txt",
+            "Download",
+            ConflictPolicy::Overwrite,
+        )
+        .unwrap();
+        assert_eq!(item.status, TransferStatus::Failed);
+        assert!(item.error.unwrap().contains("remote couldn't create file"));
+    }
+
+    #[test]
+    fn test_disconnect_marks_interrupted_and_resumes() {
+        let state = TransferState::default();
+        let offline = MockAdb::new().fail(
+            "push",
+            AppError::AdbExecutionFailed("error: device 'SER1' not found".to_string()),
+        );
+
+        let item = push_file(
+            &state,
+            &offline,
+            "SER1",
+            "C:/a.txt",
+            "Download",
+            ConflictPolicy::Overwrite,
+        )
+        .unwrap();
+        assert_eq!(item.status, TransferStatus::Interrupted);
+        assert!(has_interrupted(&state, "SER1"));
+        assert!(!has_interrupted(&state, "OTHER"));
+
+        // Remote file missing -> pushed again
+        let online = MockAdb::new()
+            .fail(
+                "stat",
+                AppError::AdbExecutionFailed("No such file or directory".to_string()),
+            )
+            .on("push", "1 file pushed");
+        let resumed = resume_interrupted(&state, &online, "SER1");
+
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].status, TransferStatus::Complete);
+        assert!(!has_interrupted(&state, "SER1"));
+        assert_eq!(get_transfer_history(&state).len(), 1);
+    }
+
+    #[test]
+    fn test_interrupted_items_outlast_the_history_cap() {
+        let state = TransferState::default();
+        let offline = MockAdb::new().fail(
+            "push",
+            AppError::AdbExecutionFailed("error: device 'SER1' not found".to_string()),
+        );
+        push_file(
+            &state,
+            &offline,
+            "SER1",
+            "C:/a.txt",
+            "Download",
+            ConflictPolicy::Overwrite,
+        )
+        .unwrap();
+
+        let pushed = MockAdb::new().on("push", "1 file pushed");
+        for _ in 0..60 {
+            push_file(
+                &state,
+                &pushed,
+                "SER2",
+                "C:/b.txt",
+                "Download",
+                ConflictPolicy::Overwrite,
+            )
+            .unwrap();
+        }
+        assert!(get_transfer_history(&state)
+            .iter()
+            .all(|i| i.status != TransferStatus::Interrupted));
+        assert!(has_interrupted(&state, "SER1"));
+
+        let online = MockAdb::new().on("stat", "0").on("push", "1 file pushed");
+        let resumed = resume_interrupted(&state, &online, "SER1");
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].status, TransferStatus::Complete);
+        assert!(!has_interrupted(&state, "SER1"));
+    }
+
+    #[test]
+    fn test_only_a_missing_device_counts_as_lost() {
+        let failed = |msg: &str| AppError::AdbExecutionFailed(msg.to_string());
+        assert!(is_device_lost(&failed(
+            "adb: error: device 'SER1' not found"
+        )));
+        assert!(is_device_lost(&failed("error: device offline")));
+        assert!(is_device_lost(&AppError::Timeout(String::new())));
+        assert!(!is_device_lost(&failed("/system/bin/sh: tar: not found")));
+        assert!(!is_device_lost(&failed(
+            "adb: error: failed to copy 'C:/a.txt' to '/sdcard/Disclosed/a.txt': remote No such file"
+        )));
+    }
+
+    fn temp_files(tag: &str, count: usize) -> (std::path::PathBuf, Vec<String>) {
+        let dir = std::env::temp_dir().join(format!("gesu_{}_{}", tag, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = (0..count)
+            .map(|i| {
+                let path = dir.join(format!("f{}.txt", i));
+                std::fs::write(&path, b"data").unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        (dir, paths)
+    }
+
+    #[test]
+    fn test_push_archived_extracts_on_device() {
+        let (dir, paths) = temp_files("archived", 3);
+        let state = TransferState::default();
+        let adb = MockAdb::new()
+            .on("command -v tar", "/system/bin/tar")
+            .on("tar -xf", EXTRACT_OK_MARKER)
+            .on("push", "1 file pushed")
+            .on("rm -f", "");
+
+        let items = push_files_archived(
+            &state,
+            &adb,
+            "SER1",
+            &paths,
+            "Download",
+            ConflictPolicy::Overwrite,
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|i| i.status == TransferStatus::Complete));
+        assert_eq!(items[0].dest_path, "/sdcard/Download/f0.txt");
+        // One archive push instead of three
+        let pushes = adb.calls().iter().filter(|c| c.contains(" push ")).count();
+        assert_eq!(pushes, 1);
+        assert!(adb.calls().iter().any(|c| c.contains("rm -f")));
+    }
+
+    #[test]
+    fn test_push_archived_sends_same_named_files_one_by_one() {
+        let (dir, mut paths) = temp_files("archived_dupes", 3);
+        let other = dir.join("other");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(other.join("f0.txt"), b"other").unwrap();
+        paths.push(other.join("f0.txt").to_string_lossy().to_string());
+
+        let state = TransferState::default();
+        let adb = MockAdb::new()
+            .on("command -v tar", "/system/bin/tar")
+            .on("tar -xf", EXTRACT_OK_MARKER)
+            .on("push", "1 file pushed")
+            .on("rm -f", "");
+
+        let items = push_files_archived(
+            &state,
+            &adb,
+            "SER1",
+            &paths,
+            "Download",
+            ConflictPolicy::Overwrite,
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(items.len(), 4);
+        assert!(items.iter().all(|i| i.status == TransferStatus::Complete));
+        // f1 and f2 in one archive, both f0.txt files on their own
+        let pushes = adb.calls().iter().filter(|c| c.contains(" push ")).count();
+        assert_eq!(pushes, 3);
+
+        let ids: std::collections::HashSet<_> = get_transfer_history(&state)
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids.len(), 4);
+    }
+
+    #[test]
+    fn test_push_archived_falls_back_without_tar() {
+        let (dir, paths) = temp_files("no_tar", 3);
+        let state = TransferState::default();
+        let adb = MockAdb::new()
+            .on("command -v tar", "")
+            .on("push", "1 file pushed");
+
+        let items = push_files_archived(
+            &state,
+            &adb,
+            "SER1",
+            &paths,
+            "Download",
+            ConflictPolicy::Overwrite,
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(items.len(), 3);
+        let pushes = adb.calls().iter().filter(|c| c.contains(" push ")).count();
+        assert_eq!(pushes, 3);
+    }
+
+    #[test]
+    fn test_push_conflict_skip_and_rename() {
+        let state = TransferState::default();
+        let adb = MockAdb::new()
+            .on("stat -c %Y '/sdcard/Download/a.txt'", "1700000000")
+            .fail(
+                "stat -c %Y",
+                AppError::AdbExecutionFailed("No such file or directory".to_string()),
+            )
+            .on("push", "1 file pushed");
+
+        let skipped = push_file(
+            &state,
+            &adb,
+            "SER1",
+            "C:/a.txt",
+            "Download",
+            ConflictPolicy::Skip,
+        )
+        .unwrap();
+        assert_eq!(skipped.status, TransferStatus::Skipped);
+
+        let renamed = push_file(
+            &state,
+            &adb,
+            "SER1",
+            "C:/a.txt",
+            "Download",
+            ConflictPolicy::Rename,
+        )
+        .unwrap();
+        assert_eq!(renamed.status, TransferStatus::Complete);
+        assert_eq!(renamed.dest_path, "/sdcard/Download/a (1).txt");
+    }
+
+    #[test]
+    fn test_normalize_device_dir() {
+        assert_eq!(normalize_device_dir("/sdcard/Music/").unwrap(), "Music");
+        assert_eq!(
+            normalize_device_dir("/storage/emulated/0/Download/ROMs").unwrap(),
+            "Download/ROMs"
+        );
+        assert_eq!(normalize_device_dir("Download").unwrap(), "Download");
+        assert!(normalize_device_dir("/sdcard").is_err());
+        assert!(normalize_device_dir("Music/../../data").is_err());
+    }
+
+    #[test]
+    fn test_push_files_reports_batch_progress() {
+        use crate::domain::models::BatchProgress;
+        use std::cell::RefCell;
+
+        let state = TransferState::default();
+        let adb = MockAdb::new().on("push C:/ok.txt", "1 file pushed");
+        let last = RefCell::new(None);
+        let sink = |p: &BatchProgress| *last.borrow_mut() = Some(p.clone());
+
+        let paths = vec!["C:/ok.txt".to_string(), "C:/bad.txt".to_string()];
+        let items = push_files(
+            &state,
+            &adb,
+            "SER1",
+            &paths,
+            "Download",
+            false,
+            ConflictPolicy::Overwrite,
+            &sink,
+        );
+
+        assert_eq!(items.len(), 2);
+        let last = last.into_inner().unwrap();
+        assert!(last.finished);
+        assert_eq!(last.completed_files, 2);
+        assert_eq!(last.failures.len(), 1);
+        assert_eq!(last.failures[0].path, "C:/bad.txt");
+    }
+}
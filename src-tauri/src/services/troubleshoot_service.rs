@@ -0,0 +1,221 @@
+//! Fix-it actions for the troubleshooting screen
+//!
+//! Each action reports a [`TroubleshootResult`] instead of failing, with the
+//! action worth trying next when it didn't work, so the UI can walk the user
+//! from "device not responding" to "restart adb" to "install platform tools".
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{Settings, TroubleshootAction, TroubleshootResult};
+use crate::services::adb_service::AdbRunner;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+/// Official download page of the Android SDK Platform Tools (adb)
+pub const PLATFORM_TOOLS_URL: &str = "https://developer.android.com/tools/releases/platform-tools";
+
+/// Stop the adb server and start a fresh one. Transfers and mirror sessions
+/// running through it are cut off.
+pub fn restart_adb_server(adb: &dyn AdbRunner) -> TroubleshootResult {
+    let started = Instant::now();
+    // kill-server fails when no server was running, which is fine
+    let _ = adb.run(&["kill-server"]);
+    let outcome = adb
+        .run(&["start-server"])
+        .map(|_| ("adb server restarted".to_string(), Vec::new()));
+    result(
+        TroubleshootAction::RestartAdbServer,
+        started,
+        outcome,
+        Some(TroubleshootAction::RedetectTools),
+    )
+}
+
+/// Report what the last tool detection found; adb is the one that must be there
+pub fn tool_report(settings: &Settings) -> TroubleshootResult {
+    let started = Instant::now();
+    let line = |tool: &str, path: &Option<String>| match path {
+        Some(path) => format!("{}: {}", tool, path),
+        None => format!("{}: not found", tool),
+    };
+    let details = vec![
+        line("adb", &settings.adb_resolved_path),
+        line("scrcpy", &settings.scrcpy_resolved_path),
+        line("ffmpeg", &settings.ffmpeg_resolved_path),
+        line("ffprobe", &settings.ffprobe_resolved_path),
+    ];
+    let outcome = match &settings.adb_resolved_path {
+        Some(path) => Ok((format!("adb found at {}", path), details)),
+        None => Err((
+            AppError::AdbNotFound(
+                "ADB not found. Install Android SDK Platform Tools or set the path in Settings."
+                    .to_string(),
+            ),
+            details,
+        )),
+    };
+    result_with_details(
+        TroubleshootAction::RedetectTools,
+        started,
+        outcome,
+        Some(TroubleshootAction::OpenPlatformToolsDownload),
+    )
+}
+
+/// Send a random token through `adb shell echo` and check it comes back
+pub fn test_device_connection(adb: &dyn AdbRunner, serial: &str) -> TroubleshootResult {
+    let started = Instant::now();
+    let token = format!(
+        "gesu-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    );
+    let outcome = adb
+        .run(&["-s", serial, "shell", "echo", &token])
+        .and_then(|output| {
+            if output.trim() == token {
+                Ok((
+                    format!(
+                        "{} answered in {} ms",
+                        serial,
+                        started.elapsed().as_millis()
+                    ),
+                    Vec::new(),
+                ))
+            } else {
+                Err(AppError::AdbExecutionFailed(format!(
+                    "{} answered with unexpected output: {}",
+                    serial,
+                    output.trim()
+                )))
+            }
+        });
+    result(
+        TroubleshootAction::TestDeviceConnection,
+        started,
+        outcome,
+        Some(TroubleshootAction::RestartAdbServer),
+    )
+}
+
+/// Open the Platform Tools download page in the default browser
+pub fn open_platform_tools_download(app: &AppHandle) -> TroubleshootResult {
+    let started = Instant::now();
+    let outcome = app
+        .opener()
+        .open_url(PLATFORM_TOOLS_URL, None::<&str>)
+        .map(|_| (format!("Opened {}", PLATFORM_TOOLS_URL), Vec::new()))
+        .map_err(|e| AppError::IoError(format!("Failed to open {}: {}", PLATFORM_TOOLS_URL, e)));
+    result(
+        TroubleshootAction::OpenPlatformToolsDownload,
+        started,
+        outcome,
+        None,
+    )
+}
+
+fn result(
+    action: TroubleshootAction,
+    started: Instant,
+    outcome: Result<(String, Vec<String>), AppError>,
+    on_failure: Option<TroubleshootAction>,
+) -> TroubleshootResult {
+    result_with_details(
+        action,
+        started,
+        outcome.map_err(|e| (e, Vec::new())),
+        on_failure,
+    )
+}
+
+/// Build the result of an action from its outcome; `on_failure` becomes the
+/// suggested next action when it failed
+pub fn result_with_details(
+    action: TroubleshootAction,
+    started: Instant,
+    outcome: Result<(String, Vec<String>), (AppError, Vec<String>)>,
+    on_failure: Option<TroubleshootAction>,
+) -> TroubleshootResult {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    match outcome {
+        Ok((message, details)) => TroubleshootResult {
+            action,
+            success: true,
+            message,
+            details,
+            error_kind: None,
+            duration_ms,
+            suggested_next: None,
+        },
+        Err((error, details)) => TroubleshootResult {
+            action,
+            success: false,
+            message: error.to_string(),
+            details,
+            error_kind: Some(error.kind().to_string()),
+            duration_ms,
+            suggested_next: on_failure,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_restart_adb_server() {
+        let adb = MockAdb::new()
+            .fail(
+                "kill-server",
+                AppError::AdbExecutionFailed("no server".into()),
+            )
+            .on("start-server", "* daemon started successfully\n");
+        let result = restart_adb_server(&adb);
+        assert!(result.success);
+        assert_eq!(adb.calls(), vec!["kill-server", "start-server"]);
+
+        let broken = restart_adb_server(&MockAdb::new());
+        assert!(!broken.success);
+        assert_eq!(
+            broken.suggested_next,
+            Some(TroubleshootAction::RedetectTools)
+        );
+    }
+
+    #[test]
+    fn test_device_connection_echo_roundtrip() {
+        // The mock can't echo the token back, so a reply without it fails
+        let adb = MockAdb::new().on("shell echo gesu-", "something else\n");
+        let result = test_device_connection(&adb, "SER1");
+        assert!(!result.success);
+        assert_eq!(result.error_kind.as_deref(), Some("AdbExecutionFailed"));
+        assert_eq!(
+            result.suggested_next,
+            Some(TroubleshootAction::RestartAdbServer)
+        );
+        assert!(adb.calls()[0].starts_with("-s SER1 shell echo gesu-"));
+    }
+
+    #[test]
+    fn test_tool_report() {
+        let mut settings = Settings::new();
+        let missing = tool_report(&settings);
+        assert!(!missing.success);
+        assert_eq!(missing.error_kind.as_deref(), Some("AdbNotFound"));
+        assert_eq!(
+            missing.suggested_next,
+            Some(TroubleshootAction::OpenPlatformToolsDownload)
+        );
+        assert_eq!(missing.details[0], "adb: not found");
+
+        settings.adb_resolved_path = Some("/opt/platform-tools/adb".to_string());
+        let found = tool_report(&settings);
+        assert!(found.success);
+        assert_eq!(found.details[0], "adb: /opt/platform-tools/adb");
+        assert_eq!(found.suggested_next, None);
+    }
+}
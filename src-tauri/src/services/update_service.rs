@@ -0,0 +1,274 @@
+//! App updates: release channel, release notes and deferred or scheduled
+//! installs
+//!
+//! Wraps the updater plugin. The channel picks the update manifest, and a
+//! plan made for a pending version (put off, or install at a set time) is
+//! kept in the settings. Scheduled installs are run by a thread that lives
+//! only as long as the app, and wait for running transfers to finish.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{PendingUpdate, UpdateChannel, UpdatePlan, UpdatePlanKind};
+use crate::services::settings_service;
+use crate::services::transfer_service::{self, TransferState};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+/// Manifest of the latest stable release
+const STABLE_ENDPOINT: &str =
+    "https://github.com/gesucreative-hub/gesu-bridge/releases/latest/download/latest.json";
+
+/// Manifest of the `beta` release, which is moved to each prerelease
+const BETA_ENDPOINT: &str =
+    "https://github.com/gesucreative-hub/gesu-bridge/releases/download/beta/latest.json";
+
+/// How often a scheduled install is checked for
+const TICK: Duration = Duration::from_secs(300);
+
+/// Update manifest URL of a channel
+pub fn endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+/// The plan made for this version, if any; plans for other versions are stale
+pub fn plan_for<'a>(plan: Option<&'a UpdatePlan>, version: &str) -> Option<&'a UpdatePlan> {
+    plan.filter(|p| p.version == version)
+}
+
+/// Whether a plan of this kind has reached its time
+pub fn plan_due(plan: &UpdatePlan, kind: UpdatePlanKind, now: DateTime<Utc>) -> bool {
+    plan.kind == kind
+        && DateTime::parse_from_rfc3339(&plan.at).is_ok_and(|at| at.with_timezone(&Utc) <= now)
+}
+
+/// A plan for a version at an RFC 3339 time, normalized to UTC
+pub fn make_plan(version: &str, kind: UpdatePlanKind, at: &str) -> Result<UpdatePlan, AppError> {
+    let version = version.trim();
+    if version.is_empty() {
+        return Err(AppError::UpdateError("No update version given".to_string()));
+    }
+    let at = DateTime::parse_from_rfc3339(at.trim())
+        .map_err(|e| AppError::SettingsError(format!("Invalid time {}: {}", at, e)))?;
+    Ok(UpdatePlan {
+        version: version.to_string(),
+        kind,
+        at: at
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+    })
+}
+
+/// Describe an update found on `channel` along with the plan made for it
+pub fn describe(
+    version: &str,
+    current_version: &str,
+    notes: Option<&str>,
+    channel: UpdateChannel,
+    plan: Option<&UpdatePlan>,
+    now: DateTime<Utc>,
+) -> PendingUpdate {
+    let plan = plan_for(plan, version).cloned();
+    let deferred = plan.as_ref().is_some_and(|p| {
+        p.kind == UpdatePlanKind::Deferred && !plan_due(p, UpdatePlanKind::Deferred, now)
+    });
+    PendingUpdate {
+        version: version.to_string(),
+        current_version: current_version.to_string(),
+        channel,
+        notes: notes
+            .filter(|notes| !notes.trim().is_empty())
+            .map(str::to_string),
+        plan,
+        deferred,
+    }
+}
+
+/// The update available on the chosen channel, with its release notes
+pub async fn pending_update(app: &AppHandle) -> Result<Option<PendingUpdate>, AppError> {
+    let settings = settings_service::load_settings(app)?;
+    let update = check(app, settings.update_channel).await?;
+    Ok(update.map(|u| {
+        describe(
+            &u.version,
+            &u.current_version,
+            u.body.as_deref(),
+            settings.update_channel,
+            settings.update_plan.as_ref(),
+            Utc::now(),
+        )
+    }))
+}
+
+/// Download and install the pending update, then restart the app
+pub async fn install(app: &AppHandle) -> Result<(), AppError> {
+    let channel = settings_service::load_settings(app)?.update_channel;
+    let update = check(app, channel)
+        .await?
+        .ok_or_else(|| AppError::UpdateError("No update available".to_string()))?;
+    install_and_restart(app, &update).await
+}
+
+async fn check(app: &AppHandle, channel: UpdateChannel) -> Result<Option<Update>, AppError> {
+    let url = endpoint(channel)
+        .parse::<tauri::Url>()
+        .map_err(|e| AppError::UpdateError(e.to_string()))?;
+    app.updater_builder()
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| AppError::UpdateError(e.to_string()))?
+        .check()
+        .await
+        .map_err(|e| AppError::UpdateError(format!("Failed to check for updates: {}", e)))
+}
+
+async fn install_and_restart(app: &AppHandle, update: &Update) -> Result<(), AppError> {
+    update
+        .download_and_install(|_, _| {}, || {})
+        .await
+        .map_err(|e| {
+            AppError::UpdateError(format!("Failed to install {}: {}", update.version, e))
+        })?;
+    set_plan(app, None)?;
+    app.restart()
+}
+
+/// Save the plan for the pending update (None clears it)
+pub fn set_plan(app: &AppHandle, plan: Option<UpdatePlan>) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(app)?;
+    if settings.update_plan != plan {
+        settings.update_plan = plan;
+        settings_service::save_settings(app, &settings)?;
+    }
+    Ok(())
+}
+
+/// Switch release channel. A plan belongs to a version of the old channel,
+/// so it's dropped.
+pub fn set_channel(app: &AppHandle, channel: UpdateChannel) -> Result<(), AppError> {
+    let mut settings = settings_service::load_settings(app)?;
+    if settings.update_channel != channel {
+        settings.update_channel = channel;
+        settings.update_plan = None;
+        settings_service::save_settings(app, &settings)?;
+    }
+    Ok(())
+}
+
+/// Start the thread that runs scheduled installs while the app is open
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK);
+        let _ = tick(&app);
+    });
+}
+
+fn tick(app: &AppHandle) -> Result<(), AppError> {
+    let settings = settings_service::load_settings(app)?;
+    let Some(plan) = settings.update_plan else {
+        return Ok(());
+    };
+    if !plan_due(&plan, UpdatePlanKind::Scheduled, Utc::now()) {
+        return Ok(());
+    }
+    // Try again next round rather than cut a transfer off
+    if !transfer_service::get_active_transfers(&app.state::<TransferState>()).is_empty() {
+        return Ok(());
+    }
+
+    tauri::async_runtime::block_on(async {
+        match check(app, settings.update_channel).await? {
+            Some(update) if update.version == plan.version => {
+                install_and_restart(app, &update).await
+            }
+            // Installed already, or replaced by a newer version to decide on
+            _ => set_plan(app, None),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_make_plan() {
+        let plan = make_plan(
+            "0.8.0",
+            UpdatePlanKind::Scheduled,
+            "2026-10-17T02:00:00+07:00",
+        )
+        .unwrap();
+        assert_eq!(plan.at, "2026-10-16T19:00:00Z");
+        assert!(make_plan("0.8.0", UpdatePlanKind::Deferred, "tomorrow").is_err());
+        assert!(make_plan(" ", UpdatePlanKind::Deferred, "2026-10-17T02:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_describe_deferred_update() {
+        let plan = make_plan("0.8.0", UpdatePlanKind::Deferred, "2026-10-20T00:00:00Z").unwrap();
+        let pending = describe(
+            "0.8.0",
+            "0.7.0",
+            Some("- Faster thumbnails\n"),
+            UpdateChannel::Stable,
+            Some(&plan),
+            at("2026-10-16T12:00:00Z"),
+        );
+        assert!(pending.deferred);
+        assert_eq!(pending.notes.as_deref(), Some("- Faster thumbnails\n"));
+
+        // Deferral over
+        let later = describe(
+            "0.8.0",
+            "0.7.0",
+            None,
+            UpdateChannel::Stable,
+            Some(&plan),
+            at("2026-10-21T00:00:00Z"),
+        );
+        assert!(!later.deferred);
+
+        // A newer version than the one put off is offered right away
+        let newer = describe(
+            "0.8.1",
+            "0.7.0",
+            Some("  "),
+            UpdateChannel::Beta,
+            Some(&plan),
+            at("2026-10-16T12:00:00Z"),
+        );
+        assert!(!newer.deferred);
+        assert_eq!(newer.plan, None);
+        assert_eq!(newer.notes, None);
+    }
+
+    #[test]
+    fn test_plan_due() {
+        let plan = make_plan("0.8.0", UpdatePlanKind::Scheduled, "2026-10-17T02:00:00Z").unwrap();
+        assert!(!plan_due(
+            &plan,
+            UpdatePlanKind::Scheduled,
+            at("2026-10-17T01:59:00Z")
+        ));
+        assert!(plan_due(
+            &plan,
+            UpdatePlanKind::Scheduled,
+            at("2026-10-17T02:00:00Z")
+        ));
+        assert!(!plan_due(
+            &plan,
+            UpdatePlanKind::Deferred,
+            at("2026-10-18T00:00:00Z")
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+//! Setting and property watches: poll a value and report every change
+//!
+//! Meant for checking that an app under development flips the system
+//! settings it should. A watch runs only between `watch_setting` and
+//! `unwatch_setting` (or until the device goes away).
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{SettingChange, SettingWatch, SettingsNamespace, WatchNamespace};
+use crate::services::adb_service::{AdbCli, AdbRunner};
+use crate::services::device_settings_service;
+use crate::state::{read_lock, write_lock};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Time between reads
+const TICK: Duration = Duration::from_secs(1);
+
+/// Consecutive failed reads after which the device is considered gone
+const MAX_FAILURES: u32 = 5;
+
+/// Event emitted with a [`SettingChange`] payload when a watched value changes
+pub const SETTING_CHANGED_EVENT: &str = "setting-changed";
+
+/// Event emitted with the watch id when a watch ends on its own
+pub const SETTING_WATCH_STOPPED_EVENT: &str = "setting-watch-stopped";
+
+/// Running watches, keyed by watch id
+#[derive(Default)]
+pub struct WatchState {
+    watches: RwLock<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// Id of the watch on one value of one device
+pub fn watch_id(serial: &str, namespace: WatchNamespace, key: &str) -> String {
+    format!("{}/{}/{}", serial, namespace.as_str(), key)
+}
+
+/// Read a watched value; `None` when it's unset
+pub fn read_value(
+    adb: &dyn AdbRunner,
+    serial: &str,
+    namespace: WatchNamespace,
+    key: &str,
+) -> Result<Option<String>, AppError> {
+    let namespace = match namespace {
+        WatchNamespace::System => SettingsNamespace::System,
+        WatchNamespace::Secure => SettingsNamespace::Secure,
+        WatchNamespace::Global => SettingsNamespace::Global,
+        WatchNamespace::Prop => {
+            let output = adb.run(&["-s", serial, "shell", "getprop", key])?;
+            let value = output.trim();
+            // getprop prints an empty line for unset properties
+            return Ok((!value.is_empty()).then(|| value.to_string()));
+        }
+    };
+    device_settings_service::get_setting(adb, serial, namespace, key)
+}
+
+/// Last seen state of one watched value
+pub struct ValueWatch {
+    pub id: String,
+    pub serial: String,
+    pub namespace: WatchNamespace,
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl ValueWatch {
+    /// Read the value again; returns the change if it differs from last time
+    pub fn poll(&mut self, adb: &dyn AdbRunner) -> Result<Option<SettingChange>, AppError> {
+        let value = read_value(adb, &self.serial, self.namespace, &self.key)?;
+        if value == self.value {
+            return Ok(None);
+        }
+
+        let old_value = std::mem::replace(&mut self.value, value.clone());
+        Ok(Some(SettingChange {
+            watch_id: self.id.clone(),
+            device_serial: self.serial.clone(),
+            namespace: self.namespace,
+            key: self.key.clone(),
+            old_value,
+            new_value: value,
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        }))
+    }
+}
+
+/// Start watching a value, replacing any watch already on it
+pub fn watch_setting(
+    app: AppHandle,
+    state: &WatchState,
+    adb: AdbCli,
+    serial: String,
+    namespace: WatchNamespace,
+    key: String,
+) -> Result<SettingWatch, AppError> {
+    if !device_settings_service::is_valid_setting_key(&key) {
+        return Err(AppError::SettingsError(format!(
+            "Invalid settings key: {}",
+            key
+        )));
+    }
+
+    // Read once up front so a bad key or missing device fails the call
+    let value = read_value(&adb, &serial, namespace, &key)?;
+    let id = watch_id(&serial, namespace, &key);
+    let mut watch = ValueWatch {
+        id: id.clone(),
+        serial,
+        namespace,
+        key,
+        value: value.clone(),
+    };
+
+    let running = Arc::new(AtomicBool::new(true));
+    if let Some(previous) = write_lock(&state.watches).insert(id.clone(), running.clone()) {
+        previous.store(false, Ordering::Relaxed);
+    }
+
+    std::thread::spawn(move || {
+        let mut failures = 0;
+        loop {
+            std::thread::sleep(TICK);
+            if !running.load(Ordering::Relaxed) {
+                break;
+            }
+            match watch.poll(&adb) {
+                Ok(change) => {
+                    failures = 0;
+                    if let Some(change) = change {
+                        let _ = app.emit(SETTING_CHANGED_EVENT, &change);
+                    }
+                }
+                Err(_) => {
+                    failures += 1;
+                    if failures >= MAX_FAILURES {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Only clean up if this watch wasn't replaced or stopped in the meantime
+        let state = app.state::<WatchState>();
+        let mut watches = write_lock(&state.watches);
+        if watches
+            .get(&watch.id)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &running))
+        {
+            watches.remove(&watch.id);
+            drop(watches);
+            let _ = app.emit(SETTING_WATCH_STOPPED_EVENT, &watch.id);
+        }
+    });
+
+    Ok(SettingWatch {
+        watch_id: id,
+        value,
+    })
+}
+
+/// Stop a watch; returns whether it was running
+pub fn unwatch_setting(state: &WatchState, id: &str) -> bool {
+    match write_lock(&state.watches).remove(id) {
+        Some(running) => {
+            running.store(false, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Ids of the running watches
+pub fn list_watches(state: &WatchState) -> Vec<String> {
+    let mut ids: Vec<String> = read_lock(&state.watches).keys().cloned().collect();
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    #[test]
+    fn test_read_value() {
+        let adb = MockAdb::new()
+            .on("getprop persist.sys.locale", "\n")
+            .on("getprop", "1\n")
+            .on("settings get secure", "null\n")
+            .on("settings get global", "1\n");
+
+        assert_eq!(
+            read_value(&adb, "SER1", WatchNamespace::Prop, "debug.hwui.profile").unwrap(),
+            Some("1".to_string())
+        );
+        assert_eq!(
+            read_value(&adb, "SER1", WatchNamespace::Prop, "persist.sys.locale").unwrap(),
+            None
+        );
+        assert_eq!(
+            read_value(&adb, "SER1", WatchNamespace::Secure, "location_mode").unwrap(),
+            None
+        );
+        assert_eq!(
+            read_value(&adb, "SER1", WatchNamespace::Global, "adb_enabled").unwrap(),
+            Some("1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_poll_reports_changes_once() {
+        let mut watch = ValueWatch {
+            id: watch_id("SER1", WatchNamespace::Global, "airplane_mode_on"),
+            serial: "SER1".to_string(),
+            namespace: WatchNamespace::Global,
+            key: "airplane_mode_on".to_string(),
+            value: Some("0".to_string()),
+        };
+        assert_eq!(watch.id, "SER1/global/airplane_mode_on");
+
+        let unchanged = MockAdb::new().on("settings get", "0\n");
+        assert_eq!(watch.poll(&unchanged).unwrap(), None);
+
+        let changed = MockAdb::new().on("settings get", "1\n");
+        let change = watch.poll(&changed).unwrap().unwrap();
+        assert_eq!(change.old_value.as_deref(), Some("0"));
+        assert_eq!(change.new_value.as_deref(), Some("1"));
+        assert_eq!(watch.poll(&changed).unwrap(), None);
+    }
+}
@@ -0,0 +1,613 @@
+//! Wi-Fi ADB connection keeper: notices wireless devices dropping off (e.g.
+//! after the device slept) and runs `adb connect` again with backoff
+//!
+//! Runs only while the app is open. A device is watched once it has been seen
+//! ready under an `ip:port` serial; `disconnect_wireless` stops watching it.
+//! Endpoints are remembered in the device registry, so devices from earlier
+//! sessions are reconnected when the app starts and when the network changes.
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{DeviceState, Settings, TcpipMode, WirelessLinkState, WirelessStatus};
+use crate::services::adb_service::{self, AdbCli, AdbRunner};
+use crate::services::retry_service::RetryPolicy;
+use crate::services::{hotspot_service, settings_service};
+use crate::state::{read_lock, write_lock};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often wireless devices are checked
+const TICK: Duration = Duration::from_secs(15);
+
+/// Backoff between reconnect attempts; after the last one the device is lost
+const RECONNECT_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 12,
+    initial_delay: Duration::from_secs(5),
+    max_delay: Duration::from_secs(300),
+};
+
+/// Port adbd listens on after `adb tcpip` unless told otherwise
+pub const TCPIP_PORT: u16 = 5555;
+
+/// Event emitted with a [`WirelessStatus`] payload whenever a device's state
+/// changes or a reconnect attempt fails
+pub const WIRELESS_STATUS_EVENT: &str = "wireless-status";
+
+/// Wireless devices being watched, keyed by endpoint
+#[derive(Default)]
+pub struct WirelessState {
+    links: RwLock<HashMap<String, WirelessLink>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WirelessLink {
+    pub status: WirelessStatus,
+    next_attempt: Option<Instant>,
+}
+
+impl WirelessLink {
+    fn connected(endpoint: &str) -> Self {
+        Self {
+            status: WirelessStatus {
+                endpoint: endpoint.to_string(),
+                state: WirelessLinkState::Connected,
+                attempts: 0,
+                next_retry_secs: None,
+            },
+            next_attempt: None,
+        }
+    }
+
+    fn set_state(&mut self, state: WirelessLinkState, now: Instant) {
+        self.status.state = state;
+        if state == WirelessLinkState::Connected {
+            self.status.attempts = 0;
+        }
+        self.next_attempt = (state == WirelessLinkState::Reconnecting).then_some(now);
+        self.status.next_retry_secs = None;
+    }
+}
+
+/// Whether a serial is an `adb connect` endpoint (`192.168.1.20:5555`).
+/// Unlike `link_service::is_wireless_serial` this leaves out wireless
+/// debugging's mDNS serials, which adb reconnects by itself.
+pub fn is_tcp_endpoint(serial: &str) -> bool {
+    serial.parse::<SocketAddr>().is_ok()
+}
+
+/// An `ip` or `ip:port` address as an `adb connect` endpoint, on
+/// [`TCPIP_PORT`] when no port is given. Host names aren't accepted.
+pub fn parse_endpoint(addr: &str) -> Result<String, AppError> {
+    let addr = addr.trim();
+    let endpoint = match addr.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, TCPIP_PORT),
+        Err(_) => addr.parse::<SocketAddr>().map_err(|_| {
+            AppError::AdbExecutionFailed(format!("Not an ip or ip:port address: {}", addr))
+        })?,
+    };
+    if endpoint.port() == 0 {
+        return Err(AppError::AdbExecutionFailed(format!(
+            "Not a valid port: {}",
+            addr
+        )));
+    }
+    Ok(endpoint.to_string())
+}
+
+/// Connect to a device over TCP/IP; returns the endpoint. `adb connect`
+/// exits 0 when it fails, so its output is checked. The keeper starts
+/// watching the device once it's listed as ready.
+pub fn connect(adb: &dyn AdbRunner, addr: &str) -> Result<String, AppError> {
+    let endpoint = parse_endpoint(addr)?;
+    let output = adb.run(&["connect", &endpoint])?;
+    if output.contains("connected to") {
+        Ok(endpoint)
+    } else {
+        Err(AppError::AdbExecutionFailed(output.trim().to_string()))
+    }
+}
+
+/// Switch a USB device's adbd to listen on `port`, so it can be unplugged
+/// and connected over Wi-Fi. The Wi-Fi address is read first, since the USB
+/// connection drops for a moment while adbd restarts.
+pub fn enable_tcpip(adb: &dyn AdbRunner, serial: &str, port: u16) -> Result<TcpipMode, AppError> {
+    if port == 0 {
+        return Err(AppError::AdbExecutionFailed(
+            "Not a valid port: 0".to_string(),
+        ));
+    }
+    let address = hotspot_service::check_network(adb, serial)
+        .ok()
+        .and_then(|check| check.device_address);
+    adb.run(&["-s", serial, "tcpip", &port.to_string()])?;
+    let endpoint = address.and_then(|address| {
+        let ip = address.split('/').next()?.parse::<IpAddr>().ok()?;
+        Some(SocketAddr::new(ip, port).to_string())
+    });
+    Ok(TcpipMode {
+        device_serial: serial.to_string(),
+        port,
+        endpoint,
+    })
+}
+
+/// Statuses of all watched wireless devices
+pub fn get_statuses(state: &WirelessState) -> Vec<WirelessStatus> {
+    let mut statuses: Vec<WirelessStatus> = read_lock(&state.links)
+        .values()
+        .map(|link| link.status.clone())
+        .collect();
+    statuses.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+    statuses
+}
+
+/// Disconnect a wireless device on purpose and stop reconnecting it
+pub fn disconnect(
+    state: &WirelessState,
+    adb: &dyn AdbRunner,
+    endpoint: &str,
+) -> Result<String, AppError> {
+    if !is_tcp_endpoint(endpoint) {
+        return Err(AppError::AdbExecutionFailed(format!(
+            "Not a wireless device: {}",
+            endpoint
+        )));
+    }
+    write_lock(&state.links).remove(endpoint);
+    adb.run(&["disconnect", endpoint])
+}
+
+/// Hardware serial of a device connected over the network
+pub fn hardware_serial(adb: &dyn AdbRunner, endpoint: &str) -> Option<String> {
+    let output = adb
+        .run(&["-s", endpoint, "shell", "getprop", "ro.serialno"])
+        .ok()?;
+    let serial = output.trim();
+    (!serial.is_empty()).then(|| serial.to_string())
+}
+
+/// Remember the endpoint a device was reached at. An address belongs to one
+/// device at a time, so it's dropped from any other device's record.
+/// Returns whether anything changed.
+pub fn remember_endpoint(settings: &mut Settings, serial: &str, endpoint: &str) -> bool {
+    let mut changed = false;
+    for (other, record) in settings.device_registry.iter_mut() {
+        if other != serial && record.wireless_endpoint.as_deref() == Some(endpoint) {
+            record.wireless_endpoint = None;
+            changed = true;
+        }
+    }
+    let record = settings
+        .device_registry
+        .entry(serial.to_string())
+        .or_default();
+    if record.wireless_endpoint.as_deref() != Some(endpoint) {
+        record.wireless_endpoint = Some(endpoint.to_string());
+        changed = true;
+    }
+    changed
+}
+
+/// Stop reconnecting an endpoint on startup; returns whether it was remembered
+pub fn forget_endpoint(settings: &mut Settings, endpoint: &str) -> bool {
+    let mut changed = false;
+    for record in settings.device_registry.values_mut() {
+        if record.wireless_endpoint.as_deref() == Some(endpoint) {
+            record.wireless_endpoint = None;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Endpoints remembered from earlier sessions
+pub fn saved_endpoints(settings: &Settings) -> Vec<String> {
+    let mut endpoints: Vec<String> = settings
+        .device_registry
+        .values()
+        .filter_map(|record| record.wireless_endpoint.clone())
+        .filter(|endpoint| is_tcp_endpoint(endpoint))
+        .collect();
+    endpoints.sort();
+    endpoints.dedup();
+    endpoints
+}
+
+/// Start watching endpoints that aren't connected; the next round connects them
+pub fn watch_endpoints(
+    links: &mut HashMap<String, WirelessLink>,
+    endpoints: &[String],
+    now: Instant,
+) {
+    for endpoint in endpoints {
+        links.entry(endpoint.clone()).or_insert_with(|| {
+            let mut link = WirelessLink::connected(endpoint);
+            link.set_state(WirelessLinkState::Reconnecting, now);
+            link
+        });
+    }
+}
+
+/// Retry every device that isn't connected right away with a fresh backoff,
+/// e.g. after the computer joined another network
+pub fn retry_all(links: &mut HashMap<String, WirelessLink>, now: Instant) {
+    for link in links.values_mut() {
+        if link.status.state != WirelessLinkState::Connected {
+            link.status.attempts = 0;
+            link.set_state(WirelessLinkState::Reconnecting, now);
+        }
+    }
+}
+
+/// Address of the interface outbound traffic leaves through, to notice
+/// network changes. Connecting a UDP socket only picks a route; nothing is sent.
+fn local_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    // TEST-NET-1, never routed anywhere
+    socket.connect("192.0.2.1:9").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// One round of the keeper: learn newly connected wireless devices, ping
+/// connected ones and retry those that dropped off when their backoff is up.
+/// Returns the statuses that changed.
+pub fn check_links(
+    adb: &dyn AdbRunner,
+    links: &mut HashMap<String, WirelessLink>,
+    now: Instant,
+) -> Result<Vec<WirelessStatus>, AppError> {
+    let devices = adb_service::parse_devices_output(&adb.run(&["devices"])?);
+    let mut changed = Vec::new();
+
+    for device in devices.iter().filter(|d| is_tcp_endpoint(&d.serial)) {
+        if device.state == DeviceState::Ready && !links.contains_key(&device.serial) {
+            let link = WirelessLink::connected(&device.serial);
+            changed.push(link.status.clone());
+            links.insert(device.serial.clone(), link);
+        }
+    }
+
+    for (endpoint, link) in links.iter_mut() {
+        let listed_ready = devices
+            .iter()
+            .any(|d| d.serial == *endpoint && d.state == DeviceState::Ready);
+
+        match link.status.state {
+            // A dead TCP connection can stay listed as ready for minutes
+            WirelessLinkState::Connected if !listed_ready || !ping(adb, endpoint) => {
+                link.set_state(WirelessLinkState::Reconnecting, now);
+                changed.push(link.status.clone());
+            }
+            WirelessLinkState::Reconnecting | WirelessLinkState::Lost if listed_ready => {
+                // Came back on its own or was connected by hand
+                link.set_state(WirelessLinkState::Connected, now);
+                changed.push(link.status.clone());
+                continue;
+            }
+            _ => {}
+        }
+
+        if link.status.state == WirelessLinkState::Reconnecting
+            && link.next_attempt.is_some_and(|at| at <= now)
+        {
+            link.status.attempts += 1;
+            if reconnect(adb, endpoint) {
+                link.set_state(WirelessLinkState::Connected, now);
+            } else if link.status.attempts >= RECONNECT_POLICY.max_attempts {
+                link.set_state(WirelessLinkState::Lost, now);
+            } else {
+                let delay = RECONNECT_POLICY.delay_for(link.status.attempts);
+                link.next_attempt = Some(now + delay);
+                link.status.next_retry_secs = Some(delay.as_secs());
+            }
+            changed.push(link.status.clone());
+        }
+    }
+
+    Ok(changed)
+}
+
+fn ping(adb: &dyn AdbRunner, endpoint: &str) -> bool {
+    adb.run(&["-s", endpoint, "shell", "echo", "ok"])
+        .is_ok_and(|output| output.trim() == "ok")
+}
+
+/// Drop the stale transport, then connect again. `adb connect` exits 0 even
+/// when it fails, so the outcome is read from its output.
+fn reconnect(adb: &dyn AdbRunner, endpoint: &str) -> bool {
+    let _ = adb.run(&["disconnect", endpoint]);
+    adb.run(&["connect", endpoint])
+        .is_ok_and(|output| output.contains("connected to"))
+}
+
+/// Start the keeper thread; it lives as long as the app. Devices remembered
+/// from earlier sessions are reconnected in its first round.
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        if let Ok(settings) = settings_service::load_settings(&app) {
+            watch_endpoints(
+                &mut write_lock(&app.state::<WirelessState>().links),
+                &saved_endpoints(&settings),
+                Instant::now(),
+            );
+        }
+
+        let mut network = local_address();
+        loop {
+            let _ = tick(&app, &mut network);
+            std::thread::sleep(TICK);
+        }
+    });
+}
+
+fn tick(app: &AppHandle, network: &mut Option<IpAddr>) -> Result<(), AppError> {
+    let settings = settings_service::get_settings_with_detection(app)?;
+    let Some(adb) = AdbCli::from_settings(&settings).map(AdbCli::background) else {
+        return Ok(());
+    };
+
+    // Work on a copy so status queries aren't blocked by slow `adb connect`s
+    let state = app.state::<WirelessState>();
+    let mut links = read_lock(&state.links).clone();
+    let watched: Vec<String> = links.keys().cloned().collect();
+    let now = Instant::now();
+
+    let address = local_address();
+    if address.is_some() && address != *network {
+        retry_all(&mut links, now);
+    }
+    *network = address;
+
+    let changed = check_links(&adb, &mut links, now)?;
+
+    {
+        let mut current = write_lock(&state.links);
+        for (endpoint, link) in links {
+            // Skip devices disconnected on purpose while this round ran
+            if current.contains_key(&endpoint) || !watched.contains(&endpoint) {
+                current.insert(endpoint, link);
+            }
+        }
+    }
+
+    let connected: Vec<(String, String)> = changed
+        .iter()
+        .filter(|status| status.state == WirelessLinkState::Connected)
+        .filter_map(|status| {
+            let serial = hardware_serial(&adb, &status.endpoint)?;
+            Some((serial, status.endpoint.clone()))
+        })
+        .collect();
+    if !connected.is_empty() {
+        let mut saved = settings_service::load_settings(app)?;
+        let mut dirty = false;
+        for (serial, endpoint) in &connected {
+            dirty |= remember_endpoint(&mut saved, serial, endpoint);
+        }
+        if dirty {
+            settings_service::save_settings(app, &saved)?;
+        }
+    }
+
+    for status in changed {
+        let _ = app.emit(WIRELESS_STATUS_EVENT, &status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::adb_service::mock::MockAdb;
+
+    const EP: &str = "192.168.1.20:5555";
+
+    #[test]
+    fn test_is_tcp_endpoint() {
+        assert!(is_tcp_endpoint("192.168.1.20:5555"));
+        assert!(is_tcp_endpoint("[fe80::1]:37000"));
+        assert!(!is_tcp_endpoint("emulator-5554"));
+        assert!(!is_tcp_endpoint("RFCT80XXXXX"));
+    }
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(parse_endpoint("192.168.1.20").unwrap(), EP);
+        assert_eq!(parse_endpoint(" 192.168.1.20:5555 ").unwrap(), EP);
+        assert_eq!(parse_endpoint("fe80::1").unwrap(), "[fe80::1]:5555");
+        assert!(parse_endpoint("192.168.1.20:0").is_err());
+        assert!(parse_endpoint("phone.local:5555").is_err());
+        assert!(parse_endpoint("192.168.1.20; reboot").is_err());
+    }
+
+    #[test]
+    fn test_connect_checks_output() {
+        let adb = MockAdb::new().on("connect", "connected to 192.168.1.20:5555\n");
+        assert_eq!(connect(&adb, "192.168.1.20").unwrap(), EP);
+        assert_eq!(adb.calls(), vec!["connect 192.168.1.20:5555"]);
+
+        // adb exits 0 on failure too
+        let adb = MockAdb::new().on(
+            "connect",
+            "failed to connect to '192.168.1.20:5555': Connection refused\n",
+        );
+        assert!(connect(&adb, EP).is_err());
+    }
+
+    #[test]
+    fn test_enable_tcpip() {
+        let adb = MockAdb::new()
+            .on(
+                "addr show",
+                "30: wlan0    inet 192.168.1.20/24 brd 192.168.1.255 scope global wlan0\n",
+            )
+            .on("tcpip", "restarting in TCP mode port: 5555\n");
+        let mode = enable_tcpip(&adb, "RFCT80XXXXX", TCPIP_PORT).unwrap();
+        assert_eq!(mode.endpoint.as_deref(), Some(EP));
+        assert_eq!(adb.calls()[1], "-s RFCT80XXXXX tcpip 5555");
+
+        // Off Wi-Fi: still switched, nothing to connect to yet
+        let adb = MockAdb::new()
+            .on("addr show", "")
+            .on("tcpip", "restarting in TCP mode port: 5556\n");
+        let mode = enable_tcpip(&adb, "RFCT80XXXXX", 5556).unwrap();
+        assert_eq!(mode.port, 5556);
+        assert_eq!(mode.endpoint, None);
+
+        assert!(enable_tcpip(&MockAdb::new(), "RFCT80XXXXX", 0).is_err());
+    }
+
+    #[test]
+    fn test_learns_connected_devices() {
+        let adb = MockAdb::new()
+            .on(
+                "devices",
+                "List of devices attached\n192.168.1.20:5555\tdevice\nRFCT80XXXXX\tdevice\n",
+            )
+            .on("echo ok", "ok\n");
+        let mut links = HashMap::new();
+
+        let changed = check_links(&adb, &mut links, Instant::now()).unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].endpoint, EP);
+        assert_eq!(changed[0].state, WirelessLinkState::Connected);
+
+        // Nothing to report once it's known and answering
+        assert!(check_links(&adb, &mut links, Instant::now())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reconnects_with_backoff() {
+        let now = Instant::now();
+        let mut links = HashMap::from([(EP.to_string(), WirelessLink::connected(EP))]);
+
+        let gone = MockAdb::new()
+            .on("devices", "List of devices attached\n")
+            .on("disconnect", "")
+            .on(
+                "connect",
+                "failed to connect to '192.168.1.20:5555': Connection refused\n",
+            );
+        let changed = check_links(&gone, &mut links, now).unwrap();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[0].state, WirelessLinkState::Reconnecting);
+        assert_eq!(changed[1].attempts, 1);
+        assert_eq!(changed[1].next_retry_secs, Some(5));
+
+        // Not retried before the backoff is up
+        assert!(check_links(&gone, &mut links, now + Duration::from_secs(2))
+            .unwrap()
+            .is_empty());
+
+        let back = MockAdb::new()
+            .on("devices", "List of devices attached\n")
+            .on("disconnect", "")
+            .on("connect", "connected to 192.168.1.20:5555\n");
+        let changed = check_links(&back, &mut links, now + Duration::from_secs(5)).unwrap();
+        assert_eq!(changed[0].state, WirelessLinkState::Connected);
+        assert_eq!(changed[0].attempts, 0);
+    }
+
+    #[test]
+    fn test_unresponsive_device_is_reconnected() {
+        let adb = MockAdb::new()
+            .on(
+                "devices",
+                "List of devices attached\n192.168.1.20:5555\tdevice\n",
+            )
+            .fail(
+                "echo ok",
+                AppError::AdbExecutionFailed("error: closed".to_string()),
+            )
+            .on("disconnect", "")
+            .on("connect", "already connected to 192.168.1.20:5555\n");
+        let mut links = HashMap::from([(EP.to_string(), WirelessLink::connected(EP))]);
+
+        check_links(&adb, &mut links, Instant::now()).unwrap();
+        assert_eq!(links[EP].status.state, WirelessLinkState::Connected);
+        assert!(adb.calls().contains(&format!("connect {}", EP)));
+    }
+
+    #[test]
+    fn test_remember_and_forget_endpoint() {
+        let mut settings = Settings::new();
+        assert!(remember_endpoint(&mut settings, "R58M123", EP));
+        assert!(!remember_endpoint(&mut settings, "R58M123", EP));
+        assert_eq!(saved_endpoints(&settings), vec![EP]);
+
+        // The address was handed to another device
+        assert!(remember_endpoint(&mut settings, "HT7A1B", EP));
+        assert_eq!(settings.device_registry["R58M123"].wireless_endpoint, None);
+        assert_eq!(saved_endpoints(&settings), vec![EP]);
+
+        assert!(forget_endpoint(&mut settings, EP));
+        assert!(saved_endpoints(&settings).is_empty());
+    }
+
+    #[test]
+    fn test_saved_endpoints_are_reconnected() {
+        let adb = MockAdb::new()
+            .on(
+                "devices",
+                "List of devices attached
+",
+            )
+            .on("disconnect", "")
+            .on(
+                "connect",
+                "connected to 192.168.1.20:5555
+",
+            );
+        let mut links = HashMap::new();
+        let now = Instant::now();
+        watch_endpoints(&mut links, &[EP.to_string()], now);
+        assert_eq!(links[EP].status.state, WirelessLinkState::Reconnecting);
+
+        let changed = check_links(&adb, &mut links, now).unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].state, WirelessLinkState::Connected);
+    }
+
+    #[test]
+    fn test_retry_all_restarts_lost_devices() {
+        let now = Instant::now();
+        let mut lost = WirelessLink::connected(EP);
+        lost.status.attempts = RECONNECT_POLICY.max_attempts;
+        lost.set_state(WirelessLinkState::Lost, now);
+        let mut links = HashMap::from([
+            (EP.to_string(), lost),
+            (
+                "10.0.0.7:5555".to_string(),
+                WirelessLink::connected("10.0.0.7:5555"),
+            ),
+        ]);
+
+        retry_all(&mut links, now);
+        assert_eq!(links[EP].status.state, WirelessLinkState::Reconnecting);
+        assert_eq!(links[EP].status.attempts, 0);
+        assert_eq!(
+            links["10.0.0.7:5555"].status.state,
+            WirelessLinkState::Connected
+        );
+    }
+
+    #[test]
+    fn test_gives_up_after_last_attempt() {
+        let adb = MockAdb::new()
+            .on("devices", "List of devices attached\n")
+            .on("disconnect", "")
+            .on("connect", "cannot connect to 192.168.1.20:5555\n");
+        let mut link = WirelessLink::connected(EP);
+        link.set_state(WirelessLinkState::Reconnecting, Instant::now());
+        link.status.attempts = RECONNECT_POLICY.max_attempts - 1;
+        let mut links = HashMap::from([(EP.to_string(), link)]);
+
+        let changed = check_links(&adb, &mut links, Instant::now()).unwrap();
+        assert_eq!(changed[0].state, WirelessLinkState::Lost);
+        assert_eq!(changed[0].next_retry_secs, None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Pulling single entries out of APKs, which are zip archives
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// Largest entry read into memory; manifests and resource tables are far smaller
+pub const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Read one entry of a zip archive by name; `Ok(None)` if there's no such entry
+pub fn read_entry(path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    if entry.size() > MAX_ENTRY_SIZE {
+        return Err(too_large(name));
+    }
+    // The declared size can't be trusted, so the read is capped as well
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry.take(MAX_ENTRY_SIZE + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_ENTRY_SIZE {
+        return Err(too_large(name));
+    }
+    Ok(Some(data))
+}
+
+fn too_large(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} is too large to read", name),
+    )
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    /// Zip archive of `(name, data)` entries, deflated like in a real APK
+    pub fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, data) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::build_zip;
+    use super::*;
+
+    #[test]
+    fn test_read_entry() {
+        let manifest = b"hello hello hello hello gesu bridge".repeat(20);
+        let zip = build_zip(&[
+            ("resources.arsc", b"table"),
+            ("AndroidManifest.xml", &manifest),
+        ]);
+        let path = std::env::temp_dir().join(format!("gesu_zip_test_{}.zip", std::process::id()));
+        std::fs::write(&path, zip).unwrap();
+
+        assert_eq!(
+            read_entry(&path, "resources.arsc").unwrap().unwrap(),
+            b"table"
+        );
+        assert_eq!(
+            read_entry(&path, "AndroidManifest.xml").unwrap().unwrap(),
+            manifest
+        );
+        assert!(read_entry(&path, "classes.dex").unwrap().is_none());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_not_a_zip() {
+        let path = std::env::temp_dir().join(format!("gesu_zip_bad_{}.apk", std::process::id()));
+        for contents in [&b"definitely not a zip"[..], &[]] {
+            std::fs::write(&path, contents).unwrap();
+            assert!(read_entry(&path, "AndroidManifest.xml").is_err());
+        }
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,16 @@
+//! Helpers for Tauri managed state
+
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Acquire a read lock, recovering the data if a previous holder panicked
+///
+/// State maps only hold plain bookkeeping, so a panic mid-update never leaves
+/// them in a shape worse than "stale entry"; refusing all future access would be worse.
+pub fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Acquire a write lock, recovering the data if a previous holder panicked
+pub fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,311 @@
+//! End-to-end tests that drive the services against a fake adb executable
+//!
+//! This binary doubles as the fake adb: when `FAKE_ADB_FIXTURE` is set it replays
+//! responses from a fixture file instead of running tests. The tests point
+//! `AdbCli` at `current_exe()` so the real process-spawning path is exercised.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tauri_app_lib::domain::models::{
+    ConflictPolicy, DeviceState, MediaFilter, MediaType, TransferStatus,
+};
+use tauri_app_lib::services::adb_service::{self, AdbCli};
+use tauri_app_lib::services::media_service;
+use tauri_app_lib::services::transfer_service::{self, TransferState};
+
+const FIXTURE_ENV: &str = "FAKE_ADB_FIXTURE";
+const SERIAL: &str = "2A281FDH200ABC";
+
+fn main() -> ExitCode {
+    if let Ok(fixture) = std::env::var(FIXTURE_ENV) {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        return replay(Path::new(&fixture), &args);
+    }
+
+    run_tests()
+}
+
+// ============================================
+// Fake adb
+// ============================================
+
+struct Reply {
+    pattern: String,
+    body: String,
+    failure: bool,
+}
+
+/// Parse a fixture file into replies (see tests/fixtures/adb/pixel7.txt for the format)
+fn parse_fixture(content: &str) -> Vec<Reply> {
+    let mut replies: Vec<Reply> = Vec::new();
+
+    for line in content.lines() {
+        let header = line
+            .strip_prefix("!> ")
+            .map(|p| (p, true))
+            .or_else(|| line.strip_prefix("> ").map(|p| (p, false)));
+
+        if let Some((pattern, failure)) = header {
+            replies.push(Reply {
+                pattern: pattern.trim().to_string(),
+                body: String::new(),
+                failure,
+            });
+        } else if let Some(reply) = replies.last_mut() {
+            reply.body.push_str(line);
+            reply.body.push('\n');
+        }
+    }
+
+    // Blank separator lines between entries are not part of the reply
+    for reply in replies.iter_mut() {
+        let trimmed = reply.body.trim_end_matches('\n').len();
+        reply.body.truncate(trimmed);
+        if !reply.body.is_empty() {
+            reply.body.push('\n');
+        }
+    }
+
+    replies
+}
+
+fn replay(fixture: &Path, args: &[String]) -> ExitCode {
+    let content = match std::fs::read_to_string(fixture) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("fake adb: cannot read fixture {}: {}", fixture.display(), e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let joined = args.join(" ");
+    let reply = match parse_fixture(&content)
+        .into_iter()
+        .find(|r| joined.contains(&r.pattern))
+    {
+        Some(r) => r,
+        None => {
+            eprintln!("fake adb: no recorded reply for `{}`", joined);
+            return ExitCode::from(1);
+        }
+    };
+
+    if reply.failure {
+        eprint!("{}", reply.body);
+        return ExitCode::from(1);
+    }
+
+    if args.iter().any(|a| a == "pull") {
+        let local = args.last().expect("pull without destination");
+        if let Err(e) = std::fs::write(local, reply.body.trim_end()) {
+            eprintln!("fake adb: cannot write {}: {}", local, e);
+            return ExitCode::from(1);
+        }
+        println!("1 file pulled, 0 skipped.");
+    } else {
+        print!("{}", reply.body);
+    }
+
+    ExitCode::SUCCESS
+}
+
+// ============================================
+// Test runner
+// ============================================
+
+fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/adb")
+        .join(name)
+}
+
+fn fake_adb(fixture: &str) -> AdbCli {
+    std::env::set_var(FIXTURE_ENV, fixture_path(fixture));
+    let exe = std::env::current_exe().expect("current exe");
+    AdbCli::new(exe.to_string_lossy().to_string())
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gesu_fake_adb_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+fn run_tests() -> ExitCode {
+    let tests: &[(&str, fn())] = &[
+        ("list_devices", list_devices),
+        ("list_media_files", list_media_files),
+        ("unicode_file_names", unicode_file_names),
+        ("thumbnail_via_content_read", thumbnail_via_content_read),
+        ("push_file", push_file),
+        ("pull_batch_reports_failures", pull_batch_reports_failures),
+    ];
+
+    // Honour `cargo test -- <filter>` like the default harness
+    let filter = std::env::args().skip(1).find(|a| !a.starts_with('-'));
+
+    let mut failed = Vec::new();
+    let mut ran = 0;
+    for (name, test) in tests {
+        if let Some(ref f) = filter {
+            if !name.contains(f.as_str()) {
+                continue;
+            }
+        }
+
+        ran += 1;
+        let result = std::panic::catch_unwind(test);
+        println!(
+            "test {} ... {}",
+            name,
+            if result.is_ok() { "ok" } else { "FAILED" }
+        );
+        if result.is_err() {
+            failed.push(*name);
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failed.is_empty() { "ok" } else { "FAILED" },
+        ran - failed.len(),
+        failed.len()
+    );
+
+    if failed.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+// ============================================
+// Tests
+// ============================================
+
+fn list_devices() {
+    let adb = fake_adb("pixel7.txt");
+    let devices = adb_service::list_devices(&adb).unwrap();
+
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0].serial, SERIAL);
+    assert_eq!(devices[0].state, DeviceState::Ready);
+    assert_eq!(devices[0].model.as_deref(), Some("Pixel 7"));
+    assert_eq!(devices[0].android_version.as_deref(), Some("14"));
+    assert_eq!(devices[0].manufacturer.as_deref(), Some("Google"));
+    assert_eq!(devices[1].state, DeviceState::Unauthorized);
+}
+
+fn list_media_files() {
+    let adb = fake_adb("pixel7.txt");
+    let items =
+        media_service::list_media_files(&adb, SERIAL, "/sdcard/DCIM/Camera", MediaFilter::All)
+            .unwrap();
+
+    let names: Vec<_> = items.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "PXL_20240201_085900.jpg",
+            "PXL_20240131_182000 (1).mp4",
+            ".pending-1706598000-PXL.jpg"
+        ]
+    );
+    assert_eq!(items[0].size_bytes, 3_145_728);
+    assert_eq!(items[0].date_taken.as_deref(), Some("2024-02-01T08:59:00Z"));
+    assert_eq!(items[1].media_type, MediaType::Video);
+    assert_eq!(
+        items[1].path,
+        "/sdcard/DCIM/Camera/PXL_20240131_182000 (1).mp4"
+    );
+}
+
+fn unicode_file_names() {
+    let adb = fake_adb("pixel7.txt");
+    let dir = scratch_dir("unicode");
+
+    let items =
+        media_service::list_media_files(&adb, SERIAL, "/sdcard/Pictures/Trip", MediaFilter::All)
+            .unwrap();
+    let names: Vec<_> = items.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["🎉 誕生日\u{3000}パーティー.jpg", "Café  à Montréal.jpg"]
+    );
+
+    let paths: Vec<String> = items.iter().map(|i| i.path.clone()).collect();
+    let results = media_service::pull_media_files_batch(
+        &adb,
+        SERIAL,
+        &paths,
+        &dir,
+        ConflictPolicy::Overwrite,
+        &|_| None,
+        &|_| {},
+    )
+    .unwrap();
+
+    assert!(results.iter().all(|r| r.success));
+    assert!(dir.join("🎉 誕生日\u{3000}パーティー.jpg").exists());
+    assert!(dir.join("Café  à Montréal.jpg").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+fn thumbnail_via_content_read() {
+    let adb = fake_adb("pixel7.txt");
+    let dir = scratch_dir("thumbs");
+
+    let thumb = media_service::get_thumbnail(
+        &adb,
+        None,
+        SERIAL,
+        "/sdcard/DCIM/Camera/PXL_20240201_085900.jpg",
+        &dir,
+    )
+    .unwrap();
+
+    assert!(thumb.starts_with("data:image/jpeg;base64,"));
+    assert!(dir.join("thumb_PXL_20240201_085900.jpg").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+fn push_file() {
+    let adb = fake_adb("pixel7.txt");
+    let state = TransferState::default();
+
+    let item = transfer_service::push_file(
+        &state,
+        &adb,
+        SERIAL,
+        "/tmp/report.pdf",
+        "Download",
+        ConflictPolicy::Overwrite,
+    )
+    .unwrap();
+
+    assert_eq!(item.status, TransferStatus::Complete);
+    assert_eq!(item.dest_path, "/sdcard/Download/report.pdf");
+    assert_eq!(transfer_service::get_transfer_history(&state).len(), 1);
+}
+
+fn pull_batch_reports_failures() {
+    let adb = fake_adb("pixel7.txt");
+    let dir = scratch_dir("pull");
+
+    let results = media_service::pull_media_files_batch(
+        &adb,
+        SERIAL,
+        &["/sdcard/missing.jpg".to_string()],
+        &dir,
+        ConflictPolicy::Overwrite,
+        &|_| None,
+        &|_| {},
+    )
+    .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(!results[0].success);
+    assert!(results[0].error.is_some());
+    let _ = std::fs::remove_dir_all(&dir);
+}
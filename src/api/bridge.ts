@@ -19,6 +19,30 @@ export interface Device {
   android_version: string | null;
 }
 
+/**
+ * Event emitted with a Device payload, properties filled in, when a device
+ * becomes ready (connected, authorized or back online)
+ */
+export const DEVICE_READY_EVENT = "device-ready";
+
+/** Payload of the device hot-plug events, from adb track-devices */
+export interface DeviceChange {
+  serial: string;
+  /** null once disconnected */
+  state: DeviceState | null;
+  /** null when just connected */
+  previous_state: DeviceState | null;
+}
+
+/** Event emitted with a DeviceChange payload when a device shows up */
+export const DEVICE_CONNECTED_EVENT = "device-connected";
+
+/** Event emitted with a DeviceChange payload when a device goes away */
+export const DEVICE_DISCONNECTED_EVENT = "device-disconnected";
+
+/** Event emitted with a DeviceChange payload when a listed device changes state */
+export const DEVICE_STATE_CHANGED_EVENT = "device-state-changed";
+
 export interface Settings {
   adb_path: string | null;
   adb_resolved_path: string | null;
@@ -30,15 +54,243 @@ export interface Settings {
   ffmpeg_path: string | null;
   ffmpeg_resolved_path: string | null;
   ffmpeg_available: boolean;
+  ffmpeg_version: string | null;
+  ffprobe_path: string | null;
+  ffprobe_resolved_path: string | null;
+  ffprobe_available: boolean;
+  camera_defaults: Record<string, CameraOptions>;
+  /** Screen mirror options used when a session starts without any */
+  mirror_options: MirrorOptions;
+  /** Convert MKV recordings to MP4 with ffmpeg when a session stops */
+  remux_recordings: boolean;
+  /** Warn about or stop sessions on devices running low on battery */
+  battery_policy: BatteryPolicy;
+  /** Post a notification on the device when a mirror session starts controlling it */
+  notify_on_mirror: boolean;
+  /** Local folder of APKs checked for newer versions of installed apps */
+  apk_repository_dir: string | null;
+  /** Packages disabled through the bloatware manager, per device serial */
+  disabled_packages: Record<string, string[]>;
+  /** Language code for backend-generated messages ("en", "id") */
+  language: string;
+  /** Timeout for adb shell/query commands (seconds) */
+  command_timeout_secs: number;
+  /** Timeout for a single adb push/pull (seconds) */
+  transfer_timeout_secs: number;
+  /** Attempts for idempotent adb operations that fail transiently */
+  retry_attempts: number;
+  /** Delay before the first retry (ms), doubled per retry */
+  retry_initial_delay_ms: number;
+  /** Scheduled sync/backup jobs */
+  sync_jobs: SyncJob[];
+  /** Named quick-send destinations */
+  transfer_presets: TransferPreset[];
+  /** Remembered per-device state, keyed by serial */
+  device_registry: Record<string, DeviceRecord>;
+  /** Named sets of devices for group operations */
+  device_groups: DeviceGroup[];
+  /** Setup profiles for new devices */
+  provisioning_profiles: ProvisioningProfile[];
+  /** Guest mode: pushes, deletes, installs and input fail with a ReadOnlyMode error */
+  read_only_mode: boolean;
+  /**
+   * Folders below a storage volume (e.g. "Pictures/Banking") left out of
+   * listings and pulls unless includePrivate is passed
+   */
+  private_folders: string[];
+  /** Thumbnails made in the background for a folder's first items when it's listed (0 = off) */
+  thumbnail_warmup_count: number;
+  /** Local folders pulled into lately, most recent first */
+  recent_destinations: string[];
+  /** Local folders pinned as pull destinations */
+  pinned_destinations: string[];
+  /** Pulls without a destination go to Downloads/GesuBridge/<device> instead of Downloads */
+  device_pull_folders: boolean;
+  /** First-run setup steps whose check passed */
+  onboarding_completed: OnboardingStep[];
+  /** Release channel updates are checked on */
+  update_channel: UpdateChannel;
+  /** A pending update put off or set to install at a later time */
+  update_plan: UpdatePlan | null;
+}
+
+/** Release channel the updater follows; beta includes prereleases */
+export type UpdateChannel = "stable" | "beta";
+
+/** What to do with one pending update version */
+export type UpdatePlanKind = "deferred" | "scheduled";
+
+/** A choice made about one pending update */
+export interface UpdatePlan {
+  version: string;
+  kind: UpdatePlanKind;
+  /** RFC 3339 */
+  at: string;
+}
+
+/** An update available on the chosen channel */
+export interface PendingUpdate {
+  version: string;
+  current_version: string;
+  channel: UpdateChannel;
+  /** Release notes, as written in the release (usually Markdown) */
+  notes: string | null;
+  /** The plan for this version, if one was made */
+  plan: UpdatePlan | null;
+  /** Put off until the plan's time, so not worth a notice yet */
+  deferred: boolean;
+}
+
+/** A step of first-run setup, in wizard order */
+export type OnboardingStep = "tools_configured" | "device_authorized" | "first_transfer";
+
+/** Where one setup step stands */
+export interface OnboardingStepStatus {
+  step: OnboardingStep;
+  /** Marked done through completeOnboardingStep */
+  completed: boolean;
+  /** Done, or its check passes now so it can be completed */
+  ready: boolean;
+}
+
+/** First-run setup progress */
+export interface OnboardingState {
+  /** Every step, in wizard order */
+  steps: OnboardingStepStatus[];
+  /** First step not completed yet */
+  next_step: OnboardingStep | null;
+  finished: boolean;
+}
+
+/** A fix-it action on the troubleshooting screen */
+export type TroubleshootAction =
+  | "restart_adb_server"
+  | "redetect_tools"
+  | "test_device_connection"
+  | "open_platform_tools_download";
+
+/** What a troubleshooting action found */
+export interface TroubleshootResult {
+  action: TroubleshootAction;
+  success: boolean;
+  /** One-line outcome, or the error message when it failed */
+  message: string;
+  /** Extra lines, e.g. each tool's detected path */
+  details: string[];
+  /** AppError kind when it failed, for the localized guidance */
+  error_kind: string | null;
+  duration_ms: number;
+  /** Action worth trying next when this one failed */
+  suggested_next: TroubleshootAction | null;
+}
+
+/** A quick choice of local folder for pull dialogs */
+export interface LocalDestination {
+  path: string;
+  pinned: boolean;
+  /** False when the folder was removed since it was used */
+  exists: boolean;
+}
+
+/** What the app remembers about a device between sessions */
+export interface DeviceRecord {
+  /** Last device folder (below /sdcard) pushed to */
+  last_device_dir: string | null;
+  /** Last local folder pulled into */
+  last_local_dest: string | null;
+  /** ip:port the device was last connected through, reconnected on startup */
+  wireless_endpoint: string | null;
+  /** Name the user gave the device, used for its Downloads subfolder */
+  nickname: string | null;
+}
+
+/** Saved destination and options for recurring pushes */
+export interface TransferPreset {
+  /** Empty when creating a preset; assigned on save */
+  id: string;
+  name: string;
+  /** Folder below /sdcard, e.g. "Music" or "Download/ROMs" */
+  dest_dir: string;
+  conflict: ConflictPolicy;
+  archive: boolean;
+}
+
+/** Modifier key for scrcpy shortcuts */
+export type ShortcutMod = 'lctrl' | 'rctrl' | 'lalt' | 'ralt' | 'lsuper' | 'rsuper';
+
+export interface MirrorOptions {
+  /** Keys that trigger scrcpy shortcuts; empty = scrcpy default (left Alt or left Super) */
+  shortcut_mod: ShortcutMod[];
+  /** Show touches on the device; the previous setting is restored on stop */
+  show_touches: boolean;
+  /** Keep the device awake while plugged in during the session */
+  stay_awake: boolean;
+  /** Screen timeout during the session (seconds), restored on stop; null = unchanged */
+  screen_timeout_secs: number | null;
+  /** Mirror only this region of the screen; null = full screen */
+  crop: CropRegion | null;
+  /** Record the session to the recordings folder */
+  record: boolean;
+  /** Video bitrate in Mbit/s; null = scrcpy default (8) */
+  bit_rate_mbps: number | null;
+  /** Lower the bitrate when the Wi-Fi preflight finds a weak link */
+  adapt_to_link: boolean;
+  /** Limit the longer side of the video to this many pixels; null = full size */
+  max_size: number | null;
+  /** Restart at a lower bitrate or resolution when frames are being skipped */
+  auto_tune: boolean;
+  /** Turn the device off when the mirror window is closed */
+  power_off_on_close: boolean;
+  /** Mirror window title; null = device model */
+  window_title: string | null;
+  /** Start the mirror window fullscreen */
+  fullscreen: boolean;
+  /** View only: no keyboard, mouse or clipboard input reaches the device */
+  no_control: boolean;
+}
+
+/** Wi-Fi preflight result for a wireless device */
+export interface LinkQuality {
+  /** Fastest adb round trip */
+  latency_ms: number;
+  /** Measured device-to-PC throughput in kilobits per second */
+  throughput_kbps: number;
+  /** Too slow or laggy for mirroring at the default bitrate */
+  weak: boolean;
+  /** Video bitrate the link can sustain (Mbit/s) */
+  recommended_bit_rate_mbps: number;
+}
+
+/** Screen region in device pixels, relative to the natural (portrait) orientation */
+export interface CropRegion {
+  width: number;
+  height: number;
+  x: number;
+  y: number;
+}
+
+export interface DisplaySize {
+  width: number;
+  height: number;
 }
 
 export interface MirrorSession {
   device_serial: string;
   process_id: number;
   started_at: string;
+  screen_off: boolean;
+  camera_options: CameraOptions | null;
+  mirror_options: MirrorOptions | null;
+  recording_path: string | null;
+  /** Wi-Fi preflight result (screen mirror of a wireless device only) */
+  link_quality: LinkQuality | null;
 }
 
-export type TransferStatus = 'queued' | 'transferring' | 'complete' | 'failed' | 'cancelled';
+/** handed_off: passed to the Bluetooth wizard or Quick Share, which don't report the outcome */
+export type TransferStatus = 'queued' | 'transferring' | 'complete' | 'failed' | 'cancelled' | 'interrupted' | 'skipped' | 'handed_off';
+
+/** What to do when a transfer destination already exists (default: overwrite) */
+export type ConflictPolicy = 'overwrite' | 'skip' | 'rename' | 'keep_newer';
 
 export interface TransferItem {
   id: string;
@@ -50,11 +302,633 @@ export interface TransferItem {
   status: TransferStatus;
   error: string | null;
   started_at: string;
+  device_serial: string;
+}
+
+/** Whether Google's Quick Share for Windows is installed */
+export interface QuickShareStatus {
+  installed: boolean;
+  /** Path of the Quick Share executable */
+  path: string | null;
+}
+
+export type SyncDirection = 'to_device' | 'from_device' | 'two_way';
+
+export type SyncActionKind = 'push' | 'pull';
+
+export interface SyncAction {
+  /** Path relative to both roots, using `/` separators */
+  relative_path: string;
+  kind: SyncActionKind;
+  reason: string;
+  size_bytes: number;
+  /** Relative path written instead of relative_path (rename policy) */
+  target_path: string | null;
+}
+
+export interface SyncPlan {
+  local_root: string;
+  remote_root: string;
+  direction: SyncDirection;
+  actions: SyncAction[];
+  /** Files already identical on both sides */
+  unchanged: number;
+  /** Differing files left alone because of the conflict policy */
+  skipped_conflicts: number;
+  total_bytes: number;
+}
+
+export interface SyncReport {
+  plan: SyncPlan;
+  transferred: number;
+  failures: AffectedItem[];
+}
+
+/** Sync profile run by the in-app scheduler when its device is connected */
+export interface SyncJob {
+  /** Empty when creating a job; assigned on save */
+  id: string;
+  name: string;
+  device_serial: string;
+  local_root: string;
+  remote_root: string;
+  direction: SyncDirection;
+  compare_hash: boolean;
+  conflict: ConflictPolicy;
+  interval_minutes: number;
+  enabled: boolean;
+  last_run_at: string | null;
+}
+
+export interface SyncJobRun {
+  job_id: string;
+  job_name: string;
+  started_at: string;
+  finished_at: string;
+  success: boolean;
+  transferred: number;
+  failed: number;
+  error: string | null;
+}
+
+/** Event emitted with a SyncJobRun payload when a scheduled run fails */
+export const SYNC_JOB_FAILED_EVENT = "sync-job-failed";
+
+export type BatchKind = 'push' | 'pull' | 'provision';
+
+/** Combined progress of a multi-file push or pull */
+export interface BatchProgress {
+  batch_id: string;
+  kind: BatchKind;
+  total_files: number;
+  completed_files: number;
+  total_bytes: number;
+  /** Bytes of finished files, including failed and skipped ones */
+  completed_bytes: number;
+  /** File being transferred now; null once finished */
+  current_file: string | null;
+  failures: AffectedItem[];
+  finished: boolean;
+}
+
+/** Event emitted with a BatchProgress payload during pushFiles/pullMediaFiles */
+export const BATCH_PROGRESS_EVENT = "batch-progress";
+
+/** Event emitted with a device path once its thumbnail is cached by the warm-up */
+export const THUMBNAIL_WARMED_EVENT = "thumbnail-warmed";
+
+/** How clipboard text reached the device */
+export type ClipboardSendMode = 'opened_url' | 'shared_text';
+
+export type DropFileKind = 'image' | 'video' | 'audio' | 'apk' | 'split_apk' | 'obb' | 'other';
+
+export type DropAction = 'push' | 'install' | 'copy_obb';
+
+export interface DropFile {
+  path: string;
+  size_bytes: number;
+  kind: DropFileKind;
+}
+
+/** Summary of a drag-and-drop payload with the suggested action */
+export interface DropAnalysis {
+  files: DropFile[];
+  total_files: number;
+  total_bytes: number;
+  media_files: number;
+  apk_files: number;
+  obb_files: number;
+  other_files: number;
+  suggested_action: DropAction;
+  /** Device folder below /sdcard that fits the content, if obvious */
+  suggested_dest: string | null;
+  /** Dropped paths that no longer exist */
+  missing: string[];
+  /** Folder expansion stopped at the file limit */
+  truncated: boolean;
+}
+
+export interface InstallResult {
+  serial: string;
+  path: string;
+  success: boolean;
+  /** "Success" or the failure reason, e.g. INSTALL_FAILED_VERSION_DOWNGRADE */
+  message: string;
+}
+
+/** Combined result of routing a drop to one or more devices */
+export interface DropRouteReport {
+  serials: string[];
+  installs: InstallResult[];
+  transfers: TransferItem[];
+  succeeded: number;
+  failed: number;
+}
+
+/** Named set of devices that commands can target together */
+export interface DeviceGroup {
+  /** Empty when creating; assigned on save */
+  id: string;
+  name: string;
+  serials: string[];
+}
+
+/** Result of a group operation on one device */
+export interface DeviceOpResult {
+  serial: string;
+  success: boolean;
+  /** Command output or a short summary (screenshots: the saved file path) */
+  output: string | null;
+  error: string | null;
+}
+
+/** Consolidated per-device results of a group operation */
+export interface GroupOpReport {
+  group_id: string;
+  results: DeviceOpResult[];
+  succeeded: number;
+  failed: number;
+}
+
+export type SettingsNamespace = 'system' | 'secure' | 'global';
+
+/** A value in the Android `settings` store */
+export interface DeviceSetting {
+  namespace: SettingsNamespace;
+  key: string;
+  value: string;
+}
+
+/** A local file pushed during provisioning */
+export interface ProvisioningFile {
+  local_path: string;
+  /** Device folder, e.g. /sdcard/Documents */
+  device_dir: string;
+}
+
+/** Setup steps for a new device: installs, then pushes, then settings */
+export interface ProvisioningProfile {
+  /** Empty when creating; assigned on save */
+  id: string;
+  name: string;
+  apks: string[];
+  files: ProvisioningFile[];
+  settings: DeviceSetting[];
+}
+
+export type ProvisioningStepKind = 'install' | 'push' | 'setting';
+
+export interface ProvisioningStepResult {
+  kind: ProvisioningStepKind;
+  /** APK or file path, or namespace/key for settings */
+  target: string;
+  success: boolean;
+  message: string;
+}
+
+export interface ProvisioningReport {
+  serial: string;
+  profile_id: string;
+  profile_name: string;
+  steps: ProvisioningStepResult[];
+  succeeded: number;
+  failed: number;
+}
+
+export type ComparisonCategory = 'property' | 'storage' | 'package';
+
+/** One compared value, in the same order as DeviceComparison.serials */
+export interface ComparisonRow {
+  category: ComparisonCategory;
+  /** Property name, "free_data_bytes", or package name */
+  key: string;
+  /** null when missing on that device (e.g. package not installed) */
+  values: (string | null)[];
+  differs: boolean;
+}
+
+export interface DeviceComparison {
+  serials: string[];
+  /** Per-device error when some information couldn't be read */
+  errors: (string | null)[];
+  rows: ComparisonRow[];
+}
+
+export type RecordingKind = 'screen' | 'camera' | 'audio';
+
+/** A recording in the recordings folder */
+export interface Recording {
+  path: string;
+  file_name: string;
+  kind: RecordingKind;
+  /** Device serial as written in the file name (':' and '.' become '_') */
+  device_serial: string;
+  size_bytes: number;
+  /** null when ffprobe isn't available */
+  duration_ms: number | null;
+  created_at: string | null;
+}
+
+/** Battery state read from the device */
+export interface BatteryStatus {
+  /** Charge in percent */
+  level: number;
+  /** Plugged in, or charging/full according to the device */
+  charging: boolean;
+}
+
+/** Battery, shared storage and memory of a device; unreadable parts are null with the reason in errors */
+export interface DeviceHealth {
+  device_serial: string;
+  battery: BatteryStatus | null;
+  /** Size of the shared storage (/sdcard) */
+  storage_total_bytes: number | null;
+  storage_free_bytes: number | null;
+  ram_total_bytes: number | null;
+  /** Memory in use, i.e. not available to new apps */
+  ram_used_bytes: number | null;
+  errors: string[];
+}
+
+/** An iPhone or iPad plugged in over USB; iOS isn't supported */
+export interface AppleDevice {
+  /** Name the system gives the device, e.g. "Apple iPhone" */
+  name: string;
+  /** USB vendor:product ID in hex */
+  usb_id: string;
+  /** Message catalog key of the explanation to show */
+  notice_key: string;
+}
+
+/** What a device restarts into: a normal restart or the recovery menu */
+export type RebootMode = 'system' | 'recovery';
+
+export type LowBatteryAction = 'warn' | 'stop';
+
+/** Guard for unattended sessions against draining the device battery */
+export interface BatteryPolicy {
+  enabled: boolean;
+  /** Act when the level drops below this percentage while not charging */
+  min_level: number;
+  action: LowBatteryAction;
+}
+
+/** Payload of the low battery event */
+export interface LowBatteryAlert {
+  device_serial: string;
+  level: number;
+  /** Whether the device's sessions were stopped */
+  stopped: boolean;
+}
+
+/** Event emitted with a LowBatteryAlert payload when a device with active sessions runs low */
+export const LOW_BATTERY_EVENT = "low-battery";
+
+/** Payload of the clock drift event */
+export interface ClockDrift {
+  device_serial: string;
+  /** Seconds the device's clock is ahead of this computer's; negative when behind */
+  drift_secs: number;
+}
+
+/**
+ * Event emitted with a ClockDrift payload when a device that just became
+ * ready has a clock more than two minutes off this computer's
+ */
+export const CLOCK_DRIFT_EVENT = "clock-drift";
+
+/** Payload of the settings error event: a state file didn't parse */
+export interface StateFileRecovery {
+  file: string;
+  /** Why it didn't parse */
+  error: string;
+  /** The last good version was put back; otherwise defaults are used */
+  restored_from_backup: boolean;
+  /** Where the unreadable file was moved, for fixing by hand */
+  corrupt_copy: string;
+}
+
+/**
+ * Event emitted with a StateFileRecovery payload when the settings, device
+ * timeline or pull history file was corrupt and had to be replaced
+ */
+export const SETTINGS_ERROR_EVENT = "settings-error";
+
+/**
+ * Event emitted with the re-resolved Settings when settings.json was edited
+ * outside the app, e.g. by hand
+ */
+export const SETTINGS_CHANGED_EVENT = "settings://changed";
+
+/** An installed third-party app in an exported app list */
+export interface AppListEntry {
+  package: string;
+  version_code: string | null;
+  /** Package that installed the app (e.g. com.android.vending), if known */
+  installer: string | null;
+}
+
+/** Installed apps of a device, exported for reinstalling elsewhere */
+export interface AppList {
+  device_serial: string;
+  exported_at: string;
+  apps: AppListEntry[];
+}
+
+/** Result of reinstalling an app list from a folder of APKs */
+export interface BulkInstallReport {
+  installed: InstallResult[];
+  /** Packages with no matching APK in the folder */
+  missing: string[];
+}
+
+/** Package, version and SDK levels read from a local APK's manifest */
+export interface ApkInfo {
+  package: string;
+  version_code: number;
+  version_name: string | null;
+  min_sdk: number | null;
+  target_sdk: number | null;
+}
+
+/** What the install UI shows about a local APK before installing it */
+export interface ApkPreview {
+  path: string;
+  info: ApkInfo;
+  /** Launcher icon as a data URL, when the APK has a bitmap one */
+  icon: string | null;
+}
+
+/** A disable-safe preinstalled package found on a device */
+export interface BloatwarePackage {
+  package: string;
+  label: string;
+  disabled: boolean;
+}
+
+/** Outcome of enabling or disabling one package */
+export interface PackageOpResult {
+  package: string;
+  success: boolean;
+  /** Output of pm, or the failure reason */
+  message: string;
+}
+
+/** A process running on a device */
+export interface DeviceProcess {
+  pid: number;
+  /** Owning user, e.g. "root", "shell" or "u0_a163" for an app */
+  user: string;
+  /** Resident memory in KiB */
+  rss_kb: number;
+  name: string;
+}
+
+/** Connection state of a wireless (ip:port) device */
+export type WirelessLinkState = "connected" | "reconnecting" | "lost";
+
+/** Status of a wireless device watched by the connection keeper */
+export interface WirelessStatus {
+  /** ip:port the device is connected through, which is also its serial */
+  endpoint: string;
+  /** "reconnecting" while `adb connect` is retried with backoff, "lost" once retries ran out */
+  state: WirelessLinkState;
+  /** Reconnect attempts since the device dropped off */
+  attempts: number;
+  /** Seconds until the next reconnect attempt, while reconnecting */
+  next_retry_secs: number | null;
+}
+
+/** Event emitted with a WirelessStatus payload when a wireless device drops off, reconnects or a retry fails */
+export const WIRELESS_STATUS_EVENT = "wireless-status";
+
+/** Something that happened with a device, for its timeline */
+export type DeviceEventKind =
+  | "connected"
+  | "disconnected"
+  | "mirror_started"
+  | "mirror_stopped"
+  | "files_pushed"
+  | "files_pulled"
+  | "synced";
+
+/** An entry of a device's timeline */
+export interface DeviceEvent {
+  kind: DeviceEventKind;
+  /** RFC 3339 time the event happened */
+  at: string;
+  /** Short summary, e.g. how many files were copied where */
+  detail: string | null;
+}
+
+/** How a device's network relates to this computer's, for wireless ADB */
+export type NetworkMatch = "same_network" | "different_network" | "device_off_wifi";
+
+/** A device switched to TCP mode with enableTcpip */
+export interface TcpipMode {
+  device_serial: string;
+  port: number;
+  /** ip:port to connect to, when the device was on Wi-Fi */
+  endpoint: string | null;
+}
+
+/** Result of checking whether wireless ADB can work between this computer and a device */
+export interface WirelessNetworkCheck {
+  device_serial: string;
+  /** Device address on the Wi-Fi network it joined, with prefix ("192.168.1.23/24") */
+  device_address: string | null;
+  /** Device address on its own hotspot while the hotspot is on */
+  hotspot_address: string | null;
+  /** Address this computer would reach the device from */
+  host_address: string | null;
+  network: NetworkMatch;
+  /** The computer has joined the device's hotspot, so it can connect over it */
+  host_on_hotspot: boolean;
+}
+
+/** Outcome of turning a device's hotspot on */
+export interface HotspotStart {
+  started: boolean;
+  /** The device refused, so its hotspot settings were opened for the user instead */
+  settings_opened: boolean;
+  message: string;
+}
+
+/** Network traffic of one app (Linux uid) since the device's stats began */
+export interface NetworkUsage {
+  uid: number;
+  /** Packages sharing the uid; empty for system uids and removed apps */
+  packages: string[];
+  /** Package name(s), or a name for system uids like "system" or "Removed apps" */
+  label: string;
+  rx_bytes: number;
+  tx_bytes: number;
+  /** Part of the traffic that went over mobile data */
+  mobile_rx_bytes: number;
+  mobile_tx_bytes: number;
+}
+
+/** One reading of the live performance stream */
+export interface PerfSample {
+  device_serial: string;
+  /** App being profiled; null samples the whole device */
+  package: string | null;
+  /** Unix time in milliseconds */
+  timestamp_ms: number;
+  /** CPU use as a share of all cores, of the app's process or the whole device */
+  cpu_percent: number | null;
+  /** Resident memory of the app, or memory in use on the device, in KiB */
+  mem_kb: number | null;
+  /** Battery current in mA; the sign for charging vs discharging varies by vendor */
+  battery_current_ma: number | null;
+  /** Frames composited per second by SurfaceFlinger */
+  fps: number | null;
+}
+
+/** Event emitted with a PerfSample payload every second while a perf stream runs */
+export const PERF_SAMPLE_EVENT = "perf-sample";
+
+/** Event emitted with the device serial when a perf stream ends because the device went away */
+export const PERF_STOPPED_EVENT = "perf-stopped";
+
+/** Invocations of one external tool (adb, ffmpeg, ...) since the app started */
+export interface ToolMetrics {
+  tool: string;
+  calls: number;
+  /** Calls that couldn't start, exited with an error or timed out */
+  failures: number;
+  timeouts: number;
+  /** Share of calls that failed, 0 to 1 */
+  failure_rate: number;
+  total_ms: number;
+  average_ms: number;
+  max_ms: number;
+}
+
+/** Where a watched value is read from; "prop" is a system property (getprop) */
+export type WatchNamespace = SettingsNamespace | "prop";
+
+/** A watch started by watchSetting, with the value it started from */
+export interface SettingWatch {
+  watch_id: string;
+  /** null when unset */
+  value: string | null;
+}
+
+/** A watched setting or property that changed value */
+export interface SettingChange {
+  watch_id: string;
+  device_serial: string;
+  namespace: WatchNamespace;
+  key: string;
+  /** null when the value was unset */
+  old_value: string | null;
+  new_value: string | null;
+  /** Unix time in milliseconds */
+  timestamp_ms: number;
+}
+
+/** Event emitted with a SettingChange payload when a watched value changes */
+export const SETTING_CHANGED_EVENT = "setting-changed";
+
+/** Event emitted with the watch id when a watch ends because the device went away */
+export const SETTING_WATCH_STOPPED_EVENT = "setting-watch-stopped";
+
+export type DefaultAppRole = "browser" | "sms" | "dialer" | "home" | "assistant";
+
+/** The app currently holding a default-app role */
+export interface DefaultApp {
+  role: DefaultAppRole;
+  /** null when no app is set and the device asks each time */
+  holder: string | null;
+}
+
+/** Verification state of one web domain an app declares links for */
+export interface AppLinkDomain {
+  domain: string;
+  /** "verified", "none", "approved", ... or a numeric error code */
+  state: string;
+}
+
+/** How an app opens web links (Android 12+ domain verification) */
+export interface AppLinks {
+  package: string;
+  domains: AppLinkDomain[];
+}
+
+/** An installed app with a newer APK in the local APK repository */
+export interface ApkUpdate {
+  package: string;
+  installed_version_code: number;
+  available_version_code: number;
+  available_version_name: string | null;
+  apk_path: string;
+}
+
+/** Commands that only run with a token from requestConfirmation */
+export type DestructiveAction =
+  | "delete_media_files"
+  | "disable_bloatware"
+  | "delete_recording"
+  | "reboot_device"
+  | "shell_on_group";
+
+/** One-time permission to run a destructive command */
+export interface ConfirmationToken {
+  token: string;
+  /** Command the token is good for */
+  action: DestructiveAction;
+  expires_in_secs: number;
 }
 
 export interface AppError {
   type: string;
+  /** English detail text */
   message: string;
+  /** Message catalog key of the error text, e.g. "error.Timeout" */
+  key: string;
+  /** Values for the `{name}` placeholders of the catalog text */
+  params: Record<string, string>;
+  /** Message catalog key of the guidance for this kind of error */
+  guidance_key: string;
+}
+
+/**
+ * Get a one-time token for a destructive command, after the user confirmed
+ * it. Valid for one call with exactly the same args within expires_in_secs;
+ * an invalid or missing token fails with a ConfirmationRequired error.
+ * @param args The command's arguments in order:
+ *   delete_media_files: [serial, ...paths]
+ *   disable_bloatware: [serial, ...packages]
+ *   delete_recording: [path]
+ *   reboot_device: [serial, mode]
+ *   shell_on_group: [groupId, ...args]
+ */
+export async function requestConfirmation(
+  action: DestructiveAction,
+  args: string[]
+): Promise<ConfirmationToken> {
+  return invoke<ConfirmationToken>("request_confirmation", { action, args });
 }
 
 /**
@@ -90,6 +964,36 @@ export function parseError(err: unknown): string {
   return String(err);
 }
 
+/**
+ * Render a catalog entry, filling in its `{name}` placeholders
+ * (returns the key when the catalog doesn't have it)
+ */
+export function formatMessage(
+  catalog: Record<string, string>,
+  key: string,
+  params: Record<string, string> = {}
+): string {
+  let text = catalog[key] ?? key;
+  for (const [name, value] of Object.entries(params)) {
+    text = text.split(`{${name}}`).join(value);
+  }
+  return text;
+}
+
+/**
+ * Error text in the catalog's language; falls back to parseError for
+ * errors that didn't come from the backend
+ */
+export function localizeError(err: unknown, catalog: Record<string, string>): string {
+  if (typeof err === "object" && err !== null) {
+    const errorObj = err as Partial<AppError>;
+    if (typeof errorObj.key === "string" && errorObj.key in catalog) {
+      return formatMessage(catalog, errorObj.key, errorObj.params ?? {});
+    }
+  }
+  return parseError(err);
+}
+
 // ============================================
 // Bridge Commands
 // ============================================
@@ -155,29 +1059,318 @@ export async function detectFfmpeg(): Promise<string | null> {
 }
 
 /**
- * List all connected Android devices
+ * Set a custom ffprobe path (or null to use auto-detect)
  */
-export async function listDevices(): Promise<Device[]> {
-  return invoke<Device[]>("list_devices");
+export async function setFfprobePath(path: string | null): Promise<Settings> {
+  return invoke<Settings>("set_ffprobe_path", { path });
 }
 
-// ============================================
-// Mirror Commands
-// ============================================
-
 /**
- * Start a mirror session for a device
- * @param screenOff - Turn off device screen while mirroring
+ * Trigger auto-detection of ffprobe path (checks next to FFmpeg first)
+ * @returns Detected path or null if not found
  */
-export async function startMirror(serial: string, screenOff: boolean = false): Promise<MirrorSession> {
-  return invoke<MirrorSession>("start_mirror", { serial, screenOff });
+export async function detectFfprobe(): Promise<string | null> {
+  return invoke<string | null>("detect_ffprobe");
 }
 
 /**
- * Stop a mirror session for a device
+ * Set the language for backend-generated messages
  */
-export async function stopMirror(serial: string): Promise<void> {
-  return invoke<void>("stop_mirror", { serial });
+export async function setLanguage(language: string): Promise<Settings> {
+  return invoke<Settings>("set_language", { language });
+}
+
+/**
+ * Get the message catalog for a language (defaults to the configured language)
+ * Keys: `error.<type>`, `guidance.<type>` (type = AppError.type) and `message.*`
+ */
+export async function getMessageCatalog(language?: string): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("get_message_catalog", { language });
+}
+
+/**
+ * Set timeouts for adb commands and transfers (seconds)
+ */
+export async function setTimeouts(
+  commandTimeoutSecs: number,
+  transferTimeoutSecs: number
+): Promise<Settings> {
+  return invoke<Settings>("set_timeouts", { commandTimeoutSecs, transferTimeoutSecs });
+}
+
+/**
+ * Set the default screen mirror options
+ */
+export async function setMirrorOptions(options: MirrorOptions): Promise<Settings> {
+  return invoke<Settings>("set_mirror_options", { options });
+}
+
+/**
+ * Turn automatic MKV -> MP4 conversion of finished recordings on or off
+ */
+export async function setRemuxRecordings(enabled: boolean): Promise<Settings> {
+  return invoke<Settings>("set_remux_recordings", { enabled });
+}
+
+/**
+ * Set how many thumbnails listDeviceMedia warms in the background (0 = off,
+ * at most 200); each one made emits THUMBNAIL_WARMED_EVENT
+ */
+export async function setThumbnailWarmup(count: number): Promise<Settings> {
+  return invoke<Settings>("set_thumbnail_warmup", { count });
+}
+
+/**
+ * Turn the on-device notification shown when mirroring starts on or off
+ */
+export async function setNotifyOnMirror(enabled: boolean): Promise<Settings> {
+  return invoke<Settings>("set_notify_on_mirror", { enabled });
+}
+
+/**
+ * Turn read-only (guest) mode on or off; while on, anything that would change
+ * a device fails with a ReadOnlyMode error and mirrors are view only
+ */
+export async function setReadOnlyMode(enabled: boolean): Promise<Settings> {
+  return invoke<Settings>("set_read_only_mode", { enabled });
+}
+
+/**
+ * Set the private folders; "/sdcard/Pictures/Banking" and
+ * "Pictures/Banking" are stored the same and cover every volume
+ */
+export async function setPrivateFolders(folders: string[]): Promise<Settings> {
+  return invoke<Settings>("set_private_folders", { folders });
+}
+
+/**
+ * Name a device; it names the device's Downloads/GesuBridge folder.
+ * null or "" goes back to the model name.
+ */
+export async function setDeviceNickname(serial: string, nickname: string | null): Promise<Settings> {
+  return invoke<Settings>("set_device_nickname", { serial, nickname });
+}
+
+/** The update available on the chosen channel with its release notes, or null when up to date */
+export async function getPendingUpdate(): Promise<PendingUpdate | null> {
+  return invoke<PendingUpdate | null>("get_pending_update");
+}
+
+/** Download and install the pending update now, then restart the app */
+export async function installUpdate(): Promise<void> {
+  return invoke<void>("install_update");
+}
+
+/** Follow the stable or beta release channel; drops any deferred or scheduled install */
+export async function setUpdateChannel(channel: UpdateChannel): Promise<Settings> {
+  return invoke<Settings>("set_update_channel", { channel });
+}
+
+/** Don't offer this update version again before `until` (RFC 3339) */
+export async function deferUpdate(version: string, until: string): Promise<Settings> {
+  return invoke<Settings>("defer_update", { version, until });
+}
+
+/** Install this update version at `at` (RFC 3339), if the app is open then and no transfer is running */
+export async function scheduleUpdateInstall(version: string, at: string): Promise<Settings> {
+  return invoke<Settings>("schedule_update_install", { version, at });
+}
+
+/** Forget a deferred or scheduled install */
+export async function clearUpdatePlan(): Promise<Settings> {
+  return invoke<Settings>("clear_update_plan");
+}
+
+/** Turn the per-device Downloads subfolder for pulls on or off */
+export async function setDevicePullFolders(enabled: boolean): Promise<Settings> {
+  return invoke<Settings>("set_device_pull_folders", { enabled });
+}
+
+/** Setup progress: which steps are completed and which pass their check now */
+export async function getOnboardingState(): Promise<OnboardingState> {
+  return invoke<OnboardingState>("get_onboarding_state");
+}
+
+/**
+ * Complete a setup step. Rejects with the reason (e.g. no authorized device
+ * yet) when its check doesn't pass.
+ */
+export async function completeOnboardingStep(step: OnboardingStep): Promise<OnboardingState> {
+  return invoke<OnboardingState>("complete_onboarding_step", { step });
+}
+
+/** Stop the adb server and start a fresh one; cuts off running transfers */
+export async function restartAdbServer(): Promise<TroubleshootResult> {
+  return invoke<TroubleshootResult>("restart_adb_server");
+}
+
+/** Look for adb, scrcpy, ffmpeg and ffprobe again and report the paths */
+export async function redetectTools(): Promise<TroubleshootResult> {
+  return invoke<TroubleshootResult>("redetect_tools");
+}
+
+/** Check a device answers an adb shell echo round trip */
+export async function testDeviceConnection(serial: string): Promise<TroubleshootResult> {
+  return invoke<TroubleshootResult>("test_device_connection", { serial });
+}
+
+/** Open the Android SDK Platform Tools download page in the browser */
+export async function openPlatformToolsDownload(): Promise<TroubleshootResult> {
+  return invoke<TroubleshootResult>("open_platform_tools_download");
+}
+
+/** Pinned folders, then the ones most recently pulled into */
+export async function getRecentDestinations(): Promise<LocalDestination[]> {
+  return invoke<LocalDestination[]>("get_recent_destinations");
+}
+
+/** Pin a local folder as a pull destination; returns the updated choices */
+export async function pinDestination(path: string): Promise<LocalDestination[]> {
+  return invoke<LocalDestination[]>("pin_destination", { path });
+}
+
+/** Unpin a local folder; returns the updated choices */
+export async function unpinDestination(path: string): Promise<LocalDestination[]> {
+  return invoke<LocalDestination[]>("unpin_destination", { path });
+}
+
+/**
+ * Set the local APK repository folder checked for app updates
+ */
+export async function setApkRepositoryDir(path: string | null): Promise<Settings> {
+  return invoke<Settings>("set_apk_repository_dir", { path });
+}
+
+/**
+ * Set what happens to sessions when a device runs low on battery
+ */
+export async function setBatteryPolicy(policy: BatteryPolicy): Promise<Settings> {
+  return invoke<Settings>("set_battery_policy", { policy });
+}
+
+/**
+ * Set the retry policy for transient adb failures
+ */
+export async function setRetryPolicy(attempts: number, initialDelayMs: number): Promise<Settings> {
+  return invoke<Settings>("set_retry_policy", { attempts, initialDelayMs });
+}
+
+/**
+ * List all connected Android devices
+ */
+export async function listDevices(): Promise<Device[]> {
+  return invoke<Device[]>("list_devices");
+}
+
+/**
+ * iPhones and iPads plugged in, so the UI can explain they aren't supported
+ * instead of showing nothing
+ */
+export async function listAppleDevices(): Promise<AppleDevice[]> {
+  return invoke<AppleDevice[]>("list_apple_devices");
+}
+
+/**
+ * Compare OS versions, key properties, free storage and installed package
+ * versions across two or more devices
+ */
+export async function compareDevices(serials: string[]): Promise<DeviceComparison> {
+  return invoke<DeviceComparison>("compare_devices", { serials });
+}
+
+/**
+ * Get a device's battery level and charging state
+ */
+export async function getBatteryStatus(serial: string): Promise<BatteryStatus> {
+  return invoke<BatteryStatus>("get_battery_status", { serial });
+}
+
+/**
+ * Get battery, free/total storage and memory use of a device in one call
+ */
+export async function getDeviceHealth(serial: string): Promise<DeviceHealth> {
+  return invoke<DeviceHealth>("get_device_health", { serial });
+}
+
+/**
+ * Restart a device normally or into recovery; its sessions and transfers are
+ * cut off
+ * @param confirmationToken From requestConfirmation("reboot_device", [serial, mode])
+ */
+export async function rebootDevice(
+  serial: string,
+  mode: RebootMode,
+  confirmationToken: string
+): Promise<void> {
+  return invoke<void>("reboot_device", { serial, mode, confirmationToken });
+}
+
+// ============================================
+// Mirror Commands
+// ============================================
+
+/**
+ * Start a mirror session for a device
+ * @param screenOff - Turn off device screen while mirroring
+ * @param options - Mirror options (defaults to the ones saved in Settings)
+ */
+export async function startMirror(
+  serial: string,
+  screenOff: boolean = false,
+  options?: MirrorOptions
+): Promise<MirrorSession> {
+  return invoke<MirrorSession>("start_mirror", { serial, screenOff, options });
+}
+
+/**
+ * Start a "headless phone" mirror: screen off and kept awake while operated
+ * from the PC, powered off when the window closes
+ */
+export async function startHeadlessMirror(serial: string): Promise<MirrorSession> {
+  return invoke<MirrorSession>("start_headless_mirror", { serial });
+}
+
+/**
+ * Kiosk/demo mode: launch an app, pin it with screen pinning and mirror fullscreen
+ */
+export async function startKiosk(serial: string, packageName: string): Promise<MirrorSession> {
+  return invoke<MirrorSession>("start_kiosk", { serial, package: packageName });
+}
+
+/**
+ * Leave kiosk mode: stop the mirror and unpin the app
+ */
+export async function stopKiosk(serial: string): Promise<void> {
+  return invoke<void>("stop_kiosk", { serial });
+}
+
+/**
+ * Save a frame of an active mirror session as a PNG (default: Downloads).
+ * Returns the saved file path.
+ */
+export async function captureMirrorFrame(serial: string, dest?: string): Promise<string> {
+  return invoke<string>("capture_mirror_frame", { serial, dest });
+}
+
+/**
+ * Get a device's display resolution, for choosing a crop region
+ */
+export async function getDisplaySize(serial: string): Promise<DisplaySize> {
+  return invoke<DisplaySize>("get_display_size", { serial });
+}
+
+/**
+ * Measure a wireless device's link to see how well it will mirror
+ */
+export async function measureWifiLink(serial: string): Promise<LinkQuality> {
+  return invoke<LinkQuality>("measure_wifi_link", { serial });
+}
+
+/**
+ * Stop a mirror session for a device
+ */
+export async function stopMirror(serial: string): Promise<void> {
+  return invoke<void>("stop_mirror", { serial });
 }
 
 /**
@@ -193,20 +1386,38 @@ export async function getMirrorSessions(): Promise<MirrorSession[]> {
 
 export type CameraFacing = 'front' | 'back';
 export type CameraResolution = '640x480' | '1280x720' | '1920x1080';
+export type CameraOrientation = 'portrait' | 'landscape';
+
+export interface CameraOptions {
+  facing: CameraFacing;
+  resolution: string;
+  /** Disable audio forwarding (mute) */
+  no_audio: boolean;
+  orientation: CameraOrientation;
+  /** Record the session to the recordings folder */
+  record: boolean;
+  /** Small always-on-top window in the top-left screen corner */
+  pip: boolean;
+  /** Relaunch the mirror when it exits, e.g. after the camera app took the camera */
+  auto_restart: boolean;
+}
 
 /**
  * Start a camera mirror session
- * @param noAudio - Disable audio forwarding (mute)
- * @param orientation - Camera orientation: 'portrait' or 'landscape'
+ * @param options - Camera options; omit to reuse the device's last used options
  */
 export async function startCamera(
-  serial: string, 
-  facing: CameraFacing = 'back', 
-  resolution: CameraResolution = '1280x720',
-  noAudio: boolean = true,
-  orientation: 'portrait' | 'landscape' = 'portrait'
+  serial: string,
+  options?: CameraOptions
 ): Promise<MirrorSession> {
-  return invoke<MirrorSession>("start_camera", { serial, facing, resolution, noAudio, orientation });
+  return invoke<MirrorSession>("start_camera", { serial, options });
+}
+
+/**
+ * Get the persisted camera options for a device
+ */
+export async function getCameraOptions(serial: string): Promise<CameraOptions> {
+  return invoke<CameraOptions>("get_camera_options", { serial });
 }
 
 /**
@@ -223,19 +1434,94 @@ export async function getCameraSessions(): Promise<MirrorSession[]> {
   return invoke<MirrorSession[]>("get_camera_sessions");
 }
 
+// ============================================
+// Audio Capture Commands
+// ============================================
+
+/**
+ * Start recording a device's microphone (Android 11+).
+ * Saves to the recordings folder unless a dest folder is given.
+ */
+export async function startAudioCapture(serial: string, dest?: string): Promise<MirrorSession> {
+  return invoke<MirrorSession>("start_audio_capture", { serial, dest });
+}
+
+/**
+ * Stop recording a device's microphone
+ */
+export async function stopAudioCapture(serial: string): Promise<void> {
+  return invoke<void>("stop_audio_capture", { serial });
+}
+
+/**
+ * Get all active audio captures
+ */
+export async function getAudioSessions(): Promise<MirrorSession[]> {
+  return invoke<MirrorSession[]>("get_audio_sessions");
+}
+
 // ============================================
 // Transfer Commands
 // ============================================
 
 /**
- * Push files to a device
+ * Push files to a device. With `archive`, files are sent as one tar and extracted on-device.
  */
 export async function pushFiles(
   serial: string,
   paths: string[],
-  dest?: string
+  dest?: string,
+  archive?: boolean,
+  conflict?: ConflictPolicy
 ): Promise<TransferItem[]> {
-  return invoke<TransferItem[]>("push_files", { serial, paths, dest });
+  return invoke<TransferItem[]>("push_files", { serial, paths, dest, archive, conflict });
+}
+
+/**
+ * Push files using a saved preset's destination and options
+ */
+export async function pushWithPreset(
+  serial: string,
+  presetId: string,
+  paths: string[]
+): Promise<TransferItem[]> {
+  return invoke<TransferItem[]>("push_with_preset", { serial, presetId, paths });
+}
+
+/**
+ * List saved transfer presets
+ */
+export async function getTransferPresets(): Promise<TransferPreset[]> {
+  return invoke<TransferPreset[]>("get_transfer_presets");
+}
+
+/**
+ * Create (empty id) or update a transfer preset
+ */
+export async function saveTransferPreset(preset: TransferPreset): Promise<TransferPreset> {
+  return invoke<TransferPreset>("save_transfer_preset", { preset });
+}
+
+/**
+ * Delete a transfer preset
+ */
+export async function deleteTransferPreset(id: string): Promise<void> {
+  return invoke<void>("delete_transfer_preset", { id });
+}
+
+/**
+ * Get what is remembered about a device (last push and pull folders).
+ * pushFiles/pullMediaFiles fall back to these when no destination is given.
+ */
+export async function getDeviceRecord(serial: string): Promise<DeviceRecord> {
+  return invoke<DeviceRecord>("get_device_record", { serial });
+}
+
+/**
+ * Expand dropped files/folders, classify them and suggest an action
+ */
+export async function analyzeDrop(paths: string[]): Promise<DropAnalysis> {
+  return invoke<DropAnalysis>("analyze_drop", { paths });
 }
 
 /**
@@ -246,6 +1532,91 @@ export async function getTransfers(): Promise<[TransferItem[], TransferItem[]]>
   return invoke<[TransferItem[], TransferItem[]]>("get_transfers");
 }
 
+/**
+ * Resume transfers interrupted by a disconnect (also happens automatically when the
+ * device shows up again in listDevices)
+ */
+export async function resumeTransfers(serial: string): Promise<TransferItem[]> {
+  return invoke<TransferItem[]>("resume_transfers", { serial });
+}
+
+/**
+ * Preview which files a folder sync would copy (nothing is transferred)
+ */
+export async function previewSync(
+  serial: string,
+  localRoot: string,
+  remoteRoot: string,
+  direction: SyncDirection,
+  compareHash?: boolean,
+  conflict?: ConflictPolicy
+): Promise<SyncPlan> {
+  return invoke<SyncPlan>("preview_sync", {
+    serial,
+    localRoot,
+    remoteRoot,
+    direction,
+    compareHash,
+    conflict,
+  });
+}
+
+/**
+ * Sync a local folder with a device folder, copying only files that differ
+ */
+export async function runSync(
+  serial: string,
+  localRoot: string,
+  remoteRoot: string,
+  direction: SyncDirection,
+  compareHash?: boolean,
+  conflict?: ConflictPolicy
+): Promise<SyncReport> {
+  return invoke<SyncReport>("run_sync", {
+    serial,
+    localRoot,
+    remoteRoot,
+    direction,
+    compareHash,
+    conflict,
+  });
+}
+
+/**
+ * List scheduled sync jobs
+ */
+export async function getSyncJobs(): Promise<SyncJob[]> {
+  return invoke<SyncJob[]>("get_sync_jobs");
+}
+
+/**
+ * Create (empty id) or update a scheduled sync job
+ */
+export async function saveSyncJob(job: SyncJob): Promise<SyncJob> {
+  return invoke<SyncJob>("save_sync_job", { job });
+}
+
+/**
+ * Delete a scheduled sync job and its history
+ */
+export async function deleteSyncJob(id: string): Promise<void> {
+  return invoke<void>("delete_sync_job", { id });
+}
+
+/**
+ * Past runs of a scheduled sync job, newest first
+ */
+export async function getSyncJobHistory(id: string): Promise<SyncJobRun[]> {
+  return invoke<SyncJobRun[]>("get_sync_job_history", { id });
+}
+
+/**
+ * Run a scheduled sync job now
+ */
+export async function runSyncJobNow(id: string): Promise<SyncJobRun> {
+  return invoke<SyncJobRun>("run_sync_job_now", { id });
+}
+
 /**
  * Cancel a transfer
  */
@@ -265,17 +1636,35 @@ export async function openBluetoothSettings(): Promise<void> {
 }
 
 /**
- * Open Bluetooth file send dialog
+ * Open Bluetooth file send dialog; tracked in the transfer history
+ */
+export async function openBluetoothSend(deviceName?: string): Promise<void> {
+  return invoke<void>("open_bluetooth_send", { deviceName });
+}
+
+/**
+ * Open Bluetooth file receive dialog; tracked in the transfer history
  */
-export async function openBluetoothSend(): Promise<void> {
-  return invoke<void>("open_bluetooth_send");
+export async function openBluetoothReceive(deviceName?: string): Promise<void> {
+  return invoke<void>("open_bluetooth_receive", { deviceName });
+}
+
+// ============================================
+// Quick Share Commands
+// ============================================
+
+/**
+ * Check whether Google's Quick Share for Windows is installed
+ */
+export async function getQuickShareStatus(): Promise<QuickShareStatus> {
+  return invoke<QuickShareStatus>("get_quick_share_status");
 }
 
 /**
- * Open Bluetooth file receive dialog
+ * Open Quick Share with local files preselected; recorded in the transfer history
  */
-export async function openBluetoothReceive(): Promise<void> {
-  return invoke<void>("open_bluetooth_receive");
+export async function sendWithQuickShare(paths: string[], deviceName?: string): Promise<void> {
+  return invoke<void>("send_with_quick_share", { paths, deviceName });
 }
 
 // ============================================
@@ -299,23 +1688,145 @@ export interface MediaItem {
   width: number | null;
   height: number | null;
   duration_ms: number | null;
+  /** ISO-8601 UTC: the capture time from MediaStore, else the file time */
   date_taken: string | null;
+  /** The device's UTC offset when listed, e.g. "+07:00" */
+  timezone: string | null;
   thumbnail_url: string | null;
+  /**
+   * A still with an embedded video. Guessed from the name when listing;
+   * detectMotionPhotos checks the file contents.
+   */
+  motion_photo: boolean;
 }
 
 export type MediaFilter = 'all' | 'images' | 'videos';
 
-export interface MediaTransferResult {
-  source_path: string;
-  dest_path: string | null;
-  success: boolean;
-  error: string | null;
-  size_bytes: number;
+/** Why photos were grouped: burst (name or seconds apart) or similar (thumbnail hash) */
+export type MediaGroupKind = 'burst' | 'similar';
+
+/** Photos that are (nearly) the same shot, so only one needs pulling */
+export interface MediaGroup {
+  kind: MediaGroupKind;
+  /** Members, oldest first */
+  paths: string[];
+  /** Suggested shot to keep: the burst cover if marked, else the largest file */
+  best_path: string;
+  total_bytes: number;
+}
+
+/** A storage volume's camera folder in an offload plan */
+export interface OffloadVolume {
+  /** Volume root, e.g. "/storage/emulated/0" or "/storage/1234-ABCD" */
+  root: string;
+  dcim_path: string;
+  file_count: number;
+  total_bytes: number;
+}
+
+/** One month of camera media in an offload plan */
+export interface OffloadMonth {
+  /** "YYYY-MM", by the device's clock */
+  month: string;
+  file_count: number;
+  total_bytes: number;
+  /** Files already on this computer */
+  backed_up_count: number;
+  backed_up_bytes: number;
+  /** Device paths still to pull (pass to pullMediaFiles), newest first */
+  pending_paths: string[];
+}
+
+/** Camera media on a device and what an offload would still have to pull */
+export interface CameraOffloadPlan {
+  device_serial: string;
+  volumes: OffloadVolume[];
+  /** Newest month first */
+  months: OffloadMonth[];
+  total_count: number;
+  total_bytes: number;
+  pending_count: number;
+  pending_bytes: number;
+}
+
+/**
+ * Which items of a folder to pull without picking them by hand.
+ * Options combine; newest applies last.
+ */
+export interface MediaSelection {
+  /** Only the newest N items */
+  newest?: number;
+  /** Only items modified at or after this time ("2024-01-30", "2024-01-30 10:30" or ISO 8601) */
+  since?: string;
+  /** Only items newer than the newest one already pulled from the folder */
+  since_last_pull?: boolean;
+}
+
+export interface ProbeResult {
+  format_name: string | null;
+  duration_ms: number | null;
+  bit_rate: number | null;
+  width: number | null;
+  height: number | null;
+  video_codec: string | null;
+  audio_codec: string | null;
+  rotation: number | null;
+  creation_time: string | null;
+  /** Where the video was recorded, from the container's location tag */
+  latitude: number | null;
+  longitude: number | null;
+}
+
+/** Where a photo was taken, from its EXIF GPS tags */
+export interface MediaLocation {
+  path: string;
+  latitude: number;
+  longitude: number;
+}
+
+export interface MediaTransferResult {
+  source_path: string;
+  dest_path: string | null;
+  success: boolean;
+  error: string | null;
+  size_bytes: number;
+  /** Not pulled because of the conflict policy (reason in `error`) */
+  skipped: boolean;
+  /** Video extracted from a pulled motion photo, when asked for */
+  motion_video_path: string | null;
+  /** The device file name, when it wasn't valid on Windows (`CON`, `a:b`) and was saved as `dest_path` */
+  renamed_from: string | null;
+}
+
+/** A device file copied to this computer, from the pull history */
+export interface PulledFile {
+  remote_path: string;
+  size_bytes: number;
+  /** SHA-256 of the local copy right after the pull */
+  sha256: string | null;
+  local_path: string;
+  /** RFC 3339 time of the latest pull */
+  pulled_at: string;
+  /** The local copy is still there */
+  local_exists: boolean;
+}
+
+export interface AffectedItem {
+  path: string;
+  size_bytes: number;
+  /** Why the item was skipped or failed; null when it is (or would be) affected */
+  error: string | null;
+}
+
+export interface DestructiveOpReport {
+  dry_run: boolean;
+  items: AffectedItem[];
+  total_bytes: number;
 }
 
 // ============================================
-// Media Previewer Commands
-// ============================================
+// Media Previewer Commands
+// ============================================
 
 /**
  * Get the best default root folder for media browsing
@@ -330,12 +1841,14 @@ export async function getDefaultMediaRoot(serial: string): Promise<string> {
  * List folders on the device at specified path
  * @param serial Device serial number
  * @param path Path to list (defaults to /sdcard if not specified)
+ * @param includePrivate Also list private folders
  */
 export async function listDeviceFolders(
   serial: string,
-  path?: string
+  path?: string,
+  includePrivate?: boolean
 ): Promise<FolderInfo[]> {
-  return invoke<FolderInfo[]>("list_device_folders", { serial, path });
+  return invoke<FolderInfo[]>("list_device_folders", { serial, path, includePrivate });
 }
 
 /**
@@ -343,13 +1856,15 @@ export async function listDeviceFolders(
  * @param serial Device serial number
  * @param path Path to list media from
  * @param filter Optional filter: 'all', 'images', or 'videos'
+ * @param includePrivate Also list items in private folders
  */
 export async function listDeviceMedia(
   serial: string,
   path: string,
-  filter?: MediaFilter
+  filter?: MediaFilter,
+  includePrivate?: boolean
 ): Promise<MediaItem[]> {
-  return invoke<MediaItem[]>("list_device_media", { serial, path, filter });
+  return invoke<MediaItem[]>("list_device_media", { serial, path, filter, includePrivate });
 }
 
 /**
@@ -368,17 +1883,141 @@ export async function getMediaThumbnail(
  * @param serial Device serial number
  * @param paths Array of remote file paths to pull
  * @param dest Optional destination folder (defaults to Downloads)
+ * @param extractMotion Also save the video of motion photos as <name>.mp4
+ * @param includePrivate Pull files in private folders too; otherwise they
+ *   come back skipped
  */
 export async function pullMediaFiles(
   serial: string,
   paths: string[],
-  dest?: string
+  dest?: string,
+  conflict?: ConflictPolicy,
+  extractMotion?: boolean,
+  includePrivate?: boolean
 ): Promise<MediaTransferResult[]> {
-  return invoke<MediaTransferResult[]>("pull_media_files", { serial, paths, dest });
+  return invoke<MediaTransferResult[]>("pull_media_files", {
+    serial,
+    paths,
+    dest,
+    conflict,
+    extractMotion,
+    includePrivate,
+  });
+}
+
+/** Which of the given device photos are motion photos, by their contents */
+export async function detectMotionPhotos(serial: string, paths: string[]): Promise<string[]> {
+  return invoke<string[]>("detect_motion_photos", { serial, paths });
+}
+
+/**
+ * Pull the items of a folder that a selection picks, e.g. { newest: 50 }
+ * or { since_last_pull: true }
+ */
+export async function pullMediaSelection(
+  serial: string,
+  path: string,
+  selection: MediaSelection,
+  filter?: MediaFilter,
+  dest?: string,
+  conflict?: ConflictPolicy,
+  includePrivate?: boolean
+): Promise<MediaTransferResult[]> {
+  return invoke<MediaTransferResult[]>("pull_media_selection", {
+    serial,
+    path,
+    selection,
+    filter,
+    dest,
+    conflict,
+    includePrivate,
+  });
+}
+
+/**
+ * Group bursts and near-identical shots in a device folder so only the best
+ * of each needs pulling
+ * @param findSimilar Also compare thumbnails (fetches one per photo; slower)
+ */
+export async function groupSimilarPhotos(
+  serial: string,
+  path: string,
+  findSimilar?: boolean
+): Promise<MediaGroup[]> {
+  return invoke<MediaGroup[]>("group_similar_photos", { serial, path, findSimilar });
+}
+
+/**
+ * Scan DCIM on every volume for the camera offload wizard: media grouped
+ * by month, with files already on this computer left out of pending_paths.
+ * Nothing is pulled.
+ * @param includePrivate Also plan files in private folders
+ */
+export async function planCameraOffload(
+  serial: string,
+  includePrivate?: boolean
+): Promise<CameraOffloadPlan> {
+  return invoke<CameraOffloadPlan>("plan_camera_offload", { serial, includePrivate });
 }
 
 /**
- * Preview a media file by pulling to temp and returning local path
+ * Pull a whole device folder as one batch into dest/<folder name>,
+ * keeping its subfolders. Progress comes as BATCH_PROGRESS_EVENT.
+ * @param filter Only pull images or videos (default: every file)
+ * @param recursive Include subfolders (default true)
+ * @param includePrivate Also pull files in private folders
+ */
+export async function pullFolder(
+  serial: string,
+  remoteDir: string,
+  dest?: string,
+  filter?: MediaFilter,
+  recursive?: boolean,
+  conflict?: ConflictPolicy,
+  includePrivate?: boolean
+): Promise<MediaTransferResult[]> {
+  return invoke<MediaTransferResult[]>("pull_folder", {
+    serial,
+    remoteDir,
+    dest,
+    filter,
+    recursive,
+    conflict,
+    includePrivate,
+  });
+}
+
+/**
+ * Files pulled from a device before, most recent first. A media item is
+ * already on the PC when an entry has its path and size and local_exists;
+ * pulling remote_path into the folder of local_path downloads it again.
+ */
+export async function getPullHistory(serial: string): Promise<PulledFile[]> {
+  return invoke<PulledFile[]>("get_pull_history", { serial });
+}
+
+/**
+ * Delete media files from the device
+ * @param dryRun Only report what would be deleted (use for the confirm dialog)
+ * @param confirmationToken From requestConfirmation; required unless dryRun
+ */
+export async function deleteMediaFiles(
+  serial: string,
+  paths: string[],
+  dryRun: boolean,
+  confirmationToken?: string
+): Promise<DestructiveOpReport> {
+  return invoke<DestructiveOpReport>("delete_media_files", {
+    serial,
+    paths,
+    dryRun,
+    confirmationToken,
+  });
+}
+
+/**
+ * Preview a media file by pulling to temp and returning local path. The
+ * copy is kept, so pulling the file afterwards copies it instead.
  * @returns Local file path for preview
  */
 export async function previewMedia(
@@ -388,6 +2027,20 @@ export async function previewMedia(
   return invoke<string>("preview_media", { serial, path });
 }
 
+/**
+ * Keep a file being previewed: moves the preview copy into dest (default:
+ * the last folder used for the device) under its device name, with no
+ * second transfer. Throws if the file wasn't previewed.
+ */
+export async function savePreviewAs(
+  serial: string,
+  path: string,
+  dest?: string,
+  conflict?: ConflictPolicy
+): Promise<MediaTransferResult> {
+  return invoke<MediaTransferResult>("save_preview_as", { serial, path, dest, conflict });
+}
+
 /**
  * Open folder in system file manager
  */
@@ -395,6 +2048,83 @@ export async function openMediaFolder(path: string): Promise<void> {
   return invoke<void>("open_media_folder", { path });
 }
 
+/**
+ * Open a pulled file with a chosen app, e.g. "VLC" on macOS or a program
+ * path on Windows/Linux; the default app when withApp is empty
+ */
+export async function openMediaFileWith(path: string, withApp?: string): Promise<void> {
+  return invoke<void>("open_media_file_with", { path, withApp });
+}
+
+/** Show a pulled file selected in its folder (Explorer, Finder, file manager) */
+export async function revealMediaFile(path: string): Promise<void> {
+  return invoke<void>("reveal_media_file", { path });
+}
+
+/**
+ * Read media metadata of a local file using ffprobe
+ * @param path Local file path
+ */
+export async function probeMediaFile(path: string): Promise<ProbeResult> {
+  return invoke<ProbeResult>("probe_media_file", { path });
+}
+
+/**
+ * Where the JPEGs in a device folder were taken, for a map view. Photos
+ * without GPS tags are left out; videos report theirs via probeMediaFile.
+ */
+export async function getMediaLocations(serial: string, folder: string): Promise<MediaLocation[]> {
+  return invoke<MediaLocation[]>("get_media_locations", { serial, folder });
+}
+
+// ============================================
+// MTP Fallback Commands (Windows, no USB debugging)
+// ============================================
+
+/**
+ * Phones Windows sees as portable devices (file transfer mode). Paths on them
+ * are as Explorer shows them, e.g. "Internal shared storage/DCIM/Camera".
+ */
+export async function listMtpDevices(): Promise<string[]> {
+  return invoke<string[]>("list_mtp_devices");
+}
+
+/**
+ * List folders in a device folder over MTP; no path lists the device's storages
+ */
+export async function listMtpFolders(
+  device: string,
+  path?: string,
+  includePrivate?: boolean
+): Promise<FolderInfo[]> {
+  return invoke<FolderInfo[]>("list_mtp_folders", { device, path, includePrivate });
+}
+
+/**
+ * List images and videos in a device folder over MTP, newest first
+ */
+export async function listMtpMedia(
+  device: string,
+  path: string,
+  filter?: MediaFilter,
+  includePrivate?: boolean
+): Promise<MediaItem[]> {
+  return invoke<MediaItem[]>("list_mtp_media", { device, path, filter, includePrivate });
+}
+
+/**
+ * Copy files from a device over MTP (read-only; Downloads by default).
+ * Files already in the destination are skipped.
+ */
+export async function pullMtpFiles(
+  device: string,
+  paths: string[],
+  dest?: string,
+  includePrivate?: boolean
+): Promise<MediaTransferResult[]> {
+  return invoke<MediaTransferResult[]>("pull_mtp_files", { device, paths, dest, includePrivate });
+}
+
 // ============================================
 // Legacy Commands (kept for reference)
 // ============================================
@@ -406,3 +2136,415 @@ export async function greet(name: string): Promise<string> {
   return invoke<string>("greet", { name });
 }
 
+// ============================================
+// Clipboard Commands
+// ============================================
+
+/**
+ * Send the PC clipboard (or the given text) to a device.
+ * URLs open in the device's browser; other text opens the share sheet.
+ */
+export async function sendClipboardText(
+  serial: string,
+  text?: string
+): Promise<ClipboardSendMode> {
+  return invoke<ClipboardSendMode>("send_clipboard_text", { serial, text });
+}
+
+// ============================================
+// Install Commands
+// ============================================
+
+/**
+ * Install APKs on the given devices. With an empty list the only connected
+ * device is used; several connected devices require choosing targets.
+ */
+export async function installApks(serials: string[], paths: string[]): Promise<InstallResult[]> {
+  return invoke<InstallResult[]>("install_apks", { serials, paths });
+}
+
+/**
+ * Read package, version, SDK levels and icon from a local APK (no Android
+ * SDK needed), e.g. to preview it before installApks
+ */
+export async function inspectApk(path: string): Promise<ApkPreview> {
+  return invoke<ApkPreview>("inspect_apk", { path });
+}
+
+/**
+ * Small launcher icon of an installed app as a PNG data URL. The APK is pulled
+ * once per installed version; later calls come from the thumbnail cache.
+ */
+export async function getAppIcon(serial: string, packageName: string): Promise<string> {
+  return invoke<string>("get_app_icon", { serial, package: packageName });
+}
+
+/**
+ * Save a device's third-party apps (package, version, installer) as JSON
+ */
+export async function exportAppList(serial: string, path: string): Promise<AppList> {
+  return invoke<AppList>("export_app_list", { serial, path });
+}
+
+/**
+ * Install the apps of an exported list from a folder of APKs. APKs are matched
+ * by file name: <package>.apk, or <package>-... / <package>_...
+ */
+export async function bulkInstallFromList(
+  serial: string,
+  path: string,
+  apkDir: string
+): Promise<BulkInstallReport> {
+  return invoke<BulkInstallReport>("bulk_install_from_list", { serial, path, apkDir });
+}
+
+/**
+ * Installed apps on a device with a newer APK (by versionCode) in the local
+ * APK repository folder
+ */
+export async function checkApkUpdates(serial: string): Promise<ApkUpdate[]> {
+  return invoke<ApkUpdate[]>("check_apk_updates", { serial });
+}
+
+/**
+ * Install the newer APK of an available update on its device
+ */
+export async function applyApkUpdate(serial: string, update: ApkUpdate): Promise<InstallResult> {
+  const [result] = await installApks([serial], [update.apk_path]);
+  return result;
+}
+
+/**
+ * Preinstalled packages on a device that are safe to disable, with their state
+ */
+export async function listBloatware(serial: string): Promise<BloatwarePackage[]> {
+  return invoke<BloatwarePackage[]>("list_bloatware", { serial });
+}
+
+/**
+ * Disable packages (pm disable-user --user 0). Only packages from
+ * listBloatware are accepted; disabled ones are recorded for restoring.
+ */
+export async function disableBloatware(
+  serial: string,
+  packages: string[],
+  confirmationToken: string
+): Promise<PackageOpResult[]> {
+  return invoke<PackageOpResult[]>("disable_bloatware", {
+    serial,
+    packages,
+    confirmationToken,
+  });
+}
+
+/**
+ * Re-enable packages; without a list, restores everything disabled on the
+ * device through the bloatware manager
+ */
+export async function restoreBloatware(
+  serial: string,
+  packages?: string[]
+): Promise<PackageOpResult[]> {
+  return invoke<PackageOpResult[]>("restore_bloatware", { serial, packages });
+}
+
+/**
+ * Current default browser, SMS, dialer, home and assistant apps (Android 10+)
+ */
+export async function getDefaultApps(serial: string): Promise<DefaultApp[]> {
+  return invoke<DefaultApp[]>("get_default_apps", { serial });
+}
+
+/**
+ * Make a package the default app for a role
+ */
+export async function setDefaultApp(
+  serial: string,
+  role: DefaultAppRole,
+  packageName: string
+): Promise<string> {
+  return invoke<string>("set_default_app", { serial, role, package: packageName });
+}
+
+/**
+ * Unset the default app for a role, so the device asks again next time
+ */
+export async function clearDefaultApp(serial: string, role: DefaultAppRole): Promise<string> {
+  return invoke<string>("clear_default_app", { serial, role });
+}
+
+/**
+ * Web domains an app opens links for and their verification state (Android 12+)
+ */
+export async function getAppLinks(serial: string, packageName: string): Promise<AppLinks> {
+  return invoke<AppLinks>("get_app_links", { serial, package: packageName });
+}
+
+/**
+ * Allow or stop an app opening its verified links directly
+ */
+export async function setAppLinksAllowed(
+  serial: string,
+  packageName: string,
+  allowed: boolean
+): Promise<string> {
+  return invoke<string>("set_app_links_allowed", { serial, package: packageName, allowed });
+}
+
+/**
+ * Reset an app's link verification and verify its domains again
+ */
+export async function resetAppLinks(serial: string, packageName: string): Promise<string> {
+  return invoke<string>("reset_app_links", { serial, package: packageName });
+}
+
+/**
+ * Processes running on a device, largest memory users first
+ */
+export async function listDeviceProcesses(serial: string): Promise<DeviceProcess[]> {
+  return invoke<DeviceProcess[]>("list_device_processes", { serial });
+}
+
+/**
+ * Force-stop an app, e.g. one misbehaving during a mirror session
+ */
+export async function forceStop(serial: string, packageName: string): Promise<string> {
+  return invoke<string>("force_stop", { serial, package: packageName });
+}
+
+/**
+ * Kill a process by pid. Without root only shell-owned processes can be
+ * killed; use forceStop for apps.
+ */
+export async function killProcess(serial: string, pid: number): Promise<string> {
+  return invoke<string>("kill_process", { serial, pid });
+}
+
+/** Network traffic per app since the device's stats began, heaviest first */
+export async function getNetworkUsage(serial: string): Promise<NetworkUsage[]> {
+  return invoke<NetworkUsage[]>("get_network_usage", { serial });
+}
+
+/** Save a device's per-app network traffic as CSV */
+export async function exportNetworkUsage(serial: string, path: string): Promise<NetworkUsage[]> {
+  return invoke<NetworkUsage[]>("export_network_usage", { serial, path });
+}
+
+/** Wireless devices watched by the connection keeper and their state */
+export async function getWirelessStatus(): Promise<WirelessStatus[]> {
+  return invoke<WirelessStatus[]>("get_wireless_status");
+}
+
+/** Disconnect a wireless device and stop the keeper reconnecting it, now and on later starts */
+export async function disconnectWireless(endpoint: string): Promise<string> {
+  return invoke<string>("disconnect_wireless", { endpoint });
+}
+
+/** Connect to a device over TCP/IP ("ip" or "ip:port"); returns the endpoint */
+export async function connectDevice(addr: string): Promise<string> {
+  return invoke<string>("connect_device", { addr });
+}
+
+/** Like disconnectWireless, taking an "ip" or "ip:port" address */
+export async function disconnectDevice(addr: string): Promise<string> {
+  return invoke<string>("disconnect_device", { addr });
+}
+
+/** Switch a USB device into TCP mode so it can be unplugged and connected over Wi-Fi */
+export async function enableTcpip(serial: string, port = 5555): Promise<TcpipMode> {
+  return invoke<TcpipMode>("enable_tcpip", { serial, port });
+}
+
+/** What happened with a device (connects, mirror sessions, transfers, syncs), newest first */
+export async function getDeviceTimeline(serial: string): Promise<DeviceEvent[]> {
+  return invoke<DeviceEvent[]>("get_device_timeline", { serial });
+}
+
+/** Check whether this computer and a device share a network, so `adb connect` can reach it */
+export async function checkWirelessNetwork(serial: string): Promise<WirelessNetworkCheck> {
+  return invoke<WirelessNetworkCheck>("check_wireless_network", { serial });
+}
+
+/**
+ * Turn on a device's hotspot; where the device doesn't allow it, its hotspot
+ * settings are opened instead
+ */
+export async function startHotspot(
+  serial: string,
+  ssid: string,
+  passphrase: string
+): Promise<HotspotStart> {
+  return invoke<HotspotStart>("start_hotspot", { serial, ssid, passphrase });
+}
+
+/** Turn a device's hotspot off */
+export async function stopHotspot(serial: string): Promise<string> {
+  return invoke<string>("stop_hotspot", { serial });
+}
+
+/** Connect to a device over its hotspot once this computer has joined it; returns the new ip:port */
+export async function connectOverHotspot(serial: string): Promise<string> {
+  return invoke<string>("connect_over_hotspot", { serial });
+}
+
+/**
+ * Sample CPU, memory, battery current and FPS every second, of one app or the
+ * whole device. Samples arrive as PERF_SAMPLE_EVENT until stopPerfStream.
+ */
+export async function startPerfStream(serial: string, packageName?: string): Promise<void> {
+  return invoke<void>("start_perf_stream", { serial, package: packageName });
+}
+
+/** Stop a device's performance stream; returns whether one was running */
+export async function stopPerfStream(serial: string): Promise<boolean> {
+  return invoke<boolean>("stop_perf_stream", { serial });
+}
+
+/** Call counts, durations and failure rates per tool since the app started */
+export async function getPerfMetrics(): Promise<ToolMetrics[]> {
+  return invoke<ToolMetrics[]>("get_perf_metrics");
+}
+
+/**
+ * Watch a setting or system property once a second; changes are emitted as
+ * SETTING_CHANGED_EVENT until unwatchSetting
+ */
+export async function watchSetting(
+  serial: string,
+  namespace: WatchNamespace,
+  key: string
+): Promise<SettingWatch> {
+  return invoke<SettingWatch>("watch_setting", { serial, namespace, key });
+}
+
+/** Stop a watch; returns whether it was running */
+export async function unwatchSetting(watchId: string): Promise<boolean> {
+  return invoke<boolean>("unwatch_setting", { watchId });
+}
+
+/** Ids of the running setting watches */
+export async function listSettingWatches(): Promise<string[]> {
+  return invoke<string[]>("list_setting_watches");
+}
+
+/**
+ * Route a drag-and-drop: install APKs, copy OBBs to Android/obb/<package>,
+ * push everything else. Targets follow the same rules as installApks.
+ */
+export async function routeDrop(
+  serials: string[],
+  paths: string[],
+  dest?: string
+): Promise<DropRouteReport> {
+  return invoke<DropRouteReport>("route_drop", { serials, paths, dest });
+}
+
+// ============================================
+// Device Group Commands
+// ============================================
+
+export async function getDeviceGroups(): Promise<DeviceGroup[]> {
+  return invoke<DeviceGroup[]>("get_device_groups");
+}
+
+export async function saveDeviceGroup(group: DeviceGroup): Promise<DeviceGroup> {
+  return invoke<DeviceGroup>("save_device_group", { group });
+}
+
+export async function deleteDeviceGroup(id: string): Promise<void> {
+  return invoke("delete_device_group", { id });
+}
+
+/**
+ * Push files to every device in a group
+ */
+export async function pushToGroup(
+  groupId: string,
+  paths: string[],
+  dest?: string
+): Promise<GroupOpReport> {
+  return invoke<GroupOpReport>("push_to_group", { groupId, paths, dest });
+}
+
+/**
+ * Install APKs on every device in a group
+ */
+export async function installToGroup(groupId: string, paths: string[]): Promise<GroupOpReport> {
+  return invoke<GroupOpReport>("install_to_group", { groupId, paths });
+}
+
+/**
+ * Run a command on every device in a group. Each argument is passed
+ * literally, so pipes and redirects are not interpreted. Needs a token from
+ * requestConfirmation("shell_on_group", [groupId, ...args]).
+ */
+export async function shellOnGroup(
+  groupId: string,
+  args: string[],
+  confirmationToken: string
+): Promise<GroupOpReport> {
+  return invoke<GroupOpReport>("shell_on_group", { groupId, args, confirmationToken });
+}
+
+/**
+ * Screenshot every device in a group into dest (default: Downloads)
+ */
+export async function screenshotGroup(groupId: string, dest?: string): Promise<GroupOpReport> {
+  return invoke<GroupOpReport>("screenshot_group", { groupId, dest });
+}
+
+// ============================================
+// Provisioning Commands
+// ============================================
+
+export async function getProvisioningProfiles(): Promise<ProvisioningProfile[]> {
+  return invoke<ProvisioningProfile[]>("get_provisioning_profiles");
+}
+
+export async function saveProvisioningProfile(
+  profile: ProvisioningProfile
+): Promise<ProvisioningProfile> {
+  return invoke<ProvisioningProfile>("save_provisioning_profile", { profile });
+}
+
+export async function deleteProvisioningProfile(id: string): Promise<void> {
+  return invoke("delete_provisioning_profile", { id });
+}
+
+/**
+ * Apply a profile to a device. Progress arrives as batch-progress events
+ * with kind 'provision'.
+ */
+export async function applyProvisioning(
+  serial: string,
+  profileId: string
+): Promise<ProvisioningReport> {
+  return invoke<ProvisioningReport>("apply_provisioning", { serial, profileId });
+}
+
+// ============================================
+// Recording Commands
+// ============================================
+
+/**
+ * List screen and camera recordings, newest first
+ */
+export async function listRecordings(): Promise<Recording[]> {
+  return invoke<Recording[]>("list_recordings");
+}
+
+/** Delete a recording; needs a token from requestConfirmation */
+export async function deleteRecording(path: string, confirmationToken: string): Promise<void> {
+  return invoke("delete_recording", { path, confirmationToken });
+}
+
+export async function openRecordingsFolder(): Promise<void> {
+  return invoke("open_recordings_folder");
+}
+
+/**
+ * Convert an MKV recording to MP4 and return the new path
+ */
+export async function remuxRecording(path: string): Promise<string> {
+  return invoke<string>("remux_recording", { path });
+}
@@ -196,7 +196,7 @@ export function MediaPreview({
               {item.date_taken && (
                 <>
                   <span className="text-surface-600">•</span>
-                  <span>{item.date_taken}</span>
+                  <span>{new Date(item.date_taken).toLocaleString()}</span>
                 </>
               )}
             </div>
@@ -27,6 +27,7 @@ export function MirrorPage() {
   const [startingSerial, setStartingSerial] = useState<string | null>(null);
   const [stoppingSerial, setStoppingSerial] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
+  const [warning, setWarning] = useState<string | null>(null);
   
   // Mode and options
   const [mode, setMode] = useState<MirrorMode>('screen');
@@ -81,11 +82,27 @@ export function MirrorPage() {
   async function handleStartMirror(serial: string) {
     setStartingSerial(serial);
     setError(null);
+    setWarning(null);
     try {
       if (mode === 'screen') {
-        await startMirror(serial, screenOff);
+        const session = await startMirror(serial, screenOff);
+        const link = session.link_quality;
+        if (link?.weak) {
+          setWarning(
+            `Weak Wi-Fi link (${(link.throughput_kbps / 1000).toFixed(1)} Mbit/s, ${link.latency_ms} ms). ` +
+            `Mirroring may stutter; try ${link.recommended_bit_rate_mbps} Mbit/s or a USB cable.`
+          );
+        }
       } else {
-        await startCamera(serial, cameraFacing, cameraResolution, muteAudio, cameraOrientation);
+        await startCamera(serial, {
+          facing: cameraFacing,
+          resolution: cameraResolution,
+          no_audio: muteAudio,
+          orientation: cameraOrientation,
+          record: false,
+          pip: false,
+          auto_restart: false,
+        });
       }
       await refreshSessions();
     } catch (err) {
@@ -167,6 +184,12 @@ export function MirrorPage() {
         </div>
       )}
 
+      {warning && (
+        <div className="bg-warning/10 border border-warning/30 rounded-xl p-4 mb-6">
+          <p className="text-warning text-sm">{warning}</p>
+        </div>
+      )}
+
       {/* Mode Toggle */}
       <div className="bg-surface-900 border border-surface-800 rounded-xl p-4 mb-6">
         <label className="block text-sm font-medium text-surface-400 mb-3">Mode</label>
@@ -1,7 +1,5 @@
 import { useState, useEffect } from "react";
-import { getSettings, setAdbPath, detectAdb, setScrcpyPath, detectScrcpy, setFfmpegPath, detectFfmpeg, parseError, type Settings } from "../api/bridge";
-import { check } from "@tauri-apps/plugin-updater";
-import { relaunch } from "@tauri-apps/plugin-process";
+import { getSettings, setAdbPath, detectAdb, setScrcpyPath, detectScrcpy, setFfmpegPath, detectFfmpeg, getPendingUpdate, installUpdate as installPendingUpdate, setUpdateChannel, parseError, type PendingUpdate, type Settings, type UpdateChannel } from "../api/bridge";
 
 export function SettingsPage() {
   const [settings, setSettings] = useState<Settings | null>(null);
@@ -22,7 +20,7 @@ export function SettingsPage() {
   
   // Update state
   const [isCheckingUpdate, setIsCheckingUpdate] = useState(false);
-  const [updateAvailable, setUpdateAvailable] = useState<{ version: string } | null>(null);
+  const [updateAvailable, setUpdateAvailable] = useState<PendingUpdate | null>(null);
   const [isUpdating, setIsUpdating] = useState(false);
   const [updateError, setUpdateError] = useState<string | null>(null);
 
@@ -156,10 +154,7 @@ export function SettingsPage() {
     setUpdateError(null);
     setUpdateAvailable(null);
     try {
-      const update = await check();
-      if (update) {
-        setUpdateAvailable({ version: update.version });
-      }
+      setUpdateAvailable(await getPendingUpdate());
     } catch (err) {
       setUpdateError(parseError(err));
     } finally {
@@ -171,17 +166,23 @@ export function SettingsPage() {
     setIsUpdating(true);
     setUpdateError(null);
     try {
-      const update = await check();
-      if (update) {
-        await update.downloadAndInstall();
-        await relaunch();
-      }
+      await installPendingUpdate();
     } catch (err) {
       setUpdateError(parseError(err));
       setIsUpdating(false);
     }
   }
 
+  async function changeUpdateChannel(channel: UpdateChannel) {
+    setUpdateError(null);
+    setUpdateAvailable(null);
+    try {
+      setSettings(await setUpdateChannel(channel));
+    } catch (err) {
+      setUpdateError(parseError(err));
+    }
+  }
+
   if (isLoading) {
     return (
       <div className="animate-fade-in flex items-center justify-center h-64">
@@ -450,6 +451,20 @@ export function SettingsPage() {
             </button>
           </div>
 
+          <div className="flex items-center justify-between">
+            <p className="text-surface-300 text-sm">Release channel</p>
+            <select
+              value={settings?.update_channel ?? "stable"}
+              onChange={(e) => changeUpdateChannel(e.target.value as UpdateChannel)}
+              disabled={isCheckingUpdate || isUpdating}
+              className="px-4 py-2 bg-surface-800 border border-surface-700 rounded-lg 
+                       text-surface-200 focus:outline-none focus:border-primary-500"
+            >
+              <option value="stable">Stable</option>
+              <option value="beta">Beta</option>
+            </select>
+          </div>
+
           {updateError && (
             <div className="bg-error/10 border border-error/30 rounded-lg p-3">
               <p className="text-error text-sm">{updateError}</p>
@@ -466,6 +481,11 @@ export function SettingsPage() {
                   <p className="text-surface-500 text-sm">
                     A new version is ready to install
                   </p>
+                  {updateAvailable.notes && (
+                    <p className="text-surface-400 text-sm whitespace-pre-wrap mt-2">
+                      {updateAvailable.notes}
+                    </p>
+                  )}
                 </div>
                 <button
                   onClick={installUpdate}
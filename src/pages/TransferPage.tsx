@@ -156,6 +156,7 @@ export function TransferPage() {
       case "failed": return "text-error";
       case "cancelled": return "text-surface-400";
       case "transferring": return "text-primary-400";
+      case "handed_off": return "text-surface-300";
       default: return "text-surface-400";
     }
   }
@@ -295,8 +296,8 @@ export function TransferPage() {
             {history.slice(0, 20).map(t => (
               <div key={t.id} className="bg-surface-900 border border-surface-800 rounded-lg px-4 py-3 flex items-center justify-between">
                 <div className="flex items-center gap-3">
-                  <span className={`text-lg ${t.status === "complete" ? "text-success" : "text-error"}`}>
-                    {t.status === "complete" ? "✓" : "✗"}
+                  <span className={`text-lg ${t.status === "complete" ? "text-success" : t.status === "handed_off" ? "text-surface-300" : "text-error"}`}>
+                    {t.status === "complete" ? "✓" : t.status === "handed_off" ? "↗" : "✗"}
                   </span>
                   <div>
                     <span className="text-surface-200">{t.file_name}</span>
@@ -304,7 +305,7 @@ export function TransferPage() {
                   </div>
                 </div>
                 <span className={`text-xs capitalize ${getStatusColor(t.status)}`}>
-                  {t.status}
+                  {t.status.replace("_", " ")}
                 </span>
               </div>
             ))}